@@ -0,0 +1,264 @@
+//! Protocol of the Otto IPC socket
+//!
+//! The compositor listens on a Unix socket whose path is exported in
+//! `$OTTO_SOCKET`. Clients write one JSON [`Request`] per line and read one
+//! JSON [`Response`] per line. After a [`Request::Subscribe`] the connection
+//! only carries [`Event`]s, one per line.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Environment variable holding the socket path
+pub const SOCKET_ENV: &str = "OTTO_SOCKET";
+
+/// Commands understood by the compositor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    Outputs,
+    Workspaces,
+    Windows,
+    FocusWindow {
+        id: u64,
+    },
+    CloseWindow {
+        id: u64,
+    },
+    /// Moves a window to `x`, `y`, on another workspace if given
+    MoveWindow {
+        id: u64,
+        x: i32,
+        y: i32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        workspace: Option<usize>,
+    },
+    ResizeWindow {
+        id: u64,
+        width: i32,
+        height: i32,
+    },
+    MinimizeWindow {
+        id: u64,
+    },
+    /// Maximizes a window, or restores it if it is maximized
+    MaximizeWindow {
+        id: u64,
+    },
+    SwitchWorkspace {
+        index: usize,
+    },
+    CreateWorkspace,
+    /// Names a workspace, an empty name restores the default one
+    RenameWorkspace {
+        index: usize,
+        name: String,
+    },
+    RemoveWorkspace {
+        index: usize,
+    },
+    /// Runs a builtin shortcut action, e.g. `ExposeShowAll`
+    Action {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        index: Option<usize>,
+    },
+    /// Turns the connection into an event stream
+    Subscribe,
+}
+
+/// Answer to a [`Request`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    Ok,
+    Error { message: String },
+    Outputs { outputs: Vec<OutputInfo> },
+    Workspaces { workspaces: Vec<WorkspaceInfo> },
+    Windows { windows: Vec<WindowInfo> },
+}
+
+/// Changes sent to subscribed clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    WindowOpened {
+        window: WindowInfo,
+    },
+    /// Title, app_id, geometry, workspace or state of a window changed
+    WindowChanged {
+        window: WindowInfo,
+    },
+    WindowClosed {
+        id: u64,
+    },
+    /// Keyboard focus moved, `None` when no window has it
+    WindowFocused {
+        id: Option<u64>,
+    },
+    WorkspaceActivated {
+        index: usize,
+    },
+    /// Workspaces were added, removed or renamed
+    WorkspacesChanged {
+        workspaces: Vec<WorkspaceInfo>,
+    },
+    OutputConnected {
+        output: OutputInfo,
+    },
+    OutputDisconnected {
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputInfo {
+    pub name: String,
+    pub make: String,
+    pub model: String,
+    /// Position in the global space, in logical pixels
+    pub x: i32,
+    pub y: i32,
+    /// Current mode, in physical pixels
+    pub width: i32,
+    pub height: i32,
+    /// Refresh rate in mHz
+    pub refresh: i32,
+    pub scale: f64,
+    pub transform: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceInfo {
+    pub index: usize,
+    pub name: String,
+    pub active: bool,
+    pub fullscreen: bool,
+    pub windows: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowInfo {
+    pub id: u64,
+    pub app_id: String,
+    pub title: String,
+    pub workspace: usize,
+    /// Geometry in the workspace, in logical pixels
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub focused: bool,
+    pub minimized: bool,
+    pub maximized: bool,
+    pub fullscreen: bool,
+}
+
+/// Socket path advertised by the running compositor
+pub fn socket_path() -> Option<PathBuf> {
+    std::env::var_os(SOCKET_ENV).map(PathBuf::from)
+}
+
+/// Blocking connection to the compositor
+#[derive(Debug)]
+pub struct Client {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl Client {
+    /// Connects to the socket in `$OTTO_SOCKET`
+    pub fn connect() -> io::Result<Self> {
+        let path = socket_path().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{SOCKET_ENV} is not set, is otto running?"),
+            )
+        })?;
+        Self::connect_to(&path)
+    }
+
+    pub fn connect_to(path: &Path) -> io::Result<Self> {
+        let writer = UnixStream::connect(path)?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(Self { reader, writer })
+    }
+
+    /// Sends a request and waits for its response
+    pub fn request(&mut self, request: &Request) -> io::Result<Response> {
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())?;
+        self.read_line()?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed by the compositor",
+            )
+        })
+    }
+
+    /// Subscribes to events, read them with [`Client::next_event`]
+    pub fn subscribe(&mut self) -> io::Result<()> {
+        match self.request(&Request::Subscribe)? {
+            Response::Ok => Ok(()),
+            Response::Error { message } => Err(io::Error::other(message)),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    /// Waits for the next event, `None` once the compositor is gone
+    pub fn next_event(&mut self) -> io::Result<Option<Event>> {
+        self.read_line()
+    }
+
+    fn read_line<T: for<'de> Deserialize<'de>>(&mut self) -> io::Result<Option<T>> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&line)?))
+    }
+}
+
+fn unexpected(response: &Response) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("unexpected response: {response:?}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_tagged_by_command() {
+        let request: Request =
+            serde_json::from_str(r#"{"command":"move_window","id":3,"x":10,"y":20}"#).unwrap();
+        assert_eq!(
+            request,
+            Request::MoveWindow {
+                id: 3,
+                x: 10,
+                y: 20,
+                workspace: None
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&Request::Windows).unwrap(),
+            r#"{"command":"windows"}"#
+        );
+    }
+
+    #[test]
+    fn events_are_tagged_by_event() {
+        let event = Event::WindowFocused { id: Some(7) };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(json, r#"{"event":"window_focused","id":7}"#);
+        assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+    }
+}
@@ -0,0 +1,204 @@
+//! Command-line client for the Otto IPC socket
+//!
+//! Queries print JSON on stdout, so they can be piped to `jq`. Commands that
+//! change something print nothing and exit with status 1 on failure.
+
+use std::process::ExitCode;
+
+use otto_ipc::{Client, Request, Response};
+
+const USAGE: &str = "\
+Usage: ottoctl <command> [arguments]
+
+Queries:
+  outputs                          List the outputs
+  workspaces                       List the workspaces
+  windows                          List the windows
+
+Windows:
+  focus <id>                       Focus a window, restoring it if minimized
+  close <id>                       Ask a window to close
+  move <id> <x> <y> [workspace]    Move a window, optionally to a workspace
+  resize <id> <width> <height>     Resize a window
+  minimize <id>                    Minimize a window
+  maximize <id>                    Maximize or restore a window
+
+Workspaces:
+  workspace <index>                Switch to a workspace
+  workspace-create                 Add a workspace
+  workspace-rename <index> [name]  Name a workspace, no name restores the default
+  workspace-remove <index>         Remove a workspace
+
+Other:
+  action <builtin> [index]         Run a builtin action, e.g. ExposeShowAll
+  subscribe                        Print window, focus and workspace events
+";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, args)) = args.split_first() else {
+        eprint!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    if matches!(command.as_str(), "-h" | "--help" | "help") {
+        print!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    match run(command, args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("ottoctl: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: &str, args: &[String]) -> Result<(), String> {
+    if command == "subscribe" {
+        expect_args(args, 0)?;
+        return subscribe(&mut connect()?);
+    }
+
+    let request = parse_request(command, args)?;
+    let mut client = connect()?;
+    let response = client.request(&request).map_err(|err| err.to_string())?;
+    let json = match response {
+        Response::Ok => return Ok(()),
+        Response::Error { message } => return Err(message),
+        Response::Outputs { outputs } => serde_json::to_string_pretty(&outputs),
+        Response::Workspaces { workspaces } => serde_json::to_string_pretty(&workspaces),
+        Response::Windows { windows } => serde_json::to_string_pretty(&windows),
+    };
+    println!("{}", json.map_err(|err| err.to_string())?);
+    Ok(())
+}
+
+fn connect() -> Result<Client, String> {
+    Client::connect().map_err(|err| format!("cannot connect: {err}"))
+}
+
+fn subscribe(client: &mut Client) -> Result<(), String> {
+    client.subscribe().map_err(|err| err.to_string())?;
+    while let Some(event) = client.next_event().map_err(|err| err.to_string())? {
+        println!(
+            "{}",
+            serde_json::to_string(&event).map_err(|err| err.to_string())?
+        );
+    }
+    Ok(())
+}
+
+fn parse_request(command: &str, args: &[String]) -> Result<Request, String> {
+    let request = match command {
+        "outputs" => {
+            expect_args(args, 0)?;
+            Request::Outputs
+        }
+        "workspaces" => {
+            expect_args(args, 0)?;
+            Request::Workspaces
+        }
+        "windows" => {
+            expect_args(args, 0)?;
+            Request::Windows
+        }
+        "focus" => {
+            expect_args(args, 1)?;
+            Request::FocusWindow {
+                id: number(&args[0], "id")?,
+            }
+        }
+        "close" => {
+            expect_args(args, 1)?;
+            Request::CloseWindow {
+                id: number(&args[0], "id")?,
+            }
+        }
+        "move" => {
+            if !(3..=4).contains(&args.len()) {
+                return Err("move takes <id> <x> <y> [workspace]".to_string());
+            }
+            Request::MoveWindow {
+                id: number(&args[0], "id")?,
+                x: number(&args[1], "x")?,
+                y: number(&args[2], "y")?,
+                workspace: args
+                    .get(3)
+                    .map(|arg| number(arg, "workspace"))
+                    .transpose()?,
+            }
+        }
+        "resize" => {
+            expect_args(args, 3)?;
+            Request::ResizeWindow {
+                id: number(&args[0], "id")?,
+                width: number(&args[1], "width")?,
+                height: number(&args[2], "height")?,
+            }
+        }
+        "minimize" => {
+            expect_args(args, 1)?;
+            Request::MinimizeWindow {
+                id: number(&args[0], "id")?,
+            }
+        }
+        "maximize" => {
+            expect_args(args, 1)?;
+            Request::MaximizeWindow {
+                id: number(&args[0], "id")?,
+            }
+        }
+        "workspace" => {
+            expect_args(args, 1)?;
+            Request::SwitchWorkspace {
+                index: number(&args[0], "index")?,
+            }
+        }
+        "workspace-create" => {
+            expect_args(args, 0)?;
+            Request::CreateWorkspace
+        }
+        "workspace-rename" => {
+            let Some((index, name)) = args.split_first() else {
+                return Err("workspace-rename takes <index> [name]".to_string());
+            };
+            Request::RenameWorkspace {
+                index: number(index, "index")?,
+                name: name.join(" "),
+            }
+        }
+        "workspace-remove" => {
+            expect_args(args, 1)?;
+            Request::RemoveWorkspace {
+                index: number(&args[0], "index")?,
+            }
+        }
+        "action" => {
+            let (name, index) = match args {
+                [name] => (name.clone(), None),
+                [name, index] => (name.clone(), Some(number(index, "index")?)),
+                _ => return Err("action takes <builtin> [index]".to_string()),
+            };
+            Request::Action { name, index }
+        }
+        other => return Err(format!("unknown command '{other}', see ottoctl --help")),
+    };
+    Ok(request)
+}
+
+fn expect_args(args: &[String], count: usize) -> Result<(), String> {
+    if args.len() == count {
+        Ok(())
+    } else {
+        Err(format!(
+            "expected {count} argument(s), got {}, see ottoctl --help",
+            args.len()
+        ))
+    }
+}
+
+fn number<T: std::str::FromStr>(arg: &str, what: &str) -> Result<T, String> {
+    arg.parse()
+        .map_err(|_| format!("invalid {what} '{arg}', expected a number"))
+}
@@ -1,7 +1,8 @@
 //! XDG Desktop Portal backend for Otto.
 //!
 //! This crate implements `org.freedesktop.impl.portal.ScreenCast` to enable
-//! screen sharing through the standard XDG Desktop Portal interface.
+//! screen sharing through the standard XDG Desktop Portal interface, plus the
+//! Settings, GlobalShortcuts and Wallpaper backends.
 
 pub mod otto_client;
 pub mod portal;
@@ -1,7 +1,9 @@
 //! XDG Desktop Portal backend for Otto.
 //!
-//! This binary implements the `org.freedesktop.impl.portal.ScreenCast` D-Bus
-//! interface, enabling screen sharing through the standard portal API.
+//! This binary implements the `org.freedesktop.impl.portal.ScreenCast`,
+//! `Settings`, `GlobalShortcuts` and `Wallpaper` D-Bus interfaces, enabling
+//! screen sharing, global shortcuts and setting the wallpaper through the
+//! standard portal API.
 
 use anyhow::Result;
 use tokio::signal;
@@ -10,7 +12,10 @@ use tracing_subscriber::EnvFilter;
 use zbus::ConnectionBuilder;
 
 use xdg_desktop_portal_otto::otto_client::OttoClient;
-use xdg_desktop_portal_otto::portal::{desktop_path, ScreenCastPortal, SettingsPortal};
+use xdg_desktop_portal_otto::portal::{
+    desktop_path, forward_global_shortcut_signals, forward_setting_changed_signals,
+    GlobalShortcutsPortal, ScreenCastPortal, SettingsPortal, WallpaperPortal,
+};
 use xdg_desktop_portal_otto::watchdog::{Watchdog, WatchdogConfig};
 
 /// Well-known D-Bus name for the Otto portal backend.
@@ -34,15 +39,43 @@ async fn main() -> Result<()> {
         .at(desktop_path(), screencast_portal)
         .await?;
 
-    let settings_portal = SettingsPortal::new(sc_client);
+    let settings_portal = SettingsPortal::new(sc_client.clone());
     connection
         .object_server()
         .at(desktop_path(), settings_portal)
         .await?;
 
+    let global_shortcuts_portal = GlobalShortcutsPortal::new(sc_client.clone());
+    connection
+        .object_server()
+        .at(desktop_path(), global_shortcuts_portal)
+        .await?;
+
+    let wallpaper_portal = WallpaperPortal::new(sc_client.clone());
+    connection
+        .object_server()
+        .at(desktop_path(), wallpaper_portal)
+        .await?;
+
+    let settings_connection = connection.clone();
+    let settings_client = sc_client.clone();
+    tokio::spawn(async move {
+        if let Err(e) = forward_setting_changed_signals(settings_connection, settings_client).await
+        {
+            tracing::error!("Settings signal forwarding error: {}", e);
+        }
+    });
+
+    let signals_connection = connection.clone();
+    tokio::spawn(async move {
+        if let Err(e) = forward_global_shortcut_signals(signals_connection, sc_client).await {
+            tracing::error!("GlobalShortcuts signal forwarding error: {}", e);
+        }
+    });
+
     info!(
         name = DBUS_NAME,
-        "ScreenCast and Settings portal backends running"
+        "ScreenCast, Settings, GlobalShortcuts and Wallpaper portal backends running"
     );
 
     // Start the watchdog in a separate task
@@ -0,0 +1,49 @@
+//! Client proxy for `org.otto.GlobalShortcuts`.
+//!
+//! This module speaks to `org.otto.GlobalShortcuts` (the backend interface
+//! exposed by the Otto compositor). Sessions are identified by the portal
+//! session handle.
+
+use std::collections::HashMap;
+
+use zbus::zvariant::OwnedValue;
+use zbus::{proxy, Result};
+
+/// Shortcuts in the portal wire format, `a(sa{sv})`.
+pub type Shortcuts = Vec<(String, HashMap<String, OwnedValue>)>;
+
+/// D-Bus proxy for `org.otto.GlobalShortcuts` service.
+#[proxy(
+    interface = "org.otto.GlobalShortcuts",
+    default_service = "org.otto.GlobalShortcuts",
+    default_path = "/org/otto/GlobalShortcuts"
+)]
+trait OttoGlobalShortcuts {
+    /// Binds shortcuts for a session after the user confirmed them.
+    ///
+    /// Fails with `AccessDenied` when the user refused the request.
+    async fn bind_shortcuts(
+        &self,
+        session: &str,
+        app_id: &str,
+        shortcuts: Shortcuts,
+    ) -> Result<Shortcuts>;
+
+    /// Lists the shortcuts bound for a session.
+    async fn list_shortcuts(&self, session: &str) -> Result<Shortcuts>;
+
+    /// Releases all shortcuts of a session.
+    async fn close_session(&self, session: &str) -> Result<()>;
+
+    /// Emitted when the trigger of a shortcut is pressed.
+    #[zbus(signal)]
+    fn activated(&self, session: String, shortcut_id: String, timestamp: u64) -> Result<()>;
+
+    /// Emitted when the trigger of an activated shortcut is released.
+    #[zbus(signal)]
+    fn deactivated(&self, session: String, shortcut_id: String, timestamp: u64) -> Result<()>;
+
+    /// Emitted when the bound shortcuts of a session change.
+    #[zbus(signal)]
+    fn shortcuts_changed(&self, session: String, shortcuts: Shortcuts) -> Result<()>;
+}
@@ -2,7 +2,8 @@
 //!
 //! This module owns the D-Bus bindings we use to talk to Otto.
 //! Each backend API should live in its own submodule (e.g. Screencast,
-//! RemoteDesktop). For now only the ScreenCast API is implemented.
+//! RemoteDesktop). ScreenCast, Settings, GlobalShortcuts and Wallpaper are
+//! implemented.
 //! See `ScreenCast-backend-spec.md` for the contract this module targets.
 
 use zbus::{Connection, Result};
@@ -20,5 +21,7 @@ impl OttoClient {
     }
 }
 
+pub mod global_shortcuts;
 pub mod screencast;
 pub mod settings;
+pub mod wallpaper;
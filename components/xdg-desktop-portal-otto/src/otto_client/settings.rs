@@ -3,7 +3,7 @@
 //! This module speaks to `org.otto.Settings` (the backend interface
 //! exposed by the Otto compositor).
 
-use zbus::{proxy, Result};
+use zbus::{proxy, zvariant::OwnedValue, Result};
 
 /// D-Bus proxy for `org.otto.Settings` service.
 #[proxy(
@@ -19,4 +19,12 @@ trait OttoSettings {
     /// - 1: Prefer dark appearance
     /// - 2: Prefer light appearance
     async fn get_color_scheme(&self) -> Result<u32>;
+
+    /// Get the accent color as red, green and blue in `0.0..=1.0`.
+    async fn get_accent_color(&self) -> Result<(f64, f64, f64)>;
+
+    /// Emitted when a setting changes, with its Settings portal namespace
+    /// and key.
+    #[zbus(signal)]
+    fn setting_changed(&self, namespace: String, key: String, value: OwnedValue) -> Result<()>;
 }
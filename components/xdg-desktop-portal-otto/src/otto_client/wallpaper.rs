@@ -0,0 +1,20 @@
+//! Client proxy for `org.otto.Wallpaper`.
+//!
+//! This module speaks to `org.otto.Wallpaper` (the backend interface exposed
+//! by the Otto compositor). Images are passed as local paths.
+
+use zbus::{proxy, Result};
+
+/// D-Bus proxy for `org.otto.Wallpaper` service.
+#[proxy(
+    interface = "org.otto.Wallpaper",
+    default_service = "org.otto.Wallpaper",
+    default_path = "/org/otto/Wallpaper"
+)]
+trait OttoWallpaper {
+    /// Sets the image at `path` as the wallpaper.
+    ///
+    /// With `show_preview` the compositor shows the image until the user
+    /// keeps or reverts it. Returns whether the wallpaper was kept.
+    async fn set_wallpaper(&self, app_id: &str, path: &str, show_preview: bool) -> Result<bool>;
+}
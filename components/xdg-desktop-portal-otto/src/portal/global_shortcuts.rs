@@ -0,0 +1,354 @@
+//! D-Bus interface implementation for `org.freedesktop.impl.portal.GlobalShortcuts`.
+//!
+//! Shortcuts are owned by the compositor, which asks the user to confirm or
+//! rebind them. The portal keeps track of the session → app id mapping and
+//! forwards the compositor signals to the frontend.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+use zbus::fdo;
+use zbus::interface;
+use zbus::object_server::ObjectServer;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+use zbus::{Connection, SignalContext};
+
+use crate::otto_client::global_shortcuts::{OttoGlobalShortcutsProxy, Shortcuts};
+use crate::otto_client::OttoClient;
+use crate::portal::{desktop_path, Request};
+
+/// Response codes per XDG Desktop Portal spec.
+const RESPONSE_SUCCESS: u32 = 0;
+const RESPONSE_CANCELLED: u32 = 1;
+const RESPONSE_OTHER: u32 = 2;
+
+/// Open GlobalShortcuts sessions, keyed by session handle.
+type Sessions = Arc<Mutex<HashMap<String, String>>>;
+
+/// GlobalShortcuts portal implementing org.freedesktop.impl.portal.GlobalShortcuts.
+#[derive(Clone)]
+pub struct GlobalShortcutsPortal {
+    client: OttoClient,
+    sessions: Sessions,
+}
+
+impl GlobalShortcutsPortal {
+    pub fn new(client: OttoClient) -> Self {
+        Self {
+            client,
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Gets a proxy to the Otto GlobalShortcuts D-Bus interface.
+    async fn get_shortcuts_proxy(&self) -> fdo::Result<OttoGlobalShortcutsProxy<'static>> {
+        OttoGlobalShortcutsProxy::new(&self.client.connection)
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to create GlobalShortcuts proxy");
+                fdo::Error::Failed(format!("Failed to connect to compositor shortcuts: {err}"))
+            })
+    }
+
+    /// Export a temporary Request object in dbus so the frontend can listen for the response signal.
+    async fn register_request(
+        &self,
+        object_server: &ObjectServer,
+        path: &OwnedObjectPath,
+    ) -> fdo::Result<()> {
+        object_server
+            .at(path.clone(), Request::new(path.clone()))
+            .await
+            .map(|_| ())
+            .map_err(|err| fdo::Error::Failed(err.to_string()))
+    }
+
+    async fn unregister_request(&self, object_server: &ObjectServer, path: &OwnedObjectPath) {
+        if let Err(err) = object_server.remove::<Request, _>(path).await {
+            warn!(request = %path.as_str(), ?err, "Failed to unregister request object");
+        }
+    }
+}
+
+/// Wraps shortcuts in the `results` dictionary of a portal response.
+fn shortcuts_results(shortcuts: Shortcuts) -> fdo::Result<HashMap<String, OwnedValue>> {
+    let value = OwnedValue::try_from(Value::from(shortcuts))
+        .map_err(|err| fdo::Error::Failed(format!("Failed to encode shortcuts: {err}")))?;
+    Ok(HashMap::from([("shortcuts".to_string(), value)]))
+}
+
+#[interface(name = "org.freedesktop.impl.portal.GlobalShortcuts")]
+impl GlobalShortcutsPortal {
+    async fn create_session(
+        &self,
+        handle: OwnedObjectPath,
+        session_handle: OwnedObjectPath,
+        app_id: String,
+        options: HashMap<String, OwnedValue>,
+        #[zbus(object_server)] object_server: &ObjectServer,
+    ) -> fdo::Result<(u32, HashMap<String, OwnedValue>)> {
+        info!(?app_id, ?options, "GlobalShortcuts CreateSession called");
+
+        self.register_request(object_server, &handle).await?;
+
+        let result = async {
+            object_server
+                .at(
+                    session_handle.clone(),
+                    ShortcutsSession::new(
+                        session_handle.clone(),
+                        self.client.clone(),
+                        self.sessions.clone(),
+                    ),
+                )
+                .await
+                .map_err(|err| fdo::Error::Failed(format!("Failed to export Session: {err}")))?;
+
+            self.sessions
+                .lock()
+                .await
+                .insert(session_handle.to_string(), app_id);
+
+            Ok((RESPONSE_SUCCESS, HashMap::new()))
+        }
+        .await;
+
+        self.unregister_request(object_server, &handle).await;
+
+        result
+    }
+
+    async fn bind_shortcuts(
+        &self,
+        handle: OwnedObjectPath,
+        session_handle: OwnedObjectPath,
+        shortcuts: Shortcuts,
+        parent_window: &str,
+        options: HashMap<String, OwnedValue>,
+        #[zbus(object_server)] object_server: &ObjectServer,
+    ) -> fdo::Result<(u32, HashMap<String, OwnedValue>)> {
+        info!(session = %session_handle, count = shortcuts.len(), parent_window, ?options, "BindShortcuts called");
+
+        self.register_request(object_server, &handle).await?;
+
+        let result = async {
+            let app_id = self
+                .sessions
+                .lock()
+                .await
+                .get(session_handle.as_str())
+                .cloned()
+                .ok_or_else(|| fdo::Error::Failed("Session not found".to_string()))?;
+
+            let proxy = self.get_shortcuts_proxy().await?;
+            match proxy
+                .bind_shortcuts(session_handle.as_str(), &app_id, shortcuts)
+                .await
+            {
+                Ok(bound) => Ok((RESPONSE_SUCCESS, shortcuts_results(bound)?)),
+                Err(zbus::Error::MethodError(name, _, _))
+                    if name.as_str() == "org.freedesktop.DBus.Error.AccessDenied" =>
+                {
+                    info!(session = %session_handle, "User denied the shortcuts");
+                    Ok((RESPONSE_CANCELLED, HashMap::new()))
+                }
+                Err(err) => {
+                    error!(session = %session_handle, ?err, "Failed to bind shortcuts");
+                    Ok((RESPONSE_OTHER, HashMap::new()))
+                }
+            }
+        }
+        .await;
+
+        self.unregister_request(object_server, &handle).await;
+
+        result
+    }
+
+    async fn list_shortcuts(
+        &self,
+        handle: OwnedObjectPath,
+        session_handle: OwnedObjectPath,
+        #[zbus(object_server)] object_server: &ObjectServer,
+    ) -> fdo::Result<(u32, HashMap<String, OwnedValue>)> {
+        self.register_request(object_server, &handle).await?;
+
+        let result = async {
+            let proxy = self.get_shortcuts_proxy().await?;
+            match proxy.list_shortcuts(session_handle.as_str()).await {
+                Ok(shortcuts) => Ok((RESPONSE_SUCCESS, shortcuts_results(shortcuts)?)),
+                Err(err) => {
+                    error!(session = %session_handle, ?err, "Failed to list shortcuts");
+                    Ok((RESPONSE_OTHER, HashMap::new()))
+                }
+            }
+        }
+        .await;
+
+        self.unregister_request(object_server, &handle).await;
+
+        result
+    }
+
+    /// Emitted when a shortcut is activated.
+    #[zbus(signal)]
+    async fn activated(
+        ctx: &SignalContext<'_>,
+        session_handle: ObjectPath<'_>,
+        shortcut_id: &str,
+        timestamp: u64,
+        options: HashMap<String, OwnedValue>,
+    ) -> zbus::Result<()>;
+
+    /// Emitted when a shortcut is deactivated.
+    #[zbus(signal)]
+    async fn deactivated(
+        ctx: &SignalContext<'_>,
+        session_handle: ObjectPath<'_>,
+        shortcut_id: &str,
+        timestamp: u64,
+        options: HashMap<String, OwnedValue>,
+    ) -> zbus::Result<()>;
+
+    /// Emitted when the shortcuts of a session changed.
+    #[zbus(signal)]
+    async fn shortcuts_changed(
+        ctx: &SignalContext<'_>,
+        session_handle: ObjectPath<'_>,
+        shortcuts: Shortcuts,
+    ) -> zbus::Result<()>;
+
+    /// Version of the GlobalShortcuts interface.
+    #[zbus(property)]
+    async fn version(&self) -> u32 {
+        1
+    }
+}
+
+/// Re-emits the compositor shortcut signals on the portal interface.
+///
+/// Runs until the compositor signal streams end.
+pub async fn forward_global_shortcut_signals(
+    connection: Connection,
+    client: OttoClient,
+) -> zbus::Result<()> {
+    use futures_util::StreamExt;
+
+    let proxy = OttoGlobalShortcutsProxy::new(&client.connection).await?;
+    let interface = connection
+        .object_server()
+        .interface::<_, GlobalShortcutsPortal>(desktop_path())
+        .await?;
+
+    let mut activated = proxy.receive_activated().await?;
+    let mut deactivated = proxy.receive_deactivated().await?;
+    let mut changed = proxy.receive_shortcuts_changed().await?;
+
+    loop {
+        let ctx = interface.signal_context();
+        let result = tokio::select! {
+            Some(signal) = activated.next() => {
+                let args = signal.args()?;
+                match ObjectPath::try_from(args.session.as_str()) {
+                    Ok(session) => {
+                        GlobalShortcutsPortal::activated(
+                            ctx, session, &args.shortcut_id, args.timestamp, HashMap::new(),
+                        )
+                        .await
+                    }
+                    Err(err) => Err(err.into()),
+                }
+            }
+            Some(signal) = deactivated.next() => {
+                let args = signal.args()?;
+                match ObjectPath::try_from(args.session.as_str()) {
+                    Ok(session) => {
+                        GlobalShortcutsPortal::deactivated(
+                            ctx, session, &args.shortcut_id, args.timestamp, HashMap::new(),
+                        )
+                        .await
+                    }
+                    Err(err) => Err(err.into()),
+                }
+            }
+            Some(signal) = changed.next() => {
+                let args = signal.args()?;
+                match ObjectPath::try_from(args.session.as_str()) {
+                    Ok(session) => {
+                        GlobalShortcutsPortal::shortcuts_changed(ctx, session, args.shortcuts)
+                            .await
+                    }
+                    Err(err) => Err(err.into()),
+                }
+            }
+            else => break,
+        };
+        if let Err(err) = result {
+            warn!(?err, "Failed to forward global shortcut signal");
+        }
+    }
+
+    Ok(())
+}
+
+/// A GlobalShortcuts session.
+///
+/// Closing the session releases its shortcuts in the compositor.
+#[derive(Clone)]
+pub struct ShortcutsSession {
+    path: OwnedObjectPath,
+    client: OttoClient,
+    sessions: Sessions,
+}
+
+impl ShortcutsSession {
+    fn new(path: OwnedObjectPath, client: OttoClient, sessions: Sessions) -> Self {
+        Self {
+            path,
+            client,
+            sessions,
+        }
+    }
+}
+
+#[interface(name = "org.freedesktop.impl.portal.Session")]
+impl ShortcutsSession {
+    /// Closes the session and releases its shortcuts.
+    async fn close(
+        &self,
+        #[zbus(object_server)] object_server: &ObjectServer,
+        #[zbus(signal_context)] ctx: SignalContext<'_>,
+    ) -> fdo::Result<()> {
+        info!(session = %self.path, "GlobalShortcuts Session.Close called");
+
+        self.sessions.lock().await.remove(self.path.as_str());
+
+        match OttoGlobalShortcutsProxy::new(&self.client.connection).await {
+            Ok(proxy) => {
+                if let Err(err) = proxy.close_session(self.path.as_str()).await {
+                    warn!(session = %self.path, ?err, "Failed to release compositor shortcuts");
+                }
+            }
+            Err(err) => {
+                warn!(session = %self.path, ?err, "Failed to create GlobalShortcuts proxy");
+            }
+        }
+
+        ShortcutsSession::closed(&ctx)
+            .await
+            .map_err(|err| fdo::Error::Failed(err.to_string()))?;
+
+        object_server
+            .remove::<ShortcutsSession, _>(&self.path)
+            .await
+            .map_err(|err| fdo::Error::Failed(err.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Signal emitted when the session is closed.
+    #[zbus(signal)]
+    async fn closed(ctx: &SignalContext<'_>) -> zbus::Result<()>;
+}
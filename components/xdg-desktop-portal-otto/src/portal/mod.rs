@@ -3,20 +3,26 @@
 //! This module provides D-Bus interface implementations for:
 //! - `org.freedesktop.impl.portal.ScreenCast`
 //! - `org.freedesktop.impl.portal.Settings`
+//! - `org.freedesktop.impl.portal.GlobalShortcuts`
+//! - `org.freedesktop.impl.portal.Wallpaper`
 
+mod global_shortcuts;
 mod interface;
 mod request;
 mod session;
 mod settings;
 mod state;
 mod stream;
+mod wallpaper;
 
+pub use global_shortcuts::{forward_global_shortcut_signals, GlobalShortcutsPortal};
 pub use interface::{
     fallback_mapping_id, validate_cursor_mode, validate_persist_mode, ScreenCastPortal,
 };
-pub use settings::SettingsPortal;
+pub use settings::{forward_setting_changed_signals, SettingsPortal};
 pub use state::{PortalState, SessionState};
 pub use stream::{build_streams_value_from_descriptors, StreamDescriptor};
+pub use wallpaper::{wallpaper_path_from_uri, WallpaperPortal};
 
 pub(crate) use request::Request;
 pub(crate) use session::Session;
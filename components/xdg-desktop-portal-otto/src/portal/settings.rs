@@ -2,13 +2,19 @@
 
 use std::collections::HashMap;
 
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 use zbus::fdo;
 use zbus::interface;
-use zbus::zvariant::OwnedValue;
+use zbus::zvariant::{OwnedValue, Value};
+use zbus::{Connection, SignalContext};
 
 use crate::otto_client::settings::OttoSettingsProxy;
 use crate::otto_client::OttoClient;
+use crate::portal::desktop_path;
+
+/// Accent color sent when the compositor can't be asked, its `accents_blue`
+/// (#0A84FF).
+const DEFAULT_ACCENT_COLOR: (f64, f64, f64) = (10.0 / 255.0, 132.0 / 255.0, 1.0);
 
 /// Settings portal implementing org.freedesktop.impl.portal.Settings.
 #[derive(Clone)]
@@ -24,11 +30,19 @@ impl SettingsPortal {
     /// Returns all settings as a nested HashMap.
     async fn get_all_settings(&self) -> fdo::Result<HashMap<String, HashMap<String, OwnedValue>>> {
         let color_scheme = self.read_color_scheme().await?;
+        let accent_color = match self.read_accent_color().await {
+            Ok(accent_color) => accent_color,
+            Err(err) => {
+                warn!(?err, "Using the default accent color");
+                accent_color_value(DEFAULT_ACCENT_COLOR)?
+            }
+        };
 
         let mut namespaces = HashMap::new();
         let mut appearance = HashMap::new();
 
         appearance.insert("color-scheme".to_string(), color_scheme.into());
+        appearance.insert("accent-color".to_string(), accent_color);
 
         namespaces.insert("org.freedesktop.appearance".to_string(), appearance);
         Ok(namespaces)
@@ -41,6 +55,7 @@ impl SettingsPortal {
                 let color_scheme = self.read_color_scheme().await?;
                 Ok(color_scheme.into())
             }
+            ("org.freedesktop.appearance", "accent-color") => self.read_accent_color().await,
             _ => Err(fdo::Error::Failed(format!(
                 "Unknown setting: {}.{}",
                 namespace, key
@@ -67,6 +82,16 @@ impl SettingsPortal {
         })
     }
 
+    /// Reads the accent color from the compositor as a `(ddd)` value.
+    async fn read_accent_color(&self) -> fdo::Result<OwnedValue> {
+        let proxy = self.get_settings_proxy().await?;
+        let color = proxy.get_accent_color().await.map_err(|err| {
+            error!(?err, "Failed to read accent color from compositor");
+            fdo::Error::Failed(format!("Failed to read accent color: {err}"))
+        })?;
+        accent_color_value(color)
+    }
+
     /// Helper to match namespace patterns (supports trailing wildcard).
     fn matches_namespace(namespace: &str, pattern: &str) -> bool {
         if pattern.ends_with(".*") {
@@ -78,6 +103,12 @@ impl SettingsPortal {
     }
 }
 
+/// An accent color as the `(ddd)` value of the portal
+fn accent_color_value(color: (f64, f64, f64)) -> fdo::Result<OwnedValue> {
+    OwnedValue::try_from(Value::new(color))
+        .map_err(|err| fdo::Error::Failed(format!("Invalid accent color: {err}")))
+}
+
 #[interface(name = "org.freedesktop.impl.portal.Settings")]
 impl SettingsPortal {
     /// Reads all settings, optionally filtered by namespace.
@@ -113,8 +144,55 @@ impl SettingsPortal {
         self.get_setting(&namespace, &key).await
     }
 
+    /// Emitted when a setting changes.
+    #[zbus(signal)]
+    async fn setting_changed(
+        ctx: &SignalContext<'_>,
+        namespace: &str,
+        key: &str,
+        value: Value<'_>,
+    ) -> zbus::Result<()>;
+
     #[zbus(property)]
     fn version(&self) -> u32 {
         1
     }
 }
+
+/// Re-emits the compositor's `SettingChanged` signals on the Settings portal
+/// so running applications follow theme changes.
+pub async fn forward_setting_changed_signals(
+    connection: Connection,
+    client: OttoClient,
+) -> zbus::Result<()> {
+    use futures_util::StreamExt;
+
+    let proxy = OttoSettingsProxy::new(&client.connection).await?;
+    let interface = connection
+        .object_server()
+        .interface::<_, SettingsPortal>(desktop_path())
+        .await?;
+
+    let mut changed = proxy.receive_setting_changed().await?;
+    while let Some(signal) = changed.next().await {
+        let args = signal.args()?;
+        debug!(namespace = args.namespace, key = args.key, "SettingChanged");
+        let result = match args.value.try_clone() {
+            Ok(value) => {
+                SettingsPortal::setting_changed(
+                    interface.signal_context(),
+                    &args.namespace,
+                    &args.key,
+                    value.into(),
+                )
+                .await
+            }
+            Err(err) => Err(err.into()),
+        };
+        if let Err(err) = result {
+            warn!(?err, "Failed to forward SettingChanged");
+        }
+    }
+
+    Ok(())
+}
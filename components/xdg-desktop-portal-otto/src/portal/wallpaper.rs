@@ -0,0 +1,152 @@
+//! D-Bus interface implementation for `org.freedesktop.impl.portal.Wallpaper`.
+//!
+//! The compositor draws the wallpaper, so the portal only turns the `file://`
+//! URI into a path and forwards it to `org.otto.Wallpaper`. Otto has no lock
+//! screen, requests for the lock screen alone fail.
+
+use std::collections::HashMap;
+
+use tracing::{error, info, warn};
+use zbus::fdo;
+use zbus::interface;
+use zbus::object_server::ObjectServer;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+use crate::otto_client::wallpaper::OttoWallpaperProxy;
+use crate::otto_client::OttoClient;
+use crate::portal::Request;
+
+/// Response codes per XDG Desktop Portal spec.
+const RESPONSE_SUCCESS: u32 = 0;
+const RESPONSE_CANCELLED: u32 = 1;
+const RESPONSE_OTHER: u32 = 2;
+
+/// Wallpaper portal implementing org.freedesktop.impl.portal.Wallpaper.
+#[derive(Clone)]
+pub struct WallpaperPortal {
+    client: OttoClient,
+}
+
+impl WallpaperPortal {
+    pub fn new(client: OttoClient) -> Self {
+        Self { client }
+    }
+
+    /// Gets a proxy to the Otto Wallpaper D-Bus interface.
+    async fn get_wallpaper_proxy(&self) -> fdo::Result<OttoWallpaperProxy<'static>> {
+        OttoWallpaperProxy::new(&self.client.connection)
+            .await
+            .map_err(|err| {
+                error!(?err, "Failed to create Wallpaper proxy");
+                fdo::Error::Failed(format!("Failed to connect to compositor wallpaper: {err}"))
+            })
+    }
+
+    /// Export a temporary Request object in dbus so the frontend can close it.
+    async fn register_request(
+        &self,
+        object_server: &ObjectServer,
+        path: &OwnedObjectPath,
+    ) -> fdo::Result<()> {
+        object_server
+            .at(path.clone(), Request::new(path.clone()))
+            .await
+            .map(|_| ())
+            .map_err(|err| fdo::Error::Failed(err.to_string()))
+    }
+
+    async fn unregister_request(&self, object_server: &ObjectServer, path: &OwnedObjectPath) {
+        if let Err(err) = object_server.remove::<Request, _>(path).await {
+            warn!(request = %path.as_str(), ?err, "Failed to unregister request object");
+        }
+    }
+}
+
+/// The local path of a `file://` URI, `None` for other URIs.
+///
+/// Percent-encoded bytes are decoded; the host may be empty or `localhost`.
+pub fn wallpaper_path_from_uri(uri: &str) -> Option<String> {
+    let rest = uri.strip_prefix("file://")?;
+    let path = rest.strip_prefix("localhost").unwrap_or(rest);
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut input = path.bytes();
+    while let Some(byte) = input.next() {
+        if byte == b'%' {
+            let high = (input.next()? as char).to_digit(16)?;
+            let low = (input.next()? as char).to_digit(16)?;
+            bytes.push((high * 16 + low) as u8);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[interface(name = "org.freedesktop.impl.portal.Wallpaper")]
+impl WallpaperPortal {
+    /// Sets the wallpaper to the image at `uri`.
+    ///
+    /// Options: `show-preview` (b) asks the user to keep the image first,
+    /// `set-on` (s) is `background`, `lockscreen` or `both`.
+    #[zbus(name = "SetWallpaperURI")]
+    async fn set_wallpaper_uri(
+        &self,
+        handle: OwnedObjectPath,
+        app_id: String,
+        parent_window: &str,
+        uri: String,
+        options: HashMap<String, OwnedValue>,
+        #[zbus(object_server)] object_server: &ObjectServer,
+    ) -> fdo::Result<u32> {
+        info!(?app_id, parent_window, %uri, ?options, "SetWallpaperURI called");
+
+        let show_preview = options
+            .get("show-preview")
+            .and_then(|value| bool::try_from(value).ok())
+            .unwrap_or(false);
+        let set_on = options
+            .get("set-on")
+            .and_then(|value| <&str>::try_from(value).ok())
+            .unwrap_or("both");
+        if set_on == "lockscreen" {
+            warn!(?app_id, "Otto has no lock screen wallpaper");
+            return Ok(RESPONSE_OTHER);
+        }
+        let Some(path) = wallpaper_path_from_uri(&uri) else {
+            warn!(?app_id, %uri, "Only local wallpaper files are supported");
+            return Ok(RESPONSE_OTHER);
+        };
+
+        self.register_request(object_server, &handle).await?;
+
+        let result = async {
+            let proxy = self.get_wallpaper_proxy().await?;
+            match proxy.set_wallpaper(&app_id, &path, show_preview).await {
+                Ok(true) => Ok(RESPONSE_SUCCESS),
+                Ok(false) => {
+                    info!(?app_id, "User reverted the wallpaper");
+                    Ok(RESPONSE_CANCELLED)
+                }
+                Err(err) => {
+                    error!(?app_id, %path, ?err, "Failed to set the wallpaper");
+                    Ok(RESPONSE_OTHER)
+                }
+            }
+        }
+        .await;
+
+        self.unregister_request(object_server, &handle).await;
+
+        result
+    }
+
+    /// Version of the Wallpaper interface.
+    #[zbus(property)]
+    async fn version(&self) -> u32 {
+        1
+    }
+}
@@ -1,6 +1,6 @@
 use xdg_desktop_portal_otto::portal::{
     fallback_mapping_id, make_output_mapping_id, validate_cursor_mode, validate_persist_mode,
-    CURSOR_MODE_EMBEDDED, CURSOR_MODE_HIDDEN, CURSOR_MODE_METADATA,
+    wallpaper_path_from_uri, CURSOR_MODE_EMBEDDED, CURSOR_MODE_HIDDEN, CURSOR_MODE_METADATA,
 };
 use zbus::DBusError;
 
@@ -62,3 +62,17 @@ fn mapping_helpers_sanitize_output_names() {
         "screencomposer:output-HDMI__".to_string()
     );
 }
+
+#[test]
+fn wallpaper_uris_become_local_paths() {
+    assert_eq!(
+        wallpaper_path_from_uri("file:///home/me/Pictures/Lake%20Como.jpg").as_deref(),
+        Some("/home/me/Pictures/Lake Como.jpg")
+    );
+    assert_eq!(
+        wallpaper_path_from_uri("file://localhost/tmp/a.png").as_deref(),
+        Some("/tmp/a.png")
+    );
+    assert_eq!(wallpaper_path_from_uri("https://example.com/a.png"), None);
+    assert_eq!(wallpaper_path_from_uri("file:///tmp/bad%2"), None);
+}
@@ -0,0 +1,548 @@
+//! `otto --check-config [path]`: validates configuration files without
+//! starting the compositor
+//!
+//! Every file is deserialized on its own to report syntax errors, type
+//! mismatches and unknown keys with their location. The merged result is
+//! then checked for shortcuts that would be skipped and for desktop entries,
+//! images and themes that do not exist.
+
+use std::{
+    collections::BTreeSet,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    default_apps::{find_desktop_entry, xdg_data_dirs, xdg_data_home},
+    files::{self, ConfigLoader},
+    shortcuts::{OpenDefaultConfig, ShortcutActionConfig, ShortcutIssue},
+    Config, ConfigError,
+};
+
+/// Type errors reported per file, each one is commented out to find the next
+const MAX_TYPE_ERRORS: usize = 32;
+
+/// Backends that may have override files next to the main one
+const BACKENDS: &[&str] = &["tty-udev", "x11", "winit"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: Option<PathBuf>,
+    /// Line and column, both starting at 1
+    pub location: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            file: None,
+            location: None,
+            message: message.into(),
+        }
+    }
+
+    fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(message)
+        }
+    }
+
+    fn at(mut self, file: &Path, location: Option<(usize, usize)>) -> Self {
+        self.file = Some(file.to_path_buf());
+        self.location = location;
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+            if let Some((line, column)) = self.location {
+                write!(f, "{line}:{column}:")?;
+            }
+            write!(f, " ")?;
+        }
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{severity}: {}", self.message)
+    }
+}
+
+/// Checks `path`, or the configuration Otto would load, and prints the
+/// diagnostics. Returns false if there are errors.
+pub fn run_check_config(path: Option<PathBuf>) -> bool {
+    let diagnostics = check_config(path.as_deref());
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+
+    #[allow(clippy::disallowed_macros)]
+    {
+        for diagnostic in &diagnostics {
+            println!("{diagnostic}");
+        }
+        if diagnostics.is_empty() {
+            println!("Configuration OK");
+        } else {
+            println!("{errors} error(s), {warnings} warning(s)");
+        }
+    }
+    errors == 0
+}
+
+pub fn check_config(path: Option<&Path>) -> Vec<Diagnostic> {
+    let Some(main) = path.map(Path::to_path_buf).or_else(files::main_config) else {
+        let searched: Vec<String> = files::config_candidates()
+            .iter()
+            .map(|candidate| candidate.display().to_string())
+            .collect();
+        return vec![Diagnostic::error(format!(
+            "no configuration file found, searched {}",
+            searched.join(", ")
+        ))];
+    };
+
+    let defaults =
+        toml::Value::try_from(Config::default()).expect("default config is always valid toml");
+    let mut loader = ConfigLoader::new(defaults);
+    if let Err(err) = loader.merge_file(&main, true) {
+        return vec![load_error(err)];
+    }
+    // The override of the running backend is merged as Otto does
+    if let Ok(backend) = std::env::var("SCREEN_COMPOSER_BACKEND") {
+        for candidate in files::backend_override_candidates(&main, &backend) {
+            match loader.merge_file(&candidate, false) {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(err) => return vec![load_error(err)],
+            }
+        }
+    }
+    let (merged, sources) = loader.finish();
+
+    let mut diagnostics = Vec::new();
+    // Overrides of the other backends are checked on their own
+    let mut overrides: Vec<PathBuf> = BACKENDS
+        .iter()
+        .flat_map(|backend| files::backend_override_candidates(&main, backend))
+        .filter(|candidate| candidate.is_file() && !sources.contains(candidate))
+        .collect();
+    overrides.dedup();
+    for file in &overrides {
+        match std::fs::read_to_string(file) {
+            Ok(source) => diagnostics.extend(check_file(file, &source)),
+            Err(err) => diagnostics.push(Diagnostic::error(format!(
+                "failed to read {}: {err}",
+                file.display()
+            ))),
+        }
+    }
+
+    let mut texts = Vec::new();
+    for file in &sources {
+        match std::fs::read_to_string(file) {
+            Ok(source) => {
+                diagnostics.extend(check_file(file, &source));
+                texts.push((file.clone(), source));
+            }
+            Err(err) => diagnostics.push(Diagnostic::error(format!(
+                "failed to read {}: {err}",
+                file.display()
+            ))),
+        }
+    }
+    if diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error)
+    {
+        // The merged configuration would not load
+        return diagnostics;
+    }
+
+    let mut config: Config = match merged.try_into() {
+        Ok(config) => config,
+        Err(err) => {
+            diagnostics.push(Diagnostic::error(err.to_string()));
+            return diagnostics;
+        }
+    };
+    for issue in config.build_shortcut_bindings() {
+        diagnostics.push(shortcut_diagnostic(&issue, &texts));
+    }
+    diagnostics.extend(check_resources(&config, &texts));
+    if let Err(err) = config.load_theme(main.parent().unwrap_or(Path::new(""))) {
+        diagnostics.push(load_error(err));
+    }
+    diagnostics
+}
+
+fn load_error(err: ConfigError) -> Diagnostic {
+    match err {
+        ConfigError::Parse { path, source } => {
+            let text = std::fs::read_to_string(&path).unwrap_or_default();
+            let location = source.span().map(|span| line_column(&text, span.start));
+            Diagnostic::error(source.message()).at(Path::new(&path), location)
+        }
+        err => Diagnostic::error(err.to_string()),
+    }
+}
+
+/// Syntax errors, type mismatches and unknown keys of a single file
+pub fn check_file(file: &Path, source: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut unknown = BTreeSet::new();
+    let mut source = source.to_string();
+
+    for pass in 0..MAX_TYPE_ERRORS {
+        if let Err(err) = source.parse::<toml::Table>() {
+            // Later passes comment out lines, which may break the syntax
+            if pass == 0 {
+                let location = err.span().map(|span| line_column(&source, span.start));
+                diagnostics.push(Diagnostic::error(err.message()).at(file, location));
+            }
+            break;
+        }
+
+        let deserializer = toml::Deserializer::new(&source);
+        let result: Result<Config, _> = serde_ignored::deserialize(deserializer, |path| {
+            unknown.insert(path.to_string());
+        });
+        let Err(err) = result else {
+            break;
+        };
+        let Some(span) = err.span() else {
+            diagnostics.push(Diagnostic::error(err.message()).at(file, None));
+            break;
+        };
+        let location = line_column(&source, span.start);
+        diagnostics.push(Diagnostic::error(err.message()).at(file, Some(location)));
+        comment_out(&mut source, span);
+    }
+
+    for key in unknown.iter().filter(|key| *key != "include") {
+        // Option values show up as `?` in the path
+        let segments: Vec<&str> = key.split('.').filter(|segment| *segment != "?").collect();
+        let key = segments.join(".");
+        let location = locate_key(&source, &segments);
+        diagnostics.push(Diagnostic::error(format!("unknown key '{key}'")).at(file, location));
+    }
+    diagnostics
+}
+
+/// Turns the lines covered by `span` into comments, keeping line numbers
+fn comment_out(source: &mut String, span: std::ops::Range<usize>) {
+    let start = source[..span.start]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let end = span.end.max(span.start + 1).min(source.len());
+    let mut offsets = vec![start];
+    offsets.extend(
+        source[start..end]
+            .match_indices('\n')
+            .map(|(index, _)| start + index + 1)
+            .filter(|offset| *offset < end),
+    );
+    for offset in offsets.into_iter().rev() {
+        source.insert(offset, '#');
+    }
+}
+
+/// Line and column, starting at 1, of a byte offset
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+        + 1;
+    (line, column)
+}
+
+/// Location of the key at `path`, following table headers and dotted keys.
+///
+/// Array indices in `path` are ignored, the first matching entry is returned.
+fn locate_key(source: &str, path: &[&str]) -> Option<(usize, usize)> {
+    let path: Vec<&str> = path
+        .iter()
+        .copied()
+        .filter(|segment| segment.parse::<usize>().is_err())
+        .collect();
+    let matches =
+        |key: &[String]| key.len() >= path.len() && key.iter().zip(&path).all(|(a, b)| a == b);
+
+    let mut table: Vec<String> = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let column = line.len() - trimmed.len() + 1;
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if trimmed.starts_with('[') {
+            let header = trimmed.trim_start_matches('[');
+            let Some(end) = find_unquoted(header, ']') else {
+                continue;
+            };
+            table = split_key(&header[..end]);
+            if matches(&table) {
+                return Some((index + 1, column));
+            }
+            continue;
+        }
+        let Some(eq) = find_unquoted(trimmed, '=') else {
+            continue;
+        };
+        let mut key = table.clone();
+        key.extend(split_key(&trimmed[..eq]));
+        if matches(&key) {
+            return Some((index + 1, column));
+        }
+    }
+    None
+}
+
+fn find_unquoted(text: &str, needle: char) -> Option<usize> {
+    let mut quote = None;
+    for (index, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), c) if c == open => quote = None,
+            (None, c) if c == needle => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits a dotted key, `a."b.c"` gives `a` and `b.c`
+fn split_key(key: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut rest = key.trim();
+    while !rest.is_empty() {
+        let end = find_unquoted(rest, '.').unwrap_or(rest.len());
+        let segment = rest[..end].trim().trim_matches(|c| c == '"' || c == '\'');
+        segments.push(segment.to_string());
+        rest = rest.get(end + 1..).unwrap_or("");
+    }
+    segments
+}
+
+/// Finds where a skipped shortcut is defined; the last file defining it wins
+fn shortcut_diagnostic(issue: &ShortcutIssue, texts: &[(PathBuf, String)]) -> Diagnostic {
+    let diagnostic = if issue.duplicate {
+        Diagnostic::warning(issue.to_string())
+    } else {
+        Diagnostic::error(issue.to_string())
+    };
+    let section: Vec<&str> = issue.section.split('.').collect();
+    let within = |keys: &[&str]| -> Vec<&str> { section.iter().chain(keys).copied().collect() };
+    let candidates = [
+        within(&[issue.trigger.as_str()]),
+        within(&["bindings", issue.trigger.as_str()]),
+        section.clone(),
+    ];
+    for (file, text) in texts.iter().rev() {
+        if let Some(location) = candidates
+            .iter()
+            .find_map(|candidate| locate_key(text, candidate))
+        {
+            return diagnostic.at(file, Some(location));
+        }
+    }
+    diagnostic
+}
+
+/// Desktop entries, images and themes referenced by the configuration
+fn check_resources(config: &Config, texts: &[(PathBuf, String)]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut missing = |message: String, path: &[&str]| {
+        let mut diagnostic = Diagnostic::error(message);
+        for (file, text) in texts.iter().rev() {
+            if let Some(location) = locate_key(text, path) {
+                diagnostic = diagnostic.at(file, Some(location));
+                break;
+            }
+        }
+        diagnostics.push(diagnostic);
+    };
+
+    for bookmark in &config.dock.bookmarks {
+        if find_desktop_entry(&bookmark.desktop_id).is_none() {
+            missing(
+                format!(
+                    "dock bookmark '{}' has no desktop entry",
+                    bookmark.desktop_id
+                ),
+                &["dock", "bookmarks"],
+            );
+        }
+    }
+
+    let actions = config.keyboard_shortcuts.iter().chain(
+        config
+            .keyboard_modes
+            .values()
+            .flat_map(|mode| mode.bindings.iter()),
+    );
+    for (trigger, action) in actions {
+        let ShortcutActionConfig::OpenDefault { open_default } = action else {
+            continue;
+        };
+        let desktop_ids = match open_default {
+            OpenDefaultConfig::Role(role) => vec![role],
+            OpenDefaultConfig::Detailed { role, fallback } => {
+                std::iter::once(role).chain(fallback).collect()
+            }
+        };
+        for desktop_id in desktop_ids {
+            if desktop_id.ends_with(".desktop") && find_desktop_entry(desktop_id).is_none() {
+                missing(
+                    format!(
+                        "shortcut '{trigger}' opens '{desktop_id}', which has no desktop entry"
+                    ),
+                    &["keyboard_shortcuts", trigger],
+                );
+            }
+        }
+    }
+
+    // Settings left to their default are not checked, and paths are
+    // relative to the file setting them
+    if let Some(dir) = defining_dir(texts, &["background_image"]) {
+        if !files::resolve_path(&config.background_image, dir).is_file() {
+            missing(
+                format!("background image '{}' not found", config.background_image),
+                &["background_image"],
+            );
+        }
+    }
+    if let Err(message) = config.wallpaper.validate() {
+        missing(message, &["wallpaper"]);
+    }
+    if let Some(dir) = defining_dir(texts, &["wallpaper"]) {
+        let wallpapers = std::iter::once(&config.wallpaper.default)
+            .chain(config.wallpaper.outputs.values())
+            .chain(config.wallpaper.workspaces.values());
+        for path in wallpapers.flat_map(|wallpaper| wallpaper.paths()) {
+            if !files::resolve_path(path, dir).exists() {
+                missing(format!("wallpaper '{path}' not found"), &["wallpaper"]);
+            }
+        }
+    }
+    if defining_dir(texts, &["cursor_theme"]).is_some()
+        && !theme_exists(&config.cursor_theme, "cursors")
+    {
+        missing(
+            format!("cursor theme '{}' is not installed", config.cursor_theme),
+            &["cursor_theme"],
+        );
+    }
+    if let Some(icon_theme) = &config.icon_theme {
+        if defining_dir(texts, &["icon_theme"]).is_some()
+            && !theme_exists(icon_theme, "index.theme")
+        {
+            missing(
+                format!("icon theme '{icon_theme}' is not installed"),
+                &["icon_theme"],
+            );
+        }
+    }
+    diagnostics
+}
+
+/// Directory of the last file setting the key at `path`, `None` if no file
+/// sets it
+fn defining_dir<'a>(texts: &'a [(PathBuf, String)], path: &[&str]) -> Option<&'a Path> {
+    texts
+        .iter()
+        .rev()
+        .find(|(_, text)| locate_key(text, path).is_some())
+        .map(|(file, _)| file.parent().unwrap_or(Path::new("")))
+}
+
+/// Whether a theme directory containing `entry` exists in the icon paths
+fn theme_exists(name: &str, entry: &str) -> bool {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    dirs.extend(std::env::var_os("HOME").map(|home| Path::new(&home).join(".icons")));
+    dirs.extend(xdg_data_home().map(|dir| dir.join("icons")));
+    dirs.extend(xdg_data_dirs().into_iter().map(|dir| dir.join("icons")));
+    dirs.push(PathBuf::from("/usr/share/pixmaps"));
+    dirs.iter().any(|dir| dir.join(name).join(entry).exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_type_errors_and_unknown_keys_with_location() {
+        let source = "cursor_size = \"big\"\n[dock]\nsize = 40\nsise = 40\n";
+        let diagnostics = check_file(Path::new("config.toml"), source);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].location, Some((1, 15)));
+        assert_eq!(diagnostics[1].location, Some((4, 1)));
+        assert_eq!(diagnostics[1].message, "unknown key 'dock.sise'");
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let diagnostics = check_file(Path::new("config.toml"), "[dock\nsize = 40\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].location.map(|(line, _)| line), Some(1));
+    }
+
+    #[test]
+    fn resources_are_checked_next_to_the_file_setting_them() {
+        let dir = std::env::temp_dir().join(format!("otto-check-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let main = dir.join("config.toml");
+        let resource_errors = |main: &Path| {
+            check_config(Some(main))
+                .into_iter()
+                .filter(|diagnostic| {
+                    diagnostic.message.contains("background image")
+                        || diagnostic.message.contains("cursor theme")
+                })
+                .count()
+        };
+
+        // The defaults depend on where Otto runs from
+        std::fs::write(&main, "").unwrap();
+        assert_eq!(resource_errors(&main), 0);
+
+        std::fs::write(dir.join("wall.jpg"), b"").unwrap();
+        std::fs::write(&main, "background_image = \"wall.jpg\"\n").unwrap();
+        assert_eq!(resource_errors(&main), 0);
+
+        std::fs::write(&main, "background_image = \"missing.jpg\"\n").unwrap();
+        assert_eq!(resource_errors(&main), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn locates_keys_in_tables_and_dotted_keys() {
+        let source = "[keyboard_shortcuts]\n\"Logo+Q\" = \"Quit\"\n\n[input]\ndevices.x = 1\n";
+        assert_eq!(
+            locate_key(source, &["keyboard_shortcuts", "Logo+Q"]),
+            Some((2, 1))
+        );
+        assert_eq!(locate_key(source, &["input", "devices", "x"]), Some((5, 1)));
+        assert_eq!(locate_key(source, &["dock"]), None);
+    }
+}
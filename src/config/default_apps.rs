@@ -85,19 +85,25 @@ fn resolve_spec(spec: &str, config: &Config) -> Option<(String, Vec<String>)> {
     Some((cmd, parts))
 }
 
-fn desktop_id_to_command(desktop_id: &str, locales: &[String]) -> Option<(String, Vec<String>)> {
+/// Path of the desktop entry for `desktop_id`, with or without the
+/// `.desktop` suffix
+pub(super) fn find_desktop_entry(desktop_id: &str) -> Option<PathBuf> {
     let normalized = if desktop_id.ends_with(".desktop") {
         desktop_id.to_string()
     } else {
         format!("{desktop_id}.desktop")
     };
 
-    let path = desktop_entry::Iter::new(desktop_entry::default_paths()).find(|path| {
+    desktop_entry::Iter::new(desktop_entry::default_paths()).find(|path| {
         path.file_name()
             .and_then(|name| name.to_str())
             .map(|name| name.eq_ignore_ascii_case(&normalized))
             .unwrap_or(false)
-    })?;
+    })
+}
+
+fn desktop_id_to_command(desktop_id: &str, locales: &[String]) -> Option<(String, Vec<String>)> {
+    let path = find_desktop_entry(desktop_id)?;
 
     let locale_refs: Vec<&str> = locales.iter().map(|s| s.as_str()).collect();
     let entry = DesktopEntry::from_path(path, Some(&locale_refs)).ok()?;
@@ -219,25 +225,31 @@ fn parse_mimeapps(path: &Path, map: &mut HashMap<String, Vec<String>>) {
     }
 }
 
-fn xdg_config_home() -> Option<PathBuf> {
+pub(super) fn xdg_config_home() -> Option<PathBuf> {
     env::var_os("XDG_CONFIG_HOME")
         .map(PathBuf::from)
         .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
 }
 
-fn xdg_data_home() -> Option<PathBuf> {
+pub(crate) fn xdg_data_home() -> Option<PathBuf> {
     env::var_os("XDG_DATA_HOME")
         .map(PathBuf::from)
         .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
 }
 
-fn xdg_config_dirs() -> Vec<PathBuf> {
+pub(super) fn xdg_state_home() -> Option<PathBuf> {
+    env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/state")))
+}
+
+pub(super) fn xdg_config_dirs() -> Vec<PathBuf> {
     env::var("XDG_CONFIG_DIRS")
         .map(|dirs| dirs.split(':').map(PathBuf::from).collect())
         .unwrap_or_else(|_| vec![PathBuf::from("/etc/xdg")])
 }
 
-fn xdg_data_dirs() -> Vec<PathBuf> {
+pub(super) fn xdg_data_dirs() -> Vec<PathBuf> {
     env::var("XDG_DATA_DIRS")
         .map(|dirs| dirs.split(':').map(PathBuf::from).collect())
         .unwrap_or_else(|_| {
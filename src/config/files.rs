@@ -0,0 +1,273 @@
+//! Locating and reading the configuration files
+//!
+//! The main file is the first one found of `$OTTO_CONFIG` (set by
+//! `--config`), `./otto_config.toml`, `$XDG_CONFIG_HOME/otto/config.toml`
+//! and `otto/config.toml` in each of `$XDG_CONFIG_DIRS` (`/etc/xdg` by
+//! default). Any file may list other files in `include = [...]`; those are
+//! merged first, so the including file overrides them.
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    default_apps::{xdg_config_dirs, xdg_config_home},
+    merge_value, ConfigError,
+};
+
+/// Environment variable naming the main configuration file
+pub const CONFIG_ENV: &str = "OTTO_CONFIG";
+
+const LEGACY_CONFIG_FILE: &str = "otto_config.toml";
+const INCLUDE_KEY: &str = "include";
+
+/// Main configuration files in lookup order
+pub fn config_candidates() -> Vec<PathBuf> {
+    if let Some(path) = env::var_os(CONFIG_ENV) {
+        return vec![PathBuf::from(path)];
+    }
+    let mut candidates = vec![PathBuf::from(LEGACY_CONFIG_FILE)];
+    candidates.extend(xdg_config_home().map(|dir| dir.join("otto/config.toml")));
+    candidates.extend(
+        xdg_config_dirs()
+            .into_iter()
+            .map(|dir| dir.join("otto/config.toml")),
+    );
+    candidates
+}
+
+/// The main configuration file, `None` if there is none.
+///
+/// A file passed with `--config` is returned even if it is missing, so
+/// loading it reports the mistake.
+pub fn main_config() -> Option<PathBuf> {
+    if let Some(path) = env::var_os(CONFIG_ENV) {
+        return Some(PathBuf::from(path));
+    }
+    config_candidates().into_iter().find(|path| path.is_file())
+}
+
+/// Backend specific files next to `main`, the first one found is used.
+///
+/// For `otto_config.toml` and the `tty-udev` backend these are
+/// `otto_config.tty-udev.toml` and `otto_config.udev.toml`.
+pub fn backend_override_candidates(main: &Path, backend: &str) -> Vec<PathBuf> {
+    let stem = main
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("otto_config");
+    let names: &[&str] = match backend {
+        "tty-udev" => &["tty-udev", "udev"],
+        "x11" => &["x11", "udev"],
+        other => &[other],
+    };
+    names
+        .iter()
+        .map(|name| main.with_file_name(format!("{stem}.{name}.toml")))
+        .collect()
+}
+
+/// Merges configuration files and their includes over a base value
+#[derive(Debug)]
+pub struct ConfigLoader {
+    value: toml::Value,
+    /// Every file that was read, in merge order
+    files: Vec<PathBuf>,
+    /// Files being read, to detect include cycles
+    stack: Vec<PathBuf>,
+}
+
+impl ConfigLoader {
+    pub fn new(base: toml::Value) -> Self {
+        Self {
+            value: base,
+            files: Vec::new(),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Merges `path` with its includes, returns false if `path` is missing
+    /// and not `required`
+    pub fn merge_file(&mut self, path: &Path, required: bool) -> Result<bool, ConfigError> {
+        let Some(mut value) = read_toml(path)? else {
+            if required {
+                return Err(ConfigError::NotFound {
+                    path: path.display().to_string(),
+                });
+            }
+            return Ok(false);
+        };
+        let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if self.stack.contains(&key) {
+            return Err(ConfigError::IncludeCycle {
+                path: path.display().to_string(),
+            });
+        }
+
+        let includes = take_includes(&mut value, path)?;
+        self.stack.push(key);
+        for include in includes {
+            let result = self.merge_file(&include, true);
+            if result.is_err() {
+                self.stack.pop();
+                return result;
+            }
+        }
+        self.stack.pop();
+
+        merge_value(&mut self.value, value);
+        self.files.push(path.to_path_buf());
+        Ok(true)
+    }
+
+    /// The merged value and the files it was read from
+    pub fn finish(self) -> (toml::Value, Vec<PathBuf>) {
+        (self.value, self.files)
+    }
+}
+
+/// Removes the `include` key of a file and resolves its paths relative to
+/// the file's directory
+fn take_includes(value: &mut toml::Value, path: &Path) -> Result<Vec<PathBuf>, ConfigError> {
+    let Some(include) = value
+        .as_table_mut()
+        .and_then(|table| table.remove(INCLUDE_KEY))
+    else {
+        return Ok(Vec::new());
+    };
+    let invalid = || ConfigError::InvalidInclude {
+        path: path.display().to_string(),
+    };
+    let entries = match include {
+        toml::Value::String(entry) => vec![entry],
+        toml::Value::Array(entries) => entries
+            .into_iter()
+            .map(|entry| entry.as_str().map(str::to_string).ok_or_else(invalid))
+            .collect::<Result<_, _>>()?,
+        _ => return Err(invalid()),
+    };
+    let dir = path.parent().unwrap_or(Path::new(""));
+    Ok(entries
+        .iter()
+        .map(|entry| resolve_path(entry, dir))
+        .collect())
+}
+
+/// Path named in a configuration file, relative to the file's directory
+pub fn resolve_path(path: &str, dir: &Path) -> PathBuf {
+    dir.join(expand_home(path))
+}
+
+/// Expands a leading `~/` to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+pub(super) fn read_toml(path: &Path) -> Result<Option<toml::Value>, ConfigError> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => {
+            return Err(ConfigError::Io {
+                path: path.display().to_string(),
+                source,
+            })
+        }
+    };
+    content
+        .parse::<toml::Value>()
+        .map(Some)
+        .map_err(|source| ConfigError::Parse {
+            path: path.display().to_string(),
+            source,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("otto-config-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn base() -> toml::Value {
+        toml::toml! {
+            cursor_size = 24
+            [dock]
+            size = 64
+            genie_scale = 0.5
+        }
+        .into()
+    }
+
+    #[test]
+    fn including_file_overrides_its_includes() {
+        let dir = temp_dir("include");
+        std::fs::create_dir_all(dir.join("shared")).unwrap();
+        std::fs::write(
+            dir.join("shared/base.toml"),
+            "cursor_size = 32\n[dock]\nsize = 40\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("config.toml"),
+            "include = [\"shared/base.toml\"]\ncursor_size = 48\n",
+        )
+        .unwrap();
+
+        let mut loader = ConfigLoader::new(base());
+        assert!(loader.merge_file(&dir.join("config.toml"), true).unwrap());
+        let (value, files) = loader.finish();
+        assert_eq!(value["cursor_size"].as_integer(), Some(48));
+        assert_eq!(value["dock"]["size"].as_integer(), Some(40));
+        assert_eq!(value["dock"]["genie_scale"].as_float(), Some(0.5));
+        assert!(value.get(INCLUDE_KEY).is_none());
+        assert_eq!(files.len(), 2);
+    }
+
+    #[test]
+    fn include_cycles_are_rejected() {
+        let dir = temp_dir("cycle");
+        std::fs::write(dir.join("a.toml"), "include = \"b.toml\"\n").unwrap();
+        std::fs::write(dir.join("b.toml"), "include = \"a.toml\"\n").unwrap();
+
+        let mut loader = ConfigLoader::new(base());
+        assert!(matches!(
+            loader.merge_file(&dir.join("a.toml"), true),
+            Err(ConfigError::IncludeCycle { .. })
+        ));
+    }
+
+    #[test]
+    fn missing_files_are_errors_only_when_required() {
+        let dir = temp_dir("missing");
+        std::fs::write(dir.join("config.toml"), "include = [\"nope.toml\"]\n").unwrap();
+
+        let mut loader = ConfigLoader::new(base());
+        assert!(!loader.merge_file(&dir.join("absent.toml"), false).unwrap());
+        assert!(matches!(
+            loader.merge_file(&dir.join("config.toml"), true),
+            Err(ConfigError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn backend_overrides_follow_the_main_file_name() {
+        let candidates =
+            backend_override_candidates(Path::new("/etc/xdg/otto/config.toml"), "tty-udev");
+        assert_eq!(
+            candidates,
+            vec![
+                PathBuf::from("/etc/xdg/otto/config.tty-udev.toml"),
+                PathBuf::from("/etc/xdg/otto/config.udev.toml"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::{BTreeMap, HashMap};
+
+use thiserror::Error;
+use tracing::info;
+
+use super::shortcuts::{
+    parse_action, BuiltinAction, ShortcutAction, ShortcutActionConfig, ShortcutError, ShortcutIssue,
+};
+
+/// Raw mapping loaded from the `[gestures]` configuration table.
+pub type GestureMap = BTreeMap<String, ShortcutActionConfig>;
+
+/// Builtin name that removes a default gesture binding.
+const UNBOUND_ACTION: &str = "None";
+
+/// Direction of a touchpad gesture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GestureDirection {
+    SwipeUp,
+    SwipeDown,
+    SwipeLeft,
+    SwipeRight,
+    PinchIn,
+    PinchOut,
+    Hold,
+}
+
+impl GestureDirection {
+    /// The direction that undoes this one, `None` for holds.
+    pub fn opposite(self) -> Option<Self> {
+        Some(match self {
+            Self::SwipeUp => Self::SwipeDown,
+            Self::SwipeDown => Self::SwipeUp,
+            Self::SwipeLeft => Self::SwipeRight,
+            Self::SwipeRight => Self::SwipeLeft,
+            Self::PinchIn => Self::PinchOut,
+            Self::PinchOut => Self::PinchIn,
+            Self::Hold => return None,
+        })
+    }
+
+    /// Projects a swipe delta onto this direction; positive values move along it.
+    pub fn project_swipe(self, dx: f64, dy: f64) -> f64 {
+        match self {
+            Self::SwipeUp => -dy,
+            Self::SwipeDown => dy,
+            Self::SwipeLeft => -dx,
+            Self::SwipeRight => dx,
+            _ => 0.0,
+        }
+    }
+
+    /// Projects a pinch scale change onto this direction.
+    pub fn project_pinch(self, scale_delta: f64) -> f64 {
+        match self {
+            Self::PinchOut => scale_delta,
+            Self::PinchIn => -scale_delta,
+            _ => 0.0,
+        }
+    }
+
+    fn min_fingers(self) -> u32 {
+        match self {
+            Self::SwipeUp | Self::SwipeDown | Self::SwipeLeft | Self::SwipeRight => 3,
+            Self::PinchIn | Self::PinchOut => 2,
+            Self::Hold => 1,
+        }
+    }
+
+    fn canonical_name(self) -> &'static str {
+        match self {
+            Self::SwipeUp => "SwipeUp",
+            Self::SwipeDown => "SwipeDown",
+            Self::SwipeLeft => "SwipeLeft",
+            Self::SwipeRight => "SwipeRight",
+            Self::PinchIn => "PinchIn",
+            Self::PinchOut => "PinchOut",
+            Self::Hold => "Hold",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GestureTrigger {
+    pub fingers: u32,
+    pub direction: GestureDirection,
+}
+
+impl GestureTrigger {
+    pub fn canonical_id(&self) -> String {
+        format!("{}+{}", self.fingers, self.direction.canonical_name())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GestureBinding {
+    pub trigger: GestureTrigger,
+    pub action: ShortcutAction,
+    #[allow(dead_code)]
+    pub trigger_repr: String,
+}
+
+/// Actions that follow the fingers when bound to a swipe or pinch.
+///
+/// Every other action runs once, as soon as the gesture direction is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContinuousAction {
+    Expose,
+    /// Exposé of the windows of every workspace
+    ExposeAllWorkspaces,
+    ShowDesktop,
+    WorkspaceNext,
+    WorkspacePrev,
+}
+
+impl ContinuousAction {
+    pub fn from_action(action: &ShortcutAction) -> Option<Self> {
+        match action {
+            ShortcutAction::Builtin(BuiltinAction::ExposeShowAll) => Some(Self::Expose),
+            ShortcutAction::Builtin(BuiltinAction::ExposeShowAllWorkspaces) => {
+                Some(Self::ExposeAllWorkspaces)
+            }
+            ShortcutAction::Builtin(BuiltinAction::ExposeShowDesktop) => Some(Self::ShowDesktop),
+            ShortcutAction::Builtin(BuiltinAction::WorkspaceNext) => Some(Self::WorkspaceNext),
+            ShortcutAction::Builtin(BuiltinAction::WorkspacePrev) => Some(Self::WorkspacePrev),
+            _ => None,
+        }
+    }
+
+    /// Whether the action shows a view that the opposite gesture hides again
+    pub fn is_toggle(self) -> bool {
+        matches!(self, Self::Expose | Self::ExposeAllWorkspaces | Self::ShowDesktop)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GestureError {
+    #[error("unparsable gesture trigger '{0}'")]
+    InvalidTrigger(String),
+    #[error("unknown gesture '{0}'")]
+    UnknownGesture(String),
+    #[error("'{gesture}' needs at least {min} fingers")]
+    TooFewFingers { gesture: String, min: u32 },
+    #[error(transparent)]
+    Action(#[from] ShortcutError),
+}
+
+pub fn build_gesture_bindings(
+    map: &GestureMap,
+    issues: &mut Vec<ShortcutIssue>,
+) -> Vec<GestureBinding> {
+    let mut bindings: Vec<GestureBinding> = Vec::new();
+    let mut seen: HashMap<GestureTrigger, String> = HashMap::new();
+
+    for (trigger_str, action_cfg) in map {
+        let trigger = match parse_gesture_trigger(trigger_str) {
+            Ok(trigger) => trigger,
+            Err(err) => {
+                issues.push(ShortcutIssue::skipped("gestures", trigger_str, err));
+                continue;
+            }
+        };
+
+        if let Some(existing) = seen.insert(trigger, trigger_str.clone()) {
+            issues.push(ShortcutIssue::duplicate(
+                "gestures",
+                trigger_str,
+                format!("same gesture as '{existing}', the new entry replaces the previous one"),
+            ));
+            bindings.retain(|binding| binding.trigger != trigger);
+        }
+
+        if matches!(action_cfg, ShortcutActionConfig::BuiltinName(name) if name == UNBOUND_ACTION)
+        {
+            continue;
+        }
+
+        match parse_action(action_cfg) {
+            Ok(action) => bindings.push(GestureBinding {
+                trigger,
+                action,
+                trigger_repr: trigger_str.clone(),
+            }),
+            Err(err) => issues.push(ShortcutIssue::skipped("gestures", trigger_str, err)),
+        }
+    }
+
+    info!("loaded {} gesture bindings", bindings.len());
+
+    bindings
+}
+
+/// Parses `"<fingers>+<Gesture>"`, e.g. `"3+SwipeUp"` or `"4+PinchOut"`.
+pub fn parse_gesture_trigger(trigger: &str) -> Result<GestureTrigger, GestureError> {
+    let (fingers, gesture) = trigger
+        .split_once('+')
+        .ok_or_else(|| GestureError::InvalidTrigger(trigger.to_string()))?;
+    let fingers: u32 = fingers
+        .trim()
+        .parse()
+        .map_err(|_| GestureError::InvalidTrigger(trigger.to_string()))?;
+
+    let gesture = gesture.trim();
+    let direction = match gesture.to_ascii_lowercase().as_str() {
+        "swipeup" => GestureDirection::SwipeUp,
+        "swipedown" => GestureDirection::SwipeDown,
+        "swipeleft" => GestureDirection::SwipeLeft,
+        "swiperight" => GestureDirection::SwipeRight,
+        "pinchin" => GestureDirection::PinchIn,
+        "pinchout" => GestureDirection::PinchOut,
+        "hold" => GestureDirection::Hold,
+        _ => return Err(GestureError::UnknownGesture(gesture.to_string())),
+    };
+
+    if fingers < direction.min_fingers() {
+        return Err(GestureError::TooFewFingers {
+            gesture: gesture.to_string(),
+            min: direction.min_fingers(),
+        });
+    }
+
+    Ok(GestureTrigger { fingers, direction })
+}
+
+/// Default bindings, matching the built-in gestures of earlier releases.
+pub fn default_gesture_map() -> GestureMap {
+    let builtin = |name: &str| ShortcutActionConfig::BuiltinName(name.to_string());
+    GestureMap::from([
+        ("3+SwipeUp".to_string(), builtin("ExposeShowAll")),
+        ("4+SwipeUp".to_string(), builtin("ExposeShowAllWorkspaces")),
+        ("3+SwipeLeft".to_string(), builtin("WorkspaceNext")),
+        ("3+SwipeRight".to_string(), builtin("WorkspacePrev")),
+        ("4+PinchOut".to_string(), builtin("ExposeShowDesktop")),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_gesture_triggers() {
+        let trigger = parse_gesture_trigger("4+PinchOut").unwrap();
+        assert_eq!(trigger.fingers, 4);
+        assert_eq!(trigger.direction, GestureDirection::PinchOut);
+
+        let trigger = parse_gesture_trigger("3 + swipeleft").unwrap();
+        assert_eq!(trigger.direction, GestureDirection::SwipeLeft);
+        assert_eq!(trigger.canonical_id(), "3+SwipeLeft");
+    }
+
+    #[test]
+    fn rejects_invalid_gesture_triggers() {
+        assert!(matches!(
+            parse_gesture_trigger("SwipeUp"),
+            Err(GestureError::InvalidTrigger(_))
+        ));
+        assert!(matches!(
+            parse_gesture_trigger("3+Twirl"),
+            Err(GestureError::UnknownGesture(_))
+        ));
+        assert!(matches!(
+            parse_gesture_trigger("2+SwipeUp"),
+            Err(GestureError::TooFewFingers { min: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn none_unbinds_default_gesture() {
+        let mut map = default_gesture_map();
+        map.insert(
+            "3+SwipeUp".into(),
+            ShortcutActionConfig::BuiltinName(UNBOUND_ACTION.into()),
+        );
+        map.insert(
+            "3+Hold".into(),
+            ShortcutActionConfig::BuiltinName("ExposeShowAll".into()),
+        );
+
+        let bindings = build_gesture_bindings(&map, &mut Vec::new());
+        assert!(!bindings
+            .iter()
+            .any(|binding| binding.trigger.canonical_id() == "3+SwipeUp"));
+        assert!(bindings
+            .iter()
+            .any(|binding| binding.trigger.canonical_id() == "3+Hold"));
+    }
+
+    #[test]
+    fn continuous_actions_follow_projection() {
+        assert_eq!(GestureDirection::SwipeUp.project_swipe(0.0, -10.0), 10.0);
+        assert_eq!(GestureDirection::SwipeLeft.project_swipe(10.0, 0.0), -10.0);
+        assert_eq!(GestureDirection::PinchIn.project_pinch(-0.2), 0.2);
+        assert!(ContinuousAction::from_action(&ShortcutAction::Builtin(
+            BuiltinAction::ExposeShowAll
+        ))
+        .is_some_and(ContinuousAction::is_toggle));
+        assert!(ContinuousAction::from_action(&ShortcutAction::Builtin(
+            BuiltinAction::ExposeShowAllWorkspaces
+        ))
+        .is_some_and(ContinuousAction::is_toggle));
+    }
+}
@@ -0,0 +1,206 @@
+//! Commands run on compositor events, configured in `[hooks]`
+//!
+//! ```toml
+//! [hooks]
+//! focus_changed = "notify-send \"$OTTO_APP_ID\""
+//! output_connected = [{ cmd = "kanshictl", args = ["reload"] }]
+//! ```
+//!
+//! A hook is a shell command line or a program with arguments, each event
+//! takes one hook or a list. Event details are passed in `OTTO_*`
+//! environment variables.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::shortcuts::RunCommandConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Minimum time between two runs of the `focus_changed` or
+    /// `workspace_changed` hooks, in milliseconds. Changes arriving faster
+    /// are coalesced and the hooks run once with the details of the latest
+    /// one; the other events run their hooks every time.
+    pub min_interval_ms: u64,
+    #[serde(deserialize_with = "one_or_many")]
+    pub window_opened: Vec<HookCommand>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub window_closed: Vec<HookCommand>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub focus_changed: Vec<HookCommand>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub workspace_changed: Vec<HookCommand>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub output_connected: Vec<HookCommand>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub output_disconnected: Vec<HookCommand>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub session_locked: Vec<HookCommand>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub session_unlocked: Vec<HookCommand>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub theme_changed: Vec<HookCommand>,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            min_interval_ms: 200,
+            window_opened: Vec::new(),
+            window_closed: Vec::new(),
+            focus_changed: Vec::new(),
+            workspace_changed: Vec::new(),
+            output_connected: Vec::new(),
+            output_disconnected: Vec::new(),
+            session_locked: Vec::new(),
+            session_unlocked: Vec::new(),
+            theme_changed: Vec::new(),
+        }
+    }
+}
+
+impl HooksConfig {
+    pub fn commands(&self, event: HookEvent) -> &[HookCommand] {
+        match event {
+            HookEvent::WindowOpened => &self.window_opened,
+            HookEvent::WindowClosed => &self.window_closed,
+            HookEvent::FocusChanged => &self.focus_changed,
+            HookEvent::WorkspaceChanged => &self.workspace_changed,
+            HookEvent::OutputConnected => &self.output_connected,
+            HookEvent::OutputDisconnected => &self.output_disconnected,
+            HookEvent::SessionLocked => &self.session_locked,
+            HookEvent::SessionUnlocked => &self.session_unlocked,
+            HookEvent::ThemeChanged => &self.theme_changed,
+        }
+    }
+
+    /// Whether a hook watches windows, workspaces or outputs, which are
+    /// compared after every loop iteration
+    pub fn watches_desktop(&self) -> bool {
+        [
+            HookEvent::WindowOpened,
+            HookEvent::WindowClosed,
+            HookEvent::FocusChanged,
+            HookEvent::WorkspaceChanged,
+            HookEvent::OutputConnected,
+            HookEvent::OutputDisconnected,
+        ]
+        .into_iter()
+        .any(|event| !self.commands(event).is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookEvent {
+    WindowOpened,
+    WindowClosed,
+    FocusChanged,
+    WorkspaceChanged,
+    OutputConnected,
+    OutputDisconnected,
+    SessionLocked,
+    SessionUnlocked,
+    ThemeChanged,
+}
+
+impl HookEvent {
+    /// Name of the event in `[hooks]` and `$OTTO_EVENT`
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::WindowOpened => "window_opened",
+            HookEvent::WindowClosed => "window_closed",
+            HookEvent::FocusChanged => "focus_changed",
+            HookEvent::WorkspaceChanged => "workspace_changed",
+            HookEvent::OutputConnected => "output_connected",
+            HookEvent::OutputDisconnected => "output_disconnected",
+            HookEvent::SessionLocked => "session_locked",
+            HookEvent::SessionUnlocked => "session_unlocked",
+            HookEvent::ThemeChanged => "theme_changed",
+        }
+    }
+
+    /// Whether the hooks run at most once per `min_interval_ms`. Events
+    /// naming one window each are never coalesced.
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, HookEvent::FocusChanged | HookEvent::WorkspaceChanged)
+    }
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum HookCommand {
+    /// `"notify-send hello"`, run with `sh -c`
+    Shell(String),
+    /// `{ cmd = "notify-send", args = ["hello"] }`
+    Program(RunCommandConfig),
+}
+
+impl HookCommand {
+    /// Program and arguments to run
+    pub fn command_line(&self) -> (String, Vec<String>) {
+        match self {
+            HookCommand::Shell(line) => ("sh".to_string(), vec!["-c".to_string(), line.clone()]),
+            HookCommand::Program(run) => (run.cmd.clone(), run.args.clone()),
+        }
+    }
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<HookCommand>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(HookCommand),
+        Many(Vec<HookCommand>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(command) => vec![command],
+        OneOrMany::Many(commands) => commands,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hooks_take_one_command_or_a_list() {
+        let hooks: HooksConfig = toml::from_str(
+            r#"
+            focus_changed = "echo focus"
+            window_opened = ["echo one", { cmd = "notify-send", args = ["two"] }]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            hooks.commands(HookEvent::FocusChanged),
+            [HookCommand::Shell("echo focus".to_string())]
+        );
+        assert_eq!(hooks.window_opened.len(), 2);
+        assert_eq!(
+            hooks.window_opened[1].command_line(),
+            ("notify-send".to_string(), vec!["two".to_string()])
+        );
+        assert_eq!(hooks.min_interval_ms, 200);
+        assert!(hooks.watches_desktop());
+        assert!(!HooksConfig::default().watches_desktop());
+    }
+
+    #[test]
+    fn window_events_are_not_rate_limited() {
+        assert!(HookEvent::FocusChanged.is_rate_limited());
+        assert!(HookEvent::WorkspaceChanged.is_rate_limited());
+        assert!(!HookEvent::WindowOpened.is_rate_limited());
+        assert!(!HookEvent::WindowClosed.is_rate_limited());
+    }
+}
@@ -1,16 +1,33 @@
 use std::collections::BTreeMap;
-use std::sync::OnceLock;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
 
 use serde::{Deserialize, Serialize};
 
+pub mod check;
 pub mod default_apps;
+pub mod files;
+pub mod gestures;
+pub mod hooks;
 pub mod shortcuts;
-
-use shortcuts::{build_bindings, ShortcutBinding, ShortcutMap};
+pub mod state_file;
+pub mod wallpaper;
+pub mod workspaces;
+
+use gestures::{build_gesture_bindings, GestureBinding, GestureMap};
+use hooks::HooksConfig;
+use shortcuts::{
+    build_bindings, build_modes, build_pointer_bindings, build_release_bindings, build_window_drag,
+    parse_trigger, retain_known_modes, KeyboardModeMap, PointerBinding, ShortcutBinding,
+    ShortcutIssue, ShortcutMap, ShortcutMode, ShortcutTrigger, WindowDragTriggers,
+};
+use thiserror::Error;
 use toml::map::Entry;
 use tracing::warn;
+use wallpaper::WallpaperConfig;
+use workspaces::WorkspacesConfig;
 
-use crate::theme::ThemeScheme;
+use crate::theme::{file::ThemeFile, schedule::ThemeScheduleConfig, ThemeScheme};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -32,18 +49,92 @@ pub struct Config {
     pub keyboard_repeat_delay: i32,
     pub keyboard_repeat_rate: i32,
     pub theme_scheme: ThemeScheme,
+    /// When `theme_scheme = "Auto"` switches between light and dark
+    #[serde(default)]
+    pub theme_schedule: ThemeScheduleConfig,
+    /// Theme file overriding colors, corner radii, blur and fonts,
+    /// relative to the directory of the main configuration file
+    pub theme_file: Option<String>,
     pub gtk_theme: Option<String>,
     pub background_image: String,
+    /// Wallpapers per workspace and output, slideshows and colors; shows
+    /// `background_image` when empty
+    #[serde(default)]
+    pub wallpaper: WallpaperConfig,
+    /// Workspaces at startup, their names, and removing empty ones
+    #[serde(default)]
+    pub workspaces: WorkspacesConfig,
     pub locales: Vec<String>,
     pub use_10bit_color: bool,
     #[serde(default = "shortcuts::default_shortcut_map")]
     pub keyboard_shortcuts: ShortcutMap,
+    #[serde(default)]
+    pub keyboard_modes: KeyboardModeMap,
+    #[serde(default)]
+    pub keyboard_chords: KeyboardChordsConfig,
+    #[serde(default = "gestures::default_gesture_map")]
+    pub gestures: GestureMap,
+    #[serde(default)]
+    pub global_shortcuts: GlobalShortcutsConfig,
+    #[serde(default)]
+    pub shortcut_inhibit: ShortcutInhibitConfig,
+    #[serde(default)]
+    pub hooks: HooksConfig,
     #[serde(skip)]
     #[serde(default)]
     shortcut_bindings: Vec<ShortcutBinding>,
+    #[serde(skip)]
+    #[serde(default)]
+    shortcut_modes: BTreeMap<String, ShortcutMode>,
+    #[serde(skip)]
+    #[serde(default)]
+    pointer_bindings: Vec<PointerBinding>,
+    #[serde(skip)]
+    #[serde(default)]
+    release_bindings: Vec<ShortcutBinding>,
+    #[serde(skip)]
+    #[serde(default)]
+    gesture_bindings: Vec<GestureBinding>,
+    #[serde(skip)]
+    #[serde(default)]
+    inhibit_escape: Option<ShortcutTrigger>,
+    #[serde(skip)]
+    #[serde(default)]
+    window_drag: Option<WindowDragTriggers>,
+    #[serde(skip)]
+    #[serde(default)]
+    theme: ThemeFile,
+    /// Files the configuration was read from, includes too
+    #[serde(skip)]
+    #[serde(default)]
+    files: Vec<PathBuf>,
 }
 
-static CONFIG: OnceLock<Config> = OnceLock::new();
+static CONFIG: OnceLock<RwLock<Arc<Config>>> = OnceLock::new();
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read {path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("failed to parse {path}: {source}")]
+    Parse {
+        path: String,
+        source: toml::de::Error,
+    },
+    #[error("{path} does not exist")]
+    NotFound { path: String },
+    #[error("{path} includes itself")]
+    IncludeCycle { path: String },
+    #[error("include in {path} must be a path or a list of paths")]
+    InvalidInclude { path: String },
+    #[error("invalid theme {path}: {message}")]
+    Theme { path: String, message: String },
+    #[error("invalid configuration: {0}")]
+    Invalid(#[from] toml::de::Error),
+}
 
 impl Default for Config {
     fn default() -> Self {
@@ -61,12 +152,30 @@ impl Default for Config {
             keyboard_repeat_delay: 300,
             keyboard_repeat_rate: 30,
             theme_scheme: ThemeScheme::Light,
+            theme_schedule: ThemeScheduleConfig::default(),
+            theme_file: None,
             gtk_theme: None,
             background_image: "./resources/background.jpg".to_string(),
+            wallpaper: WallpaperConfig::default(),
+            workspaces: WorkspacesConfig::default(),
             locales: vec!["en".to_string()],
             use_10bit_color: false,
             keyboard_shortcuts: shortcuts::default_shortcut_map(),
+            keyboard_modes: KeyboardModeMap::new(),
+            keyboard_chords: KeyboardChordsConfig::default(),
+            gestures: gestures::default_gesture_map(),
+            global_shortcuts: GlobalShortcutsConfig::default(),
+            shortcut_inhibit: ShortcutInhibitConfig::default(),
+            hooks: HooksConfig::default(),
             shortcut_bindings: Vec::new(),
+            shortcut_modes: BTreeMap::new(),
+            pointer_bindings: Vec::new(),
+            release_bindings: Vec::new(),
+            gesture_bindings: Vec::new(),
+            inhibit_escape: None,
+            window_drag: None,
+            theme: ThemeFile::default(),
+            files: Vec::new(),
         };
         config.rebuild_shortcut_bindings();
         config
@@ -76,60 +185,186 @@ pub const WINIT_DISPLAY_ID: &str = "winit";
 
 impl Config {
     pub fn with<R>(f: impl FnOnce(&Config) -> R) -> R {
-        let config = CONFIG.get_or_init(Config::init);
-        f(config)
+        let config = Self::current();
+        f(&config)
+    }
+
+    /// Snapshot of the active configuration; it stays valid when a reload
+    /// replaces the configuration.
+    pub fn current() -> Arc<Config> {
+        Self::cell().read().unwrap().clone()
+    }
+
+    /// Makes `config` the active configuration and returns the previous one
+    pub fn replace(config: Config) -> Arc<Config> {
+        std::mem::replace(&mut *Self::cell().write().unwrap(), Arc::new(config))
     }
+
+    fn cell() -> &'static RwLock<Arc<Config>> {
+        CONFIG.get_or_init(|| RwLock::new(Arc::new(Config::init())))
+    }
+
     fn init() -> Self {
-        let mut merged =
+        let config = Self::load().unwrap_or_else(|err| {
+            warn!("Falling back to default config: {err}");
+            Self::default()
+        });
+
+        // Environment variables for Wayland session
+        std::env::set_var("XDG_SESSION_TYPE", "wayland");
+        std::env::set_var("XDG_CURRENT_DESKTOP", "otto");
+
+        tracing::info!("Config initialized: {:#?}", config.theme_scheme);
+        config
+    }
+
+    /// Files whose creation or change affects the configuration, whether
+    /// they exist or not
+    pub fn sources(&self) -> Vec<PathBuf> {
+        let mut sources = files::config_candidates();
+        if let Ok(backend) = std::env::var("SCREEN_COMPOSER_BACKEND") {
+            let main = files::main_config().unwrap_or_else(|| sources[0].clone());
+            sources.extend(files::backend_override_candidates(&main, &backend));
+        }
+        for file in &self.files {
+            if !sources.contains(file) {
+                sources.push(file.clone());
+            }
+        }
+        sources
+    }
+
+    /// Reads the configuration files and merges them over the defaults
+    pub fn load() -> Result<Self, ConfigError> {
+        let defaults =
             toml::Value::try_from(Self::default()).expect("default config is always valid toml");
+        let mut loader = files::ConfigLoader::new(defaults);
 
-        if let Ok(content) = std::fs::read_to_string("otto_config.toml") {
-            match content.parse::<toml::Value>() {
-                Ok(value) => merge_value(&mut merged, value),
-                Err(err) => warn!("Failed to parse otto_config.toml: {err}"),
+        let main = files::main_config();
+        if let Some(main) = &main {
+            let required = std::env::var_os(files::CONFIG_ENV).is_some();
+            if loader.merge_file(main, required)? {
+                tracing::info!("Loaded config: {}", main.display());
             }
         }
 
         if let Ok(backend) = std::env::var("SCREEN_COMPOSER_BACKEND") {
-            for candidate in backend_override_candidates(&backend) {
-                println!("Trying to load backend override config: {}", &candidate);
-                if let Ok(content) = std::fs::read_to_string(&candidate) {
-                    match content.parse::<toml::Value>() {
-                        Ok(value) => {
-                            merge_value(&mut merged, value);
-                            break;
-                        }
-                        Err(err) => {
-                            warn!("Failed to parse {candidate}: {err}");
-                        }
-                    }
+            let main = main
+                .clone()
+                .unwrap_or_else(|| PathBuf::from("otto_config.toml"));
+            for candidate in files::backend_override_candidates(&main, &backend) {
+                if loader.merge_file(&candidate, false)? {
+                    tracing::info!("Loaded backend override config: {}", candidate.display());
+                    break;
                 }
             }
         }
 
-        let mut config: Config = merged.try_into().unwrap_or_else(|err| {
-            warn!("Falling back to default config due to invalid overrides: {err}");
-            Self::default()
-        });
-
+        let (merged, mut files) = loader.finish();
+        let mut config: Config = merged.try_into()?;
+        let config_dir = main
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new(""));
+        if let Some(theme_path) = config.load_theme(config_dir)? {
+            files.push(theme_path);
+        }
+        config.files = files;
         config.rebuild_shortcut_bindings();
+        Ok(config)
+    }
 
-        // Environment variables for Wayland session
-        std::env::set_var("XDG_SESSION_TYPE", "wayland");
-        std::env::set_var("XDG_CURRENT_DESKTOP", "otto");
+    /// Reads `theme_file`, returns its path if one is set
+    pub(crate) fn load_theme(&mut self, config_dir: &Path) -> Result<Option<PathBuf>, ConfigError> {
+        let Some(theme_file) = &self.theme_file else {
+            return Ok(None);
+        };
+        let path = files::resolve_path(theme_file, config_dir);
+        self.theme = ThemeFile::load(&path)?;
+        Ok(Some(path))
+    }
 
-        tracing::info!("Config initialized: {:#?}", config.theme_scheme);
-        config
+    /// The theme file contents, empty if there is none
+    pub fn theme(&self) -> &ThemeFile {
+        &self.theme
+    }
+
+    pub(crate) fn rebuild_shortcut_bindings(&mut self) {
+        for issue in self.build_shortcut_bindings() {
+            if issue.duplicate {
+                warn!("Duplicate shortcut {issue}");
+            } else {
+                warn!("Skipping shortcut {issue}");
+            }
+        }
     }
 
-    fn rebuild_shortcut_bindings(&mut self) {
-        self.shortcut_bindings = build_bindings(&self.keyboard_shortcuts);
+    /// Parses the shortcut, gesture and drag tables, returns the entries
+    /// that were skipped or replaced
+    pub fn build_shortcut_bindings(&mut self) -> Vec<ShortcutIssue> {
+        let mut issues = Vec::new();
+        let section = "keyboard_shortcuts";
+        self.shortcut_modes = build_modes(&self.keyboard_modes, &mut issues);
+        self.shortcut_bindings = build_bindings(&self.keyboard_shortcuts, &mut issues);
+        retain_known_modes(
+            &mut self.shortcut_bindings,
+            &self.keyboard_modes,
+            section,
+            &mut issues,
+        );
+        self.pointer_bindings = build_pointer_bindings(&self.keyboard_shortcuts, &mut issues);
+        self.release_bindings = build_release_bindings(&self.keyboard_shortcuts, &mut issues);
+        retain_known_modes(
+            &mut self.release_bindings,
+            &self.keyboard_modes,
+            section,
+            &mut issues,
+        );
+        self.gesture_bindings = build_gesture_bindings(&self.gestures, &mut issues);
+        self.window_drag = build_window_drag(&self.input.window_drag_modifier, &mut issues);
+        self.inhibit_escape = match parse_trigger(&self.shortcut_inhibit.escape) {
+            Ok(trigger) => Some(trigger),
+            Err(err) => {
+                issues.push(ShortcutIssue::skipped(
+                    "shortcut_inhibit.escape",
+                    &self.shortcut_inhibit.escape,
+                    err,
+                ));
+                None
+            }
+        };
+        issues
     }
 
     pub fn shortcut_bindings(&self) -> &[ShortcutBinding] {
         &self.shortcut_bindings
     }
 
+    pub fn pointer_bindings(&self) -> &[PointerBinding] {
+        &self.pointer_bindings
+    }
+
+    pub fn release_bindings(&self) -> &[ShortcutBinding] {
+        &self.release_bindings
+    }
+
+    pub fn shortcut_mode(&self, name: &str) -> Option<&ShortcutMode> {
+        self.shortcut_modes.get(name)
+    }
+
+    pub fn gesture_bindings(&self) -> &[GestureBinding] {
+        &self.gesture_bindings
+    }
+
+    pub fn window_drag(&self) -> Option<&WindowDragTriggers> {
+        self.window_drag.as_ref()
+    }
+
+    /// Trigger that reaches the compositor even while shortcuts are inhibited
+    pub fn inhibit_escape(&self) -> Option<&ShortcutTrigger> {
+        self.inhibit_escape.as_ref()
+    }
+
     pub fn resolve_display_profile(
         &self,
         name: &str,
@@ -139,6 +374,60 @@ impl Config {
     }
 }
 
+/// Sections that differ between two configurations, applied on reload
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigChanges {
+    pub shortcuts: bool,
+    /// XKB layout and key repeat
+    pub keyboard: bool,
+    /// Libinput options of touchpads, mice and other devices
+    pub input_devices: bool,
+    pub dock: bool,
+    pub theme: bool,
+    pub background: bool,
+    pub cursor: bool,
+    pub displays: bool,
+}
+
+impl ConfigChanges {
+    pub fn between(old: &Config, new: &Config) -> Self {
+        Self {
+            shortcuts: changed(&old.keyboard_shortcuts, &new.keyboard_shortcuts)
+                || changed(&old.keyboard_modes, &new.keyboard_modes)
+                || changed(&old.keyboard_chords, &new.keyboard_chords)
+                || changed(&old.gestures, &new.gestures)
+                || changed(&old.global_shortcuts, &new.global_shortcuts)
+                || changed(&old.shortcut_inhibit, &new.shortcut_inhibit),
+            keyboard: old.input.xkb_layout != new.input.xkb_layout
+                || old.input.xkb_variant != new.input.xkb_variant
+                || old.input.xkb_options != new.input.xkb_options
+                || changed(&old.input.devices, &new.input.devices)
+                || old.keyboard_repeat_delay != new.keyboard_repeat_delay
+                || old.keyboard_repeat_rate != new.keyboard_repeat_rate,
+            input_devices: changed(&old.input, &new.input),
+            dock: changed(&old.dock, &new.dock),
+            theme: changed(&old.theme_scheme, &new.theme_scheme)
+                || changed(&old.theme_schedule, &new.theme_schedule)
+                || old.gtk_theme != new.gtk_theme
+                || old.font_family != new.font_family
+                || old.theme_file != new.theme_file
+                || old.theme != new.theme,
+            background: old.background_image != new.background_image
+                || old.wallpaper != new.wallpaper,
+            cursor: old.cursor_theme != new.cursor_theme || old.cursor_size != new.cursor_size,
+            displays: changed(&old.displays, &new.displays),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn changed<T: Serialize>(old: &T, new: &T) -> bool {
+    toml::Value::try_from(old).ok() != toml::Value::try_from(new).ok()
+}
+
 fn merge_value(base: &mut toml::Value, overrides: toml::Value) {
     match (base, overrides) {
         (toml::Value::Table(base_map), toml::Value::Table(override_map)) => {
@@ -157,21 +446,6 @@ fn merge_value(base: &mut toml::Value, overrides: toml::Value) {
     }
 }
 
-fn backend_override_candidates(backend: &str) -> Vec<String> {
-    match backend {
-        "winit" => vec!["otto_config.winit.toml".into()],
-        "tty-udev" => vec![
-            "otto_config.tty-udev.toml".into(),
-            "otto_config.udev.toml".into(),
-        ],
-        "x11" => vec![
-            "otto_config.x11.toml".into(),
-            "otto_config.udev.toml".into(),
-        ],
-        other => vec![format!("otto_config.{other}.toml")],
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DockConfig {
     #[serde(default = "default_dock_size")]
@@ -270,6 +544,15 @@ pub struct InputConfig {
     pub xkb_variant: Option<String>,
     #[serde(default)]
     pub xkb_options: Vec<String>,
+    /// Per-device overrides, applied in order on top of the options above
+    #[serde(default)]
+    pub devices: Vec<InputDeviceProfile>,
+    #[serde(default)]
+    pub scroll: ScrollConfig,
+    /// Held while dragging a window with the left button to move it, or
+    /// with the right button to resize it. `"None"` disables it.
+    #[serde(default = "default_window_drag_modifier")]
+    pub window_drag_modifier: String,
 }
 
 /// Touchpad click method configuration
@@ -303,10 +586,236 @@ impl Default for InputConfig {
             xkb_layout: None,
             xkb_variant: None,
             xkb_options: Vec::new(),
+            devices: Vec::new(),
+            scroll: ScrollConfig::default(),
+            window_drag_modifier: default_window_drag_modifier(),
+        }
+    }
+}
+
+impl InputConfig {
+    /// Keyboard layout shared by every keyboard without a device override
+    pub fn xkb_settings(&self) -> XkbSettings {
+        XkbSettings {
+            layout: self.xkb_layout.clone().unwrap_or_default(),
+            variant: self.xkb_variant.clone().unwrap_or_default(),
+            options: self.xkb_options.clone(),
+        }
+    }
+
+    /// Resolves the settings for a device by merging every matching
+    /// `[[input.devices]]` entry; later entries win.
+    pub fn device_settings(&self, descriptor: &InputDeviceDescriptor) -> InputDeviceSettings {
+        self.devices
+            .iter()
+            .filter(|profile| profile.matcher.matches(descriptor))
+            .fold(InputDeviceSettings::default(), |mut settings, profile| {
+                settings.merge(&profile.settings);
+                settings
+            })
+    }
+
+    /// Keyboard layout to use while typing on the given device
+    pub fn xkb_settings_for(&self, descriptor: &InputDeviceDescriptor) -> XkbSettings {
+        let settings = self.device_settings(descriptor);
+        let mut xkb = self.xkb_settings();
+        if let Some(layout) = settings.xkb_layout {
+            xkb.layout = layout;
+            xkb.variant = settings.xkb_variant.unwrap_or_default();
+        } else if let Some(variant) = settings.xkb_variant {
+            xkb.variant = variant;
+        }
+        if let Some(options) = settings.xkb_options {
+            xkb.options = options;
+        }
+        xkb
+    }
+}
+
+/// Resolved XKB keymap names
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XkbSettings {
+    pub layout: String,
+    pub variant: String,
+    pub options: Vec<String>,
+}
+
+impl XkbSettings {
+    /// Options in the comma separated form expected by xkbcommon
+    pub fn options_string(&self) -> Option<String> {
+        if self.options.is_empty() {
+            None
+        } else {
+            Some(self.options.join(","))
         }
     }
 }
 
+/// An `[[input.devices]]` entry
+///
+/// ```toml
+/// [[input.devices]]
+/// match = { name = "Logitech MX Ergo", type = "pointer" }
+/// accel_profile = "flat"
+/// scroll_method = "on_button_down"
+/// scroll_button = 274 # BTN_MIDDLE
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct InputDeviceProfile {
+    #[serde(default, rename = "match")]
+    pub matcher: InputDeviceMatcher,
+    #[serde(flatten)]
+    pub settings: InputDeviceSettings,
+}
+
+/// Selects the devices an `[[input.devices]]` entry applies to.
+///
+/// Every field that is set must match; an empty matcher matches all devices.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct InputDeviceMatcher {
+    /// Device name as reported by libinput (case-insensitive)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// USB/Bluetooth vendor id
+    #[serde(default)]
+    pub vendor: Option<u32>,
+    /// USB/Bluetooth product id
+    #[serde(default)]
+    pub product: Option<u32>,
+    #[serde(default, rename = "type")]
+    pub kind: Option<InputDeviceKind>,
+}
+
+impl InputDeviceMatcher {
+    fn matches(&self, descriptor: &InputDeviceDescriptor) -> bool {
+        if let Some(expected_name) = &self.name {
+            if !equals_ignore_case(&descriptor.name, expected_name) {
+                return false;
+            }
+        }
+
+        if let Some(expected_vendor) = self.vendor {
+            if descriptor.vendor != Some(expected_vendor) {
+                return false;
+            }
+        }
+
+        if let Some(expected_product) = self.product {
+            if descriptor.product != Some(expected_product) {
+                return false;
+            }
+        }
+
+        if let Some(expected_kind) = self.kind {
+            if !descriptor.kinds.contains(&expected_kind) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Kind of input device, derived from the device capabilities
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InputDeviceKind {
+    Keyboard,
+    /// Mice, trackballs and trackpoints
+    Pointer,
+    Touchpad,
+    Touchscreen,
+    Tablet,
+}
+
+#[derive(Debug, Clone)]
+pub struct InputDeviceDescriptor {
+    pub name: String,
+    pub vendor: Option<u32>,
+    pub product: Option<u32>,
+    pub kinds: Vec<InputDeviceKind>,
+}
+
+/// Settings applied to the devices matched by an `[[input.devices]]` entry
+///
+/// Unset options keep the libinput default (or the touchpad options of
+/// `[input]` for touchpads).
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct InputDeviceSettings {
+    #[serde(default)]
+    pub accel_profile: Option<AccelProfile>,
+    /// Pointer acceleration speed in the [-1.0, 1.0] range
+    #[serde(default)]
+    pub accel_speed: Option<f64>,
+    #[serde(default)]
+    pub scroll_method: Option<ScrollMethod>,
+    /// Evdev button code used by `scroll_method = "on_button_down"`
+    #[serde(default)]
+    pub scroll_button: Option<u32>,
+    /// Multiplier applied to scroll deltas before they reach clients
+    #[serde(default)]
+    pub scroll_factor: Option<f64>,
+    #[serde(default)]
+    pub natural_scroll: Option<bool>,
+    #[serde(default)]
+    pub left_handed: Option<bool>,
+    /// Touchscreen/tablet calibration, the first two rows of a 3x3 matrix
+    #[serde(default)]
+    pub calibration_matrix: Option<[f32; 6]>,
+    #[serde(default)]
+    pub xkb_layout: Option<String>,
+    #[serde(default)]
+    pub xkb_variant: Option<String>,
+    #[serde(default)]
+    pub xkb_options: Option<Vec<String>>,
+}
+
+impl InputDeviceSettings {
+    fn merge(&mut self, other: &InputDeviceSettings) {
+        fn take<T: Clone>(target: &mut Option<T>, value: &Option<T>) {
+            if value.is_some() {
+                target.clone_from(value);
+            }
+        }
+        take(&mut self.accel_profile, &other.accel_profile);
+        take(&mut self.accel_speed, &other.accel_speed);
+        take(&mut self.scroll_method, &other.scroll_method);
+        take(&mut self.scroll_button, &other.scroll_button);
+        take(&mut self.scroll_factor, &other.scroll_factor);
+        take(&mut self.natural_scroll, &other.natural_scroll);
+        take(&mut self.left_handed, &other.left_handed);
+        take(&mut self.calibration_matrix, &other.calibration_matrix);
+        take(&mut self.xkb_layout, &other.xkb_layout);
+        take(&mut self.xkb_variant, &other.xkb_variant);
+        take(&mut self.xkb_options, &other.xkb_options);
+    }
+}
+
+/// Pointer acceleration profile
+///
+/// Maps to libinput's LIBINPUT_CONFIG_ACCEL_PROFILE_* enum values.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AccelProfile {
+    /// Constant factor, pointer speed equals device speed times `accel_speed`
+    Flat,
+    /// Acceleration depends on how fast the device moves
+    Adaptive,
+}
+
+/// Scroll method
+///
+/// Maps to libinput's LIBINPUT_CONFIG_SCROLL_* enum values.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollMethod {
+    NoScroll,
+    TwoFinger,
+    Edge,
+    /// Scroll by moving the device while `scroll_button` is held
+    OnButtonDown,
+}
+
 fn default_tap_enabled() -> bool {
     true
 }
@@ -339,6 +848,91 @@ fn default_touchpad_middle_emulation_enabled() -> bool {
     false
 }
 
+fn default_window_drag_modifier() -> String {
+    "Logo".to_string()
+}
+
+/// Scroll acceleration and kinetic scrolling
+///
+/// Applies to wheel and touchpad scrolling after the libinput and per-device
+/// settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrollConfig {
+    /// How much faster scrolling gets with speed, 0.0 keeps deltas linear
+    #[serde(default)]
+    pub acceleration: f64,
+    /// Upper bound for the acceleration multiplier
+    #[serde(default = "default_scroll_acceleration_max")]
+    pub acceleration_max: f64,
+    /// Keep scrolling after the fingers leave the touchpad
+    #[serde(default)]
+    pub kinetic: bool,
+    /// Time in milliseconds for the kinetic velocity to decay to ~37%
+    #[serde(default = "default_kinetic_time_constant_ms")]
+    pub kinetic_time_constant_ms: f64,
+    /// Overrides keyed by app_id, for apps that already implement their own
+    /// acceleration or kinetic scrolling
+    #[serde(default)]
+    pub apps: BTreeMap<String, ScrollOverride>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ScrollOverride {
+    #[serde(default)]
+    pub acceleration: Option<f64>,
+    #[serde(default)]
+    pub kinetic: Option<bool>,
+}
+
+/// Scroll settings resolved for the app under the pointer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollSettings {
+    pub acceleration: f64,
+    pub acceleration_max: f64,
+    pub kinetic: bool,
+    pub kinetic_time_constant_ms: f64,
+}
+
+impl Default for ScrollConfig {
+    fn default() -> Self {
+        Self {
+            acceleration: 0.0,
+            acceleration_max: default_scroll_acceleration_max(),
+            kinetic: false,
+            kinetic_time_constant_ms: default_kinetic_time_constant_ms(),
+            apps: BTreeMap::new(),
+        }
+    }
+}
+
+impl ScrollConfig {
+    pub fn resolve(&self, app_id: Option<&str>) -> ScrollSettings {
+        let mut settings = ScrollSettings {
+            acceleration: self.acceleration,
+            acceleration_max: self.acceleration_max,
+            kinetic: self.kinetic,
+            kinetic_time_constant_ms: self.kinetic_time_constant_ms,
+        };
+        if let Some(overrides) = app_id.and_then(|app_id| self.apps.get(app_id)) {
+            if let Some(acceleration) = overrides.acceleration {
+                settings.acceleration = acceleration;
+            }
+            if let Some(kinetic) = overrides.kinetic {
+                settings.kinetic = kinetic;
+            }
+        }
+        settings
+    }
+}
+
+fn default_scroll_acceleration_max() -> f64 {
+    4.0
+}
+
+fn default_kinetic_time_constant_ms() -> f64 {
+    325.0
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DockBookmark {
     pub desktop_id: String,
@@ -373,7 +967,7 @@ impl DisplaysConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct DisplayProfile {
     #[serde(default)]
     pub name: Option<String>,
@@ -496,6 +1090,132 @@ impl<'a> DisplayDescriptor<'a> {
     }
 }
 
+/// Timing of multi-key shortcut sequences and keyboard modes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyboardChordsConfig {
+    /// Cancel an unfinished sequence after this long without a key press
+    #[serde(default = "default_chord_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Delay before the hint overlay lists the possible continuations
+    #[serde(default = "default_chord_hint_delay_ms")]
+    pub hint_delay_ms: u64,
+    #[serde(default = "default_chord_show_hints")]
+    pub show_hints: bool,
+}
+
+impl Default for KeyboardChordsConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: default_chord_timeout_ms(),
+            hint_delay_ms: default_chord_hint_delay_ms(),
+            show_hints: default_chord_show_hints(),
+        }
+    }
+}
+
+fn default_chord_timeout_ms() -> u64 {
+    1500
+}
+
+fn default_chord_hint_delay_ms() -> u64 {
+    400
+}
+
+fn default_chord_show_hints() -> bool {
+    true
+}
+
+/// Shortcuts that apps register through the GlobalShortcuts portal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalShortcutsConfig {
+    /// Ask before binding the triggers an app requests
+    #[serde(default = "default_global_shortcuts_confirm")]
+    pub confirm: bool,
+    /// Triggers keyed by app_id and shortcut id, applied without asking.
+    /// `"None"` leaves the shortcut unbound.
+    #[serde(default)]
+    pub apps: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Default for GlobalShortcutsConfig {
+    fn default() -> Self {
+        Self {
+            confirm: default_global_shortcuts_confirm(),
+            apps: BTreeMap::new(),
+        }
+    }
+}
+
+impl GlobalShortcutsConfig {
+    /// Trigger configured for a shortcut of an app, `Some(None)` if it is
+    /// explicitly unbound
+    pub fn trigger_for(&self, app_id: &str, shortcut_id: &str) -> Option<Option<&str>> {
+        let trigger = self.apps.get(app_id)?.get(shortcut_id)?;
+        Some((trigger != "None").then_some(trigger.as_str()))
+    }
+}
+
+fn default_global_shortcuts_confirm() -> bool {
+    true
+}
+
+/// What happens when an app asks to inhibit compositor shortcuts
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InhibitPolicy {
+    /// Inhibit without asking
+    Allow,
+    /// Never inhibit, the app gets the keys compositor shortcuts leave over
+    Deny,
+    /// Ask the first time the app requests it
+    #[default]
+    Ask,
+}
+
+/// Apps that ask to receive all keys through keyboard-shortcuts-inhibit,
+/// e.g. remote desktop and virtual machine viewers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShortcutInhibitConfig {
+    /// Policy for apps not listed in `apps`
+    #[serde(default)]
+    pub default: InhibitPolicy,
+    /// Policy keyed by app_id
+    #[serde(default)]
+    pub apps: BTreeMap<String, InhibitPolicy>,
+    /// Shortcut that always reaches the compositor and toggles the
+    /// inhibition of the app under the pointer
+    #[serde(default = "default_inhibit_escape")]
+    pub escape: String,
+    /// Show a badge while an app inhibits shortcuts
+    #[serde(default = "default_inhibit_show_indicator")]
+    pub show_indicator: bool,
+}
+
+impl Default for ShortcutInhibitConfig {
+    fn default() -> Self {
+        Self {
+            default: InhibitPolicy::default(),
+            apps: BTreeMap::new(),
+            escape: default_inhibit_escape(),
+            show_indicator: default_inhibit_show_indicator(),
+        }
+    }
+}
+
+impl ShortcutInhibitConfig {
+    pub fn policy_for(&self, app_id: &str) -> InhibitPolicy {
+        self.apps.get(app_id).copied().unwrap_or(self.default)
+    }
+}
+
+fn default_inhibit_escape() -> String {
+    "Logo+Shift+Escape".to_string()
+}
+
+fn default_inhibit_show_indicator() -> bool {
+    true
+}
+
 fn equals_ignore_case(actual: &str, expected: &str) -> bool {
     actual.eq_ignore_ascii_case(expected)
 }
@@ -519,4 +1239,152 @@ mod tests {
         let config: Config = toml::from_str(overrides).expect("Config should deserialize");
         assert!(matches!(config.theme_scheme, ThemeScheme::Dark));
     }
+
+    #[test]
+    fn auto_theme_scheme_reads_the_schedule() {
+        let config: Config = toml::from_str(
+            r#"
+            theme_scheme = "Auto"
+            [theme_schedule]
+            mode = "Sun"
+            latitude = 45.46
+            longitude = 9.19
+        "#,
+        )
+        .expect("Config should deserialize");
+        assert!(matches!(config.theme_scheme, ThemeScheme::Auto));
+        assert_eq!(config.theme_schedule.latitude, 45.46);
+        assert_eq!(
+            config.theme_schedule.dark_at,
+            ThemeScheduleConfig::default().dark_at
+        );
+    }
+
+    fn mouse_descriptor() -> InputDeviceDescriptor {
+        InputDeviceDescriptor {
+            name: "Logitech MX Master 3".to_string(),
+            vendor: Some(0x046d),
+            product: Some(0x4082),
+            kinds: vec![InputDeviceKind::Pointer],
+        }
+    }
+
+    #[test]
+    fn input_devices_merge_matching_entries_in_order() {
+        let overrides = r#"
+            [[input.devices]]
+            match = { type = "pointer" }
+            accel_profile = "flat"
+            natural_scroll = true
+
+            [[input.devices]]
+            match = { vendor = 0x046d, product = 0x4082 }
+            accel_speed = -0.5
+            natural_scroll = false
+
+            [[input.devices]]
+            match = { type = "keyboard" }
+            xkb_layout = "de"
+        "#;
+
+        let config: Config = toml::from_str(overrides).expect("Config should deserialize");
+        let settings = config.input.device_settings(&mouse_descriptor());
+        assert_eq!(settings.accel_profile, Some(AccelProfile::Flat));
+        assert_eq!(settings.accel_speed, Some(-0.5));
+        assert_eq!(settings.natural_scroll, Some(false));
+        assert_eq!(settings.xkb_layout, None);
+    }
+
+    #[test]
+    fn scroll_app_overrides_replace_global_settings() {
+        let overrides = r#"
+            [input.scroll]
+            acceleration = 0.5
+            kinetic = true
+
+            [input.scroll.apps."org.mozilla.firefox"]
+            kinetic = false
+        "#;
+
+        let config: Config = toml::from_str(overrides).expect("Config should deserialize");
+        let global = config.input.scroll.resolve(Some("org.gnome.Nautilus"));
+        assert!(global.kinetic);
+        assert_eq!(global.acceleration, 0.5);
+
+        let firefox = config.input.scroll.resolve(Some("org.mozilla.firefox"));
+        assert!(!firefox.kinetic);
+        assert_eq!(firefox.acceleration, 0.5);
+    }
+
+    #[test]
+    fn input_device_xkb_layout_overrides_global_layout() {
+        let overrides = r#"
+            [input]
+            xkb_layout = "us"
+            xkb_variant = "dvorak"
+            xkb_options = ["caps:escape"]
+
+            [[input.devices]]
+            match = { name = "ZSA Moonlander" }
+            xkb_layout = "de"
+        "#;
+
+        let config: Config = toml::from_str(overrides).expect("Config should deserialize");
+        let keyboard = InputDeviceDescriptor {
+            name: "zsa moonlander".to_string(),
+            vendor: None,
+            product: None,
+            kinds: vec![InputDeviceKind::Keyboard],
+        };
+        let xkb = config.input.xkb_settings_for(&keyboard);
+        assert_eq!(xkb.layout, "de");
+        assert_eq!(xkb.variant, "");
+        assert_eq!(xkb.options, vec!["caps:escape".to_string()]);
+        assert_eq!(
+            config.input.xkb_settings_for(&mouse_descriptor()),
+            config.input.xkb_settings()
+        );
+    }
+
+    #[test]
+    fn shortcut_inhibit_policy_falls_back_to_default() {
+        let overrides = r#"
+            [shortcut_inhibit]
+            default = "deny"
+
+            [shortcut_inhibit.apps]
+            "org.remmina.Remmina" = "allow"
+        "#;
+
+        let config: Config = toml::from_str(overrides).expect("Config should deserialize");
+        let inhibit = &config.shortcut_inhibit;
+        assert_eq!(
+            inhibit.policy_for("org.remmina.Remmina"),
+            InhibitPolicy::Allow
+        );
+        assert_eq!(inhibit.policy_for("virt-manager"), InhibitPolicy::Deny);
+        assert_eq!(inhibit.escape, "Logo+Shift+Escape");
+        assert!(Config::default().inhibit_escape().is_some());
+    }
+
+    #[test]
+    fn config_changes_only_report_edited_sections() {
+        let old = Config::default();
+        let new: Config = toml::from_str(
+            r#"
+            cursor_size = 32
+
+            [dock]
+            size = 1.5
+        "#,
+        )
+        .expect("Config should deserialize");
+
+        let changes = ConfigChanges::between(&old, &new);
+        assert!(changes.cursor);
+        assert!(changes.dock);
+        assert!(!changes.shortcuts);
+        assert!(!changes.keyboard);
+        assert!(ConfigChanges::between(&old, &Config::default()).is_empty());
+    }
 }
@@ -1,14 +1,19 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use smithay::input::keyboard::ModifiersState;
 use thiserror::Error;
-use tracing::{info, warn};
+use tracing::info;
 use xkbcommon::xkb::{self, keysyms::KEY_NoSymbol};
 
 /// Raw mapping loaded from configuration.
 pub type ShortcutMap = BTreeMap<String, ShortcutActionConfig>;
 
+/// Keyboard modes loaded from configuration, keyed by mode name.
+pub type KeyboardModeMap = BTreeMap<String, KeyboardModeConfig>;
+
 /// Supported shortcut action encodings in the configuration file.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -21,6 +26,9 @@ pub enum ShortcutActionConfig {
         builtin: String,
         #[serde(default)]
         index: Option<usize>,
+        /// Switch to the workspace a window is moved to
+        #[serde(default)]
+        follow: bool,
     },
 
     /// `action = { run = { cmd = "kitty", args = [] } }`
@@ -28,6 +36,9 @@ pub enum ShortcutActionConfig {
 
     /// `action = { open_default = "browser" }`
     OpenDefault { open_default: OpenDefaultConfig },
+
+    /// `action = { mode = "resize" }`
+    EnterMode { mode: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,21 +52,77 @@ pub enum OpenDefaultConfig {
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RunCommandConfig {
     pub cmd: String,
     #[serde(default)]
     pub args: Vec<String>,
 }
 
+/// A keyboard mode: while active only its own bindings are matched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyboardModeConfig {
+    /// Keys leaving the mode
+    #[serde(default = "default_mode_exit")]
+    pub exit: Vec<String>,
+    /// Leave the mode after the first action
+    #[serde(default)]
+    pub oneshot: bool,
+    /// Leave the mode after this long without a key press
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub bindings: ShortcutMap,
+}
+
+fn default_mode_exit() -> Vec<String> {
+    vec!["Escape".to_string()]
+}
+
 #[derive(Debug, Clone)]
 pub struct ShortcutBinding {
     pub trigger: ShortcutTrigger,
+    /// Keys following `trigger` for multi-key sequences, e.g. `h` in `"Logo+w h"`
+    pub continuation: Vec<ShortcutTrigger>,
     pub action: ShortcutAction,
     #[allow(dead_code)]
     pub trigger_repr: String,
 }
 
+impl ShortcutBinding {
+    /// All keys of the binding, starting with `trigger`
+    pub fn keys(&self) -> impl Iterator<Item = &ShortcutTrigger> {
+        std::iter::once(&self.trigger).chain(self.continuation.iter())
+    }
+
+    pub fn len(&self) -> usize {
+        1 + self.continuation.len()
+    }
+
+    /// Whether `keys` are the start of this binding: `Some(true)` if they
+    /// complete it, `Some(false)` if more keys are needed.
+    pub fn match_prefix(&self, keys: &[ShortcutTrigger]) -> Option<bool> {
+        if keys.len() > self.len() || !self.keys().zip(keys).all(|(own, key)| own == key) {
+            return None;
+        }
+        Some(keys.len() == self.len())
+    }
+
+    pub fn canonical_id(&self) -> String {
+        sequence_canonical_id(self.keys())
+    }
+}
+
+/// A keyboard mode ready for matching.
+#[derive(Debug, Clone)]
+pub struct ShortcutMode {
+    pub name: String,
+    pub bindings: Vec<ShortcutBinding>,
+    pub exit: Vec<ShortcutTrigger>,
+    pub oneshot: bool,
+    pub timeout: Option<Duration>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ShortcutTrigger {
     pub ctrl: bool,
@@ -66,6 +133,17 @@ pub struct ShortcutTrigger {
 }
 
 impl ShortcutTrigger {
+    /// Trigger for a key pressed with the given modifiers
+    pub fn from_keypress(modifiers: &ModifiersState, keysym: xkb::Keysym) -> Self {
+        Self {
+            ctrl: modifiers.ctrl,
+            alt: modifiers.alt,
+            shift: modifiers.shift,
+            logo: modifiers.logo,
+            keysym: normalize_alphanumeric_keysym(keysym),
+        }
+    }
+
     pub fn matches(&self, modifiers: &ModifiersState, keysym: xkb::Keysym) -> bool {
         let normalized_keysym = normalize_alphanumeric_keysym(keysym);
         self.ctrl == modifiers.ctrl
@@ -75,6 +153,21 @@ impl ShortcutTrigger {
             && self.keysym == normalized_keysym
     }
 
+    /// Like [`Self::matches`] for a released key; the modifier of the key
+    /// itself is ignored, so `Logo:release` matches releasing `Super_L`.
+    pub fn matches_release(&self, modifiers: &ModifiersState, keysym: xkb::Keysym) -> bool {
+        let keysym = normalize_modifier_keysym(keysym);
+        let mut modifiers = *modifiers;
+        match keysym {
+            xkb::Keysym::Control_L => modifiers.ctrl = false,
+            xkb::Keysym::Alt_L => modifiers.alt = false,
+            xkb::Keysym::Shift_L => modifiers.shift = false,
+            xkb::Keysym::Super_L => modifiers.logo = false,
+            _ => {}
+        }
+        self.matches(&modifiers, keysym)
+    }
+
     pub fn canonical_id(&self) -> String {
         let mut parts: Vec<String> = Vec::new();
         if self.ctrl {
@@ -94,6 +187,56 @@ impl ShortcutTrigger {
     }
 }
 
+pub fn sequence_canonical_id<'a>(keys: impl Iterator<Item = &'a ShortcutTrigger>) -> String {
+    keys.map(ShortcutTrigger::canonical_id)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Suffix of triggers firing when the key is released, e.g. `"Logo:release"`
+const RELEASE_SUFFIX: &str = ":release";
+
+/// evdev code of the left mouse button
+const BTN_LEFT: u32 = 0x110;
+
+/// Pointer input that can trigger a shortcut
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerInput {
+    /// evdev button code
+    Button(u32),
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PointerTrigger {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub logo: bool,
+    pub input: PointerInput,
+}
+
+impl PointerTrigger {
+    pub fn matches(&self, modifiers: &ModifiersState, input: PointerInput) -> bool {
+        self.ctrl == modifiers.ctrl
+            && self.alt == modifiers.alt
+            && self.shift == modifiers.shift
+            && self.logo == modifiers.logo
+            && self.input == input
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PointerBinding {
+    pub trigger: PointerTrigger,
+    pub action: ShortcutAction,
+    #[allow(dead_code)]
+    pub trigger_repr: String,
+}
+
 #[derive(Debug, Clone)]
 pub enum ShortcutAction {
     Builtin(BuiltinAction),
@@ -102,6 +245,33 @@ pub enum ShortcutAction {
         role: String,
         fallback: Option<String>,
     },
+    EnterMode(String),
+}
+
+impl fmt::Display for ShortcutAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShortcutAction::Builtin(BuiltinAction::Screen { index }) => {
+                write!(f, "Screen {}", index + 1)
+            }
+            ShortcutAction::Builtin(BuiltinAction::WorkspaceNum { index }) => {
+                write!(f, "Workspace {}", index + 1)
+            }
+            ShortcutAction::Builtin(BuiltinAction::MoveWindowToWorkspace { index, .. }) => {
+                write!(f, "Move window to workspace {}", index + 1)
+            }
+            ShortcutAction::Builtin(BuiltinAction::MoveWindowToWorkspaceNext { .. }) => {
+                write!(f, "MoveWindowToWorkspaceNext")
+            }
+            ShortcutAction::Builtin(BuiltinAction::MoveWindowToWorkspacePrev { .. }) => {
+                write!(f, "MoveWindowToWorkspacePrev")
+            }
+            ShortcutAction::Builtin(builtin) => write!(f, "{builtin:?}"),
+            ShortcutAction::RunCommand(run) => write!(f, "{}", run.cmd),
+            ShortcutAction::OpenDefaultApp { role, .. } => write!(f, "Open {role}"),
+            ShortcutAction::EnterMode(mode) => write!(f, "{mode} mode"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -118,10 +288,24 @@ pub enum BuiltinAction {
     ApplicationSwitchQuit,
     CloseWindow,
     ToggleMaximizeWindow,
+    ToggleSplitView,
     ExposeShowDesktop,
     ExposeShowAll,
+    ExposeShowAllWorkspaces,
+    ExposeShowApp,
     WorkspaceNum { index: usize },
+    WorkspaceNext,
+    WorkspacePrev,
+    WorkspaceCreate,
+    WorkspaceRemove,
+    WorkspaceRename,
+    MoveWindowToWorkspace { index: usize, follow: bool },
+    MoveWindowToWorkspaceNext { follow: bool },
+    MoveWindowToWorkspacePrev { follow: bool },
     SceneSnapshot,
+    ExitMode,
+    ToggleThemeScheme,
+    ResetWallpaper,
 }
 
 #[derive(Debug, Error)]
@@ -136,57 +320,372 @@ pub enum ShortcutError {
     UnknownBuiltin(String),
     #[error("missing index for action that requires one: '{0}'")]
     MissingIndex(String),
+    #[error("sequence '{sequence}' is already bound as '{existing}'")]
+    ConflictingSequence { sequence: String, existing: String },
+    #[error("sequence '{sequence}' is ambiguous with '{other}', one starts with the other")]
+    AmbiguousSequence { sequence: String, other: String },
+    #[error("unknown keyboard mode '{0}'")]
+    UnknownMode(String),
+    #[error("unknown pointer button or scroll direction '{0}'")]
+    UnknownPointerInput(String),
+}
+
+/// A shortcut entry that was skipped, or that replaced an equivalent one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortcutIssue {
+    /// Table of the entry, e.g. `keyboard_shortcuts` or `keyboard_modes.resize`
+    pub section: String,
+    pub trigger: String,
+    pub message: String,
+    /// The entry is a duplicate and replaced the previous one, nothing is lost
+    pub duplicate: bool,
+}
+
+impl ShortcutIssue {
+    pub fn skipped(section: &str, trigger: &str, error: impl fmt::Display) -> Self {
+        Self {
+            section: section.to_string(),
+            trigger: trigger.to_string(),
+            message: error.to_string(),
+            duplicate: false,
+        }
+    }
+
+    pub fn duplicate(section: &str, trigger: &str, message: impl fmt::Display) -> Self {
+        Self {
+            duplicate: true,
+            ..Self::skipped(section, trigger, message)
+        }
+    }
+}
+
+impl fmt::Display for ShortcutIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] '{}': {}", self.section, self.trigger, self.message)
+    }
+}
+
+pub fn build_bindings(map: &ShortcutMap, issues: &mut Vec<ShortcutIssue>) -> Vec<ShortcutBinding> {
+    let bindings = build_binding_list(map, "keyboard_shortcuts", issues);
+
+    if !bindings.is_empty() {
+        info!("loaded {} keyboard shortcut bindings", bindings.len());
+    } else {
+        info!("no keyboard shortcut bindings configured");
+    }
+
+    bindings
 }
 
-pub fn build_bindings(map: &ShortcutMap) -> Vec<ShortcutBinding> {
-    let mut bindings = Vec::new();
-    let mut seen: HashMap<ShortcutTrigger, String> = HashMap::new();
+/// Whether the trigger is a pointer button or scroll direction, e.g. `"Logo+BTN_SIDE"`
+fn is_pointer_trigger(trigger: &str) -> bool {
+    !trigger.trim().contains(char::is_whitespace)
+        && trigger
+            .rsplit('+')
+            .next()
+            .and_then(|input| parse_pointer_input(input.trim()))
+            .is_some()
+}
+
+fn is_release_trigger(trigger: &str) -> bool {
+    strip_release_suffix(trigger).is_some()
+}
+
+fn build_binding_list(
+    map: &ShortcutMap,
+    section: &str,
+    issues: &mut Vec<ShortcutIssue>,
+) -> Vec<ShortcutBinding> {
+    let mut bindings: Vec<ShortcutBinding> = Vec::new();
 
-    for (trigger_str, action_cfg) in map {
-        match parse_trigger(trigger_str) {
-            Ok(trigger) => match parse_action(action_cfg) {
+    let key_triggers = map
+        .iter()
+        .filter(|(trigger, _)| !is_pointer_trigger(trigger) && !is_release_trigger(trigger));
+    for (trigger_str, action_cfg) in key_triggers {
+        match parse_sequence(trigger_str) {
+            Ok(mut keys) => match parse_action(action_cfg) {
                 Ok(action) => {
-                    if let Some(existing) = seen.insert(trigger.clone(), trigger_str.clone()) {
-                        warn!(
-                            trigger = %trigger.canonical_id(),
-                            existing = %existing,
-                            new = %trigger_str,
-                            "duplicate shortcut definition, new entry replaces the previous one"
-                        );
-                    }
-                    bindings.push(ShortcutBinding {
+                    let trigger = keys.remove(0);
+                    let binding = ShortcutBinding {
                         trigger,
+                        continuation: keys,
                         action,
                         trigger_repr: trigger_str.clone(),
-                    });
-                }
-                Err(err) => {
-                    warn!(trigger = %trigger_str, error = %err, "skipping shortcut due to invalid action")
+                    };
+                    match check_sequence(&bindings, &binding) {
+                        Ok(()) => {}
+                        Err(err @ ShortcutError::ConflictingSequence { .. }) => {
+                            issues.push(ShortcutIssue::duplicate(
+                                section,
+                                trigger_str,
+                                format!("{err}, the new entry replaces the previous one"),
+                            ));
+                            let keys: Vec<ShortcutTrigger> = binding.keys().cloned().collect();
+                            bindings.retain(|existing| existing.match_prefix(&keys) != Some(true));
+                        }
+                        Err(err) => {
+                            issues.push(ShortcutIssue::skipped(section, trigger_str, err));
+                            continue;
+                        }
+                    }
+                    bindings.push(binding);
                 }
+                Err(err) => issues.push(ShortcutIssue::skipped(section, trigger_str, err)),
             },
-            Err(err) => {
-                warn!(trigger = %trigger_str, error = %err, "skipping shortcut due to invalid trigger")
+            Err(err) => issues.push(ShortcutIssue::skipped(section, trigger_str, err)),
+        }
+    }
+
+    bindings
+}
+
+/// Checks that a new binding can be told apart from the existing ones.
+///
+/// A sequence must not be bound twice and must not start with another bound
+/// sequence, otherwise it is not possible to know when the sequence ends.
+pub fn check_sequence(
+    bindings: &[ShortcutBinding],
+    binding: &ShortcutBinding,
+) -> Result<(), ShortcutError> {
+    let keys: Vec<ShortcutTrigger> = binding.keys().cloned().collect();
+    for existing in bindings {
+        let existing_keys: Vec<ShortcutTrigger> = existing.keys().cloned().collect();
+        if existing.match_prefix(&keys) == Some(true) {
+            return Err(ShortcutError::ConflictingSequence {
+                sequence: binding.trigger_repr.clone(),
+                existing: existing.trigger_repr.clone(),
+            });
+        }
+        if existing.match_prefix(&keys).is_some() || binding.match_prefix(&existing_keys).is_some()
+        {
+            return Err(ShortcutError::AmbiguousSequence {
+                sequence: binding.trigger_repr.clone(),
+                other: existing.trigger_repr.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Bindings for pointer buttons and scrolling with modifiers.
+pub fn build_pointer_bindings(
+    map: &ShortcutMap,
+    issues: &mut Vec<ShortcutIssue>,
+) -> Vec<PointerBinding> {
+    let mut bindings: Vec<PointerBinding> = Vec::new();
+    for (trigger_str, action_cfg) in map.iter().filter(|(t, _)| is_pointer_trigger(t)) {
+        let binding = parse_pointer_trigger(trigger_str).and_then(|trigger| {
+            Ok(PointerBinding {
+                trigger,
+                action: parse_action(action_cfg)?,
+                trigger_repr: trigger_str.clone(),
+            })
+        });
+        match binding {
+            Ok(binding) => {
+                if bindings
+                    .iter()
+                    .any(|existing| existing.trigger == binding.trigger)
+                {
+                    issues.push(ShortcutIssue::duplicate(
+                        "keyboard_shortcuts",
+                        trigger_str,
+                        "duplicate pointer shortcut, the new entry replaces the previous one",
+                    ));
+                    bindings.retain(|existing| existing.trigger != binding.trigger);
+                }
+                bindings.push(binding);
             }
+            Err(err) => issues.push(ShortcutIssue::skipped(
+                "keyboard_shortcuts",
+                trigger_str,
+                err,
+            )),
         }
     }
+    bindings
+}
 
-    if !bindings.is_empty() {
-        info!("loaded {} keyboard shortcut bindings", bindings.len());
-    } else {
-        info!("no keyboard shortcut bindings configured");
+/// What a modifier-drag does to the window under the pointer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowDrag {
+    Move,
+    Resize,
+}
+
+/// Modifier plus left button moves a window, plus right button resizes it
+#[derive(Debug, Clone)]
+pub struct WindowDragTriggers {
+    pub move_window: PointerTrigger,
+    pub resize_window: PointerTrigger,
+}
+
+impl WindowDragTriggers {
+    pub fn matches(&self, modifiers: &ModifiersState, button: u32) -> Option<WindowDrag> {
+        let input = PointerInput::Button(button);
+        if self.move_window.matches(modifiers, input) {
+            Some(WindowDrag::Move)
+        } else if self.resize_window.matches(modifiers, input) {
+            Some(WindowDrag::Resize)
+        } else {
+            None
+        }
+    }
+}
+
+/// Drag triggers for a modifier such as `"Logo"` or `"Alt+Shift"`, `None`
+/// if dragging is disabled with `"None"` or the modifier is invalid.
+pub fn build_window_drag(
+    modifier: &str,
+    issues: &mut Vec<ShortcutIssue>,
+) -> Option<WindowDragTriggers> {
+    let modifier = modifier.trim();
+    if modifier.is_empty() || modifier.eq_ignore_ascii_case("none") {
+        return None;
     }
+    let parse = |button| parse_pointer_trigger(&format!("{modifier}+{button}"));
+    match (parse("BTN_LEFT"), parse("BTN_RIGHT")) {
+        (Ok(move_window), Ok(resize_window)) => Some(WindowDragTriggers {
+            move_window,
+            resize_window,
+        }),
+        (Err(err), _) | (_, Err(err)) => {
+            issues.push(ShortcutIssue::skipped(
+                "input.window_drag_modifier",
+                modifier,
+                format!("{err}, dragging disabled"),
+            ));
+            None
+        }
+    }
+}
 
+/// Bindings firing when a key is released without another key pressed in between.
+pub fn build_release_bindings(
+    map: &ShortcutMap,
+    issues: &mut Vec<ShortcutIssue>,
+) -> Vec<ShortcutBinding> {
+    let mut bindings: Vec<ShortcutBinding> = Vec::new();
+    for (trigger_str, action_cfg) in map.iter().filter(|(t, _)| is_release_trigger(t)) {
+        let binding = parse_release_trigger(trigger_str).and_then(|trigger| {
+            Ok(ShortcutBinding {
+                trigger,
+                continuation: Vec::new(),
+                action: parse_action(action_cfg)?,
+                trigger_repr: trigger_str.clone(),
+            })
+        });
+        match binding {
+            Ok(binding) => {
+                if bindings
+                    .iter()
+                    .any(|existing| existing.trigger == binding.trigger)
+                {
+                    issues.push(ShortcutIssue::duplicate(
+                        "keyboard_shortcuts",
+                        trigger_str,
+                        "duplicate release shortcut, the new entry replaces the previous one",
+                    ));
+                    bindings.retain(|existing| existing.trigger != binding.trigger);
+                }
+                bindings.push(binding);
+            }
+            Err(err) => issues.push(ShortcutIssue::skipped(
+                "keyboard_shortcuts",
+                trigger_str,
+                err,
+            )),
+        }
+    }
     bindings
 }
 
-fn parse_action(cfg: &ShortcutActionConfig) -> Result<ShortcutAction, ShortcutError> {
+pub fn build_modes(
+    map: &KeyboardModeMap,
+    issues: &mut Vec<ShortcutIssue>,
+) -> BTreeMap<String, ShortcutMode> {
+    let mut modes = BTreeMap::new();
+    for (name, mode_cfg) in map {
+        let section = format!("keyboard_modes.{name}");
+        let exit: Vec<ShortcutTrigger> = mode_cfg
+            .exit
+            .iter()
+            .filter_map(|key| match parse_trigger(key) {
+                Ok(trigger) => Some(trigger),
+                Err(err) => {
+                    issues.push(ShortcutIssue::skipped(&section, key, err));
+                    None
+                }
+            })
+            .collect();
+        for trigger in mode_cfg
+            .bindings
+            .keys()
+            .filter(|t| is_pointer_trigger(t) || is_release_trigger(t))
+        {
+            issues.push(ShortcutIssue::skipped(
+                &section,
+                trigger,
+                "pointer and release shortcuts are not supported in keyboard modes",
+            ));
+        }
+        let mut bindings = build_binding_list(&mode_cfg.bindings, &section, issues);
+        bindings.retain(|binding| {
+            let shadowed = exit.contains(&binding.trigger);
+            if shadowed {
+                issues.push(ShortcutIssue::skipped(
+                    &section,
+                    &binding.trigger_repr,
+                    "binding shadowed by a mode exit key",
+                ));
+            }
+            !shadowed
+        });
+        retain_known_modes(&mut bindings, map, &section, issues);
+        info!(mode = %name, "loaded {} bindings for keyboard mode", bindings.len());
+        modes.insert(
+            name.clone(),
+            ShortcutMode {
+                name: name.clone(),
+                bindings,
+                exit,
+                oneshot: mode_cfg.oneshot,
+                timeout: mode_cfg.timeout_ms.map(Duration::from_millis),
+            },
+        );
+    }
+    modes
+}
+
+/// Drops bindings entering a mode that is not configured.
+pub fn retain_known_modes(
+    bindings: &mut Vec<ShortcutBinding>,
+    modes: &KeyboardModeMap,
+    section: &str,
+    issues: &mut Vec<ShortcutIssue>,
+) {
+    bindings.retain(|binding| match &binding.action {
+        ShortcutAction::EnterMode(mode) if !modes.contains_key(mode) => {
+            let err = ShortcutError::UnknownMode(mode.clone());
+            issues.push(ShortcutIssue::skipped(section, &binding.trigger_repr, err));
+            false
+        }
+        _ => true,
+    });
+}
+
+pub(crate) fn parse_action(cfg: &ShortcutActionConfig) -> Result<ShortcutAction, ShortcutError> {
     match cfg {
         ShortcutActionConfig::BuiltinName(name) => {
-            let action = parse_builtin(name, None)?;
+            let action = parse_builtin(name, None, false)?;
             Ok(ShortcutAction::Builtin(action))
         }
-        ShortcutActionConfig::BuiltinDetailed { builtin, index } => {
-            let action = parse_builtin(builtin, *index)?;
+        ShortcutActionConfig::BuiltinDetailed {
+            builtin,
+            index,
+            follow,
+        } => {
+            let action = parse_builtin(builtin, *index, *follow)?;
             Ok(ShortcutAction::Builtin(action))
         }
         ShortcutActionConfig::RunCommand { run } => Ok(ShortcutAction::RunCommand(run.clone())),
@@ -202,10 +701,15 @@ fn parse_action(cfg: &ShortcutActionConfig) -> Result<ShortcutAction, ShortcutEr
             role: role.clone(),
             fallback: fallback.clone(),
         }),
+        ShortcutActionConfig::EnterMode { mode } => Ok(ShortcutAction::EnterMode(mode.clone())),
     }
 }
 
-fn parse_builtin(name: &str, index: Option<usize>) -> Result<BuiltinAction, ShortcutError> {
+fn parse_builtin(
+    name: &str,
+    index: Option<usize>,
+    follow: bool,
+) -> Result<BuiltinAction, ShortcutError> {
     Ok(match name {
         "Quit" => BuiltinAction::Quit,
         "ScaleUp" => BuiltinAction::ScaleUp,
@@ -218,9 +722,22 @@ fn parse_builtin(name: &str, index: Option<usize>) -> Result<BuiltinAction, Shor
         "ApplicationSwitchQuit" => BuiltinAction::ApplicationSwitchQuit,
         "CloseWindow" => BuiltinAction::CloseWindow,
         "ToggleMaximizeWindow" => BuiltinAction::ToggleMaximizeWindow,
+        "ToggleSplitView" => BuiltinAction::ToggleSplitView,
         "ExposeShowDesktop" => BuiltinAction::ExposeShowDesktop,
         "ExposeShowAll" => BuiltinAction::ExposeShowAll,
+        "ExposeShowAllWorkspaces" => BuiltinAction::ExposeShowAllWorkspaces,
+        "ExposeShowApp" => BuiltinAction::ExposeShowApp,
         "SceneSnapshot" => BuiltinAction::SceneSnapshot,
+        "WorkspaceNext" => BuiltinAction::WorkspaceNext,
+        "WorkspacePrev" => BuiltinAction::WorkspacePrev,
+        "WorkspaceCreate" => BuiltinAction::WorkspaceCreate,
+        "WorkspaceRemove" => BuiltinAction::WorkspaceRemove,
+        "WorkspaceRename" => BuiltinAction::WorkspaceRename,
+        "MoveWindowToWorkspaceNext" => BuiltinAction::MoveWindowToWorkspaceNext { follow },
+        "MoveWindowToWorkspacePrev" => BuiltinAction::MoveWindowToWorkspacePrev { follow },
+        "ExitMode" => BuiltinAction::ExitMode,
+        "ToggleThemeScheme" => BuiltinAction::ToggleThemeScheme,
+        "ResetWallpaper" => BuiltinAction::ResetWallpaper,
         "Screen" => {
             let index = index.ok_or_else(|| ShortcutError::MissingIndex(name.to_string()))?;
             BuiltinAction::Screen { index }
@@ -229,29 +746,103 @@ fn parse_builtin(name: &str, index: Option<usize>) -> Result<BuiltinAction, Shor
             let index = index.ok_or_else(|| ShortcutError::MissingIndex(name.to_string()))?;
             BuiltinAction::WorkspaceNum { index }
         }
+        "MoveWindowToWorkspace" => {
+            let index = index.ok_or_else(|| ShortcutError::MissingIndex(name.to_string()))?;
+            BuiltinAction::MoveWindowToWorkspace { index, follow }
+        }
         other => return Err(ShortcutError::UnknownBuiltin(other.to_string())),
     })
 }
 
-fn parse_trigger(trigger: &str) -> Result<ShortcutTrigger, ShortcutError> {
+/// Parses a whitespace separated key sequence such as `"Logo+w h"`.
+pub(crate) fn parse_sequence(sequence: &str) -> Result<Vec<ShortcutTrigger>, ShortcutError> {
+    // Spaces around `+` belong to a single key, e.g. `"Logo + w"`
+    let normalized = sequence
+        .split('+')
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("+");
+    let keys = normalized
+        .split_whitespace()
+        .map(parse_trigger)
+        .collect::<Result<Vec<_>, _>>()?;
+    if keys.is_empty() {
+        return Err(ShortcutError::InvalidTrigger(sequence.to_string()));
+    }
+    Ok(keys)
+}
+
+pub(crate) fn parse_trigger(trigger: &str) -> Result<ShortcutTrigger, ShortcutError> {
+    let (modifiers, key) = split_trigger(trigger)?;
+    let keysym = parse_keysym(key)?;
+
+    Ok(ShortcutTrigger {
+        ctrl: modifiers.ctrl,
+        alt: modifiers.alt,
+        shift: modifiers.shift,
+        logo: modifiers.logo,
+        keysym,
+    })
+}
+
+/// Parses `"<key>:release"`, the key may also be a modifier name, e.g. `"Logo:release"`.
+pub(crate) fn parse_release_trigger(trigger: &str) -> Result<ShortcutTrigger, ShortcutError> {
+    let base = strip_release_suffix(trigger)
+        .ok_or_else(|| ShortcutError::InvalidTrigger(trigger.to_string()))?;
+    let (modifiers, key) = split_trigger(base)?;
+    let keysym = match modifier_keysym(key) {
+        Some(keysym) => keysym,
+        None => parse_keysym(key)?,
+    };
+
+    Ok(ShortcutTrigger {
+        ctrl: modifiers.ctrl,
+        alt: modifiers.alt,
+        shift: modifiers.shift,
+        logo: modifiers.logo,
+        keysym,
+    })
+}
+
+pub(crate) fn parse_pointer_trigger(trigger: &str) -> Result<PointerTrigger, ShortcutError> {
+    let (modifiers, input) = split_trigger(trigger)?;
+    let input = parse_pointer_input(input)
+        .ok_or_else(|| ShortcutError::UnknownPointerInput(input.to_string()))?;
+
+    Ok(PointerTrigger {
+        ctrl: modifiers.ctrl,
+        alt: modifiers.alt,
+        shift: modifiers.shift,
+        logo: modifiers.logo,
+        input,
+    })
+}
+
+#[derive(Debug, Default)]
+struct TriggerModifiers {
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    logo: bool,
+}
+
+/// Splits a trigger into its modifiers and the trailing key
+fn split_trigger(trigger: &str) -> Result<(TriggerModifiers, &str), ShortcutError> {
     let parts: Vec<&str> = trigger.split('+').collect();
     if parts.is_empty() {
         return Err(ShortcutError::InvalidTrigger(trigger.to_string()));
     }
 
-    let mut ctrl = false;
-    let mut alt = false;
-    let mut shift = false;
-    let mut logo = false;
+    let mut modifiers = TriggerModifiers::default();
 
     for modifier in &parts[..parts.len() - 1] {
         let normalized = modifier.trim();
 
         match normalized.to_ascii_lowercase().as_str() {
-            "ctrl" | "control" | "primary" => ctrl = true,
-            "alt" => alt = true,
-            "shift" => shift = true,
-            "logo" | "super" | "meta" | "win" | "command" => logo = true,
+            "ctrl" | "control" | "primary" => modifiers.ctrl = true,
+            "alt" => modifiers.alt = true,
+            "shift" => modifiers.shift = true,
+            "logo" | "super" | "meta" | "win" | "command" => modifiers.logo = true,
             other => return Err(ShortcutError::UnknownModifier(other.to_string())),
         }
     }
@@ -261,17 +852,61 @@ fn parse_trigger(trigger: &str) -> Result<ShortcutTrigger, ShortcutError> {
         return Err(ShortcutError::InvalidTrigger(trigger.to_string()));
     }
 
-    let keysym = parse_keysym(key)?;
+    Ok((modifiers, key))
+}
 
-    Ok(ShortcutTrigger {
-        ctrl,
-        alt,
-        shift,
-        logo,
-        keysym,
+fn strip_release_suffix(trigger: &str) -> Option<&str> {
+    let trimmed = trigger.trim();
+    let split = trimmed.len().checked_sub(RELEASE_SUFFIX.len())?;
+    let (base, suffix) = trimmed.split_at_checked(split)?;
+    suffix.eq_ignore_ascii_case(RELEASE_SUFFIX).then_some(base)
+}
+
+/// Left-hand keysym for a modifier name used as a key
+fn modifier_keysym(key: &str) -> Option<xkb::Keysym> {
+    Some(match key.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" | "primary" => xkb::Keysym::Control_L,
+        "alt" => xkb::Keysym::Alt_L,
+        "shift" => xkb::Keysym::Shift_L,
+        "logo" | "super" | "meta" | "win" | "command" => xkb::Keysym::Super_L,
+        _ => return None,
     })
 }
 
+/// Maps right-hand modifier keysyms to their left-hand counterpart
+pub(crate) fn normalize_modifier_keysym(sym: xkb::Keysym) -> xkb::Keysym {
+    match sym {
+        xkb::Keysym::Control_R => xkb::Keysym::Control_L,
+        xkb::Keysym::Alt_R => xkb::Keysym::Alt_L,
+        xkb::Keysym::Shift_R => xkb::Keysym::Shift_L,
+        xkb::Keysym::Super_R | xkb::Keysym::Meta_L | xkb::Keysym::Meta_R => xkb::Keysym::Super_L,
+        other => other,
+    }
+}
+
+fn parse_pointer_input(input: &str) -> Option<PointerInput> {
+    let input = match input.to_ascii_uppercase().as_str() {
+        "BTN_LEFT" => PointerInput::Button(BTN_LEFT),
+        "BTN_RIGHT" => PointerInput::Button(BTN_LEFT + 1),
+        "BTN_MIDDLE" => PointerInput::Button(BTN_LEFT + 2),
+        "BTN_SIDE" => PointerInput::Button(BTN_LEFT + 3),
+        "BTN_EXTRA" => PointerInput::Button(BTN_LEFT + 4),
+        "BTN_FORWARD" => PointerInput::Button(BTN_LEFT + 5),
+        "BTN_BACK" => PointerInput::Button(BTN_LEFT + 6),
+        "BTN_TASK" => PointerInput::Button(BTN_LEFT + 7),
+        "SCROLLUP" => PointerInput::ScrollUp,
+        "SCROLLDOWN" => PointerInput::ScrollDown,
+        "SCROLLLEFT" => PointerInput::ScrollLeft,
+        "SCROLLRIGHT" => PointerInput::ScrollRight,
+        other => {
+            // Raw evdev codes, e.g. `BUTTON277`
+            let code = other.strip_prefix("BUTTON")?.parse().ok()?;
+            PointerInput::Button(code)
+        }
+    };
+    Some(input)
+}
+
 fn parse_keysym(key: &str) -> Result<xkb::Keysym, ShortcutError> {
     let alias = match key {
         "ArrowUp" => Some("Up"),
@@ -357,7 +992,7 @@ mod tests {
             },
         );
 
-        let bindings = build_bindings(&map);
+        let bindings = build_bindings(&map, &mut Vec::new());
         assert_eq!(bindings.len(), 2);
 
         let quit_binding = bindings
@@ -391,7 +1026,7 @@ mod tests {
             ShortcutActionConfig::BuiltinName("Quit".into()),
         );
 
-        let bindings = build_bindings(&map);
+        let bindings = build_bindings(&map, &mut Vec::new());
         assert_eq!(bindings.len(), 1);
         let binding = &bindings[0];
 
@@ -405,4 +1040,211 @@ mod tests {
         assert!(binding.trigger.matches(&modifiers, uppercase));
         assert!(binding.trigger.matches(&modifiers, lowercase));
     }
+
+    #[test]
+    fn parses_move_window_actions() {
+        let action = parse_action(&ShortcutActionConfig::BuiltinDetailed {
+            builtin: "MoveWindowToWorkspace".into(),
+            index: Some(2),
+            follow: true,
+        })
+        .expect("valid action");
+        assert!(matches!(
+            action,
+            ShortcutAction::Builtin(BuiltinAction::MoveWindowToWorkspace {
+                index: 2,
+                follow: true
+            })
+        ));
+        assert_eq!(action.to_string(), "Move window to workspace 3");
+
+        let action = parse_action(&ShortcutActionConfig::BuiltinName(
+            "MoveWindowToWorkspaceNext".into(),
+        ))
+        .expect("valid action");
+        assert!(matches!(
+            action,
+            ShortcutAction::Builtin(BuiltinAction::MoveWindowToWorkspaceNext { follow: false })
+        ));
+        assert!(matches!(
+            parse_action(&ShortcutActionConfig::BuiltinName(
+                "MoveWindowToWorkspace".into()
+            )),
+            Err(ShortcutError::MissingIndex(_))
+        ));
+    }
+
+    #[test]
+    fn parses_key_sequences() {
+        let keys = parse_sequence("Logo + w  h").expect("valid sequence");
+        assert_eq!(keys.len(), 2);
+        assert!(keys[0].logo);
+        assert_eq!(keys[1].canonical_id(), "h");
+        assert!(matches!(
+            parse_sequence("  "),
+            Err(ShortcutError::InvalidTrigger(_))
+        ));
+    }
+
+    #[test]
+    fn ambiguous_sequences_are_skipped() {
+        let mut map = ShortcutMap::new();
+        map.insert(
+            "Logo+w".into(),
+            ShortcutActionConfig::BuiltinName("Quit".into()),
+        );
+        map.insert(
+            "Logo+w h".into(),
+            ShortcutActionConfig::BuiltinName("WorkspacePrev".into()),
+        );
+
+        let bindings = build_bindings(&map, &mut Vec::new());
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(bindings[0].trigger_repr, "Logo+w");
+
+        let sequence = ShortcutBinding {
+            trigger: parse_trigger("Logo+w").unwrap(),
+            continuation: vec![parse_trigger("h").unwrap()],
+            action: ShortcutAction::Builtin(BuiltinAction::WorkspacePrev),
+            trigger_repr: "Logo+w h".into(),
+        };
+        let err = check_sequence(&bindings, &sequence).unwrap_err();
+        assert!(matches!(err, ShortcutError::AmbiguousSequence { .. }));
+        assert_eq!(
+            err.to_string(),
+            "sequence 'Logo+w h' is ambiguous with 'Logo+w', one starts with the other"
+        );
+    }
+
+    #[test]
+    fn duplicate_sequences_replace_previous_entry() {
+        let mut map = ShortcutMap::new();
+        map.insert(
+            "Logo+W h".into(),
+            ShortcutActionConfig::BuiltinName("Quit".into()),
+        );
+        map.insert(
+            "Logo+w h".into(),
+            ShortcutActionConfig::BuiltinName("WorkspacePrev".into()),
+        );
+
+        let bindings = build_bindings(&map, &mut Vec::new());
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(bindings[0].trigger_repr, "Logo+w h");
+    }
+
+    #[test]
+    fn modes_drop_bindings_shadowed_by_exit_keys() {
+        let mut bindings = ShortcutMap::new();
+        bindings.insert(
+            "Escape".into(),
+            ShortcutActionConfig::BuiltinName("Quit".into()),
+        );
+        bindings.insert(
+            "h".into(),
+            ShortcutActionConfig::EnterMode {
+                mode: "missing".into(),
+            },
+        );
+        bindings.insert(
+            "l".into(),
+            ShortcutActionConfig::BuiltinName("WorkspaceNext".into()),
+        );
+        let mut map = KeyboardModeMap::new();
+        map.insert(
+            "resize".into(),
+            KeyboardModeConfig {
+                exit: default_mode_exit(),
+                oneshot: false,
+                timeout_ms: Some(2000),
+                bindings,
+            },
+        );
+
+        let modes = build_modes(&map, &mut Vec::new());
+        let mode = &modes["resize"];
+        assert_eq!(mode.bindings.len(), 1);
+        assert_eq!(mode.bindings[0].trigger_repr, "l");
+        assert_eq!(mode.timeout, Some(Duration::from_millis(2000)));
+    }
+
+    #[test]
+    fn pointer_and_release_triggers_get_their_own_bindings() {
+        let mut map = ShortcutMap::new();
+        map.insert(
+            "Logo+BTN_SIDE".into(),
+            ShortcutActionConfig::BuiltinName("ExposeShowAll".into()),
+        );
+        map.insert(
+            "Logo+ScrollUp".into(),
+            ShortcutActionConfig::BuiltinName("WorkspacePrev".into()),
+        );
+        map.insert(
+            "Logo:release".into(),
+            ShortcutActionConfig::RunCommand {
+                run: RunCommandConfig {
+                    cmd: "launcher".into(),
+                    args: vec![],
+                },
+            },
+        );
+        map.insert(
+            "Logo+Q".into(),
+            ShortcutActionConfig::BuiltinName("Quit".into()),
+        );
+
+        assert_eq!(build_bindings(&map, &mut Vec::new()).len(), 1);
+
+        let pointer = build_pointer_bindings(&map, &mut Vec::new());
+        assert_eq!(pointer.len(), 2);
+        let mut modifiers = ModifiersState::default();
+        modifiers.logo = true;
+        assert!(pointer.iter().any(|binding| binding
+            .trigger
+            .matches(&modifiers, PointerInput::Button(0x113))));
+        assert!(pointer
+            .iter()
+            .any(|binding| binding.trigger.matches(&modifiers, PointerInput::ScrollUp)));
+
+        let release = build_release_bindings(&map, &mut Vec::new());
+        assert_eq!(release.len(), 1);
+        // Releasing either Super key, the logo modifier of the key itself is ignored
+        assert!(release[0]
+            .trigger
+            .matches_release(&modifiers, xkb::Keysym::Super_R));
+        modifiers.ctrl = true;
+        assert!(!release[0]
+            .trigger
+            .matches_release(&modifiers, xkb::Keysym::Super_L));
+    }
+
+    #[test]
+    fn parses_raw_button_codes() {
+        let trigger = parse_pointer_trigger("Alt+Button277").expect("valid trigger");
+        assert!(trigger.alt);
+        assert_eq!(trigger.input, PointerInput::Button(277));
+        assert!(matches!(
+            parse_pointer_trigger("Alt+Wheel"),
+            Err(ShortcutError::UnknownPointerInput(_))
+        ));
+    }
+
+    #[test]
+    fn window_drag_uses_left_and_right_button() {
+        let drag = build_window_drag("Logo", &mut Vec::new()).expect("valid modifier");
+        let modifiers = ModifiersState {
+            logo: true,
+            ..Default::default()
+        };
+        assert_eq!(drag.matches(&modifiers, BTN_LEFT), Some(WindowDrag::Move));
+        assert_eq!(drag.matches(&modifiers, 0x111), Some(WindowDrag::Resize));
+        assert_eq!(drag.matches(&ModifiersState::default(), BTN_LEFT), None);
+    }
+
+    #[test]
+    fn window_drag_can_be_disabled() {
+        assert!(build_window_drag("None", &mut Vec::new()).is_none());
+        assert!(build_window_drag("Hyper", &mut Vec::new()).is_none());
+        assert!(build_window_drag("Alt+Shift", &mut Vec::new()).is_some());
+    }
 }
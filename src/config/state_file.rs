@@ -0,0 +1,88 @@
+//! State kept across restarts as TOML in `$XDG_STATE_HOME/otto`
+
+use std::{io, marker::PhantomData, path::PathBuf};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::default_apps::xdg_state_home;
+
+/// A TOML file holding a `T` under `$XDG_STATE_HOME/otto`
+#[derive(Debug, Clone)]
+pub struct StateFile<T> {
+    path: Option<PathBuf>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Serialize + DeserializeOwned> StateFile<T> {
+    /// The file `name` in the state directory
+    pub fn new(name: &str) -> Self {
+        Self::at(xdg_state_home().map(|dir| dir.join("otto").join(name)))
+    }
+
+    fn at(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The value saved last, `None` if there is none or it is unreadable
+    pub fn load(&self) -> Option<T> {
+        let content = std::fs::read_to_string(self.path.as_deref()?).ok()?;
+        toml::from_str(&content).ok()
+    }
+
+    pub fn save(&self, value: &T) -> io::Result<()> {
+        let path = self
+            .path
+            .as_deref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no state directory"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let content = toml::to_string(value).map_err(io::Error::other)?;
+        std::fs::write(path, content)
+    }
+
+    /// Forgets the saved value, a missing file is not an error
+    pub fn remove(&self) -> io::Result<()> {
+        let Some(path) = self.path.as_deref() else {
+            return Ok(());
+        };
+        match std::fs::remove_file(path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Saved {
+        names: Vec<String>,
+    }
+
+    #[test]
+    fn saved_state_survives_a_restart() {
+        let dir = std::env::temp_dir().join(format!("otto-state-{}", std::process::id()));
+        let file = StateFile::<Saved>::at(Some(dir.join("otto/state.toml")));
+        assert_eq!(file.load(), None);
+
+        let saved = Saved {
+            names: vec!["Mail".into(), "Code".into()],
+        };
+        file.save(&saved).unwrap();
+        assert_eq!(file.load(), Some(saved));
+
+        file.remove().unwrap();
+        assert_eq!(file.load(), None);
+        file.remove().unwrap();
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,300 @@
+//! Wallpapers, configured in `[wallpaper]`
+//!
+//! ```toml
+//! [wallpaper]
+//! image_light = "~/Pictures/dunes-day.jpg"
+//! image_dark = "~/Pictures/dunes-night.jpg"
+//! mode = "fill"
+//!
+//! [wallpaper.workspaces.2]
+//! gradient = ["#1E3C72", "#2A5298"]
+//!
+//! [wallpaper.outputs."HDMI-A-1"]
+//! directory = "~/Pictures/Wallpapers"
+//! interval_secs = 300
+//! ```
+//!
+//! A workspace entry, matched by number from 1 or by name, wins over the
+//! wallpaper picked through the Wallpaper portal, which wins over an output
+//! entry and the default. Without any, the `background_image` is shown.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::state_file::StateFile;
+use crate::theme::{parse_color, ThemeScheme};
+
+/// How an image covers the screen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScalingMode {
+    /// Scaled to cover the screen, cropping the overflow
+    #[default]
+    Fill,
+    /// Scaled to fit the screen, the rest shows `color`
+    Fit,
+    /// Original size in the middle of the screen
+    Center,
+    /// Repeated from the top left corner at original size
+    Tile,
+    /// Scaled to the screen, ignoring the aspect ratio
+    Stretch,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WallpaperConfig {
+    #[serde(flatten)]
+    pub default: Wallpaper,
+    /// Duration of the cross-fade between two wallpapers
+    pub transition_ms: u64,
+    /// By output connector name
+    pub outputs: BTreeMap<String, Wallpaper>,
+    /// By workspace number, from 1, or name
+    pub workspaces: BTreeMap<String, Wallpaper>,
+}
+
+impl Default for WallpaperConfig {
+    fn default() -> Self {
+        Self {
+            default: Wallpaper::default(),
+            transition_ms: 800,
+            outputs: BTreeMap::new(),
+            workspaces: BTreeMap::new(),
+        }
+    }
+}
+
+/// What to draw; a slideshow `directory` wins over images, images over a
+/// `gradient`, and a gradient over a solid `color`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Wallpaper {
+    pub image: Option<String>,
+    /// Shown with the light scheme instead of `image`
+    pub image_light: Option<String>,
+    /// Shown with the dark scheme instead of `image`
+    pub image_dark: Option<String>,
+    /// Slideshow of the images in a directory
+    pub directory: Option<String>,
+    /// Time each slideshow image is shown
+    pub interval_secs: Option<u64>,
+    /// Slideshow in random order instead of by file name
+    pub shuffle: Option<bool>,
+    /// Solid color, and the color around images that do not cover the
+    /// screen
+    pub color: Option<String>,
+    /// Two or more colors, from top to bottom
+    pub gradient: Vec<String>,
+    /// Rotation of the gradient, in degrees clockwise
+    pub gradient_angle: Option<f32>,
+    pub mode: Option<ScalingMode>,
+}
+
+impl WallpaperConfig {
+    /// The wallpaper of the workspace at `index` shown on `output`.
+    ///
+    /// The most specific entry that sets what to draw is used; `mode` is
+    /// taken from a less specific one if it does not set it.
+    pub fn resolve(
+        &self,
+        index: usize,
+        name: Option<&str>,
+        output: Option<&str>,
+        picked: Option<&Wallpaper>,
+        fallback_image: &str,
+    ) -> Wallpaper {
+        let workspace = self
+            .workspaces
+            .get(&(index + 1).to_string())
+            .or_else(|| name.and_then(|name| self.workspaces.get(name)));
+        let output = output.and_then(|output| self.outputs.get(output));
+        let chain: Vec<&Wallpaper> = [workspace, picked, output, Some(&self.default)]
+            .into_iter()
+            .flatten()
+            .collect();
+
+        let mut wallpaper = chain
+            .iter()
+            .find(|wallpaper| wallpaper.has_source())
+            .map(|wallpaper| (*wallpaper).clone())
+            .unwrap_or_else(|| Wallpaper {
+                image: Some(fallback_image.to_string()),
+                ..Default::default()
+            });
+        wallpaper.mode = chain.iter().find_map(|wallpaper| wallpaper.mode);
+        wallpaper
+    }
+
+    /// Checks colors and gradients
+    pub fn validate(&self) -> Result<(), String> {
+        let entries = std::iter::once(("wallpaper".to_string(), &self.default))
+            .chain(
+                self.outputs
+                    .iter()
+                    .map(|(name, w)| (format!("wallpaper.outputs.{name}"), w)),
+            )
+            .chain(
+                self.workspaces
+                    .iter()
+                    .map(|(name, w)| (format!("wallpaper.workspaces.{name}"), w)),
+            );
+        for (section, wallpaper) in entries {
+            for color in wallpaper.color.iter().chain(&wallpaper.gradient) {
+                if parse_color(color).is_none() {
+                    return Err(format!(
+                        "[{section}] '{color}' is not a #RRGGBB or #RRGGBBAA color"
+                    ));
+                }
+            }
+            if wallpaper.gradient.len() == 1 {
+                return Err(format!("[{section}] a gradient needs two or more colors"));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Wallpaper {
+    pub fn has_source(&self) -> bool {
+        self.image.is_some()
+            || self.image_light.is_some()
+            || self.image_dark.is_some()
+            || self.directory.is_some()
+            || self.color.is_some()
+            || !self.gradient.is_empty()
+    }
+
+    /// The image for `scheme`, if one is set
+    pub fn image_for(&self, scheme: ThemeScheme) -> Option<&str> {
+        let themed = match scheme {
+            ThemeScheme::Dark => &self.image_dark,
+            _ => &self.image_light,
+        };
+        themed.as_ref().or(self.image.as_ref()).map(String::as_str)
+    }
+
+    /// Image files in the slideshow directory, files and directories the
+    /// configuration names
+    pub fn paths(&self) -> impl Iterator<Item = &String> {
+        self.image
+            .iter()
+            .chain(&self.image_light)
+            .chain(&self.image_dark)
+            .chain(&self.directory)
+    }
+}
+
+/// Wallpaper picked through the Wallpaper portal, kept across restarts in
+/// `$XDG_STATE_HOME/otto/wallpaper.toml`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PickedWallpaper {
+    pub image: PathBuf,
+}
+
+impl PickedWallpaper {
+    pub fn state_file() -> StateFile<Self> {
+        StateFile::new("wallpaper.toml")
+    }
+
+    /// As a `[wallpaper]` entry, the mode comes from the configuration
+    pub fn wallpaper(&self) -> Wallpaper {
+        Wallpaper {
+            image: Some(self.image.to_string_lossy().into_owned()),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(toml: &str) -> WallpaperConfig {
+        toml::from_str(toml).expect("wallpaper config should deserialize")
+    }
+
+    #[test]
+    fn workspace_entries_win_over_outputs_and_default() {
+        let config = config(
+            r##"
+            image = "default.jpg"
+            mode = "fit"
+            [workspaces.2]
+            color = "#112233"
+            [workspaces.code]
+            image = "code.png"
+            mode = "tile"
+            [outputs."HDMI-A-1"]
+            directory = "slides"
+            "##,
+        );
+
+        let second = config.resolve(1, None, Some("HDMI-A-1"), None, "fallback.jpg");
+        assert_eq!(second.color.as_deref(), Some("#112233"));
+        assert_eq!(second.image, None);
+        assert_eq!(second.mode, Some(ScalingMode::Fit));
+
+        let named = config.resolve(2, Some("code"), None, None, "fallback.jpg");
+        assert_eq!(named.image.as_deref(), Some("code.png"));
+        assert_eq!(named.mode, Some(ScalingMode::Tile));
+
+        let external = config.resolve(0, None, Some("HDMI-A-1"), None, "fallback.jpg");
+        assert_eq!(external.directory.as_deref(), Some("slides"));
+
+        let internal = config.resolve(0, None, Some("eDP-1"), None, "fallback.jpg");
+        assert_eq!(internal.image.as_deref(), Some("default.jpg"));
+    }
+
+    #[test]
+    fn background_image_is_the_fallback() {
+        let wallpaper = WallpaperConfig::default().resolve(0, None, None, None, "fallback.jpg");
+        assert_eq!(wallpaper.image.as_deref(), Some("fallback.jpg"));
+        assert_eq!(wallpaper.mode, None);
+    }
+
+    #[test]
+    fn picked_wallpaper_wins_over_outputs_but_not_workspaces() {
+        let config = config(
+            r##"
+            mode = "fit"
+            [workspaces.2]
+            color = "#112233"
+            [outputs."HDMI-A-1"]
+            directory = "slides"
+            "##,
+        );
+        let picked = PickedWallpaper {
+            image: PathBuf::from("/photos/lake.jpg"),
+        }
+        .wallpaper();
+
+        let first = config.resolve(0, None, Some("HDMI-A-1"), Some(&picked), "fallback.jpg");
+        assert_eq!(first.image.as_deref(), Some("/photos/lake.jpg"));
+        assert_eq!(first.directory, None);
+        assert_eq!(first.mode, Some(ScalingMode::Fit));
+
+        let second = config.resolve(1, None, Some("HDMI-A-1"), Some(&picked), "fallback.jpg");
+        assert_eq!(second.color.as_deref(), Some("#112233"));
+    }
+
+    #[test]
+    fn scheme_images_replace_the_image() {
+        let wallpaper = config("image = \"day.jpg\"\nimage_dark = \"night.jpg\"").default;
+        assert_eq!(wallpaper.image_for(ThemeScheme::Light), Some("day.jpg"));
+        assert_eq!(wallpaper.image_for(ThemeScheme::Dark), Some("night.jpg"));
+    }
+
+    #[test]
+    fn bad_colors_and_gradients_are_rejected() {
+        assert!(config("color = \"red\"").validate().is_err());
+        assert!(config("[workspaces.1]\ngradient = [\"#000000\"]")
+            .validate()
+            .unwrap_err()
+            .contains("wallpaper.workspaces.1"));
+        assert!(config("gradient = [\"#000000\", \"#FFFFFF\"]")
+            .validate()
+            .is_ok());
+    }
+}
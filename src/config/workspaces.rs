@@ -0,0 +1,132 @@
+//! Workspaces, configured in `[workspaces]`
+//!
+//! ```toml
+//! [workspaces]
+//! count = 3
+//! names = ["Mail", "Code"]
+//! remove_empty = true
+//! ```
+//!
+//! The workspaces, their order and names are kept across restarts in
+//! `$XDG_STATE_HOME/otto/workspaces.toml`; configured names missing from it
+//! are added after the saved workspaces. `count` and `names` apply at
+//! startup.
+
+use serde::{Deserialize, Serialize};
+
+use super::state_file::StateFile;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspacesConfig {
+    /// Workspaces at startup, also the fewest left by `remove_empty`
+    pub count: usize,
+    /// Named workspaces, created at startup and never removed when empty
+    pub names: Vec<String>,
+    /// Removes a workspace without a name once it has no window left and
+    /// another workspace is shown
+    pub remove_empty: bool,
+}
+
+impl Default for WorkspacesConfig {
+    fn default() -> Self {
+        Self {
+            count: 2,
+            names: Vec::new(),
+            remove_empty: false,
+        }
+    }
+}
+
+impl WorkspacesConfig {
+    /// The fewest workspaces to keep
+    pub fn min_count(&self) -> usize {
+        self.count.max(1)
+    }
+
+    /// Names of the workspaces to create at startup, in order, `None` for
+    /// a workspace without a name
+    pub fn initial(&self, saved: Option<&SavedWorkspaces>) -> Vec<Option<String>> {
+        let mut workspaces: Vec<Option<String>> = saved
+            .map(|saved| saved.workspaces.iter().map(|w| w.name.clone()).collect())
+            .unwrap_or_default();
+        for name in &self.names {
+            if !workspaces.iter().any(|w| w.as_deref() == Some(name)) {
+                workspaces.push(Some(name.clone()));
+            }
+        }
+        if workspaces.len() < self.min_count() {
+            workspaces.resize(self.min_count(), None);
+        }
+        workspaces
+    }
+}
+
+/// The workspaces of the last session, in order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedWorkspaces {
+    #[serde(default)]
+    pub workspaces: Vec<SavedWorkspace>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedWorkspace {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl SavedWorkspaces {
+    pub fn state_file() -> StateFile<Self> {
+        StateFile::new("workspaces.toml")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved(names: &[Option<&str>]) -> SavedWorkspaces {
+        SavedWorkspaces {
+            workspaces: names
+                .iter()
+                .map(|name| SavedWorkspace {
+                    name: name.map(str::to_string),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn first_start_uses_the_configuration() {
+        let config = WorkspacesConfig {
+            count: 3,
+            names: vec!["Mail".into()],
+            remove_empty: false,
+        };
+        assert_eq!(
+            config.initial(None),
+            vec![Some("Mail".to_string()), None, None]
+        );
+        assert_eq!(WorkspacesConfig::default().initial(None), vec![None, None]);
+    }
+
+    #[test]
+    fn saved_workspaces_keep_their_order() {
+        let config = WorkspacesConfig {
+            count: 2,
+            names: vec!["Mail".into(), "Code".into()],
+            remove_empty: false,
+        };
+        let saved = saved(&[None, Some("Code"), None, Some("Music")]);
+        assert_eq!(
+            config.initial(Some(&saved)),
+            vec![
+                None,
+                Some("Code".to_string()),
+                None,
+                Some("Music".to_string()),
+                Some("Mail".to_string()),
+            ]
+        );
+    }
+}
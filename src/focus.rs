@@ -29,7 +29,10 @@ use crate::{
     interactive_view::InteractiveView,
     shell::WindowElement,
     state::{Backend, Otto},
-    workspaces::{AppSwitcherView, DockView, WindowSelectorView, WorkspaceSelectorView},
+    workspaces::{
+        AppExposeView, AppSwitcherView, DockView, SplitDivider, WindowSelectorView,
+        WorkspaceSelectorView,
+    },
 };
 
 pub enum KeyboardFocusTarget<B: Backend> {
@@ -685,6 +688,16 @@ impl<B: Backend> From<WindowSelectorView> for PointerFocusTarget<B> {
     }
 }
 
+impl<B: Backend> From<AppExposeView> for PointerFocusTarget<B> {
+    fn from(value: AppExposeView) -> Self {
+        let view = value.clone();
+        let d = InteractiveView {
+            view: Box::new(view),
+        };
+        PointerFocusTarget::View(d)
+    }
+}
+
 impl<B: Backend> From<AppSwitcherView> for PointerFocusTarget<B> {
     fn from(value: AppSwitcherView) -> Self {
         let view = value.clone();
@@ -705,6 +718,16 @@ impl<B: Backend> From<DockView> for PointerFocusTarget<B> {
     }
 }
 
+impl<B: Backend> From<SplitDivider> for PointerFocusTarget<B> {
+    fn from(value: SplitDivider) -> Self {
+        let view = value.clone();
+        let d = InteractiveView {
+            view: Box::new(view),
+        };
+        PointerFocusTarget::View(d)
+    }
+}
+
 impl<B: Backend> From<WorkspaceSelectorView> for PointerFocusTarget<B> {
     fn from(value: WorkspaceSelectorView) -> Self {
         let view = value.clone();
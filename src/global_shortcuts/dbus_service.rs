@@ -0,0 +1,208 @@
+//! D-Bus service implementation for `org.otto.GlobalShortcuts`.
+//!
+//! Implements the backend D-Bus API used by the portal's GlobalShortcuts
+//! implementation, see `otto_client::global_shortcuts` in the portal crate.
+
+use std::collections::HashMap;
+
+use smithay::reexports::calloop::channel::Sender;
+use tokio::sync::{mpsc::UnboundedReceiver, oneshot};
+use tracing::{info, warn};
+use zbus::zvariant::{OwnedValue, Str};
+use zbus::{fdo, interface, Connection, SignalContext};
+
+use super::{
+    BindError, BoundShortcut, GlobalShortcutSignal, GlobalShortcutsCommand, ShortcutRequest,
+};
+
+const GLOBAL_SHORTCUTS_PATH: &str = "/org/otto/GlobalShortcuts";
+
+/// Shortcuts in the portal wire format, `a(sa{sv})`.
+type DBusShortcuts = Vec<(String, HashMap<String, OwnedValue>)>;
+
+fn string_property(properties: &HashMap<String, OwnedValue>, key: &str) -> Option<String> {
+    properties
+        .get(key)
+        .and_then(|value| <&str>::try_from(value).ok())
+        .map(str::to_owned)
+}
+
+fn shortcuts_to_dbus(shortcuts: &[BoundShortcut]) -> DBusShortcuts {
+    shortcuts
+        .iter()
+        .map(|shortcut| {
+            let mut properties = HashMap::new();
+            properties.insert(
+                "description".to_string(),
+                OwnedValue::from(Str::from(shortcut.description.clone())),
+            );
+            properties.insert(
+                "trigger_description".to_string(),
+                OwnedValue::from(Str::from(shortcut.trigger_description())),
+            );
+            (shortcut.id.clone(), properties)
+        })
+        .collect()
+}
+
+/// The GlobalShortcuts D-Bus interface.
+///
+/// Implements `org.otto.GlobalShortcuts` at `/org/otto/GlobalShortcuts`.
+pub struct GlobalShortcutsInterface {
+    /// Channel to send commands to the compositor's main loop.
+    compositor_tx: Sender<GlobalShortcutsCommand>,
+}
+
+#[interface(name = "org.otto.GlobalShortcuts")]
+impl GlobalShortcutsInterface {
+    /// Binds shortcuts for a portal session.
+    ///
+    /// Each shortcut is `(id, { description: s, preferred_trigger: s })`. The
+    /// user confirms or rebinds the triggers before the call returns. Returns
+    /// the bound shortcuts as `(id, { description: s, trigger_description: s })`.
+    async fn bind_shortcuts(
+        &self,
+        session: String,
+        app_id: String,
+        shortcuts: DBusShortcuts,
+    ) -> fdo::Result<DBusShortcuts> {
+        let shortcuts = shortcuts
+            .into_iter()
+            .map(|(id, properties)| ShortcutRequest {
+                id,
+                description: string_property(&properties, "description").unwrap_or_default(),
+                preferred_trigger: string_property(&properties, "preferred_trigger"),
+            })
+            .collect();
+
+        let (response_tx, response_rx) = oneshot::channel();
+        self.compositor_tx
+            .send(GlobalShortcutsCommand::BindShortcuts {
+                session,
+                app_id,
+                shortcuts,
+                response_tx,
+            })
+            .map_err(|e| fdo::Error::Failed(format!("Failed to send command: {e}")))?;
+
+        match response_rx.await {
+            Ok(Ok(bound)) => Ok(shortcuts_to_dbus(&bound)),
+            Ok(Err(err @ BindError::Denied)) => Err(fdo::Error::AccessDenied(err.to_string())),
+            Ok(Err(err)) => Err(fdo::Error::Failed(err.to_string())),
+            Err(_) => Err(fdo::Error::Failed(
+                "Compositor dropped the request".to_string(),
+            )),
+        }
+    }
+
+    /// Lists the shortcuts bound for a portal session.
+    async fn list_shortcuts(&self, session: String) -> fdo::Result<DBusShortcuts> {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.compositor_tx
+            .send(GlobalShortcutsCommand::ListShortcuts {
+                session,
+                response_tx,
+            })
+            .map_err(|e| fdo::Error::Failed(format!("Failed to send command: {e}")))?;
+
+        let shortcuts = response_rx
+            .await
+            .map_err(|_| fdo::Error::Failed("Compositor dropped the request".to_string()))?;
+        Ok(shortcuts_to_dbus(&shortcuts))
+    }
+
+    /// Releases all shortcuts of a portal session.
+    async fn close_session(&self, session: String) -> fdo::Result<()> {
+        self.compositor_tx
+            .send(GlobalShortcutsCommand::CloseSession { session })
+            .map_err(|e| fdo::Error::Failed(format!("Failed to send command: {e}")))
+    }
+
+    /// Emitted when the trigger of a shortcut is pressed.
+    #[zbus(signal)]
+    async fn activated(
+        ctx: &SignalContext<'_>,
+        session: &str,
+        shortcut_id: &str,
+        timestamp: u64,
+    ) -> zbus::Result<()>;
+
+    /// Emitted when the trigger of an activated shortcut is released.
+    #[zbus(signal)]
+    async fn deactivated(
+        ctx: &SignalContext<'_>,
+        session: &str,
+        shortcut_id: &str,
+        timestamp: u64,
+    ) -> zbus::Result<()>;
+
+    /// Emitted when the bound shortcuts of a session change.
+    #[zbus(signal)]
+    async fn shortcuts_changed(
+        ctx: &SignalContext<'_>,
+        session: &str,
+        shortcuts: DBusShortcuts,
+    ) -> zbus::Result<()>;
+}
+
+/// Registers the GlobalShortcuts interface on the existing D-Bus connection
+/// and forwards the compositor signals until the channel closes.
+pub async fn register_global_shortcuts_interface(
+    connection: &Connection,
+    compositor_tx: Sender<GlobalShortcutsCommand>,
+    mut signals: UnboundedReceiver<GlobalShortcutSignal>,
+) -> zbus::Result<()> {
+    connection
+        .object_server()
+        .at(
+            GLOBAL_SHORTCUTS_PATH,
+            GlobalShortcutsInterface { compositor_tx },
+        )
+        .await?;
+
+    connection.request_name("org.otto.GlobalShortcuts").await?;
+
+    let interface = connection
+        .object_server()
+        .interface::<_, GlobalShortcutsInterface>(GLOBAL_SHORTCUTS_PATH)
+        .await?;
+
+    tokio::spawn(async move {
+        while let Some(signal) = signals.recv().await {
+            let ctx = interface.signal_context();
+            let result = match signal {
+                GlobalShortcutSignal::Activated {
+                    session,
+                    shortcut_id,
+                    timestamp,
+                } => {
+                    GlobalShortcutsInterface::activated(ctx, &session, &shortcut_id, timestamp)
+                        .await
+                }
+                GlobalShortcutSignal::Deactivated {
+                    session,
+                    shortcut_id,
+                    timestamp,
+                } => {
+                    GlobalShortcutsInterface::deactivated(ctx, &session, &shortcut_id, timestamp)
+                        .await
+                }
+                GlobalShortcutSignal::ShortcutsChanged { session, shortcuts } => {
+                    GlobalShortcutsInterface::shortcuts_changed(
+                        ctx,
+                        &session,
+                        shortcuts_to_dbus(&shortcuts),
+                    )
+                    .await
+                }
+            };
+            if let Err(err) = result {
+                warn!(?err, "Failed to emit global shortcut signal");
+            }
+        }
+    });
+
+    info!("GlobalShortcuts D-Bus interface registered at org.otto.GlobalShortcuts");
+
+    Ok(())
+}
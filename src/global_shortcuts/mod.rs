@@ -0,0 +1,614 @@
+//! Compositor side of the GlobalShortcuts portal.
+//!
+//! Apps ask the portal backend (`xdg-desktop-portal-otto`) to bind shortcuts
+//! for a session. The backend forwards the request to `org.otto.GlobalShortcuts`,
+//! the compositor asks the user to accept or rebind the requested triggers and
+//! then reports matching key presses as `Activated`/`Deactivated` signals.
+//!
+//! Like screenshare, commands flow from D-Bus to the main loop via
+//! `calloop::channel` and signals flow back via `tokio::sync::mpsc`.
+
+mod dbus_service;
+
+pub use dbus_service::register_global_shortcuts_interface;
+
+use std::collections::{HashMap, VecDeque};
+
+use smithay::input::keyboard::{Keycode, Keysym, ModifiersState};
+use thiserror::Error;
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+use tracing::{info, warn};
+
+use crate::{
+    config::{
+        shortcuts::{parse_trigger, ShortcutTrigger},
+        Config,
+    },
+    input::keyboard::is_modifier_keysym,
+    state::Backend,
+    workspaces::{PromptLine, PromptModel},
+    Otto,
+};
+
+/// A shortcut an app asks to bind
+#[derive(Debug, Clone)]
+pub struct ShortcutRequest {
+    pub id: String,
+    pub description: String,
+    /// Trigger in the shortcuts spec syntax, e.g. `CTRL+ALT+t`
+    pub preferred_trigger: Option<String>,
+}
+
+/// A shortcut bound for a portal session
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundShortcut {
+    pub id: String,
+    pub description: String,
+    /// `None` if the user left the shortcut unassigned
+    pub trigger: Option<ShortcutTrigger>,
+}
+
+impl BoundShortcut {
+    /// Human readable trigger, empty if unassigned
+    pub fn trigger_description(&self) -> String {
+        self.trigger
+            .as_ref()
+            .map(ShortcutTrigger::canonical_id)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum BindError {
+    #[error("the user denied the request")]
+    Denied,
+    #[error("the session was closed")]
+    SessionClosed,
+}
+
+/// Commands sent from the D-Bus service to the compositor main loop.
+#[derive(Debug)]
+pub enum GlobalShortcutsCommand {
+    /// Bind shortcuts for a session, asking the user if needed.
+    BindShortcuts {
+        session: String,
+        app_id: String,
+        shortcuts: Vec<ShortcutRequest>,
+        response_tx: oneshot::Sender<Result<Vec<BoundShortcut>, BindError>>,
+    },
+    /// List the shortcuts bound for a session.
+    ListShortcuts {
+        session: String,
+        response_tx: oneshot::Sender<Vec<BoundShortcut>>,
+    },
+    /// Drop all shortcuts of a session.
+    CloseSession { session: String },
+}
+
+/// Signals sent from the compositor main loop to the D-Bus service.
+#[derive(Debug, Clone)]
+pub enum GlobalShortcutSignal {
+    Activated {
+        session: String,
+        shortcut_id: String,
+        timestamp: u64,
+    },
+    Deactivated {
+        session: String,
+        shortcut_id: String,
+        timestamp: u64,
+    },
+    ShortcutsChanged {
+        session: String,
+        shortcuts: Vec<BoundShortcut>,
+    },
+}
+
+#[derive(Debug)]
+struct ShortcutSession {
+    app_id: String,
+    shortcuts: Vec<BoundShortcut>,
+}
+
+/// A bind request waiting for the user
+#[derive(Debug)]
+struct BindPrompt {
+    session: String,
+    app_id: String,
+    shortcuts: Vec<BoundShortcut>,
+    selected: usize,
+    /// Why the last typed trigger was refused
+    message: Option<String>,
+    response_tx: oneshot::Sender<Result<Vec<BoundShortcut>, BindError>>,
+}
+
+impl BindPrompt {
+    fn model(&self) -> PromptModel {
+        let app = if self.app_id.is_empty() {
+            "An application"
+        } else {
+            self.app_id.as_str()
+        };
+        PromptModel {
+            title: format!("{app} wants to use global shortcuts"),
+            lines: self
+                .shortcuts
+                .iter()
+                .enumerate()
+                .map(|(index, shortcut)| PromptLine {
+                    text: if shortcut.description.is_empty() {
+                        shortcut.id.clone()
+                    } else {
+                        shortcut.description.clone()
+                    },
+                    detail: shortcut
+                        .trigger
+                        .as_ref()
+                        .map(ShortcutTrigger::canonical_id)
+                        .unwrap_or_else(|| "Not assigned".to_string()),
+                    highlighted: index == self.selected,
+                })
+                .collect(),
+            hint: self.message.clone().unwrap_or_else(|| {
+                "Type keys to rebind · Tab next · Backspace clear · Return allow · Esc deny"
+                    .to_string()
+            }),
+        }
+    }
+
+    fn select(&mut self, offset: isize) {
+        let len = self.shortcuts.len() as isize;
+        if len > 0 {
+            self.selected = (self.selected as isize + offset).rem_euclid(len) as usize;
+        }
+        self.message = None;
+    }
+}
+
+/// Shortcuts bound by apps through the GlobalShortcuts portal
+#[derive(Debug, Default)]
+pub struct GlobalShortcuts {
+    sessions: HashMap<String, ShortcutSession>,
+    /// Triggers the user accepted, keyed by app_id and shortcut id. Reused
+    /// without asking when the app binds the same shortcuts again.
+    approved: HashMap<String, HashMap<String, Option<ShortcutTrigger>>>,
+    prompts: VecDeque<BindPrompt>,
+    /// Shortcuts held down: (keycode, session, shortcut id). By keycode, the
+    /// keysym of the release differs when a modifier is released first.
+    pressed: Vec<(Keycode, String, String)>,
+    signals: Option<UnboundedSender<GlobalShortcutSignal>>,
+}
+
+impl GlobalShortcuts {
+    pub fn set_signal_sender(&mut self, signals: UnboundedSender<GlobalShortcutSignal>) {
+        self.signals = Some(signals);
+    }
+
+    fn emit(&self, signal: GlobalShortcutSignal) {
+        if let Some(signals) = &self.signals {
+            let _ = signals.send(signal);
+        }
+    }
+
+    /// Emits `Activated` for the shortcut bound to a key press.
+    /// Returns `true` if a shortcut matched.
+    pub fn activate(
+        &mut self,
+        modifiers: &ModifiersState,
+        keysym: Keysym,
+        keycode: Keycode,
+        time: u32,
+    ) -> bool {
+        let trigger = ShortcutTrigger::from_keypress(modifiers, keysym);
+        let Some((session, shortcut_id)) = self.sessions.iter().find_map(|(session, s)| {
+            s.shortcuts
+                .iter()
+                .find(|shortcut| shortcut.trigger.as_ref() == Some(&trigger))
+                .map(|shortcut| (session.clone(), shortcut.id.clone()))
+        }) else {
+            return false;
+        };
+
+        self.pressed
+            .push((keycode, session.clone(), shortcut_id.clone()));
+        self.emit(GlobalShortcutSignal::Activated {
+            session,
+            shortcut_id,
+            timestamp: time as u64,
+        });
+        true
+    }
+
+    /// Emits `Deactivated` for the shortcuts activated by a key
+    pub fn deactivate(&mut self, keycode: Keycode, time: u32) {
+        let (released, pressed): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pressed)
+            .into_iter()
+            .partition(|(key, _, _)| *key == keycode);
+        self.pressed = pressed;
+        for (_, session, shortcut_id) in released {
+            self.emit(GlobalShortcutSignal::Deactivated {
+                session,
+                shortcut_id,
+                timestamp: time as u64,
+            });
+        }
+    }
+
+    /// Describes what already uses a trigger, if anything
+    fn conflict(
+        &self,
+        config: &Config,
+        session: &str,
+        trigger: &ShortcutTrigger,
+    ) -> Option<String> {
+        if config
+            .shortcut_bindings()
+            .iter()
+            .any(|binding| &binding.trigger == trigger)
+        {
+            return Some("a compositor shortcut".to_string());
+        }
+        self.sessions
+            .iter()
+            .filter(|(id, _)| id.as_str() != session)
+            .find(|(_, s)| {
+                s.shortcuts
+                    .iter()
+                    .any(|shortcut| shortcut.trigger.as_ref() == Some(trigger))
+            })
+            .map(|(_, s)| s.app_id.clone())
+    }
+
+    /// Triggers for a bind request, and whether the user has to confirm them
+    fn resolve_requests(
+        &self,
+        config: &Config,
+        session: &str,
+        app_id: &str,
+        requests: Vec<ShortcutRequest>,
+    ) -> (Vec<BoundShortcut>, bool) {
+        let mut needs_confirmation = false;
+        let shortcuts = requests
+            .into_iter()
+            .map(|request| {
+                let configured = config.global_shortcuts.trigger_for(app_id, &request.id);
+                let approved = self
+                    .approved
+                    .get(app_id)
+                    .and_then(|approved| approved.get(&request.id));
+
+                let trigger = match (configured, approved) {
+                    (Some(trigger), _) => trigger.and_then(|trigger| {
+                        parse_trigger(trigger)
+                            .map_err(|err| {
+                                warn!(app_id, trigger, error = %err, "invalid global shortcut trigger")
+                            })
+                            .ok()
+                    }),
+                    (None, Some(trigger)) => trigger.clone(),
+                    (None, None) => {
+                        needs_confirmation |= config.global_shortcuts.confirm;
+                        request
+                            .preferred_trigger
+                            .as_deref()
+                            .and_then(|trigger| parse_trigger(trigger).ok())
+                            .filter(|trigger| self.conflict(config, session, trigger).is_none())
+                    }
+                };
+
+                BoundShortcut {
+                    id: request.id,
+                    description: request.description,
+                    trigger,
+                }
+            })
+            .collect();
+        (shortcuts, needs_confirmation)
+    }
+
+    fn bind(&mut self, session: String, app_id: String, shortcuts: Vec<BoundShortcut>) {
+        let approved = self.approved.entry(app_id.clone()).or_default();
+        for shortcut in &shortcuts {
+            approved.insert(shortcut.id.clone(), shortcut.trigger.clone());
+        }
+
+        let previous = self.sessions.insert(
+            session.clone(),
+            ShortcutSession {
+                app_id,
+                shortcuts: shortcuts.clone(),
+            },
+        );
+        if previous.is_some_and(|previous| previous.shortcuts != shortcuts) {
+            self.emit(GlobalShortcutSignal::ShortcutsChanged { session, shortcuts });
+        }
+    }
+
+    fn list(&self, session: &str) -> Vec<BoundShortcut> {
+        self.sessions
+            .get(session)
+            .map(|s| s.shortcuts.clone())
+            .unwrap_or_default()
+    }
+
+    fn close_session(&mut self, session: &str) {
+        self.sessions.remove(session);
+        self.pressed.retain(|(_, s, _)| s != session);
+        let (closed, prompts): (VecDeque<_>, VecDeque<_>) = std::mem::take(&mut self.prompts)
+            .into_iter()
+            .partition(|prompt| prompt.session == session);
+        self.prompts = prompts;
+        for prompt in closed {
+            let _ = prompt.response_tx.send(Err(BindError::SessionClosed));
+        }
+    }
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    pub(crate) fn handle_global_shortcuts_command(&mut self, cmd: GlobalShortcutsCommand) {
+        match cmd {
+            GlobalShortcutsCommand::BindShortcuts {
+                session,
+                app_id,
+                shortcuts,
+                response_tx,
+            } => {
+                let (shortcuts, needs_confirmation) = Config::with(|config| {
+                    self.global_shortcuts
+                        .resolve_requests(config, &session, &app_id, shortcuts)
+                });
+                if needs_confirmation {
+                    info!(%app_id, %session, "Asking to confirm global shortcuts");
+                    self.global_shortcuts.prompts.push_back(BindPrompt {
+                        session,
+                        app_id,
+                        shortcuts,
+                        selected: 0,
+                        message: None,
+                        response_tx,
+                    });
+                    self.update_shortcut_prompt();
+                } else {
+                    info!(%app_id, %session, "Binding global shortcuts");
+                    self.global_shortcuts
+                        .bind(session, app_id, shortcuts.clone());
+                    let _ = response_tx.send(Ok(shortcuts));
+                }
+            }
+            GlobalShortcutsCommand::ListShortcuts {
+                session,
+                response_tx,
+            } => {
+                let _ = response_tx.send(self.global_shortcuts.list(&session));
+            }
+            GlobalShortcutsCommand::CloseSession { session } => {
+                self.global_shortcuts.close_session(&session);
+                self.update_shortcut_prompt();
+            }
+        }
+    }
+
+    pub fn is_shortcut_prompt_active(&self) -> bool {
+        !self.global_shortcuts.prompts.is_empty()
+    }
+
+    /// Shows the first pending bind request, or hands the prompt over to
+    /// pending shortcut inhibitor requests
+    fn update_shortcut_prompt(&self) {
+        match self.global_shortcuts.prompts.front() {
+            Some(prompt) => self.workspaces.prompt.show(prompt.model()),
+            None => self.update_inhibit_prompt(),
+        }
+    }
+
+    /// Handles a key press while a bind request is shown
+    pub(crate) fn shortcut_prompt_key(&mut self, modifiers: &ModifiersState, keysym: Keysym) {
+        let plain = !(modifiers.ctrl || modifiers.alt || modifiers.logo || modifiers.shift);
+        let Some(prompt) = self.global_shortcuts.prompts.front_mut() else {
+            return;
+        };
+
+        match keysym {
+            Keysym::Return | Keysym::KP_Enter if plain => self.finish_shortcut_prompt(true),
+            Keysym::Escape if plain => self.finish_shortcut_prompt(false),
+            Keysym::Tab | Keysym::Down if plain => prompt.select(1),
+            Keysym::ISO_Left_Tab | Keysym::Up => prompt.select(-1),
+            Keysym::BackSpace if plain => {
+                if let Some(shortcut) = prompt.shortcuts.get_mut(prompt.selected) {
+                    shortcut.trigger = None;
+                }
+                prompt.message = None;
+            }
+            keysym if is_modifier_keysym(keysym) => return,
+            keysym => {
+                let trigger = ShortcutTrigger::from_keypress(modifiers, keysym);
+                let selected = prompt.selected;
+                let in_prompt = prompt
+                    .shortcuts
+                    .iter()
+                    .enumerate()
+                    .find(|(index, s)| *index != selected && s.trigger.as_ref() == Some(&trigger))
+                    .map(|(_, s)| s.description.clone());
+                let session = prompt.session.clone();
+                let conflict = in_prompt.or_else(|| {
+                    Config::with(|config| {
+                        self.global_shortcuts.conflict(config, &session, &trigger)
+                    })
+                });
+
+                let Some(prompt) = self.global_shortcuts.prompts.front_mut() else {
+                    return;
+                };
+                match conflict {
+                    Some(used_by) => {
+                        prompt.message = Some(format!(
+                            "{} is already used by {used_by}",
+                            trigger.canonical_id()
+                        ));
+                    }
+                    None => {
+                        if let Some(shortcut) = prompt.shortcuts.get_mut(selected) {
+                            shortcut.trigger = Some(trigger);
+                        }
+                        prompt.message = None;
+                    }
+                }
+            }
+        }
+        self.update_shortcut_prompt();
+    }
+
+    fn finish_shortcut_prompt(&mut self, accepted: bool) {
+        let Some(prompt) = self.global_shortcuts.prompts.pop_front() else {
+            return;
+        };
+        if accepted {
+            info!(app_id = %prompt.app_id, "Global shortcuts accepted");
+            let shortcuts = prompt.shortcuts;
+            self.global_shortcuts
+                .bind(prompt.session, prompt.app_id, shortcuts.clone());
+            let _ = prompt.response_tx.send(Ok(shortcuts));
+        } else {
+            info!(app_id = %prompt.app_id, "Global shortcuts denied");
+            let _ = prompt.response_tx.send(Err(BindError::Denied));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(id: &str, trigger: &str) -> ShortcutRequest {
+        ShortcutRequest {
+            id: id.to_string(),
+            description: id.to_string(),
+            preferred_trigger: Some(trigger.to_string()),
+        }
+    }
+
+    const KEY_T: Keycode = Keycode::new(28);
+    const KEY_LEFTALT: Keycode = Keycode::new(64);
+
+    fn ctrl_alt() -> ModifiersState {
+        ModifiersState {
+            ctrl: true,
+            alt: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn new_requests_need_confirmation() {
+        let shortcuts = GlobalShortcuts::default();
+        let config = Config::default();
+        let (bound, confirm) = shortcuts.resolve_requests(
+            &config,
+            "/session/1",
+            "org.example.App",
+            vec![request("talk", "CTRL+ALT+t")],
+        );
+        assert!(confirm);
+        assert_eq!(bound[0].trigger_description(), "Ctrl+Alt+t");
+    }
+
+    #[test]
+    fn configured_triggers_skip_confirmation() {
+        let shortcuts = GlobalShortcuts::default();
+        let mut config = Config::default();
+        config.global_shortcuts.apps.insert(
+            "org.example.App".to_string(),
+            [("talk".to_string(), "None".to_string())].into(),
+        );
+        let (bound, confirm) = shortcuts.resolve_requests(
+            &config,
+            "/session/1",
+            "org.example.App",
+            vec![request("talk", "CTRL+ALT+t")],
+        );
+        assert!(!confirm);
+        assert!(bound[0].trigger.is_none());
+    }
+
+    #[test]
+    fn conflicting_preferred_trigger_is_unassigned() {
+        let mut shortcuts = GlobalShortcuts::default();
+        let config = Config::default();
+        let (bound, _) = shortcuts.resolve_requests(
+            &config,
+            "/session/1",
+            "org.example.First",
+            vec![request("talk", "CTRL+ALT+t")],
+        );
+        shortcuts.bind("/session/1".into(), "org.example.First".into(), bound);
+
+        let (bound, _) = shortcuts.resolve_requests(
+            &config,
+            "/session/2",
+            "org.example.Second",
+            vec![request("mute", "CTRL+ALT+t")],
+        );
+        assert!(bound[0].trigger.is_none());
+    }
+
+    #[test]
+    fn key_presses_emit_activated_and_deactivated() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut shortcuts = GlobalShortcuts::default();
+        shortcuts.set_signal_sender(tx);
+        shortcuts.bind(
+            "/session/1".into(),
+            "org.example.App".into(),
+            vec![BoundShortcut {
+                id: "talk".into(),
+                description: "Push to talk".into(),
+                trigger: parse_trigger("Ctrl+Alt+t").ok(),
+            }],
+        );
+
+        assert!(!shortcuts.activate(&ModifiersState::default(), Keysym::t, KEY_T, 10));
+        assert!(shortcuts.activate(&ctrl_alt(), Keysym::t, KEY_T, 20));
+        shortcuts.deactivate(KEY_T, 30);
+
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(GlobalShortcutSignal::Activated { timestamp: 20, .. })
+        ));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(GlobalShortcutSignal::Deactivated { timestamp: 30, .. })
+        ));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn releasing_the_modifier_first_still_deactivates() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut shortcuts = GlobalShortcuts::default();
+        shortcuts.set_signal_sender(tx);
+        shortcuts.bind(
+            "/session/1".into(),
+            "org.example.App".into(),
+            vec![BoundShortcut {
+                id: "talk".into(),
+                description: "Push to talk".into(),
+                trigger: parse_trigger("Ctrl+Alt+t").ok(),
+            }],
+        );
+
+        assert!(shortcuts.activate(&ctrl_alt(), Keysym::t, KEY_T, 20));
+        shortcuts.deactivate(KEY_LEFTALT, 30);
+        shortcuts.deactivate(KEY_T, 40);
+
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(GlobalShortcutSignal::Activated { timestamp: 20, .. })
+        ));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(GlobalShortcutSignal::Deactivated { timestamp: 40, .. })
+        ));
+        assert!(rx.try_recv().is_err());
+        assert!(shortcuts.pressed.is_empty());
+    }
+}
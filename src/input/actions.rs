@@ -2,6 +2,7 @@ use std::{fs, process::Command, sync::atomic::Ordering};
 
 use smithay::{
     reexports::wayland_protocols::xdg::decoration::zv1::server::zxdg_toplevel_decoration_v1,
+    utils::IsAlive,
     wayland::{compositor::with_states, shell::xdg::XdgToplevelSurfaceData},
 };
 use tracing::{error, info};
@@ -36,12 +37,38 @@ pub enum KeyAction {
     ApplicationSwitchPrev,
     ApplicationSwitchQuit,
     ToggleMaximize,
+    /// Split a fullscreen workspace with a second window, or leave the split
+    ToggleSplitView,
     CloseWindow,
     ApplicationSwitchNextWindow,
     ExposeShowDesktop,
     ExposeShowAll,
+    /// Show the windows of every workspace, grouped per workspace
+    ExposeShowAllWorkspaces,
+    /// Show the windows and recent documents of the focused app
+    ExposeShowApp,
     WorkspaceNum(usize),
+    WorkspaceNext,
+    WorkspacePrev,
+    /// Add a workspace after the last one and switch to it
+    WorkspaceCreate,
+    /// Remove the current workspace
+    WorkspaceRemove,
+    /// Edit the name of the current workspace in the workspace selector
+    WorkspaceRename,
+    /// Move the focused window to a workspace, switching to it if `true`
+    MoveWindowToWorkspace(usize, bool),
+    MoveWindowToWorkspaceNext(bool),
+    MoveWindowToWorkspacePrev(bool),
     SceneSnapshot,
+    /// Enter a keyboard mode by name
+    EnterMode(String),
+    /// Leave the active keyboard mode
+    ExitMode,
+    /// Switch between the light and dark scheme
+    ToggleThemeScheme,
+    /// Forget the wallpaper picked through the portal
+    ResetWallpaper,
     /// Do nothing more
     None,
 }
@@ -52,24 +79,28 @@ impl<BackendData: Backend> Otto<BackendData> {
 
         if let Err(e) = Command::new(&cmd)
             .args(&args)
-            .envs(
-                self.socket_name
-                    .clone()
-                    .map(|v| ("WAYLAND_DISPLAY", v))
-                    .into_iter()
-                    .chain(
-                        #[cfg(feature = "xwayland")]
-                        self.xdisplay.map(|v| ("DISPLAY", format!(":{}", v))),
-                        #[cfg(not(feature = "xwayland"))]
-                        None,
-                    ),
-            )
+            .envs(self.client_env())
             .spawn()
         {
             error!(program = %cmd, err = %e, "Failed to start program");
         }
     }
 
+    /// Display variables for programs started by the compositor
+    pub(crate) fn client_env(&self) -> Vec<(&'static str, String)> {
+        self.socket_name
+            .clone()
+            .map(|v| ("WAYLAND_DISPLAY", v))
+            .into_iter()
+            .chain(
+                #[cfg(feature = "xwayland")]
+                self.xdisplay.map(|v| ("DISPLAY", format!(":{}", v))),
+                #[cfg(not(feature = "xwayland"))]
+                None,
+            )
+            .collect()
+    }
+
     pub(crate) fn process_common_key_action(&mut self, action: KeyAction) {
         match action {
             KeyAction::None => (),
@@ -83,6 +114,54 @@ impl<BackendData: Backend> Otto<BackendData> {
                 self.launch_program(cmd, args);
             }
 
+            KeyAction::EnterMode(mode) => {
+                self.enter_keyboard_mode(&mode);
+            }
+
+            KeyAction::ExitMode => {
+                self.exit_keyboard_mode();
+            }
+
+            KeyAction::ToggleThemeScheme => {
+                self.toggle_theme_scheme();
+            }
+
+            KeyAction::ResetWallpaper => {
+                self.reset_picked_wallpaper();
+            }
+
+            KeyAction::ToggleSplitView => {
+                self.toggle_split_view();
+            }
+
+            KeyAction::ExposeShowApp => {
+                self.handle_expose_show_app();
+            }
+
+            KeyAction::WorkspaceCreate => {
+                self.create_workspace();
+            }
+
+            KeyAction::WorkspaceRemove => {
+                self.remove_current_workspace();
+            }
+
+            KeyAction::WorkspaceRename => {
+                self.start_workspace_rename();
+            }
+
+            KeyAction::MoveWindowToWorkspace(index, follow) => {
+                self.move_focused_window_to_workspace(index, follow);
+            }
+
+            KeyAction::MoveWindowToWorkspaceNext(follow) => {
+                self.move_focused_window_to_adjacent_workspace(true, follow);
+            }
+
+            KeyAction::MoveWindowToWorkspacePrev(follow) => {
+                self.move_focused_window_to_adjacent_workspace(false, follow);
+            }
+
             KeyAction::ToggleDecorations => {
                 for element in self.workspaces.spaces_elements() {
                     #[allow(irrefutable_let_patterns)]
@@ -194,13 +273,70 @@ impl<BackendData: Backend> Otto<BackendData> {
             // Dismiss all popups before entering expose mode
             // to release pointer grabs that would intercept events
             self.dismiss_all_popups();
+            self.workspaces.set_expose_global(false);
+            self.workspaces.expose_set_visible(true);
+        }
+    }
+
+    pub(crate) fn handle_expose_show_all_workspaces(&mut self) {
+        if self.workspaces.get_show_all() {
+            if self.workspaces.is_expose_global() {
+                self.workspaces.expose_set_visible(false);
+            } else {
+                // Exposé of the current workspace opens up to all of them
+                self.workspaces.set_expose_global(true);
+                self.workspaces.expose_set_visible(true);
+            }
+        } else {
+            if self.workspaces.get_show_desktop() {
+                self.workspaces.expose_show_desktop(-1.0, true);
+                return;
+            }
+            self.dismiss_all_popups();
+            self.workspaces.set_expose_global(true);
             self.workspaces.expose_set_visible(true);
         }
     }
 
+    pub(crate) fn handle_expose_show_app(&mut self) {
+        if self.workspaces.app_expose.alive() {
+            self.workspaces.hide_app_expose();
+            return;
+        }
+        let Some(window) = self.focused_window() else {
+            return;
+        };
+        self.dismiss_all_popups();
+        self.workspaces.show_app_expose(&window.xdg_app_id());
+    }
+
     pub(crate) fn handle_workspace_num(&mut self, n: usize) {
         self.set_current_workspace_index(n);
     }
+
+    pub(crate) fn handle_workspace_next(&mut self) {
+        let current = self.workspaces.get_current_workspace_index();
+        let count = self.workspaces.with_model(|m| m.workspaces.len());
+        self.set_current_workspace_index(wrapping_workspace_index(current, count, true));
+    }
+
+    pub(crate) fn handle_workspace_prev(&mut self) {
+        let current = self.workspaces.get_current_workspace_index();
+        let count = self.workspaces.with_model(|m| m.workspaces.len());
+        self.set_current_workspace_index(wrapping_workspace_index(current, count, false));
+    }
+}
+
+/// The workspace after `current`, or before it, going round past the ends
+pub(crate) fn wrapping_workspace_index(current: usize, count: usize, forward: bool) -> usize {
+    if count == 0 {
+        return 0;
+    }
+    if forward {
+        (current + 1) % count
+    } else {
+        (current + count - 1) % count
+    }
 }
 
 pub fn resolve_shortcut_action(config: &Config, action: &ShortcutAction) -> Option<KeyAction> {
@@ -216,14 +352,34 @@ pub fn resolve_shortcut_action(config: &Config, action: &ShortcutAction) -> Opti
             BuiltinAction::ApplicationSwitchPrev => Some(KeyAction::ApplicationSwitchPrev),
             BuiltinAction::ApplicationSwitchQuit => Some(KeyAction::ApplicationSwitchQuit),
             BuiltinAction::ToggleMaximizeWindow => Some(KeyAction::ToggleMaximize),
+            BuiltinAction::ToggleSplitView => Some(KeyAction::ToggleSplitView),
             BuiltinAction::CloseWindow => Some(KeyAction::CloseWindow),
             BuiltinAction::ApplicationSwitchNextWindow => {
                 Some(KeyAction::ApplicationSwitchNextWindow)
             }
             BuiltinAction::ExposeShowDesktop => Some(KeyAction::ExposeShowDesktop),
             BuiltinAction::ExposeShowAll => Some(KeyAction::ExposeShowAll),
+            BuiltinAction::ExposeShowAllWorkspaces => Some(KeyAction::ExposeShowAllWorkspaces),
+            BuiltinAction::ExposeShowApp => Some(KeyAction::ExposeShowApp),
             BuiltinAction::WorkspaceNum { index } => Some(KeyAction::WorkspaceNum(*index)),
+            BuiltinAction::WorkspaceNext => Some(KeyAction::WorkspaceNext),
+            BuiltinAction::WorkspacePrev => Some(KeyAction::WorkspacePrev),
+            BuiltinAction::WorkspaceCreate => Some(KeyAction::WorkspaceCreate),
+            BuiltinAction::WorkspaceRemove => Some(KeyAction::WorkspaceRemove),
+            BuiltinAction::WorkspaceRename => Some(KeyAction::WorkspaceRename),
+            BuiltinAction::MoveWindowToWorkspace { index, follow } => {
+                Some(KeyAction::MoveWindowToWorkspace(*index, *follow))
+            }
+            BuiltinAction::MoveWindowToWorkspaceNext { follow } => {
+                Some(KeyAction::MoveWindowToWorkspaceNext(*follow))
+            }
+            BuiltinAction::MoveWindowToWorkspacePrev { follow } => {
+                Some(KeyAction::MoveWindowToWorkspacePrev(*follow))
+            }
             BuiltinAction::SceneSnapshot => Some(KeyAction::SceneSnapshot),
+            BuiltinAction::ExitMode => Some(KeyAction::ExitMode),
+            BuiltinAction::ToggleThemeScheme => Some(KeyAction::ToggleThemeScheme),
+            BuiltinAction::ResetWallpaper => Some(KeyAction::ResetWallpaper),
         },
         ShortcutAction::RunCommand(run) => {
             Some(KeyAction::Run((run.cmd.clone(), run.args.clone())))
@@ -240,6 +396,7 @@ pub fn resolve_shortcut_action(config: &Config, action: &ShortcutAction) -> Opti
                 }
             }
         }
+        ShortcutAction::EnterMode(mode) => Some(KeyAction::EnterMode(mode.clone())),
     }
 }
 
@@ -258,6 +415,14 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn workspace_navigation_wraps_around() {
+        assert_eq!(wrapping_workspace_index(0, 3, true), 1);
+        assert_eq!(wrapping_workspace_index(2, 3, true), 0);
+        assert_eq!(wrapping_workspace_index(0, 3, false), 2);
+        assert_eq!(wrapping_workspace_index(0, 1, false), 0);
+    }
+
     #[test]
     fn run_command_maps_to_key_action() {
         let config = Config::default();
@@ -0,0 +1,115 @@
+//! Per-device input configuration
+//!
+//! Resolves `[[input.devices]]` entries for the device that produced an
+//! event. Libinput options are applied by the udev backend when a device is
+//! added; the settings handled here (scroll factor and per-keyboard XKB
+//! layout) are applied while events are processed. The XKB layout of a
+//! keyboard is resolved once, when the device is added.
+
+use smithay::{
+    backend::input::{Device, DeviceCapability},
+    input::keyboard::XkbConfig,
+};
+use tracing::{debug, warn};
+
+use crate::{
+    config::{Config, InputDeviceDescriptor, InputDeviceKind},
+    state::Backend,
+    Otto,
+};
+
+/// Builds the descriptor used to match a device against `[[input.devices]]`
+pub fn device_descriptor<D: Device>(device: &D) -> InputDeviceDescriptor {
+    let mut kinds = Vec::new();
+    if device.has_capability(DeviceCapability::Keyboard) {
+        kinds.push(InputDeviceKind::Keyboard);
+    }
+    if device.has_capability(DeviceCapability::Pointer) {
+        // libinput only reports gestures for touchpads
+        if device.has_capability(DeviceCapability::Gesture) {
+            kinds.push(InputDeviceKind::Touchpad);
+        } else {
+            kinds.push(InputDeviceKind::Pointer);
+        }
+    }
+    if device.has_capability(DeviceCapability::Touch) {
+        kinds.push(InputDeviceKind::Touchscreen);
+    }
+    if device.has_capability(DeviceCapability::TabletTool) {
+        kinds.push(InputDeviceKind::Tablet);
+    }
+
+    let (vendor, product) = device
+        .usb_id()
+        .map(|(vendor, product)| (Some(vendor), Some(product)))
+        .unwrap_or((None, None));
+
+    InputDeviceDescriptor {
+        name: device.name(),
+        vendor,
+        product,
+        kinds,
+    }
+}
+
+/// Scroll multiplier configured for the device, 1.0 when unset
+pub fn scroll_factor<D: Device>(device: &D) -> f64 {
+    let descriptor = device_descriptor(device);
+    Config::with(|config| config.input.device_settings(&descriptor).scroll_factor).unwrap_or(1.0)
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Resolves the keymap configured for `device`, kept until the device is
+    /// removed or the configuration changes
+    pub(crate) fn cache_device_keymap<D: Device>(&mut self, device: &D) {
+        let descriptor = device_descriptor(device);
+        let xkb = Config::with(|config| config.input.xkb_settings_for(&descriptor));
+        self.device_keymaps.insert(device.id(), xkb);
+    }
+
+    pub(crate) fn forget_device_keymap<D: Device>(&mut self, device: &D) {
+        self.device_keymaps.remove(&device.id());
+    }
+
+    /// Switches the seat keymap to the layout configured for `device`.
+    ///
+    /// Wayland has a single keymap per seat, so the keymap follows the
+    /// keyboard that was typed on last.
+    pub(crate) fn update_keymap_for_device<D: Device>(&mut self, device: &D) {
+        let id = device.id();
+        if !self.device_keymaps.contains_key(&id) {
+            self.cache_device_keymap(device);
+        }
+        let xkb = &self.device_keymaps[&id];
+        if *xkb == self.active_xkb_settings {
+            return;
+        }
+        let xkb = xkb.clone();
+
+        let Some(keyboard) = self.seat.get_keyboard() else {
+            return;
+        };
+        let xkb_config = XkbConfig {
+            layout: &xkb.layout,
+            variant: &xkb.variant,
+            options: xkb.options_string(),
+            ..Default::default()
+        };
+        match keyboard.set_xkb_config(self, xkb_config) {
+            Ok(()) => {
+                debug!(
+                    device = device.name(),
+                    layout = xkb.layout,
+                    variant = xkb.variant,
+                    "Switched keymap"
+                );
+                self.active_xkb_settings = xkb;
+            }
+            Err(err) => {
+                warn!(device = device.name(), ?err, "Failed to switch keymap");
+                // Don't retry on every key press
+                self.active_xkb_settings = xkb;
+            }
+        }
+    }
+}
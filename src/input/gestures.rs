@@ -12,6 +12,40 @@ use smithay::{
     utils::SERIAL_COUNTER as SCOUNTER,
 };
 
+#[cfg(feature = "udev")]
+use crate::{
+    config::{
+        gestures::{ContinuousAction, GestureDirection, GestureTrigger},
+        Config,
+    },
+    input::actions::{resolve_shortcut_action, KeyAction},
+    state::{GestureState, SwipeDirection},
+};
+
+/// What a gesture does once its direction is known
+#[cfg(feature = "udev")]
+enum GestureOutcome {
+    Continuous(ContinuousAction, GestureDirection),
+    Discrete(KeyAction),
+}
+
+#[cfg(feature = "udev")]
+fn push_velocity_sample(samples: &mut Vec<f64>, sample: f64) {
+    samples.push(sample);
+    if samples.len() > crate::state::VELOCITY_SAMPLE_COUNT {
+        samples.remove(0);
+    }
+}
+
+#[cfg(feature = "udev")]
+fn average_velocity(samples: &[f64]) -> f64 {
+    if samples.is_empty() {
+        0.0
+    } else {
+        samples.iter().sum::<f64>() / samples.len() as f64
+    }
+}
+
 #[cfg(feature = "udev")]
 impl crate::Otto<crate::udev::UdevData> {
     pub(crate) fn on_gesture_swipe_begin<B: InputBackend>(
@@ -21,10 +55,11 @@ impl crate::Otto<crate::udev::UdevData> {
         let serial = SCOUNTER.next_serial();
         let pointer = self.pointer.clone();
 
-        // 3-finger swipe: start detecting direction (but not if show desktop is active)
-        let is_show_desktop_active = self.workspaces.get_show_desktop();
-        if evt.fingers() == 3 && !self.is_pinching && !is_show_desktop_active {
-            self.gesture_swipe_begin_3finger();
+        if !self.pinch_gesture.is_active() && Self::has_gesture_bindings(evt.fingers()) {
+            self.swipe_gesture = GestureState::Detecting {
+                fingers: evt.fingers(),
+                accumulated: (0.0, 0.0),
+            };
         }
 
         pointer.gesture_swipe_begin(
@@ -37,12 +72,6 @@ impl crate::Otto<crate::udev::UdevData> {
         );
     }
 
-    fn gesture_swipe_begin_3finger(&mut self) {
-        self.swipe_gesture = crate::state::SwipeGestureState::Detecting {
-            accumulated: (0.0, 0.0),
-        };
-    }
-
     pub(crate) fn on_gesture_swipe_update<B: InputBackend>(
         &mut self,
         evt: B::GestureSwipeUpdateEvent,
@@ -51,60 +80,30 @@ impl crate::Otto<crate::udev::UdevData> {
         let delta = evt.delta();
 
         match &mut self.swipe_gesture {
-            crate::state::SwipeGestureState::Detecting { accumulated } => {
+            GestureState::Detecting {
+                fingers,
+                accumulated,
+            } => {
                 accumulated.0 += delta.x;
                 accumulated.1 += delta.y;
 
-                let direction = crate::state::SwipeDirection::from_accumulated(
-                    accumulated.0.abs(),
-                    accumulated.1.abs(),
-                );
-
-                match direction {
-                    crate::state::SwipeDirection::Horizontal(_) => {
-                        // Initialize workspace switching mode and apply current delta
-                        self.swipe_gesture = crate::state::SwipeGestureState::WorkspaceSwitching {
-                            velocity_samples: vec![delta.x],
-                        };
-                        // Apply the current frame's delta (not accumulated)
-                        self.workspaces.workspace_swipe_update(delta.x as f32);
-                    }
-                    crate::state::SwipeDirection::Vertical(_) => {
-                        // Initialize expose mode and apply current delta
-                        self.dismiss_all_popups();
-
-                        // Reset accumulated gesture value to prevent accumulation across repeated gestures
-                        self.workspaces.reset_expose_gesture();
-
-                        self.swipe_gesture = crate::state::SwipeGestureState::Expose {
-                            velocity_samples: vec![-delta.y],
-                        };
-                        // Apply the current frame's delta (not accumulated)
-                        let expose_delta =
-                            (-delta.y / crate::state::EXPOSE_DELTA_MULTIPLIER) as f32;
-                        self.workspaces.expose_update(expose_delta);
+                let fingers = *fingers;
+                if let Some(direction) =
+                    SwipeDirection::from_accumulated(accumulated.0, accumulated.1)
+                        .to_gesture_direction()
+                {
+                    self.swipe_gesture = self.start_gesture(fingers, direction);
+                    // Apply the current frame's delta (not accumulated)
+                    if let GestureState::Continuous { direction, .. } = self.swipe_gesture {
+                        self.update_continuous_swipe(direction.project_swipe(delta.x, delta.y));
                     }
-                    crate::state::SwipeDirection::Undetermined => {}
                 }
             }
-            crate::state::SwipeGestureState::WorkspaceSwitching { velocity_samples } => {
-                velocity_samples.push(delta.x);
-                if velocity_samples.len() > crate::state::VELOCITY_SAMPLE_COUNT {
-                    velocity_samples.remove(0);
-                }
-                self.workspaces.workspace_swipe_update(delta.x as f32);
+            GestureState::Continuous { direction, .. } => {
+                let amount = direction.project_swipe(delta.x, delta.y);
+                self.update_continuous_swipe(amount);
             }
-            crate::state::SwipeGestureState::Expose { velocity_samples } => {
-                // Collect velocity samples for momentum-based spring animation
-                velocity_samples.push(-delta.y);
-                if velocity_samples.len() > crate::state::VELOCITY_SAMPLE_COUNT {
-                    velocity_samples.remove(0);
-                }
-
-                let expose_delta = (-delta.y / crate::state::EXPOSE_DELTA_MULTIPLIER) as f32;
-                self.workspaces.expose_update(expose_delta);
-            }
-            crate::state::SwipeGestureState::Idle => {}
+            GestureState::Triggered | GestureState::Idle => {}
         }
 
         pointer.gesture_swipe_update(
@@ -116,21 +115,30 @@ impl crate::Otto<crate::udev::UdevData> {
         );
     }
 
+    fn update_continuous_swipe(&mut self, amount: f64) {
+        if let GestureState::Continuous {
+            action,
+            velocity_samples,
+            ..
+        } = &mut self.swipe_gesture
+        {
+            push_velocity_sample(velocity_samples, amount);
+            let action = *action;
+            self.update_continuous_action(action, amount);
+        }
+    }
+
     pub(crate) fn on_gesture_swipe_end<B: InputBackend>(&mut self, evt: B::GestureSwipeEndEvent) {
         let serial = SCOUNTER.next_serial();
         let pointer = self.pointer.clone();
 
-        match std::mem::replace(
-            &mut self.swipe_gesture,
-            crate::state::SwipeGestureState::Idle,
-        ) {
-            crate::state::SwipeGestureState::Expose { velocity_samples } => {
-                self.gesture_swipe_end_expose(velocity_samples);
-            }
-            crate::state::SwipeGestureState::WorkspaceSwitching { velocity_samples } => {
-                self.gesture_swipe_end_workspace(velocity_samples, evt.cancelled());
-            }
-            _ => {}
+        if let GestureState::Continuous {
+            action,
+            velocity_samples,
+            ..
+        } = std::mem::replace(&mut self.swipe_gesture, GestureState::Idle)
+        {
+            self.end_continuous_action(action, &velocity_samples, evt.cancelled());
         }
 
         pointer.gesture_swipe_end(
@@ -143,39 +151,6 @@ impl crate::Otto<crate::udev::UdevData> {
         );
     }
 
-    fn gesture_swipe_end_expose(&mut self, velocity_samples: Vec<f64>) {
-        // Calculate average velocity from samples
-        let velocity = if velocity_samples.is_empty() {
-            0.0
-        } else {
-            velocity_samples.iter().sum::<f64>() / velocity_samples.len() as f64
-        };
-
-        self.workspaces.expose_end_with_velocity(velocity as f32);
-    }
-
-    fn gesture_swipe_end_workspace(&mut self, velocity_samples: Vec<f64>, cancelled: bool) {
-        let target_index = if !cancelled {
-            let velocity = if velocity_samples.is_empty() {
-                0.0
-            } else {
-                velocity_samples.iter().sum::<f64>() / velocity_samples.len() as f64
-            };
-            Some(self.workspaces.workspace_swipe_end(velocity as f32))
-        } else {
-            Some(self.workspaces.workspace_swipe_end(0.0))
-        };
-
-        // Update keyboard focus to top window of the target workspace
-        if let Some(index) = target_index {
-            if let Some(top_wid) = self.workspaces.get_top_window_of_workspace(index) {
-                self.set_keyboard_focus_on_surface(&top_wid);
-            } else {
-                self.clear_keyboard_focus();
-            }
-        }
-    }
-
     pub(crate) fn on_gesture_pinch_begin<B: InputBackend>(
         &mut self,
         evt: B::GesturePinchBeginEvent,
@@ -183,13 +158,13 @@ impl crate::Otto<crate::udev::UdevData> {
         let serial = SCOUNTER.next_serial();
         let pointer = self.pointer.clone();
 
-        // 4-finger pinch for show desktop (don't activate if we're in a swipe gesture or expose is active)
-        let is_swiping = !matches!(self.swipe_gesture, crate::state::SwipeGestureState::Idle);
-        let is_expose_active = self.workspaces.get_show_all();
-        if evt.fingers() == 4 && !is_swiping && !is_expose_active {
-            self.is_pinching = true;
+        // Don't start a pinch while a swipe gesture is running
+        if !self.swipe_gesture.is_active() && Self::has_gesture_bindings(evt.fingers()) {
+            self.pinch_gesture = GestureState::Detecting {
+                fingers: evt.fingers(),
+                accumulated: (0.0, 0.0),
+            };
             self.pinch_last_scale = 1.0; // Reset to baseline
-            self.workspaces.reset_show_desktop_gesture();
         }
 
         pointer.gesture_pinch_begin(
@@ -208,21 +183,26 @@ impl crate::Otto<crate::udev::UdevData> {
     ) {
         let pointer = self.pointer.clone();
 
-        if self.is_pinching {
-            // Scale > 1.0 = pinch out (spread fingers) = show desktop (positive delta)
-            // Scale < 1.0 = pinch in (close fingers) = hide desktop (negative delta)
-            let current_scale = evt.scale() as f32;
-            let last_scale = self.pinch_last_scale as f32;
-
-            // Calculate the change in scale since last event
-            let scale_delta = current_scale - last_scale;
-
-            // Pinching out (positive delta) should show desktop (positive)
-            // Amplify the gesture for better sensitivity (reduced from 5.0 to 2.5)
-            let delta = scale_delta * 1.5;
-
-            self.pinch_last_scale = current_scale as f64;
-            self.workspaces.expose_show_desktop(delta, false);
+        // Scale > 1.0 = pinch out (spread fingers), scale < 1.0 = pinch in
+        let scale_delta = evt.scale() - self.pinch_last_scale;
+        self.pinch_last_scale = evt.scale();
+
+        match &self.pinch_gesture {
+            GestureState::Detecting { fingers, .. } => {
+                let fingers = *fingers;
+                let deviation = evt.scale() - 1.0;
+                if deviation.abs() > crate::state::PINCH_THRESHOLD {
+                    let direction = if deviation > 0.0 {
+                        GestureDirection::PinchOut
+                    } else {
+                        GestureDirection::PinchIn
+                    };
+                    self.pinch_gesture = self.start_gesture(fingers, direction);
+                    self.update_continuous_pinch(scale_delta);
+                }
+            }
+            GestureState::Continuous { .. } => self.update_continuous_pinch(scale_delta),
+            GestureState::Triggered | GestureState::Idle => {}
         }
 
         pointer.gesture_pinch_update(
@@ -236,14 +216,35 @@ impl crate::Otto<crate::udev::UdevData> {
         );
     }
 
+    fn update_continuous_pinch(&mut self, scale_delta: f64) {
+        if let GestureState::Continuous {
+            action,
+            direction,
+            velocity_samples,
+        } = &mut self.pinch_gesture
+        {
+            // Amplify the scale change to the range of a swipe in px
+            let amount =
+                direction.project_pinch(scale_delta) * crate::state::PINCH_DELTA_MULTIPLIER;
+            push_velocity_sample(velocity_samples, amount);
+            let action = *action;
+            self.update_continuous_action(action, amount);
+        }
+    }
+
     pub(crate) fn on_gesture_pinch_end<B: InputBackend>(&mut self, evt: B::GesturePinchEndEvent) {
         let serial = SCOUNTER.next_serial();
         let pointer = self.pointer.clone();
 
-        if self.is_pinching {
-            self.workspaces.expose_show_desktop(0.0, true);
-            self.is_pinching = false;
+        if let GestureState::Continuous {
+            action,
+            velocity_samples,
+            ..
+        } = std::mem::replace(&mut self.pinch_gesture, GestureState::Idle)
+        {
+            self.end_continuous_action(action, &velocity_samples, evt.cancelled());
         }
+
         pointer.gesture_pinch_end(
             self,
             &GesturePinchEndEvent {
@@ -257,6 +258,10 @@ impl crate::Otto<crate::udev::UdevData> {
     pub(crate) fn on_gesture_hold_begin<B: InputBackend>(&mut self, evt: B::GestureHoldBeginEvent) {
         let serial = SCOUNTER.next_serial();
         let pointer = self.pointer.clone();
+
+        // libinput reports a hold as soon as fingers rest on the touchpad
+        self.cancel_kinetic_scroll();
+        self.hold_gesture = Some((evt.fingers(), evt.time_msec()));
         pointer.gesture_hold_begin(
             self,
             &GestureHoldBeginEvent {
@@ -270,6 +275,19 @@ impl crate::Otto<crate::udev::UdevData> {
     pub(crate) fn on_gesture_hold_end<B: InputBackend>(&mut self, evt: B::GestureHoldEndEvent) {
         let serial = SCOUNTER.next_serial();
         let pointer = self.pointer.clone();
+
+        // A hold that turns into a swipe or pinch ends cancelled
+        if let Some((fingers, start)) = self.hold_gesture.take() {
+            let held = evt.time_msec().wrapping_sub(start);
+            if !evt.cancelled() && held >= crate::state::HOLD_MIN_DURATION_MS {
+                if let Some(GestureOutcome::Discrete(action)) =
+                    self.gesture_outcome(fingers, GestureDirection::Hold)
+                {
+                    self.process_key_action(action);
+                }
+            }
+        }
+
         pointer.gesture_hold_end(
             self,
             &GestureHoldEndEvent {
@@ -279,6 +297,159 @@ impl crate::Otto<crate::udev::UdevData> {
             },
         );
     }
+
+    fn has_gesture_bindings(fingers: u32) -> bool {
+        Config::with(|config| {
+            config
+                .gesture_bindings()
+                .iter()
+                .any(|binding| binding.trigger.fingers == fingers)
+        })
+    }
+
+    /// Looks up the binding for a gesture. A gesture in the opposite direction
+    /// of a bound toggle (e.g. swiping down while exposé is open) closes it.
+    fn gesture_outcome(&self, fingers: u32, direction: GestureDirection) -> Option<GestureOutcome> {
+        let show_all = self.workspaces.get_show_all();
+        let show_desktop = self.workspaces.get_show_desktop();
+
+        Config::with(|config| {
+            let find = |direction| {
+                config
+                    .gesture_bindings()
+                    .iter()
+                    .find(|binding| binding.trigger == GestureTrigger { fingers, direction })
+                    .map(|binding| &binding.action)
+            };
+
+            if let Some(action) = find(direction) {
+                // Holds have no movement to follow, so they always run once
+                let continuous = ContinuousAction::from_action(action)
+                    .filter(|_| direction != GestureDirection::Hold);
+                return match continuous {
+                    Some(continuous) => Some(GestureOutcome::Continuous(continuous, direction)),
+                    None => resolve_shortcut_action(config, action).map(GestureOutcome::Discrete),
+                };
+            }
+
+            let opposite = direction.opposite()?;
+            let continuous = ContinuousAction::from_action(find(opposite)?)
+                .filter(|continuous| continuous.is_toggle())?;
+            let is_open = match continuous {
+                ContinuousAction::Expose | ContinuousAction::ExposeAllWorkspaces => show_all,
+                ContinuousAction::ShowDesktop => show_desktop,
+                _ => false,
+            };
+            is_open.then_some(GestureOutcome::Continuous(continuous, opposite))
+        })
+    }
+
+    /// Starts the action bound to a gesture whose direction was just detected
+    fn start_gesture(&mut self, fingers: u32, direction: GestureDirection) -> GestureState {
+        match self.gesture_outcome(fingers, direction) {
+            Some(GestureOutcome::Continuous(action, direction)) => {
+                if self.begin_continuous_action(action) {
+                    GestureState::Continuous {
+                        action,
+                        direction,
+                        velocity_samples: Vec::new(),
+                    }
+                } else {
+                    GestureState::Triggered
+                }
+            }
+            Some(GestureOutcome::Discrete(action)) => {
+                self.process_key_action(action);
+                GestureState::Triggered
+            }
+            None => GestureState::Triggered,
+        }
+    }
+
+    /// Prepares a continuous action, returns `false` if it can't run now
+    fn begin_continuous_action(&mut self, action: ContinuousAction) -> bool {
+        match action {
+            ContinuousAction::Expose | ContinuousAction::ExposeAllWorkspaces => {
+                if self.workspaces.get_show_desktop() {
+                    return false;
+                }
+                if !self.workspaces.get_show_all() {
+                    self.workspaces
+                        .set_expose_global(action == ContinuousAction::ExposeAllWorkspaces);
+                }
+                self.dismiss_all_popups();
+                // Reset accumulated gesture value to prevent accumulation across repeated gestures
+                self.workspaces.reset_expose_gesture();
+            }
+            ContinuousAction::ShowDesktop => {
+                if self.workspaces.get_show_all() {
+                    return false;
+                }
+                self.workspaces.reset_show_desktop_gesture();
+            }
+            ContinuousAction::WorkspaceNext | ContinuousAction::WorkspacePrev => {
+                // Workspaces don't move while the desktop is shown
+                if self.workspaces.get_show_desktop() {
+                    return false;
+                }
+                let current = self.workspaces.get_current_workspace_index();
+                self.workspaces.remove_empty_workspaces(current);
+            }
+        }
+        true
+    }
+
+    /// Moves a continuous action by `amount` px along its opening direction
+    fn update_continuous_action(&mut self, action: ContinuousAction, amount: f64) {
+        let delta = amount / crate::state::EXPOSE_DELTA_MULTIPLIER;
+        match action {
+            ContinuousAction::Expose | ContinuousAction::ExposeAllWorkspaces => {
+                self.workspaces.expose_update(delta as f32)
+            }
+            ContinuousAction::ShowDesktop => {
+                self.workspaces.expose_show_desktop(delta as f32, false)
+            }
+            // Negative deltas move toward the next workspace
+            ContinuousAction::WorkspaceNext => {
+                self.workspaces.workspace_swipe_update(-amount as f32)
+            }
+            ContinuousAction::WorkspacePrev => {
+                self.workspaces.workspace_swipe_update(amount as f32)
+            }
+        }
+    }
+
+    fn end_continuous_action(
+        &mut self,
+        action: ContinuousAction,
+        velocity_samples: &[f64],
+        cancelled: bool,
+    ) {
+        let velocity = average_velocity(velocity_samples);
+        match action {
+            ContinuousAction::Expose | ContinuousAction::ExposeAllWorkspaces => {
+                self.workspaces.expose_end_with_velocity(velocity as f32);
+            }
+            ContinuousAction::ShowDesktop => {
+                self.workspaces.expose_show_desktop(0.0, true);
+            }
+            ContinuousAction::WorkspaceNext | ContinuousAction::WorkspacePrev => {
+                let velocity = match (cancelled, action) {
+                    (true, _) => 0.0,
+                    (false, ContinuousAction::WorkspaceNext) => -velocity,
+                    (false, _) => velocity,
+                };
+                let index = self.workspaces.workspace_swipe_end(velocity as f32);
+
+                // Update keyboard focus to top window of the target workspace
+                if let Some(top_wid) = self.workspaces.get_top_window_of_workspace(index) {
+                    self.set_keyboard_focus_on_surface(&top_wid);
+                } else {
+                    self.clear_keyboard_focus();
+                }
+            }
+        }
+    }
 }
 
 #[cfg(all(test, feature = "udev"))]
@@ -293,6 +464,32 @@ mod tests {
         assert_eq!(avg, 200.0);
     }
 
+    #[test]
+    fn test_average_velocity() {
+        assert_eq!(average_velocity(&[100.0, 200.0, 300.0]), 200.0);
+        assert_eq!(average_velocity(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_velocity_samples_are_bounded() {
+        let mut samples = Vec::new();
+        for i in 0..10 {
+            push_velocity_sample(&mut samples, i as f64);
+        }
+        assert_eq!(samples.len(), crate::state::VELOCITY_SAMPLE_COUNT);
+        assert_eq!(samples.last(), Some(&9.0));
+    }
+
+    #[test]
+    fn test_swipe_direction_detection() {
+        let direction = SwipeDirection::from_accumulated(-8.0, 2.0).to_gesture_direction();
+        assert_eq!(direction, Some(GestureDirection::SwipeLeft));
+        let direction = SwipeDirection::from_accumulated(1.0, -9.0).to_gesture_direction();
+        assert_eq!(direction, Some(GestureDirection::SwipeUp));
+        let direction = SwipeDirection::from_accumulated(1.0, 2.0).to_gesture_direction();
+        assert_eq!(direction, None);
+    }
+
     #[test]
     fn test_pinch_scale_delta() {
         let current = 1.5_f32;
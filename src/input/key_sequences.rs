@@ -0,0 +1,404 @@
+//! Multi-key shortcut sequences and keyboard modes
+//!
+//! Keys of an unfinished sequence such as `"Logo+w h"` are kept until the
+//! sequence completes, a key does not continue it or it times out. A keyboard
+//! mode replaces the top level bindings with its own until one of its exit
+//! keys is pressed.
+
+use std::time::Duration;
+
+use smithay::{
+    reexports::calloop::{
+        timer::{TimeoutAction, Timer},
+        RegistrationToken,
+    },
+    utils::IsAlive,
+};
+use tracing::{debug, info, warn};
+
+use crate::{
+    config::{
+        shortcuts::{sequence_canonical_id, ShortcutAction, ShortcutBinding, ShortcutTrigger},
+        Config,
+    },
+    state::Backend,
+    workspaces::{PromptLine, PromptModel},
+    Otto,
+};
+
+/// Maximum number of continuations listed in the hint overlay
+const MAX_HINT_LINES: usize = 12;
+
+#[derive(Debug, Default)]
+pub struct KeySequenceState {
+    /// Active keyboard mode
+    mode: Option<String>,
+    /// Keys of the unfinished sequence
+    pending: Vec<ShortcutTrigger>,
+    hint_timer: Option<RegistrationToken>,
+    timeout_timer: Option<RegistrationToken>,
+}
+
+/// Result of a key press fed to [`KeySequenceState::press`]
+#[derive(Debug, Clone)]
+pub enum SequenceStep {
+    /// The key completed a binding
+    Complete(ShortcutAction),
+    /// The key started or continued a sequence
+    Pending,
+    /// The key did not continue the pending sequence, which is dropped
+    Cancelled,
+    /// The key left the active mode
+    ExitMode,
+    /// The key is not part of any binding
+    NoMatch,
+}
+
+impl KeySequenceState {
+    pub fn mode(&self) -> Option<&str> {
+        self.mode.as_deref()
+    }
+
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Whether a sequence or a mode is waiting for keys
+    pub fn is_active(&self) -> bool {
+        self.is_pending() || self.mode.is_some()
+    }
+
+    fn bindings<'a>(&self, config: &'a Config) -> &'a [ShortcutBinding] {
+        match &self.mode {
+            Some(mode) => config
+                .shortcut_mode(mode)
+                .map(|mode| mode.bindings.as_slice())
+                .unwrap_or_default(),
+            None => config.shortcut_bindings(),
+        }
+    }
+
+    pub fn press(&mut self, config: &Config, trigger: ShortcutTrigger) -> SequenceStep {
+        let mode = self
+            .mode
+            .as_deref()
+            .and_then(|mode| config.shortcut_mode(mode));
+        if self.pending.is_empty() && mode.is_some_and(|mode| mode.exit.contains(&trigger)) {
+            self.mode = None;
+            return SequenceStep::ExitMode;
+        }
+
+        self.pending.push(trigger);
+        let bindings = self.bindings(config);
+
+        if let Some(binding) = bindings
+            .iter()
+            .find(|binding| binding.match_prefix(&self.pending) == Some(true))
+        {
+            debug!("Matched shortcut: {}", binding.trigger_repr);
+            self.pending.clear();
+            if mode.is_some_and(|mode| mode.oneshot) {
+                self.mode = None;
+            }
+            return SequenceStep::Complete(binding.action.clone());
+        }
+
+        if bindings
+            .iter()
+            .any(|binding| binding.match_prefix(&self.pending).is_some())
+        {
+            return SequenceStep::Pending;
+        }
+
+        let was_pending = self.pending.len() > 1;
+        self.pending.clear();
+        if was_pending {
+            SequenceStep::Cancelled
+        } else {
+            SequenceStep::NoMatch
+        }
+    }
+
+    /// Bindings that can still be completed from the pending keys
+    pub fn continuations<'a>(&self, config: &'a Config) -> Vec<&'a ShortcutBinding> {
+        self.bindings(config)
+            .iter()
+            .filter(|binding| binding.match_prefix(&self.pending) == Some(false))
+            .collect()
+    }
+
+    /// Overlay listing the keys that continue the sequence or the active mode
+    pub fn hint_model(&self, config: &Config) -> Option<PromptModel> {
+        let mut lines: Vec<PromptLine> = self
+            .continuations(config)
+            .into_iter()
+            .map(|binding| PromptLine {
+                text: sequence_canonical_id(binding.keys().skip(self.pending.len())),
+                detail: binding.action.to_string(),
+                highlighted: false,
+            })
+            .collect();
+        if lines.is_empty() {
+            return None;
+        }
+        lines.sort_by(|a, b| a.text.cmp(&b.text));
+        lines.truncate(MAX_HINT_LINES);
+
+        let keys = sequence_canonical_id(self.pending.iter());
+        let title = match &self.mode {
+            Some(mode) if keys.is_empty() => format!("{mode} mode"),
+            Some(mode) => format!("{mode} mode: {keys}"),
+            None => keys,
+        };
+        let hint = match self
+            .mode
+            .as_deref()
+            .and_then(|mode| config.shortcut_mode(mode))
+        {
+            Some(mode) if !self.is_pending() && !mode.exit.is_empty() => format!(
+                "{} to leave",
+                mode.exit
+                    .iter()
+                    .map(ShortcutTrigger::canonical_id)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            _ => "Any other key cancels".to_string(),
+        };
+
+        Some(PromptModel { title, lines, hint })
+    }
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    pub(crate) fn enter_keyboard_mode(&mut self, mode: &str) {
+        if Config::with(|config| config.shortcut_mode(mode).is_none()) {
+            warn!(mode, "unknown keyboard mode");
+            return;
+        }
+        info!(mode, "Entering keyboard mode");
+        self.key_sequences.mode = Some(mode.to_string());
+        self.key_sequences.pending.clear();
+        self.refresh_key_sequence();
+    }
+
+    pub(crate) fn exit_keyboard_mode(&mut self) {
+        if let Some(mode) = self.key_sequences.mode.take() {
+            info!(mode, "Leaving keyboard mode");
+        }
+        self.key_sequences.pending.clear();
+        self.refresh_key_sequence();
+    }
+
+    /// Reschedules the timeout and updates the hint overlay after the
+    /// sequence or mode changed
+    pub(crate) fn refresh_key_sequence(&mut self) {
+        self.cancel_key_sequence_timers();
+        if !self.key_sequences.is_active() {
+            if self.workspaces.key_hints.alive() {
+                self.workspaces.key_hints.hide();
+            }
+            return;
+        }
+
+        let (timeout, hint_delay, show_hints) = Config::with(|config| {
+            let chords = &config.keyboard_chords;
+            let timeout = if self.key_sequences.is_pending() {
+                Some(Duration::from_millis(chords.timeout_ms))
+            } else {
+                self.key_sequences
+                    .mode()
+                    .and_then(|mode| config.shortcut_mode(mode))
+                    .and_then(|mode| mode.timeout)
+            };
+            (
+                timeout,
+                Duration::from_millis(chords.hint_delay_ms),
+                chords.show_hints,
+            )
+        });
+
+        if let Some(timeout) = timeout {
+            self.key_sequences.timeout_timer =
+                self.schedule_key_sequence_timer(timeout, |data| data.key_sequence_timeout());
+        }
+
+        if !show_hints {
+            return;
+        }
+        // Modes show their bindings right away, sequences only after a pause
+        if self.workspaces.key_hints.alive() || !self.key_sequences.is_pending() {
+            self.show_key_sequence_hints();
+        } else {
+            self.key_sequences.hint_timer = self.schedule_key_sequence_timer(hint_delay, |data| {
+                data.key_sequences.hint_timer = None;
+                data.show_key_sequence_hints();
+            });
+        }
+    }
+
+    fn show_key_sequence_hints(&mut self) {
+        match Config::with(|config| self.key_sequences.hint_model(config)) {
+            Some(model) => self.workspaces.key_hints.show(model),
+            None if self.workspaces.key_hints.alive() => self.workspaces.key_hints.hide(),
+            None => {}
+        }
+    }
+
+    fn key_sequence_timeout(&mut self) {
+        self.key_sequences.timeout_timer = None;
+        if self.key_sequences.is_pending() {
+            debug!("Key sequence timed out");
+            self.key_sequences.pending.clear();
+        } else if let Some(mode) = self.key_sequences.mode.take() {
+            info!(mode, "Keyboard mode timed out");
+        }
+        self.refresh_key_sequence();
+    }
+
+    fn schedule_key_sequence_timer(
+        &self,
+        delay: Duration,
+        mut callback: impl FnMut(&mut Otto<BackendData>) + 'static,
+    ) -> Option<RegistrationToken> {
+        self.handle
+            .insert_source(Timer::from_duration(delay), move |_, _, data| {
+                callback(data);
+                TimeoutAction::Drop
+            })
+            .map_err(|err| warn!(?err, "Failed to schedule key sequence timer"))
+            .ok()
+    }
+
+    fn cancel_key_sequence_timers(&mut self) {
+        let timers = [
+            self.key_sequences.hint_timer.take(),
+            self.key_sequences.timeout_timer.take(),
+        ];
+        for token in timers.into_iter().flatten() {
+            self.handle.remove(token);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::shortcuts::{
+        parse_trigger, KeyboardModeConfig, ShortcutActionConfig, ShortcutMap,
+    };
+
+    fn key(trigger: &str) -> ShortcutTrigger {
+        parse_trigger(trigger).expect("valid trigger")
+    }
+
+    fn config_with(shortcuts: &[(&str, ShortcutActionConfig)]) -> Config {
+        let mut config = Config::default();
+        config.keyboard_shortcuts = shortcuts
+            .iter()
+            .map(|(trigger, action)| (trigger.to_string(), action.clone()))
+            .collect();
+        config.rebuild_shortcut_bindings();
+        config
+    }
+
+    fn builtin(name: &str) -> ShortcutActionConfig {
+        ShortcutActionConfig::BuiltinName(name.into())
+    }
+
+    #[test]
+    fn sequence_completes_after_all_keys() {
+        let config = config_with(&[("Logo+w h", builtin("WorkspacePrev"))]);
+        let mut state = KeySequenceState::default();
+
+        assert!(matches!(
+            state.press(&config, key("Logo+w")),
+            SequenceStep::Pending
+        ));
+        assert!(state.is_pending());
+        assert!(matches!(
+            state.press(&config, key("h")),
+            SequenceStep::Complete(ShortcutAction::Builtin(_))
+        ));
+        assert!(!state.is_active());
+    }
+
+    #[test]
+    fn unknown_continuation_cancels_sequence() {
+        let config = config_with(&[("Logo+w h", builtin("WorkspacePrev"))]);
+        let mut state = KeySequenceState::default();
+
+        state.press(&config, key("Logo+w"));
+        assert!(matches!(
+            state.press(&config, key("x")),
+            SequenceStep::Cancelled
+        ));
+        assert!(matches!(
+            state.press(&config, key("x")),
+            SequenceStep::NoMatch
+        ));
+    }
+
+    #[test]
+    fn mode_uses_own_bindings_until_exit() {
+        let mut config = config_with(&[(
+            "Logo+r",
+            ShortcutActionConfig::EnterMode {
+                mode: "resize".into(),
+            },
+        )]);
+        let mut bindings = ShortcutMap::new();
+        bindings.insert("h".into(), builtin("WorkspacePrev"));
+        config.keyboard_modes.insert(
+            "resize".into(),
+            KeyboardModeConfig {
+                exit: vec!["Escape".into()],
+                oneshot: false,
+                timeout_ms: None,
+                bindings,
+            },
+        );
+        config.rebuild_shortcut_bindings();
+
+        let mut state = KeySequenceState::default();
+        assert!(matches!(
+            state.press(&config, key("Logo+r")),
+            SequenceStep::Complete(ShortcutAction::EnterMode(_))
+        ));
+        state.mode = Some("resize".into());
+
+        assert!(matches!(
+            state.press(&config, key("h")),
+            SequenceStep::Complete(_)
+        ));
+        assert_eq!(state.mode(), Some("resize"));
+        assert!(matches!(
+            state.press(&config, key("Logo+r")),
+            SequenceStep::NoMatch
+        ));
+        assert!(state.hint_model(&config).is_some());
+        assert!(matches!(
+            state.press(&config, key("Escape")),
+            SequenceStep::ExitMode
+        ));
+        assert_eq!(state.mode(), None);
+    }
+
+    #[test]
+    fn hints_list_remaining_keys() {
+        let config = config_with(&[
+            ("Logo+w h", builtin("WorkspacePrev")),
+            ("Logo+w l", builtin("WorkspaceNext")),
+        ]);
+        let mut state = KeySequenceState::default();
+        state.press(&config, key("Logo+w"));
+
+        let model = state
+            .hint_model(&config)
+            .expect("hints for pending sequence");
+        assert_eq!(model.title, "Logo+w");
+        let keys: Vec<&str> = model.lines.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(keys, vec!["h", "l"]);
+        assert_eq!(model.lines[0].detail, "WorkspacePrev");
+    }
+}
@@ -1,20 +1,29 @@
-use smithay::wayland::{
-    compositor::with_states, keyboard_shortcuts_inhibit::KeyboardShortcutsInhibitorSeat,
-};
+use smithay::wayland::compositor::with_states;
 use smithay::{
     backend::input::{Event, InputBackend, KeyState, KeyboardKeyEvent},
     desktop::layer_map_for_output,
-    input::keyboard::{FilterResult, Keysym, ModifiersState},
-    utils::{IsAlive, SERIAL_COUNTER as SCOUNTER},
+    input::keyboard::{FilterResult, Keycode, Keysym, ModifiersState},
+    utils::{IsAlive, Serial, SERIAL_COUNTER as SCOUNTER},
     wayland::shell::wlr_layer::{
         KeyboardInteractivity, Layer as WlrLayer, LayerSurfaceCachedState,
     },
 };
 use tracing::debug;
 
-use crate::{config::Config, state::Backend, Otto};
+use crate::{
+    config::{shortcuts::ShortcutTrigger, Config},
+    global_shortcuts::GlobalShortcuts,
+    state::{expose_keyboard::is_expose_key, Backend},
+    Otto,
+};
+
+use super::{
+    actions::KeyAction,
+    key_sequences::{KeySequenceState, SequenceStep},
+};
 
-use super::actions::KeyAction;
+/// Handles a key press while a prompt is shown
+type ModalKeyHandler<BackendData> = fn(&mut Otto<BackendData>, &ModifiersState, Keysym);
 
 pub fn capture_app_switcher_hold_modifiers(
     mut modifiers: ModifiersState,
@@ -46,10 +55,36 @@ pub fn app_switcher_hold_is_active(hold: Option<ModifiersState>, current: Modifi
     }
 }
 
+/// Whether the key only changes modifiers, such keys never trigger shortcuts
+pub fn is_modifier_keysym(keysym: Keysym) -> bool {
+    matches!(
+        keysym,
+        Keysym::Shift_L
+            | Keysym::Shift_R
+            | Keysym::Control_L
+            | Keysym::Control_R
+            | Keysym::Alt_L
+            | Keysym::Alt_R
+            | Keysym::Super_L
+            | Keysym::Super_R
+            | Keysym::Meta_L
+            | Keysym::Meta_R
+            | Keysym::Hyper_L
+            | Keysym::Hyper_R
+            | Keysym::ISO_Level3_Shift
+            | Keysym::Caps_Lock
+            | Keysym::Num_Lock
+    )
+}
+
 pub fn process_keyboard_shortcut(
     config: &Config,
+    key_sequences: &mut KeySequenceState,
+    global_shortcuts: &mut GlobalShortcuts,
     modifiers: ModifiersState,
     keysym: Keysym,
+    keycode: Keycode,
+    time: u32,
 ) -> Option<KeyAction> {
     use smithay::input::keyboard::xkb::{self, keysyms::*};
 
@@ -80,17 +115,27 @@ pub fn process_keyboard_shortcut(
         ));
     }
 
-    let result = config
-        .shortcut_bindings()
-        .iter()
-        .find(|binding| {
-            let matches = binding.trigger.matches(&modifiers, keysym);
-            if matches {
-                debug!("Matched shortcut: {}", binding.trigger_repr);
-            }
-            matches
-        })
-        .and_then(|binding| super::actions::resolve_shortcut_action(config, &binding.action));
+    // Modifiers alone neither start nor cancel a sequence
+    if is_modifier_keysym(keysym) {
+        return None;
+    }
+
+    let in_mode = key_sequences.mode().is_some();
+    let trigger = ShortcutTrigger::from_keypress(&modifiers, keysym);
+    let result = match key_sequences.press(config, trigger) {
+        SequenceStep::Complete(action) => super::actions::resolve_shortcut_action(config, &action),
+        SequenceStep::Pending | SequenceStep::Cancelled | SequenceStep::ExitMode => {
+            Some(KeyAction::None)
+        }
+        SequenceStep::NoMatch => None,
+    };
+
+    // Shortcuts registered by apps through the GlobalShortcuts portal
+    if result.is_none() && !in_mode && global_shortcuts.activate(&modifiers, keysym, keycode, time)
+    {
+        debug!("Matched global shortcut for {}", keysym_name);
+        return Some(KeyAction::None);
+    }
 
     if result.is_none() {
         debug!("No shortcut matched for {}", keysym_name);
@@ -99,6 +144,38 @@ pub fn process_keyboard_shortcut(
     result
 }
 
+/// Whether a release shortcut is bound to the key with the given modifiers
+fn has_release_binding(
+    config: &Config,
+    modifiers: &ModifiersState,
+    keysym: Keysym,
+    state: KeyState,
+) -> bool {
+    config.release_bindings().iter().any(|binding| match state {
+        KeyState::Pressed => {
+            binding.trigger.matches(modifiers, keysym)
+                || binding.trigger.matches_release(modifiers, keysym)
+        }
+        KeyState::Released => binding.trigger.matches_release(modifiers, keysym),
+    })
+}
+
+/// Action of the release shortcut bound to a key that was tapped
+pub fn process_key_release(
+    config: &Config,
+    modifiers: ModifiersState,
+    keysym: Keysym,
+) -> Option<KeyAction> {
+    config
+        .release_bindings()
+        .iter()
+        .find(|binding| binding.trigger.matches_release(&modifiers, keysym))
+        .and_then(|binding| {
+            debug!("Matched release shortcut: {}", binding.trigger_repr);
+            super::actions::resolve_shortcut_action(config, &binding.action)
+        })
+}
+
 impl<BackendData: Backend> Otto<BackendData> {
     pub fn keyboard_key_to_action<B: InputBackend>(
         &mut self,
@@ -109,6 +186,15 @@ impl<BackendData: Backend> Otto<BackendData> {
         debug!(?keycode, ?state, "key");
         let serial = SCOUNTER.next_serial();
         let time = Event::time_msec(&evt);
+        if matches!(state, KeyState::Pressed) {
+            self.update_keymap_for_device(&evt.device());
+        }
+        // A prompt takes all key presses until it is answered
+        if let Some(prompt_key) = self.modal_key_handler() {
+            self.capture_modal_key(keycode, state, serial, time, prompt_key);
+            return KeyAction::None;
+        }
+
         let mut suppressed_keys = self.suppressed_keys.clone();
         let keyboard = self.seat.get_keyboard().unwrap();
         let mut updated_modifiers: Option<ModifiersState> = None;
@@ -138,15 +224,12 @@ impl<BackendData: Backend> Otto<BackendData> {
             }
         }
 
-        let inhibited = self
-            .workspaces
-            .element_under(self.pointer.current_location())
-            .and_then(|(window, _)| {
-                let surface = window.wl_surface()?;
-                self.seat.keyboard_shortcuts_inhibitor_for_surface(&surface)
-            })
-            .map(|inhibitor| inhibitor.is_active())
-            .unwrap_or(false);
+        let inhibited = self.shortcuts_inhibited();
+        let escape_available = self.inhibit_escape_available();
+        let mut escape_pressed = false;
+        let expose_keys = self.is_expose_keyboard_active();
+        let mut expose_key: Option<Keysym> = None;
+        let mut release_action: Option<KeyAction> = None;
 
         let action = keyboard
             .input(
@@ -155,7 +238,7 @@ impl<BackendData: Backend> Otto<BackendData> {
                 state,
                 serial,
                 time,
-                |_, modifiers, handle| {
+                |data, modifiers, handle| {
                     let keysym = handle.modified_sym();
 
                     debug!(
@@ -165,28 +248,92 @@ impl<BackendData: Backend> Otto<BackendData> {
                         "keysym"
                     );
 
+                    // The escape shortcut reaches the compositor even when
+                    // the app with keyboard focus inhibits shortcuts
+                    if matches!(state, KeyState::Pressed)
+                        && escape_available
+                        && Config::with(|config| {
+                            config
+                                .inhibit_escape()
+                                .is_some_and(|escape| escape.matches(modifiers, keysym))
+                        })
+                    {
+                        escape_pressed = true;
+                        suppressed_keys.push(keysym);
+                        updated_modifiers = Some(*modifiers);
+                        return FilterResult::Intercept(KeyAction::None);
+                    }
+
+                    // Exposé takes the plain key presses it has a use for
+                    if matches!(state, KeyState::Pressed)
+                        && expose_keys
+                        && is_expose_key(modifiers, keysym)
+                    {
+                        expose_key = Some(keysym);
+                        suppressed_keys.push(keysym);
+                        updated_modifiers = Some(*modifiers);
+                        return FilterResult::Intercept(KeyAction::None);
+                    }
+
+                    let sequence_was_active = data.key_sequences.is_active();
                     let shortcut_action = Config::with(|config| {
                         if matches!(state, KeyState::Pressed) && !inhibited {
-                            process_keyboard_shortcut(config, *modifiers, keysym)
+                            process_keyboard_shortcut(
+                                config,
+                                &mut data.key_sequences,
+                                &mut data.global_shortcuts,
+                                *modifiers,
+                                keysym,
+                                keycode,
+                                time,
+                            )
                         } else {
                             None
                         }
                     });
+                    if sequence_was_active || data.key_sequences.is_active() {
+                        data.refresh_key_sequence();
+                    }
                     updated_modifiers = Some(*modifiers);
 
+                    // Release shortcuts fire when the key is released
+                    // right after being pressed, e.g. tapping `Logo`
+                    let release_binding = !inhibited
+                        && Config::with(|config| {
+                            has_release_binding(config, modifiers, keysym, state)
+                        });
+                    if let KeyState::Pressed = state {
+                        data.key_tap =
+                            (shortcut_action.is_none() && release_binding).then_some(keysym);
+                    } else if data.key_tap == Some(keysym) {
+                        data.key_tap = None;
+                        if release_binding {
+                            release_action = Config::with(|config| {
+                                process_key_release(config, *modifiers, keysym)
+                            });
+                        }
+                    }
+
                     // If the key is pressed and triggered an action
                     // we will not forward the key to the client.
                     // Additionally add the key to the suppressed keys
                     // so that we can decide on a release if the key
                     // should be forwarded to the client or not.
+                    // Keys with a release shortcut are suppressed too,
+                    // unless they are modifiers that other shortcuts and
+                    // clients need to see.
                     if let KeyState::Pressed = state {
                         if let Some(action) = shortcut_action {
                             suppressed_keys.push(keysym);
                             FilterResult::Intercept(action)
+                        } else if release_binding && !is_modifier_keysym(keysym) {
+                            suppressed_keys.push(keysym);
+                            FilterResult::Intercept(KeyAction::None)
                         } else {
                             FilterResult::Forward
                         }
                     } else {
+                        data.global_shortcuts.deactivate(keycode, time);
                         let suppressed = suppressed_keys.contains(&keysym);
                         if suppressed {
                             suppressed_keys.retain(|k| *k != keysym);
@@ -198,6 +345,13 @@ impl<BackendData: Backend> Otto<BackendData> {
                 },
             )
             .unwrap_or(KeyAction::None);
+        let action = release_action.unwrap_or(action);
+        if escape_pressed {
+            self.toggle_shortcut_inhibit();
+        }
+        if let Some(keysym) = expose_key {
+            self.expose_key(keysym);
+        }
 
         // Capture modifiers when pressing app switcher actions
         if matches!(state, KeyState::Pressed)
@@ -231,6 +385,65 @@ impl<BackendData: Backend> Otto<BackendData> {
         action
     }
 
+    /// Handler of the key presses while a prompt or a workspace rename is
+    /// shown, the first one shown gets them
+    fn modal_key_handler(&self) -> Option<ModalKeyHandler<BackendData>> {
+        if self.is_shortcut_prompt_active() {
+            Some(Self::shortcut_prompt_key)
+        } else if self.is_inhibit_prompt_active() {
+            Some(Self::inhibit_prompt_key)
+        } else if self.is_wallpaper_prompt_active() {
+            Some(Self::wallpaper_prompt_key)
+        } else if self.is_workspace_rename_active() {
+            Some(Self::workspace_rename_key)
+        } else {
+            None
+        }
+    }
+
+    /// Gives a key press to `on_press` instead of the focused client. The
+    /// key goes to `suppressed_keys` so its release is kept from the client
+    /// too, even once the prompt is gone.
+    fn capture_modal_key(
+        &mut self,
+        keycode: Keycode,
+        state: KeyState,
+        serial: Serial,
+        time: u32,
+        on_press: ModalKeyHandler<BackendData>,
+    ) {
+        let keyboard = self.seat.get_keyboard().unwrap();
+        keyboard.input::<(), _>(
+            self,
+            keycode,
+            state,
+            serial,
+            time,
+            |data, modifiers, handle| {
+                let keysym = handle.modified_sym();
+                match state {
+                    KeyState::Pressed => {
+                        on_press(data, modifiers, keysym);
+                        data.suppressed_keys.push(keysym);
+                        FilterResult::Intercept(())
+                    }
+                    KeyState::Released if data.suppressed_keys.contains(&keysym) => {
+                        data.suppressed_keys.retain(|k| *k != keysym);
+                        FilterResult::Intercept(())
+                    }
+                    // Pressed before the prompt showed, the client saw it
+                    KeyState::Released => FilterResult::Forward,
+                }
+            },
+        );
+    }
+
+    /// Whether the client with keyboard focus inhibits compositor shortcuts
+    pub(crate) fn shortcuts_inhibited(&self) -> bool {
+        self.focused_inhibitor()
+            .is_some_and(|inhibitor| inhibitor.is_active())
+    }
+
     fn dismiss_app_switcher(&mut self) {
         if self.workspaces.app_switcher.alive() {
             self.workspaces.app_switcher.hide();
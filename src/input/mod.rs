@@ -2,14 +2,26 @@
 //!
 //! This module provides a modular input handling system split by device type:
 //! - `actions`: Key action definitions and processing
+//! - `devices`: Per-device settings from `[[input.devices]]`
 //! - `keyboard`: Keyboard event handling and shortcuts
+//! - `key_sequences`: Multi-key shortcut sequences and keyboard modes
 //! - `pointer`: Mouse/pointer event handling
+//! - `pointer_shortcuts`: Shortcuts on pointer buttons and scrolling
+//! - `scroll`: Scroll acceleration and kinetic scrolling
+//! - `shortcut_inhibit`: Per-app policy for clients inhibiting shortcuts
+//! - `window_drag`: Moving and resizing windows with a modifier held
 //! - `gestures`: Gesture processing (swipe, pinch, hold)
 //! - `tablet`: Tablet input support
 
 pub mod actions;
+pub mod devices;
+pub mod key_sequences;
 pub mod keyboard;
 pub mod pointer;
+pub mod pointer_shortcuts;
+pub mod scroll;
+pub mod shortcut_inhibit;
+pub mod window_drag;
 
 #[cfg(feature = "udev")]
 pub mod gestures;
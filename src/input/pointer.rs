@@ -1,4 +1,10 @@
-use crate::{focus::PointerFocusTarget, shell::FullscreenSurface, state::Backend, Otto};
+use crate::{
+    focus::PointerFocusTarget,
+    shell::FullscreenSurface,
+    state::Backend,
+    workspaces::{split_view::split_layout, SplitDivider},
+    Otto,
+};
 use smithay::{
     backend::input::{
         self, Axis, AxisSource, ButtonState, Event, InputBackend, PointerAxisEvent,
@@ -27,17 +33,28 @@ use smithay::{
     },
 };
 
-use crate::config::Config;
+use crate::{config::Config, input::KeyAction};
 
 impl<BackendData: Backend> Otto<BackendData> {
-    pub(crate) fn on_pointer_button<B: InputBackend>(&mut self, evt: B::PointerButtonEvent) {
+    pub(crate) fn on_pointer_button<B: InputBackend>(
+        &mut self,
+        evt: B::PointerButtonEvent,
+    ) -> KeyAction {
         let serial = SCOUNTER.next_serial();
         let button = evt.button_code();
 
         let state = wl_pointer::ButtonState::from(evt.state());
 
+        if wl_pointer::ButtonState::Pressed == state {
+            self.cancel_kinetic_scroll();
+        }
+        if let Some(action) = self.pointer_button_shortcut(button, evt.state()) {
+            return action;
+        }
         if !self.workspaces.get_show_all() && wl_pointer::ButtonState::Pressed == state {
             self.focus_window_under_cursor(serial);
+            // The press below goes to the drag grab instead of the client
+            self.start_window_drag(button, serial);
         }
         let pointer = self.pointer.clone();
         let button_state = state.try_into().unwrap();
@@ -59,6 +76,7 @@ impl<BackendData: Backend> Otto<BackendData> {
                 self.layers_engine.pointer_button_up();
             }
         }
+        KeyAction::None
     }
 
     /// Update the focus on the topmost surface under the cursor in the current workspace
@@ -207,6 +225,12 @@ impl<BackendData: Backend> Otto<BackendData> {
             return Some((focus, (0.0, 0.0).into()));
         }
 
+        // App Exposé
+        if self.workspaces.app_expose.alive() {
+            let focus = self.workspaces.app_expose.clone().into();
+            return Some((focus, (0.0, 0.0).into()));
+        }
+
         // Workspace selector
         if self.workspaces.get_show_all() {
             let focus = self
@@ -229,7 +253,9 @@ impl<BackendData: Backend> Otto<BackendData> {
         }
         // Window selector check
         if self.workspaces.get_show_all() {
-            let workspace = self.workspaces.get_current_workspace();
+            let workspace = self
+                .workspaces
+                .expose_workspace_at((physical_pos.x as f32, physical_pos.y as f32));
             let focus = workspace.window_selector_view.as_ref().clone().into();
             let position = workspace.window_selector_view.layer.render_position();
 
@@ -243,6 +269,10 @@ impl<BackendData: Backend> Otto<BackendData> {
             let layer_loc = layers.layer_geometry(layer).unwrap().loc;
             under = Some((layer.clone().into(), output_geo.loc + layer_loc));
         }
+        // Divider of a split fullscreen workspace
+        else if let Some(divider) = self.split_divider_under(pos) {
+            under = Some((divider.into(), (0, 0).into()));
+        }
         // Check dock
         else if self
             .workspaces
@@ -277,18 +307,62 @@ impl<BackendData: Backend> Otto<BackendData> {
         under.map(|(s, l)| (s, l.to_f64()))
     }
 
-    pub(crate) fn on_pointer_axis<B: InputBackend>(&mut self, evt: B::PointerAxisEvent) {
+    fn split_divider_under(&self, pos: Point<f64, Logical>) -> Option<SplitDivider> {
+        let workspace = self.workspaces.get_current_workspace();
+        let split = workspace.get_split_view()?;
+        let layout = split_layout(self.workspaces.get_logical_rect(), split.ratio);
+        layout
+            .divider
+            .contains(pos.to_i32_round())
+            .then(|| workspace.split_divider.clone())
+    }
+
+    pub(crate) fn on_pointer_axis<B: InputBackend>(
+        &mut self,
+        evt: B::PointerAxisEvent,
+    ) -> KeyAction {
+        let source = evt.source();
         let horizontal_amount = evt.amount(input::Axis::Horizontal).unwrap_or_else(|| {
             evt.amount_v120(input::Axis::Horizontal).unwrap_or(0.0) * 15.0 / 120.
         });
         let vertical_amount = evt
             .amount(input::Axis::Vertical)
             .unwrap_or_else(|| evt.amount_v120(input::Axis::Vertical).unwrap_or(0.0) * 15.0 / 120.);
-        let horizontal_amount_discrete = evt.amount_v120(input::Axis::Horizontal);
-        let vertical_amount_discrete = evt.amount_v120(input::Axis::Vertical);
+
+        if let Some(action) =
+            self.pointer_axis_shortcut(source, (horizontal_amount, vertical_amount))
+        {
+            return action;
+        }
+
+        let scroll_settings = self.scroll_settings_under_pointer();
+        let acceleration = self.accelerate_scroll(
+            source,
+            evt.time_msec(),
+            (horizontal_amount, vertical_amount),
+            &scroll_settings,
+        );
+        let scroll_factor = crate::input::devices::scroll_factor(&evt.device()) * acceleration;
+
+        let horizontal_amount = horizontal_amount * scroll_factor;
+        let vertical_amount = vertical_amount * scroll_factor;
+        let horizontal_amount_discrete = evt
+            .amount_v120(input::Axis::Horizontal)
+            .map(|v120| v120 * scroll_factor);
+        let vertical_amount_discrete = evt
+            .amount_v120(input::Axis::Vertical)
+            .map(|v120| v120 * scroll_factor);
+
+        // Fingers lifted: either continue with kinetic scrolling or let the
+        // client know the scroll stopped
+        let finger_lifted =
+            source == AxisSource::Finger && horizontal_amount == 0.0 && vertical_amount == 0.0;
+        if finger_lifted && self.start_kinetic_scroll(evt.time_msec(), &scroll_settings) {
+            return KeyAction::None;
+        }
 
         {
-            let mut frame = AxisFrame::new(evt.time_msec()).source(evt.source());
+            let mut frame = AxisFrame::new(evt.time_msec()).source(source);
             if horizontal_amount != 0.0 {
                 frame = frame
                     .relative_direction(Axis::Horizontal, evt.relative_direction(Axis::Horizontal));
@@ -305,7 +379,7 @@ impl<BackendData: Backend> Otto<BackendData> {
                     frame = frame.v120(Axis::Vertical, discrete as i32);
                 }
             }
-            if evt.source() == AxisSource::Finger {
+            if source == AxisSource::Finger {
                 if evt.amount(Axis::Horizontal) == Some(0.0) {
                     frame = frame.stop(Axis::Horizontal);
                 }
@@ -317,6 +391,7 @@ impl<BackendData: Backend> Otto<BackendData> {
             pointer.axis(self, frame);
             pointer.frame(self);
         }
+        KeyAction::None
     }
 }
 
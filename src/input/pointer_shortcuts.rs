@@ -0,0 +1,172 @@
+//! Shortcuts triggered by pointer buttons and scrolling
+//!
+//! Bindings such as `"Logo+BTN_SIDE"` or `"Logo+ScrollUp"` come from the
+//! `[keyboard_shortcuts]` table. Like keys in `suppressed_keys`, buttons that
+//! triggered a shortcut are not sent to clients, neither is their release.
+
+use smithay::backend::input::{AxisSource, ButtonState};
+
+use crate::{
+    config::{shortcuts::PointerInput, Config},
+    state::Backend,
+    Otto,
+};
+
+use super::actions::{resolve_shortcut_action, KeyAction};
+
+/// Scroll distance for one shortcut step with a wheel, one notch
+const WHEEL_STEP: f64 = 15.0;
+/// Scroll distance for one shortcut step on a touchpad
+const FINGER_STEP: f64 = 60.0;
+
+#[derive(Debug, Default)]
+pub struct PointerShortcutState {
+    /// Buttons whose press triggered a shortcut
+    suppressed_buttons: Vec<u32>,
+    /// Scroll distance not yet turned into shortcut steps
+    scroll_accumulated: (f64, f64),
+}
+
+/// Adds `delta` to the accumulated distance on one axis and returns the
+/// direction of a completed step, `-1` or `1`.
+pub fn scroll_step(accumulated: &mut f64, delta: f64, step: f64) -> Option<i8> {
+    // A change of direction starts over
+    if delta * *accumulated < 0.0 {
+        *accumulated = 0.0;
+    }
+    *accumulated += delta;
+    if accumulated.abs() < step {
+        return None;
+    }
+    let direction = accumulated.signum() as i8;
+    *accumulated = 0.0;
+    Some(direction)
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Runs the shortcut bound to a button with the current modifiers.
+    ///
+    /// Returns `None` if the button goes to the client.
+    pub(crate) fn pointer_button_shortcut(
+        &mut self,
+        button: u32,
+        state: ButtonState,
+    ) -> Option<KeyAction> {
+        match state {
+            ButtonState::Pressed => {
+                // Clicking cancels a tap of a modifier key
+                self.key_tap = None;
+                if self.shortcuts_inhibited() {
+                    return None;
+                }
+                let modifiers = self.current_modifiers;
+                let action = Config::with(|config| {
+                    config
+                        .pointer_bindings()
+                        .iter()
+                        .find(|binding| {
+                            binding
+                                .trigger
+                                .matches(&modifiers, PointerInput::Button(button))
+                        })
+                        .map(|binding| resolve_shortcut_action(config, &binding.action))
+                })?;
+                self.pointer_shortcuts.suppressed_buttons.push(button);
+                Some(action.unwrap_or(KeyAction::None))
+            }
+            ButtonState::Released => {
+                let suppressed = &mut self.pointer_shortcuts.suppressed_buttons;
+                let index = suppressed.iter().position(|b| *b == button)?;
+                suppressed.remove(index);
+                Some(KeyAction::None)
+            }
+        }
+    }
+
+    /// Runs the shortcuts bound to scrolling with the current modifiers.
+    ///
+    /// Returns `None` if the scroll goes to the client. While a scroll
+    /// binding matches the modifiers, scrolling on that axis is not sent to
+    /// clients even between steps.
+    pub(crate) fn pointer_axis_shortcut(
+        &mut self,
+        source: AxisSource,
+        delta: (f64, f64),
+    ) -> Option<KeyAction> {
+        let modifiers = self.current_modifiers;
+        let (horizontal, vertical) = Config::with(|config| {
+            let bound = |input| {
+                config
+                    .pointer_bindings()
+                    .iter()
+                    .any(|binding| binding.trigger.matches(&modifiers, input))
+            };
+            (
+                bound(PointerInput::ScrollLeft) || bound(PointerInput::ScrollRight),
+                bound(PointerInput::ScrollUp) || bound(PointerInput::ScrollDown),
+            )
+        });
+        if !(horizontal || vertical) || self.shortcuts_inhibited() {
+            self.pointer_shortcuts.scroll_accumulated = (0.0, 0.0);
+            return None;
+        }
+        self.key_tap = None;
+
+        let step = match source {
+            AxisSource::Wheel | AxisSource::WheelTilt => WHEEL_STEP,
+            _ => FINGER_STEP,
+        };
+        let accumulated = &mut self.pointer_shortcuts.scroll_accumulated;
+        let mut input = None;
+        if horizontal {
+            input = scroll_step(&mut accumulated.0, delta.0, step).map(|direction| {
+                if direction < 0 {
+                    PointerInput::ScrollLeft
+                } else {
+                    PointerInput::ScrollRight
+                }
+            });
+        }
+        if vertical && input.is_none() {
+            input = scroll_step(&mut accumulated.1, delta.1, step).map(|direction| {
+                if direction < 0 {
+                    PointerInput::ScrollUp
+                } else {
+                    PointerInput::ScrollDown
+                }
+            });
+        }
+
+        let action = input.and_then(|input| {
+            Config::with(|config| {
+                config
+                    .pointer_bindings()
+                    .iter()
+                    .find(|binding| binding.trigger.matches(&modifiers, input))
+                    .and_then(|binding| resolve_shortcut_action(config, &binding.action))
+            })
+        });
+        Some(action.unwrap_or(KeyAction::None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scroll_step_needs_full_distance() {
+        let mut accumulated = 0.0;
+        assert_eq!(scroll_step(&mut accumulated, -10.0, WHEEL_STEP), None);
+        assert_eq!(scroll_step(&mut accumulated, -10.0, WHEEL_STEP), Some(-1));
+        assert_eq!(accumulated, 0.0);
+    }
+
+    #[test]
+    fn scroll_step_resets_on_direction_change() {
+        let mut accumulated = 0.0;
+        assert_eq!(scroll_step(&mut accumulated, 50.0, FINGER_STEP), None);
+        assert_eq!(scroll_step(&mut accumulated, -20.0, FINGER_STEP), None);
+        assert_eq!(accumulated, -20.0);
+    }
+}
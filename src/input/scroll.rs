@@ -0,0 +1,291 @@
+//! Scroll acceleration and kinetic scrolling
+//!
+//! Wheel and touchpad deltas are scaled by an acceleration curve before they
+//! are forwarded to clients. When the fingers leave the touchpad, the scroll
+//! can continue with the velocity estimated from the last samples and decay
+//! over time, like the momentum used for swipe gestures.
+
+use std::{collections::VecDeque, time::Duration};
+
+use smithay::{
+    backend::input::{Axis, AxisSource},
+    input::pointer::AxisFrame,
+    reexports::calloop::{
+        timer::{TimeoutAction, Timer},
+        RegistrationToken,
+    },
+};
+
+use crate::{
+    config::{Config, ScrollSettings},
+    state::{Backend, VELOCITY_SAMPLE_COUNT},
+    Otto,
+};
+
+/// Interval between synthetic kinetic scroll frames
+const KINETIC_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+/// Kinetic scrolling stops below this speed (logical px per ms)
+const KINETIC_MIN_VELOCITY: f64 = 0.05;
+/// Samples older than this at finger lift mean the fingers rested before lifting
+const KINETIC_MAX_SAMPLE_AGE_MS: u32 = 50;
+/// Pauses longer than this reset the acceleration curve
+const ACCELERATION_RESET_MS: u32 = 100;
+
+#[derive(Debug, Clone, Copy)]
+struct ScrollSample {
+    time: u32,
+    delta: (f64, f64),
+}
+
+#[derive(Debug)]
+struct KineticScroll {
+    /// Logical px per ms
+    velocity: (f64, f64),
+    last_tick: u32,
+    token: RegistrationToken,
+}
+
+/// Scroll tracking state kept between axis events
+#[derive(Debug, Default)]
+pub struct ScrollState {
+    last_event_time: Option<u32>,
+    samples: VecDeque<ScrollSample>,
+    kinetic: Option<KineticScroll>,
+}
+
+/// Multiplier applied to a scroll delta given the time since the previous event
+pub fn acceleration_factor(delta: f64, elapsed_ms: u32, settings: &ScrollSettings) -> f64 {
+    if settings.acceleration <= 0.0 || elapsed_ms > ACCELERATION_RESET_MS {
+        return 1.0;
+    }
+    let speed = delta.abs() / elapsed_ms.max(1) as f64;
+    (1.0 + settings.acceleration * speed).min(settings.acceleration_max.max(1.0))
+}
+
+/// Average velocity (px per ms) of the samples, or `None` if the fingers
+/// stopped before lifting.
+fn estimate_velocity(samples: &VecDeque<ScrollSample>, lift_time: u32) -> Option<(f64, f64)> {
+    let first = samples.front()?;
+    let last = samples.back()?;
+    if lift_time.wrapping_sub(last.time) > KINETIC_MAX_SAMPLE_AGE_MS {
+        return None;
+    }
+    // The first sample's delta covers the interval before it, so count one
+    // extra event interval to avoid overestimating short flicks
+    let span = last.time.wrapping_sub(first.time) as f64;
+    let interval = (span / samples.len().saturating_sub(1).max(1) as f64).max(1.0);
+    let duration = span + interval;
+    let (h, v) = samples.iter().fold((0.0, 0.0), |(h, v), sample| {
+        (h + sample.delta.0, v + sample.delta.1)
+    });
+    Some((h / duration, v / duration))
+}
+
+/// Velocity after `elapsed_ms` of exponential decay
+fn decay_velocity(velocity: (f64, f64), elapsed_ms: f64, time_constant_ms: f64) -> (f64, f64) {
+    let decay = (-elapsed_ms / time_constant_ms.max(1.0)).exp();
+    (velocity.0 * decay, velocity.1 * decay)
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Scroll settings for the app under the pointer
+    pub(crate) fn scroll_settings_under_pointer(&self) -> ScrollSettings {
+        let app_id = self
+            .workspaces
+            .element_under(self.pointer.current_location())
+            .map(|(window, _)| window.xdg_app_id())
+            .filter(|app_id| !app_id.is_empty());
+        Config::with(|config| config.input.scroll.resolve(app_id.as_deref()))
+    }
+
+    /// Applies the acceleration curve to an axis event and records the
+    /// sample for kinetic scrolling. Returns the multiplier for the deltas.
+    pub(crate) fn accelerate_scroll(
+        &mut self,
+        source: AxisSource,
+        time: u32,
+        delta: (f64, f64),
+        settings: &ScrollSettings,
+    ) -> f64 {
+        self.cancel_kinetic_scroll();
+
+        let elapsed = self
+            .scroll_state
+            .last_event_time
+            .map(|last| time.wrapping_sub(last))
+            .unwrap_or(u32::MAX);
+        self.scroll_state.last_event_time = Some(time);
+
+        if elapsed > ACCELERATION_RESET_MS {
+            self.scroll_state.samples.clear();
+        }
+
+        let factor = match source {
+            AxisSource::Wheel | AxisSource::Finger | AxisSource::Continuous => {
+                let magnitude = delta.0.hypot(delta.1);
+                acceleration_factor(magnitude, elapsed, settings)
+            }
+            _ => 1.0,
+        };
+
+        if source == AxisSource::Finger && (delta.0 != 0.0 || delta.1 != 0.0) {
+            self.scroll_state.samples.push_back(ScrollSample {
+                time,
+                delta: (delta.0 * factor, delta.1 * factor),
+            });
+            if self.scroll_state.samples.len() > VELOCITY_SAMPLE_COUNT {
+                self.scroll_state.samples.pop_front();
+            }
+        }
+
+        factor
+    }
+
+    /// Starts kinetic scrolling after a finger lift.
+    ///
+    /// Returns `true` if the scroll continues, in which case the stop event
+    /// must not be forwarded to the client.
+    pub(crate) fn start_kinetic_scroll(&mut self, time: u32, settings: &ScrollSettings) -> bool {
+        let samples = std::mem::take(&mut self.scroll_state.samples);
+        if !settings.kinetic {
+            return false;
+        }
+        let Some(velocity) = estimate_velocity(&samples, time) else {
+            return false;
+        };
+        if velocity.0.hypot(velocity.1) < KINETIC_MIN_VELOCITY {
+            return false;
+        }
+
+        let time_constant_ms = settings.kinetic_time_constant_ms;
+        let timer = Timer::from_duration(KINETIC_FRAME_INTERVAL);
+        let token = self.handle.insert_source(timer, move |_, _, data| {
+            data.kinetic_scroll_tick(time_constant_ms)
+        });
+        match token {
+            Ok(token) => {
+                self.scroll_state.kinetic = Some(KineticScroll {
+                    velocity,
+                    last_tick: self.clock.now().as_millis(),
+                    token,
+                });
+                true
+            }
+            Err(err) => {
+                tracing::warn!(?err, "Failed to schedule kinetic scroll");
+                false
+            }
+        }
+    }
+
+    /// Stops a running kinetic scroll, e.g. when the touchpad is touched again
+    pub(crate) fn cancel_kinetic_scroll(&mut self) {
+        if let Some(kinetic) = self.scroll_state.kinetic.take() {
+            self.handle.remove(kinetic.token);
+            self.send_axis_stop(self.clock.now().as_millis());
+        }
+    }
+
+    /// Tells the client the scroll stopped, like lifting the fingers does
+    fn send_axis_stop(&mut self, time: u32) {
+        let frame = AxisFrame::new(time)
+            .source(AxisSource::Finger)
+            .stop(Axis::Horizontal)
+            .stop(Axis::Vertical);
+        let pointer = self.pointer.clone();
+        pointer.axis(self, frame);
+        pointer.frame(self);
+    }
+
+    fn kinetic_scroll_tick(&mut self, time_constant_ms: f64) -> TimeoutAction {
+        let now = self.clock.now().as_millis();
+        let Some(kinetic) = self.scroll_state.kinetic.as_mut() else {
+            return TimeoutAction::Drop;
+        };
+
+        let elapsed = now.wrapping_sub(kinetic.last_tick) as f64;
+        kinetic.velocity = decay_velocity(kinetic.velocity, elapsed, time_constant_ms);
+        kinetic.last_tick = now;
+        let velocity = kinetic.velocity;
+
+        if velocity.0.hypot(velocity.1) < KINETIC_MIN_VELOCITY {
+            self.scroll_state.kinetic = None;
+            self.send_axis_stop(now);
+            return TimeoutAction::Drop;
+        }
+
+        let mut frame = AxisFrame::new(now).source(AxisSource::Finger);
+        if velocity.0 != 0.0 {
+            frame = frame.value(Axis::Horizontal, velocity.0 * elapsed);
+        }
+        if velocity.1 != 0.0 {
+            frame = frame.value(Axis::Vertical, velocity.1 * elapsed);
+        }
+        let pointer = self.pointer.clone();
+        pointer.axis(self, frame);
+        pointer.frame(self);
+
+        TimeoutAction::ToDuration(KINETIC_FRAME_INTERVAL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(acceleration: f64) -> ScrollSettings {
+        ScrollSettings {
+            acceleration,
+            acceleration_max: 3.0,
+            kinetic: true,
+            kinetic_time_constant_ms: 325.0,
+        }
+    }
+
+    fn samples(deltas: &[(u32, f64)]) -> VecDeque<ScrollSample> {
+        deltas
+            .iter()
+            .map(|(time, delta)| ScrollSample {
+                time: *time,
+                delta: (0.0, *delta),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_acceleration_disabled_is_linear() {
+        assert_eq!(acceleration_factor(40.0, 5, &settings(0.0)), 1.0);
+    }
+
+    #[test]
+    fn test_acceleration_grows_with_speed_and_is_capped() {
+        let slow = acceleration_factor(2.0, 10, &settings(1.0));
+        let fast = acceleration_factor(20.0, 10, &settings(1.0));
+        assert!(slow > 1.0 && slow < fast);
+        assert_eq!(acceleration_factor(500.0, 1, &settings(1.0)), 3.0);
+    }
+
+    #[test]
+    fn test_acceleration_resets_after_pause() {
+        assert_eq!(acceleration_factor(20.0, 500, &settings(1.0)), 1.0);
+    }
+
+    #[test]
+    fn test_velocity_estimation() {
+        let samples = samples(&[(100, 10.0), (110, 10.0), (120, 10.0), (130, 10.0)]);
+        let (_, v) = estimate_velocity(&samples, 135).unwrap();
+        assert!((v - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_velocity_when_fingers_rested() {
+        let samples = samples(&[(100, 10.0), (110, 10.0)]);
+        assert!(estimate_velocity(&samples, 400).is_none());
+    }
+
+    #[test]
+    fn test_velocity_decay() {
+        let (_, v) = decay_velocity((0.0, 2.0), 325.0, 325.0);
+        assert!((v - 2.0 / std::f64::consts::E).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,304 @@
+//! Policy for clients that inhibit compositor shortcuts
+//!
+//! Remote desktop and virtual machine viewers use keyboard-shortcuts-inhibit
+//! to receive every key. `[shortcut_inhibit]` decides per app_id whether the
+//! request is granted; apps without a policy are asked about the first time.
+//! The escape shortcut is always handled by the compositor and suspends or
+//! restores the inhibition of the app with keyboard focus.
+
+use std::collections::{HashMap, VecDeque};
+
+use smithay::{
+    input::keyboard::{Keysym, ModifiersState},
+    reexports::wayland_server::{protocol::wl_surface::WlSurface, Resource},
+    utils::IsAlive,
+    wayland::{
+        keyboard_shortcuts_inhibit::{KeyboardShortcutsInhibitor, KeyboardShortcutsInhibitorSeat},
+        seat::WaylandFocus,
+    },
+};
+use tracing::info;
+
+use crate::{
+    config::{Config, InhibitPolicy},
+    state::Backend,
+    workspaces::{InhibitIndicatorModel, PromptModel},
+    Otto,
+};
+
+#[derive(Debug, Default)]
+pub struct ShortcutInhibitState {
+    /// Answers given in the prompt by app_id, kept until the compositor exits
+    decisions: HashMap<String, bool>,
+    /// Requests waiting for an answer, the first one is shown
+    prompts: VecDeque<InhibitPrompt>,
+    /// Inhibitors released with the escape shortcut
+    suspended: Vec<KeyboardShortcutsInhibitor>,
+    /// App named by the indicator while it is shown
+    indicator: Option<String>,
+}
+
+#[derive(Debug)]
+struct InhibitPrompt {
+    inhibitor: KeyboardShortcutsInhibitor,
+    app_id: String,
+}
+
+impl InhibitPrompt {
+    fn model(&self) -> PromptModel {
+        PromptModel {
+            title: format!("{} wants to receive all keys", app_name(&self.app_id)),
+            lines: Vec::new(),
+            hint: format!(
+                "Compositor shortcuts stop working in this app, {} releases it · Return allow · Esc deny",
+                Config::with(|config| config.shortcut_inhibit.escape.clone())
+            ),
+        }
+    }
+}
+
+fn app_name(app_id: &str) -> &str {
+    if app_id.is_empty() {
+        "An application"
+    } else {
+        app_id
+    }
+}
+
+/// Inhibitor that applies to the keys: the one of the surface with keyboard
+/// focus, whatever is under the pointer
+pub fn inhibitor_for_focus<S, I>(
+    keyboard_focus: Option<&S>,
+    inhibitor_of: impl Fn(&S) -> Option<I>,
+) -> Option<I> {
+    keyboard_focus.and_then(inhibitor_of)
+}
+
+/// Whether to grant an inhibitor, `None` if the user has to be asked
+pub fn inhibit_decision(policy: InhibitPolicy, answer: Option<bool>) -> Option<bool> {
+    match policy {
+        InhibitPolicy::Allow => Some(true),
+        InhibitPolicy::Deny => Some(false),
+        InhibitPolicy::Ask => answer,
+    }
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Applies the configured policy to a new inhibitor
+    pub(crate) fn request_shortcut_inhibit(&mut self, inhibitor: KeyboardShortcutsInhibitor) {
+        let app_id = self.inhibitor_app_id(&inhibitor);
+        let policy = Config::with(|config| config.shortcut_inhibit.policy_for(&app_id));
+        let answer = self.shortcut_inhibit.decisions.get(&app_id).copied();
+        match inhibit_decision(policy, answer) {
+            Some(true) => {
+                info!(%app_id, "Shortcut inhibitor granted");
+                inhibitor.activate();
+            }
+            Some(false) => info!(%app_id, "Shortcut inhibitor denied"),
+            None => {
+                info!(%app_id, "Asking to confirm shortcut inhibitor");
+                self.shortcut_inhibit
+                    .prompts
+                    .push_back(InhibitPrompt { inhibitor, app_id });
+                self.update_inhibit_prompt();
+            }
+        }
+        self.update_inhibit_indicator();
+    }
+
+    pub(crate) fn shortcut_inhibitor_destroyed(&mut self, inhibitor: &KeyboardShortcutsInhibitor) {
+        let state = &mut self.shortcut_inhibit;
+        state
+            .prompts
+            .retain(|prompt| &prompt.inhibitor != inhibitor);
+        state.suspended.retain(|suspended| suspended != inhibitor);
+        self.update_inhibit_prompt();
+        self.update_inhibit_indicator();
+    }
+
+    fn inhibitor_app_id(&self, inhibitor: &KeyboardShortcutsInhibitor) -> String {
+        self.workspaces
+            .get_window_for_surface(&inhibitor.wl_surface().id())
+            .map(|window| window.display_app_id(&self.display_handle))
+            .unwrap_or_default()
+    }
+
+    fn keyboard_focus_surface(&self) -> Option<WlSurface> {
+        self.seat
+            .get_keyboard()
+            .and_then(|keyboard| keyboard.current_focus())
+            .and_then(|focus| focus.wl_surface().map(|surface| surface.into_owned()))
+    }
+
+    /// Inhibitor of the client with keyboard focus, granted or not
+    pub(crate) fn focused_inhibitor(&self) -> Option<KeyboardShortcutsInhibitor> {
+        self.focused_inhibitor_for(self.keyboard_focus_surface().as_ref())
+    }
+
+    fn focused_inhibitor_for(
+        &self,
+        focus: Option<&WlSurface>,
+    ) -> Option<KeyboardShortcutsInhibitor> {
+        inhibitor_for_focus(focus, |surface| {
+            self.seat.keyboard_shortcuts_inhibitor_for_surface(surface)
+        })
+    }
+
+    /// Whether the escape shortcut has an inhibitor to suspend or restore
+    pub(crate) fn inhibit_escape_available(&self) -> bool {
+        self.focused_inhibitor().is_some_and(|inhibitor| {
+            inhibitor.is_active() || self.shortcut_inhibit.suspended.contains(&inhibitor)
+        })
+    }
+
+    /// Suspends the inhibitor of the focused client, or restores a suspended
+    /// one
+    pub(crate) fn toggle_shortcut_inhibit(&mut self) {
+        let Some(inhibitor) = self.focused_inhibitor() else {
+            return;
+        };
+        let suspended = &mut self.shortcut_inhibit.suspended;
+        if inhibitor.is_active() {
+            info!("Shortcut inhibitor suspended");
+            inhibitor.inactivate();
+            suspended.push(inhibitor);
+        } else if let Some(index) = suspended.iter().position(|s| *s == inhibitor) {
+            info!("Shortcut inhibitor restored");
+            suspended.remove(index);
+            inhibitor.activate();
+        }
+        self.update_inhibit_indicator();
+    }
+
+    pub fn is_inhibit_prompt_active(&self) -> bool {
+        !self.shortcut_inhibit.prompts.is_empty()
+    }
+
+    /// Shows the first pending request, or hands the prompt over to a
+    /// wallpaper preview.
+    ///
+    /// Global shortcut requests share the prompt and are shown first.
+    pub(crate) fn update_inhibit_prompt(&self) {
+        if self.is_shortcut_prompt_active() {
+            return;
+        }
+        match self.shortcut_inhibit.prompts.front() {
+            Some(prompt) => self.workspaces.prompt.show(prompt.model()),
+            None => self.update_wallpaper_prompt(),
+        }
+    }
+
+    /// Handles a key press while an inhibitor request is shown
+    pub(crate) fn inhibit_prompt_key(&mut self, modifiers: &ModifiersState, keysym: Keysym) {
+        let plain = !(modifiers.ctrl || modifiers.alt || modifiers.logo || modifiers.shift);
+        match keysym {
+            Keysym::Return | Keysym::KP_Enter if plain => self.finish_inhibit_prompt(true),
+            Keysym::Escape if plain => self.finish_inhibit_prompt(false),
+            _ => {}
+        }
+    }
+
+    fn finish_inhibit_prompt(&mut self, accepted: bool) {
+        let Some(prompt) = self.shortcut_inhibit.prompts.pop_front() else {
+            return;
+        };
+        info!(app_id = %prompt.app_id, accepted, "Shortcut inhibitor answered");
+        let state = &mut self.shortcut_inhibit;
+        state.decisions.insert(prompt.app_id.clone(), accepted);
+
+        // The answer covers every pending request of the app
+        let (answered, pending): (VecDeque<_>, VecDeque<_>) = std::mem::take(&mut state.prompts)
+            .into_iter()
+            .partition(|pending| pending.app_id == prompt.app_id);
+        state.prompts = pending;
+        if accepted {
+            for request in std::iter::once(prompt).chain(answered) {
+                if request.inhibitor.wl_surface().alive() {
+                    request.inhibitor.activate();
+                }
+            }
+        }
+        self.update_inhibit_prompt();
+        self.update_inhibit_indicator();
+    }
+
+    /// Shows the indicator while the client with keyboard focus inhibits
+    /// shortcuts
+    pub(crate) fn update_inhibit_indicator(&mut self) {
+        let focus = self.keyboard_focus_surface();
+        self.update_inhibit_indicator_for(focus.as_ref());
+    }
+
+    /// Shows the indicator while `focus` inhibits shortcuts, called as the
+    /// keyboard focus changes
+    pub(crate) fn update_inhibit_indicator_for(&mut self, focus: Option<&WlSurface>) {
+        let show = Config::with(|config| config.shortcut_inhibit.show_indicator);
+        let app_id = self
+            .focused_inhibitor_for(focus)
+            .filter(|inhibitor| show && inhibitor.is_active())
+            .map(|inhibitor| self.inhibitor_app_id(&inhibitor));
+        if app_id == self.shortcut_inhibit.indicator {
+            return;
+        }
+        match &app_id {
+            Some(app_id) => {
+                let escape = Config::with(|config| config.shortcut_inhibit.escape.clone());
+                self.workspaces
+                    .inhibit_indicator
+                    .show(InhibitIndicatorModel {
+                        text: format!(
+                            "{} receives all keys · {escape} to release",
+                            app_name(app_id)
+                        ),
+                    });
+            }
+            None => self.workspaces.inhibit_indicator.hide(),
+        }
+        self.shortcut_inhibit.indicator = app_id;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configured_policy_overrides_answer() {
+        assert_eq!(
+            inhibit_decision(InhibitPolicy::Allow, Some(false)),
+            Some(true)
+        );
+        assert_eq!(
+            inhibit_decision(InhibitPolicy::Deny, Some(true)),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn keyboard_focus_decides_over_pointer_focus() {
+        // Only the viewer inhibits shortcuts
+        let inhibitor_of = |surface: &&str| (*surface == "viewer").then_some("viewer inhibitor");
+
+        // Pointer over the viewer, keys going to the editor
+        let (pointer_focus, keyboard_focus) = ("viewer", "editor");
+        assert!(inhibitor_of(&pointer_focus).is_some());
+        assert_eq!(
+            inhibitor_for_focus(Some(&keyboard_focus), inhibitor_of),
+            None
+        );
+
+        // Pointer over the editor, keys going to the viewer
+        let (pointer_focus, keyboard_focus) = ("editor", "viewer");
+        assert!(inhibitor_of(&pointer_focus).is_none());
+        assert_eq!(
+            inhibitor_for_focus(Some(&keyboard_focus), inhibitor_of),
+            Some("viewer inhibitor")
+        );
+    }
+
+    #[test]
+    fn ask_policy_uses_previous_answer() {
+        assert_eq!(inhibit_decision(InhibitPolicy::Ask, None), None);
+        assert_eq!(inhibit_decision(InhibitPolicy::Ask, Some(true)), Some(true));
+    }
+}
@@ -0,0 +1,142 @@
+//! Moving and resizing windows by dragging with a modifier held
+//!
+//! With `input.window_drag_modifier` held, the left button moves the window
+//! under the pointer and the right button resizes it from the nearest corner.
+//! This works for every window, including clients with broken or missing
+//! decorations, by starting the same grabs as client move and resize requests.
+
+use std::cell::RefCell;
+
+use smithay::{
+    input::pointer::{Focus, GrabStartData as PointerGrabStartData},
+    utils::{Logical, Point, Serial, Size},
+    wayland::compositor::with_states,
+};
+
+use crate::{
+    config::{shortcuts::WindowDrag, Config},
+    shell::{
+        PointerMoveSurfaceGrab, PointerResizeSurfaceGrab, ResizeData, ResizeEdge, ResizeState,
+        SurfaceData,
+    },
+    state::Backend,
+    Otto,
+};
+
+/// Corner of a window of `size` closest to `point`, relative to the window
+pub fn nearest_corner(point: Point<f64, Logical>, size: Size<i32, Logical>) -> ResizeEdge {
+    let horizontal = if point.x < size.w as f64 / 2.0 {
+        ResizeEdge::LEFT
+    } else {
+        ResizeEdge::RIGHT
+    };
+    let vertical = if point.y < size.h as f64 / 2.0 {
+        ResizeEdge::TOP
+    } else {
+        ResizeEdge::BOTTOM
+    };
+    horizontal | vertical
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Starts moving or resizing the window under the pointer if `button`
+    /// is pressed with the drag modifier.
+    ///
+    /// The press must then be passed to the pointer, so the grab ends when
+    /// the button is released.
+    pub(crate) fn start_window_drag(&mut self, button: u32, serial: Serial) {
+        if self.pointer.is_grabbed() || self.workspaces.get_show_all() || self.shortcuts_inhibited()
+        {
+            return;
+        }
+        let modifiers = self.current_modifiers;
+        let Some(drag) = Config::with(|config| {
+            config
+                .window_drag()
+                .and_then(|triggers| triggers.matches(&modifiers, button))
+        }) else {
+            return;
+        };
+
+        let location = self.pointer.current_location();
+        let Some(window) = self
+            .workspaces
+            .element_under(location)
+            .map(|(window, _)| window.clone())
+        else {
+            return;
+        };
+        if window.is_fullscreen() || window.is_maximized() || window.is_minimised() {
+            return;
+        }
+        let Some(initial_window_location) = self.workspaces.element_location(&window) else {
+            return;
+        };
+
+        let start_data = PointerGrabStartData {
+            focus: None,
+            button,
+            location,
+        };
+        let pointer = self.pointer.clone();
+        match drag {
+            WindowDrag::Move => {
+                let grab = PointerMoveSurfaceGrab {
+                    start_data,
+                    window,
+                    initial_window_location,
+                };
+                pointer.set_grab(self, grab, serial, Focus::Clear);
+            }
+            WindowDrag::Resize => {
+                let initial_window_size = window.geometry().size;
+                let edges = nearest_corner(
+                    location - initial_window_location.to_f64(),
+                    initial_window_size,
+                );
+                if let Some(surface) = window.wl_surface() {
+                    with_states(&surface, |states| {
+                        if let Some(data) = states.data_map.get::<RefCell<SurfaceData>>() {
+                            data.borrow_mut().resize_state = ResizeState::Resizing(ResizeData {
+                                edges,
+                                initial_window_location,
+                                initial_window_size,
+                            });
+                        }
+                    });
+                }
+                let grab = PointerResizeSurfaceGrab {
+                    start_data,
+                    window,
+                    edges,
+                    initial_window_location,
+                    initial_window_size,
+                    last_window_size: initial_window_size,
+                };
+                pointer.set_grab(self, grab, serial, Focus::Clear);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_corner_splits_window_in_quadrants() {
+        let size = Size::from((800, 600));
+        assert_eq!(
+            nearest_corner(Point::from((10.0, 10.0)), size),
+            ResizeEdge::TOP_LEFT
+        );
+        assert_eq!(
+            nearest_corner(Point::from((700.0, 500.0)), size),
+            ResizeEdge::BOTTOM_RIGHT
+        );
+        assert_eq!(
+            nearest_corner(Point::from((700.0, 20.0)), size),
+            ResizeEdge::TOP_RIGHT
+        );
+    }
+}
@@ -32,114 +32,10 @@ impl<Backend: crate::state::Backend> Otto<Backend> {
         output_name: &str,
     ) {
         match event {
-            InputEvent::Keyboard { event } => match self.keyboard_key_to_action::<B>(event) {
-                KeyAction::ScaleUp => {
-                    let output = self
-                        .workspaces
-                        .outputs()
-                        .find(|o| o.name() == output_name)
-                        .unwrap()
-                        .clone();
-
-                    let current_scale = output.current_scale().fractional_scale();
-                    let new_scale = current_scale + 0.25;
-                    output.change_current_state(
-                        None,
-                        None,
-                        Some(Scale::Fractional(new_scale)),
-                        None,
-                    );
-                    let current_location = self.pointer.current_location();
-
-                    crate::shell::fixup_positions(&mut self.workspaces, current_location);
-                    self.backend_data.reset_buffers(&output);
-                }
-
-                KeyAction::ScaleDown => {
-                    let output = self
-                        .workspaces
-                        .outputs()
-                        .find(|o| o.name() == output_name)
-                        .unwrap()
-                        .clone();
-
-                    let current_scale = output.current_scale().fractional_scale();
-                    let new_scale = f64::max(1.0, current_scale - 0.25);
-                    output.change_current_state(
-                        None,
-                        None,
-                        Some(Scale::Fractional(new_scale)),
-                        None,
-                    );
-                    let current_location = self.pointer.current_location();
-                    crate::shell::fixup_positions(&mut self.workspaces, current_location);
-                    self.backend_data.reset_buffers(&output);
-                }
-
-                KeyAction::RotateOutput => {
-                    let output = self
-                        .workspaces
-                        .outputs()
-                        .find(|o| o.name() == output_name)
-                        .unwrap()
-                        .clone();
-
-                    let current_transform = output.current_transform();
-                    let new_transform = match current_transform {
-                        Transform::Normal => Transform::_90,
-                        Transform::_90 => Transform::_180,
-                        Transform::_180 => Transform::_270,
-                        Transform::_270 => Transform::Normal,
-                        _ => Transform::Normal,
-                    };
-                    output.change_current_state(None, Some(new_transform), None, None);
-                    let current_location = self.pointer.current_location();
-
-                    crate::shell::fixup_positions(&mut self.workspaces, current_location);
-                    self.backend_data.reset_buffers(&output);
-                }
-                KeyAction::ApplicationSwitchNext => {
-                    self.handle_app_switcher_next();
-                }
-                KeyAction::ApplicationSwitchPrev => {
-                    self.handle_app_switcher_prev();
-                }
-                KeyAction::ApplicationSwitchQuit => {
-                    self.handle_app_switcher_quit();
-                }
-                KeyAction::ToggleMaximize => {
-                    self.handle_toggle_maximize();
-                }
-                KeyAction::CloseWindow => {
-                    self.handle_close_window();
-                }
-                KeyAction::ApplicationSwitchNextWindow => {
-                    self.handle_app_switcher_next_window();
-                }
-                KeyAction::ExposeShowDesktop => {
-                    self.handle_expose_show_desktop();
-                }
-                KeyAction::ExposeShowAll => {
-                    self.handle_expose_show_all();
-                }
-                KeyAction::WorkspaceNum(n) => {
-                    self.handle_workspace_num(n);
-                }
-
-                action => match action {
-                    KeyAction::None
-                    | KeyAction::Quit
-                    | KeyAction::Run(_)
-                    | KeyAction::ToggleDecorations
-                    | KeyAction::SceneSnapshot => self.process_common_key_action(action),
-
-                    _ => tracing::warn!(
-                        ?action,
-                        output_name,
-                        "Key action unsupported on output backend.",
-                    ),
-                },
-            },
+            InputEvent::Keyboard { event } => {
+                let action = self.keyboard_key_to_action::<B>(event);
+                self.process_key_action_windowed(action, output_name);
+            }
 
             InputEvent::PointerMotionAbsolute { event } => {
                 let output = self
@@ -150,209 +46,177 @@ impl<Backend: crate::state::Backend> Otto<Backend> {
                     .clone();
                 self.on_pointer_move_absolute_windowed::<B>(event, &output)
             }
-            InputEvent::PointerButton { event } => self.on_pointer_button::<B>(event),
-            InputEvent::PointerAxis { event } => self.on_pointer_axis::<B>(event),
+            InputEvent::PointerButton { event } => {
+                let action = self.on_pointer_button::<B>(event);
+                self.process_key_action_windowed(action, output_name);
+            }
+            InputEvent::PointerAxis { event } => {
+                let action = self.on_pointer_axis::<B>(event);
+                self.process_key_action_windowed(action, output_name);
+            }
             _ => (), // other events are not handled (yet)
         }
     }
-}
 
-#[cfg(feature = "udev")]
-impl Otto<UdevData> {
-    pub fn process_input_event<B: InputBackend>(
-        &mut self,
-        dh: &DisplayHandle,
-        event: InputEvent<B>,
-    ) {
-        match event {
-            InputEvent::Keyboard { event, .. } => match self.keyboard_key_to_action::<B>(event) {
-                #[cfg(feature = "udev")]
-                KeyAction::VtSwitch(vt) => {
-                    tracing::info!(to = vt, "Trying to switch vt");
-                    if let Err(err) = self.backend_data.session.change_vt(vt) {
-                        tracing::error!(vt, "Error switching vt: {}", err);
-                    }
-                }
-                KeyAction::Screen(num) => {
-                    let geometry = self
-                        .workspaces
-                        .outputs()
-                        .nth(num)
-                        .map(|o| self.workspaces.output_geometry(o).unwrap());
+    /// Runs an action without an input event on the first output of a
+    /// windowed backend
+    pub(crate) fn process_key_action_on_first_output(&mut self, action: KeyAction) {
+        let output_name = self
+            .workspaces
+            .outputs()
+            .next()
+            .map(|output| output.name())
+            .unwrap_or_default();
+        self.process_key_action_windowed(action, &output_name);
+    }
 
-                    if let Some(geometry) = geometry {
-                        let x = geometry.loc.x as f64 + geometry.size.w as f64 / 2.0;
-                        let y = geometry.size.h as f64 / 2.0;
-                        let location = (x, y).into();
-                        let pointer = self.pointer.clone();
-                        let under = self.surface_under(location);
-                        pointer.motion(
-                            self,
-                            under,
-                            &smithay::input::pointer::MotionEvent {
-                                location,
-                                serial: smithay::utils::SERIAL_COUNTER.next_serial(),
-                                time: 0,
-                            },
-                        );
-                        pointer.frame(self);
-                    }
-                }
-                KeyAction::ScaleUp => {
-                    let pos = self.pointer.current_location().to_i32_round();
-                    let output = self
-                        .workspaces
-                        .outputs()
-                        .find(|o| self.workspaces.output_geometry(o).unwrap().contains(pos))
-                        .cloned();
+    /// Runs the action bound to a shortcut on a windowed backend
+    pub(crate) fn process_key_action_windowed(&mut self, action: KeyAction, output_name: &str) {
+        match action {
+            KeyAction::ScaleUp => {
+                let output = self
+                    .workspaces
+                    .outputs()
+                    .find(|o| o.name() == output_name)
+                    .unwrap()
+                    .clone();
 
-                    if let Some(output) = output {
-                        let (output_location, scale) = (
-                            self.workspaces.output_geometry(&output).unwrap().loc,
-                            output.current_scale().fractional_scale(),
-                        );
-                        let new_scale = scale + 0.25;
-                        output.change_current_state(
-                            None,
-                            None,
-                            Some(Scale::Fractional(new_scale)),
-                            None,
-                        );
+                let current_scale = output.current_scale().fractional_scale();
+                let new_scale = current_scale + 0.25;
+                output.change_current_state(None, None, Some(Scale::Fractional(new_scale)), None);
+                let current_location = self.pointer.current_location();
 
-                        let rescale = scale / new_scale;
-                        let output_location = output_location.to_f64();
-                        let mut pointer_output_location =
-                            self.pointer.current_location() - output_location;
-                        pointer_output_location.x *= rescale;
-                        pointer_output_location.y *= rescale;
-                        let pointer_location = output_location + pointer_output_location;
-                        crate::shell::fixup_positions(&mut self.workspaces, pointer_location);
-                        let pointer = self.pointer.clone();
-                        let under = self.surface_under(pointer_location);
-                        pointer.motion(
-                            self,
-                            under,
-                            &smithay::input::pointer::MotionEvent {
-                                location: pointer_location,
-                                serial: smithay::utils::SERIAL_COUNTER.next_serial(),
-                                time: 0,
-                            },
-                        );
-                        pointer.frame(self);
-                        self.backend_data.reset_buffers(&output);
-                    }
-                }
-                KeyAction::ScaleDown => {
-                    let pos = self.pointer.current_location().to_i32_round();
-                    let output = self
-                        .workspaces
-                        .outputs()
-                        .find(|o| self.workspaces.output_geometry(o).unwrap().contains(pos))
-                        .cloned();
+                crate::shell::fixup_positions(&mut self.workspaces, current_location);
+                self.backend_data.reset_buffers(&output);
+            }
 
-                    if let Some(output) = output {
-                        let (output_location, scale) = (
-                            self.workspaces.output_geometry(&output).unwrap().loc,
-                            output.current_scale().fractional_scale(),
-                        );
-                        let new_scale = f64::max(1.0, scale - 0.25);
-                        output.change_current_state(
-                            None,
-                            None,
-                            Some(Scale::Fractional(new_scale)),
-                            None,
-                        );
+            KeyAction::ScaleDown => {
+                let output = self
+                    .workspaces
+                    .outputs()
+                    .find(|o| o.name() == output_name)
+                    .unwrap()
+                    .clone();
 
-                        let rescale = scale / new_scale;
-                        let output_location = output_location.to_f64();
-                        let mut pointer_output_location =
-                            self.pointer.current_location() - output_location;
-                        pointer_output_location.x *= rescale;
-                        pointer_output_location.y *= rescale;
-                        let pointer_location = output_location + pointer_output_location;
+                let current_scale = output.current_scale().fractional_scale();
+                let new_scale = f64::max(1.0, current_scale - 0.25);
+                output.change_current_state(None, None, Some(Scale::Fractional(new_scale)), None);
+                let current_location = self.pointer.current_location();
+                crate::shell::fixup_positions(&mut self.workspaces, current_location);
+                self.backend_data.reset_buffers(&output);
+            }
 
-                        crate::shell::fixup_positions(&mut self.workspaces, pointer_location);
-                        let pointer = self.pointer.clone();
-                        let under = self.surface_under(pointer_location);
-                        pointer.motion(
-                            self,
-                            under,
-                            &smithay::input::pointer::MotionEvent {
-                                location: pointer_location,
-                                serial: smithay::utils::SERIAL_COUNTER.next_serial(),
-                                time: 0,
-                            },
-                        );
-                        pointer.frame(self);
-                        self.backend_data.reset_buffers(&output);
-                    }
-                }
-                KeyAction::RotateOutput => {
-                    let pos = self.pointer.current_location().to_i32_round();
-                    let output = self
-                        .workspaces
-                        .outputs()
-                        .find(|o| self.workspaces.output_geometry(o).unwrap().contains(pos))
-                        .cloned();
+            KeyAction::RotateOutput => {
+                let output = self
+                    .workspaces
+                    .outputs()
+                    .find(|o| o.name() == output_name)
+                    .unwrap()
+                    .clone();
 
-                    if let Some(output) = output {
-                        let current_transform = output.current_transform();
-                        let new_transform = match current_transform {
-                            Transform::Normal => Transform::_90,
-                            Transform::_90 => Transform::_180,
-                            Transform::_180 => Transform::_270,
-                            Transform::_270 => Transform::Normal,
-                            _ => Transform::Normal,
-                        };
-                        output.change_current_state(None, Some(new_transform), None, None);
-                        let current_location = self.pointer.current_location();
-                        crate::shell::fixup_positions(&mut self.workspaces, current_location);
-                        self.backend_data.reset_buffers(&output);
-                    }
-                }
-                KeyAction::ApplicationSwitchNext => {
-                    self.handle_app_switcher_next();
-                }
-                KeyAction::ApplicationSwitchPrev => {
-                    self.handle_app_switcher_prev();
-                }
-                KeyAction::ApplicationSwitchNextWindow => {
-                    self.handle_app_switcher_next_window();
-                }
-                KeyAction::ApplicationSwitchQuit => {
-                    self.handle_app_switcher_quit();
-                }
-                KeyAction::ToggleMaximize => {
-                    self.handle_toggle_maximize();
-                }
-                KeyAction::CloseWindow => {
-                    self.handle_close_window();
-                }
-                KeyAction::ExposeShowDesktop => {
-                    self.handle_expose_show_desktop();
-                }
-                KeyAction::ExposeShowAll => {
-                    self.handle_expose_show_all();
-                }
-                KeyAction::WorkspaceNum(index) => {
-                    self.handle_workspace_num(index);
-                }
-                action => match action {
-                    KeyAction::None
-                    | KeyAction::Quit
-                    | KeyAction::Run(_)
-                    | KeyAction::ToggleDecorations
-                    | KeyAction::SceneSnapshot => self.process_common_key_action(action),
+                let current_transform = output.current_transform();
+                let new_transform = match current_transform {
+                    Transform::Normal => Transform::_90,
+                    Transform::_90 => Transform::_180,
+                    Transform::_180 => Transform::_270,
+                    Transform::_270 => Transform::Normal,
+                    _ => Transform::Normal,
+                };
+                output.change_current_state(None, Some(new_transform), None, None);
+                let current_location = self.pointer.current_location();
 
-                    _ => unreachable!(),
-                },
+                crate::shell::fixup_positions(&mut self.workspaces, current_location);
+                self.backend_data.reset_buffers(&output);
+            }
+            KeyAction::ApplicationSwitchNext => {
+                self.handle_app_switcher_next();
+            }
+            KeyAction::ApplicationSwitchPrev => {
+                self.handle_app_switcher_prev();
+            }
+            KeyAction::ApplicationSwitchQuit => {
+                self.handle_app_switcher_quit();
+            }
+            KeyAction::ToggleMaximize => {
+                self.handle_toggle_maximize();
+            }
+            KeyAction::CloseWindow => {
+                self.handle_close_window();
+            }
+            KeyAction::ApplicationSwitchNextWindow => {
+                self.handle_app_switcher_next_window();
+            }
+            KeyAction::ExposeShowDesktop => {
+                self.handle_expose_show_desktop();
+            }
+            KeyAction::ExposeShowAll => {
+                self.handle_expose_show_all();
+            }
+            KeyAction::ExposeShowAllWorkspaces => {
+                self.handle_expose_show_all_workspaces();
+            }
+            KeyAction::WorkspaceNum(n) => {
+                self.handle_workspace_num(n);
+            }
+            KeyAction::WorkspaceNext => {
+                self.handle_workspace_next();
+            }
+            KeyAction::WorkspacePrev => {
+                self.handle_workspace_prev();
+            }
+
+            action => match action {
+                KeyAction::None
+                | KeyAction::Quit
+                | KeyAction::Run(_)
+                | KeyAction::ToggleDecorations
+                | KeyAction::SceneSnapshot
+                | KeyAction::EnterMode(_)
+                | KeyAction::ExitMode
+                | KeyAction::ToggleSplitView
+                | KeyAction::ExposeShowApp
+                | KeyAction::WorkspaceCreate
+                | KeyAction::WorkspaceRemove
+                | KeyAction::WorkspaceRename
+                | KeyAction::MoveWindowToWorkspace(..)
+                | KeyAction::MoveWindowToWorkspaceNext(_)
+                | KeyAction::MoveWindowToWorkspacePrev(_)
+                | KeyAction::ToggleThemeScheme
+                | KeyAction::ResetWallpaper => self.process_common_key_action(action),
+
+                _ => tracing::warn!(
+                    ?action,
+                    output_name,
+                    "Key action unsupported on output backend.",
+                ),
             },
+        }
+    }
+}
+
+#[cfg(feature = "udev")]
+impl Otto<UdevData> {
+    pub fn process_input_event<B>(&mut self, dh: &DisplayHandle, event: InputEvent<B>)
+    where
+        B: InputBackend<Device = <UdevData as Backend>::InputDevice>,
+    {
+        match event {
+            InputEvent::Keyboard { event, .. } => {
+                let action = self.keyboard_key_to_action::<B>(event);
+                self.process_key_action(action);
+            }
             InputEvent::PointerMotion { event, .. } => self.on_pointer_move::<B>(dh, event),
             InputEvent::PointerMotionAbsolute { event, .. } => {
                 self.on_pointer_move_absolute::<B>(dh, event)
             }
-            InputEvent::PointerButton { event, .. } => self.on_pointer_button::<B>(event),
-            InputEvent::PointerAxis { event, .. } => self.on_pointer_axis::<B>(event),
+            InputEvent::PointerButton { event, .. } => {
+                let action = self.on_pointer_button::<B>(event);
+                self.process_key_action(action);
+            }
+            InputEvent::PointerAxis { event, .. } => {
+                let action = self.on_pointer_axis::<B>(event);
+                self.process_key_action(action);
+            }
             InputEvent::TabletToolAxis { event, .. } => self.on_tablet_tool_axis::<B>(event),
             InputEvent::TabletToolProximity { event, .. } => {
                 self.on_tablet_tool_proximity::<B>(dh, event)
@@ -371,7 +235,9 @@ impl Otto<UdevData> {
             InputEvent::GesturePinchEnd { event, .. } => self.on_gesture_pinch_end::<B>(event),
             InputEvent::GestureHoldBegin { event, .. } => self.on_gesture_hold_begin::<B>(event),
             InputEvent::GestureHoldEnd { event, .. } => self.on_gesture_hold_end::<B>(event),
-            InputEvent::DeviceAdded { device } => {
+            InputEvent::DeviceAdded { mut device } => {
+                UdevData::input_device_added(self, &mut device);
+                self.cache_device_keymap(&device);
                 if device.has_capability(DeviceCapability::TabletTool) {
                     self.seat
                         .tablet_seat()
@@ -379,6 +245,8 @@ impl Otto<UdevData> {
                 }
             }
             InputEvent::DeviceRemoved { device } => {
+                UdevData::input_device_removed(self, &device);
+                self.forget_device_keymap(&device);
                 if device.has_capability(DeviceCapability::TabletTool) {
                     let tablet_seat = self.seat.tablet_seat();
 
@@ -395,4 +263,211 @@ impl Otto<UdevData> {
             }
         }
     }
+
+    /// Runs the action bound to a keyboard shortcut or gesture
+    pub(crate) fn process_key_action(&mut self, action: KeyAction) {
+        match action {
+            #[cfg(feature = "udev")]
+            KeyAction::VtSwitch(vt) => {
+                tracing::info!(to = vt, "Trying to switch vt");
+                if let Err(err) = self.backend_data.session.change_vt(vt) {
+                    tracing::error!(vt, "Error switching vt: {}", err);
+                }
+            }
+            KeyAction::Screen(num) => {
+                let geometry = self
+                    .workspaces
+                    .outputs()
+                    .nth(num)
+                    .map(|o| self.workspaces.output_geometry(o).unwrap());
+
+                if let Some(geometry) = geometry {
+                    let x = geometry.loc.x as f64 + geometry.size.w as f64 / 2.0;
+                    let y = geometry.size.h as f64 / 2.0;
+                    let location = (x, y).into();
+                    let pointer = self.pointer.clone();
+                    let under = self.surface_under(location);
+                    pointer.motion(
+                        self,
+                        under,
+                        &smithay::input::pointer::MotionEvent {
+                            location,
+                            serial: smithay::utils::SERIAL_COUNTER.next_serial(),
+                            time: 0,
+                        },
+                    );
+                    pointer.frame(self);
+                }
+            }
+            KeyAction::ScaleUp => {
+                let pos = self.pointer.current_location().to_i32_round();
+                let output = self
+                    .workspaces
+                    .outputs()
+                    .find(|o| self.workspaces.output_geometry(o).unwrap().contains(pos))
+                    .cloned();
+
+                if let Some(output) = output {
+                    let (output_location, scale) = (
+                        self.workspaces.output_geometry(&output).unwrap().loc,
+                        output.current_scale().fractional_scale(),
+                    );
+                    let new_scale = scale + 0.25;
+                    output.change_current_state(
+                        None,
+                        None,
+                        Some(Scale::Fractional(new_scale)),
+                        None,
+                    );
+
+                    let rescale = scale / new_scale;
+                    let output_location = output_location.to_f64();
+                    let mut pointer_output_location =
+                        self.pointer.current_location() - output_location;
+                    pointer_output_location.x *= rescale;
+                    pointer_output_location.y *= rescale;
+                    let pointer_location = output_location + pointer_output_location;
+                    crate::shell::fixup_positions(&mut self.workspaces, pointer_location);
+                    let pointer = self.pointer.clone();
+                    let under = self.surface_under(pointer_location);
+                    pointer.motion(
+                        self,
+                        under,
+                        &smithay::input::pointer::MotionEvent {
+                            location: pointer_location,
+                            serial: smithay::utils::SERIAL_COUNTER.next_serial(),
+                            time: 0,
+                        },
+                    );
+                    pointer.frame(self);
+                    self.backend_data.reset_buffers(&output);
+                }
+            }
+            KeyAction::ScaleDown => {
+                let pos = self.pointer.current_location().to_i32_round();
+                let output = self
+                    .workspaces
+                    .outputs()
+                    .find(|o| self.workspaces.output_geometry(o).unwrap().contains(pos))
+                    .cloned();
+
+                if let Some(output) = output {
+                    let (output_location, scale) = (
+                        self.workspaces.output_geometry(&output).unwrap().loc,
+                        output.current_scale().fractional_scale(),
+                    );
+                    let new_scale = f64::max(1.0, scale - 0.25);
+                    output.change_current_state(
+                        None,
+                        None,
+                        Some(Scale::Fractional(new_scale)),
+                        None,
+                    );
+
+                    let rescale = scale / new_scale;
+                    let output_location = output_location.to_f64();
+                    let mut pointer_output_location =
+                        self.pointer.current_location() - output_location;
+                    pointer_output_location.x *= rescale;
+                    pointer_output_location.y *= rescale;
+                    let pointer_location = output_location + pointer_output_location;
+
+                    crate::shell::fixup_positions(&mut self.workspaces, pointer_location);
+                    let pointer = self.pointer.clone();
+                    let under = self.surface_under(pointer_location);
+                    pointer.motion(
+                        self,
+                        under,
+                        &smithay::input::pointer::MotionEvent {
+                            location: pointer_location,
+                            serial: smithay::utils::SERIAL_COUNTER.next_serial(),
+                            time: 0,
+                        },
+                    );
+                    pointer.frame(self);
+                    self.backend_data.reset_buffers(&output);
+                }
+            }
+            KeyAction::RotateOutput => {
+                let pos = self.pointer.current_location().to_i32_round();
+                let output = self
+                    .workspaces
+                    .outputs()
+                    .find(|o| self.workspaces.output_geometry(o).unwrap().contains(pos))
+                    .cloned();
+
+                if let Some(output) = output {
+                    let current_transform = output.current_transform();
+                    let new_transform = match current_transform {
+                        Transform::Normal => Transform::_90,
+                        Transform::_90 => Transform::_180,
+                        Transform::_180 => Transform::_270,
+                        Transform::_270 => Transform::Normal,
+                        _ => Transform::Normal,
+                    };
+                    output.change_current_state(None, Some(new_transform), None, None);
+                    let current_location = self.pointer.current_location();
+                    crate::shell::fixup_positions(&mut self.workspaces, current_location);
+                    self.backend_data.reset_buffers(&output);
+                }
+            }
+            KeyAction::ApplicationSwitchNext => {
+                self.handle_app_switcher_next();
+            }
+            KeyAction::ApplicationSwitchPrev => {
+                self.handle_app_switcher_prev();
+            }
+            KeyAction::ApplicationSwitchNextWindow => {
+                self.handle_app_switcher_next_window();
+            }
+            KeyAction::ApplicationSwitchQuit => {
+                self.handle_app_switcher_quit();
+            }
+            KeyAction::ToggleMaximize => {
+                self.handle_toggle_maximize();
+            }
+            KeyAction::CloseWindow => {
+                self.handle_close_window();
+            }
+            KeyAction::ExposeShowDesktop => {
+                self.handle_expose_show_desktop();
+            }
+            KeyAction::ExposeShowAll => {
+                self.handle_expose_show_all();
+            }
+            KeyAction::ExposeShowAllWorkspaces => {
+                self.handle_expose_show_all_workspaces();
+            }
+            KeyAction::WorkspaceNum(index) => {
+                self.handle_workspace_num(index);
+            }
+            KeyAction::WorkspaceNext => {
+                self.handle_workspace_next();
+            }
+            KeyAction::WorkspacePrev => {
+                self.handle_workspace_prev();
+            }
+            action => match action {
+                KeyAction::None
+                | KeyAction::Quit
+                | KeyAction::Run(_)
+                | KeyAction::ToggleDecorations
+                | KeyAction::SceneSnapshot
+                | KeyAction::EnterMode(_)
+                | KeyAction::ExitMode
+                | KeyAction::ToggleSplitView
+                | KeyAction::ExposeShowApp
+                | KeyAction::WorkspaceCreate
+                | KeyAction::WorkspaceRemove
+                | KeyAction::WorkspaceRename
+                | KeyAction::MoveWindowToWorkspace(..)
+                | KeyAction::MoveWindowToWorkspaceNext(_)
+                | KeyAction::MoveWindowToWorkspacePrev(_)
+                | KeyAction::ToggleThemeScheme
+                | KeyAction::ResetWallpaper => self.process_common_key_action(action),
+
+                _ => unreachable!(),
+            },
+        }
+    }
 }
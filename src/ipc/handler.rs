@@ -0,0 +1,288 @@
+//! Requests of IPC clients
+
+use std::collections::BTreeMap;
+
+use otto_ipc::{OutputInfo, Request, Response, WindowInfo, WorkspaceInfo};
+use smithay::{desktop::WindowSurface, reexports::wayland_server::backend::ObjectId};
+
+use super::Snapshot;
+use crate::{
+    config::{
+        shortcuts::{parse_action, ShortcutActionConfig},
+        Config,
+    },
+    input::actions::{resolve_shortcut_action, KeyAction},
+    shell::WindowElement,
+    state::Backend,
+    Otto,
+};
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    pub(super) fn handle_ipc_request(&mut self, client: u64, line: &str) -> Response {
+        let request = match serde_json::from_str::<Request>(line) {
+            Ok(request) => request,
+            Err(err) => return error(format!("invalid request: {err}")),
+        };
+        match self.run_ipc_request(client, request) {
+            Ok(response) => response,
+            Err(message) => error(message),
+        }
+    }
+
+    fn run_ipc_request(&mut self, client: u64, request: Request) -> Result<Response, String> {
+        match request {
+            Request::Outputs => Ok(Response::Outputs {
+                outputs: self.ipc_outputs(),
+            }),
+            Request::Workspaces => Ok(Response::Workspaces {
+                workspaces: self.ipc_workspaces(),
+            }),
+            Request::Windows => Ok(Response::Windows {
+                windows: self.ipc_windows().into_values().collect(),
+            }),
+            Request::FocusWindow { id } => {
+                let window = self.ipc_window(id)?;
+                self.activate_window(&window.id());
+                Ok(Response::Ok)
+            }
+            Request::CloseWindow { id } => {
+                let window = self.ipc_window(id)?;
+                self.close_window(&window);
+                Ok(Response::Ok)
+            }
+            Request::MoveWindow {
+                id,
+                x,
+                y,
+                workspace,
+            } => {
+                let window = self.ipc_window(id)?;
+                let workspace = match workspace {
+                    Some(index) => self.ipc_workspace_index(index)?,
+                    None => self
+                        .workspaces
+                        .window_workspace_index(&window)
+                        .ok_or("window is not mapped")?,
+                };
+                if window.is_fullscreen() || window.is_maximized() {
+                    return Err("cannot move a maximized or fullscreen window".to_string());
+                }
+                self.workspaces
+                    .move_window_to_workspace(&window, workspace, (x, y));
+                Ok(Response::Ok)
+            }
+            Request::ResizeWindow { id, width, height } => {
+                let window = self.ipc_window(id)?;
+                if width <= 0 || height <= 0 {
+                    return Err("width and height must be positive".to_string());
+                }
+                if window.is_fullscreen() || window.is_maximized() {
+                    return Err("cannot resize a maximized or fullscreen window".to_string());
+                }
+                resize_window(&window, width, height);
+                Ok(Response::Ok)
+            }
+            Request::MinimizeWindow { id } => {
+                let window = self.ipc_window(id)?;
+                if !window.is_minimised() {
+                    self.minimize_window(&window);
+                }
+                Ok(Response::Ok)
+            }
+            Request::MaximizeWindow { id } => {
+                let window = self.ipc_window(id)?;
+                self.toggle_maximize_window(&window);
+                Ok(Response::Ok)
+            }
+            Request::SwitchWorkspace { index } => {
+                let index = self.ipc_workspace_index(index)?;
+                self.set_current_workspace_index(index);
+                Ok(Response::Ok)
+            }
+            Request::CreateWorkspace => {
+                self.workspaces.add_workspace();
+                Ok(Response::Ok)
+            }
+            Request::RenameWorkspace { index, name } => {
+                let index = self.ipc_workspace_index(index)?;
+                let name = Some(name).filter(|name| !name.is_empty());
+                self.workspaces.rename_workspace(index, name);
+                Ok(Response::Ok)
+            }
+            Request::RemoveWorkspace { index } => {
+                let index = self.ipc_workspace_index(index)?;
+                self.remove_workspace(index)?;
+                Ok(Response::Ok)
+            }
+            Request::Action { name, index } => {
+                let action = parse_action(&ShortcutActionConfig::BuiltinDetailed {
+                    builtin: name,
+                    index,
+                    follow: false,
+                })
+                .map_err(|err| err.to_string())?;
+                let key_action = Config::with(|config| resolve_shortcut_action(config, &action))
+                    .ok_or_else(|| format!("'{action}' cannot be run"))?;
+                self.run_ipc_action(key_action);
+                Ok(Response::Ok)
+            }
+            Request::Subscribe => {
+                if let Some(client) = self.ipc.clients.get_mut(&client) {
+                    client.subscribed = true;
+                }
+                Ok(Response::Ok)
+            }
+        }
+    }
+
+    /// Runs a builtin action the way the bound shortcut would
+    fn run_ipc_action(&mut self, action: KeyAction) {
+        BackendData::process_key_action(self, action);
+    }
+
+    fn ipc_window(&self, id: u64) -> Result<WindowElement, String> {
+        self.ipc
+            .window_surface(id)
+            .and_then(|surface| self.workspaces.get_window_for_surface(&surface))
+            .cloned()
+            .ok_or_else(|| format!("no window with id {id}"))
+    }
+
+    fn ipc_workspace_index(&self, index: usize) -> Result<usize, String> {
+        let count = self.workspaces.with_model(|m| m.workspaces.len());
+        if index < count {
+            Ok(index)
+        } else {
+            Err(format!("no workspace {index}, there are {count}"))
+        }
+    }
+
+    pub(super) fn ipc_snapshot(&mut self) -> Snapshot {
+        let windows = self.ipc_windows();
+        Snapshot {
+            focused: windows
+                .values()
+                .find(|window| window.focused)
+                .map(|window| window.id),
+            windows,
+            workspaces: self.ipc_workspaces(),
+            outputs: self
+                .ipc_outputs()
+                .into_iter()
+                .map(|output| (output.name.clone(), output))
+                .collect(),
+        }
+    }
+
+    fn ipc_outputs(&self) -> Vec<OutputInfo> {
+        self.workspaces
+            .outputs()
+            .map(|output| {
+                let properties = output.physical_properties();
+                let location = self
+                    .workspaces
+                    .output_geometry(output)
+                    .map(|geometry| geometry.loc)
+                    .unwrap_or_default();
+                let mode = output.current_mode();
+                OutputInfo {
+                    name: output.name(),
+                    make: properties.make,
+                    model: properties.model,
+                    x: location.x,
+                    y: location.y,
+                    width: mode.map(|mode| mode.size.w).unwrap_or_default(),
+                    height: mode.map(|mode| mode.size.h).unwrap_or_default(),
+                    refresh: mode.map(|mode| mode.refresh).unwrap_or_default(),
+                    scale: output.current_scale().fractional_scale(),
+                    transform: format!("{:?}", output.current_transform()),
+                }
+            })
+            .collect()
+    }
+
+    fn ipc_workspaces(&self) -> Vec<WorkspaceInfo> {
+        self.workspaces.with_model(|m| {
+            m.workspaces
+                .iter()
+                .enumerate()
+                .map(|(index, workspace)| WorkspaceInfo {
+                    index,
+                    name: workspace
+                        .get_name()
+                        .unwrap_or_else(|| format!("Workspace {}", index + 1)),
+                    active: index == m.current_workspace,
+                    fullscreen: workspace.get_fullscreen_mode(),
+                    windows: workspace.windows_list.read().unwrap().len(),
+                })
+                .collect()
+        })
+    }
+
+    /// Every window by id, forgetting the ids of closed windows
+    fn ipc_windows(&mut self) -> BTreeMap<u64, WindowInfo> {
+        let focused = self.focused_window().map(|window| window.id());
+        let surfaces: Vec<ObjectId> = self.workspaces.windows_map.keys().cloned().collect();
+        self.ipc
+            .window_ids
+            .retain(|surface, _| surfaces.contains(surface));
+
+        let mut windows = BTreeMap::new();
+        for surface in surfaces {
+            let id = self.ipc.window_id(&surface);
+            let Some(window) = self.workspaces.get_window_for_surface(&surface) else {
+                continue;
+            };
+            let geometry = self.workspaces.window_geometry(window).unwrap_or_default();
+            let info = WindowInfo {
+                id,
+                app_id: app_id(window),
+                title: window.title(),
+                workspace: self
+                    .workspaces
+                    .window_workspace_index(window)
+                    .unwrap_or_else(|| window.get_workspace()),
+                x: geometry.loc.x,
+                y: geometry.loc.y,
+                width: geometry.size.w,
+                height: geometry.size.h,
+                focused: focused.as_ref() == Some(&surface),
+                minimized: window.is_minimised(),
+                maximized: window.is_maximized(),
+                fullscreen: window.is_fullscreen(),
+            };
+            windows.insert(id, info);
+        }
+        windows
+    }
+}
+
+fn error(message: String) -> Response {
+    Response::Error { message }
+}
+
+/// app_id set by the client, the window class for X11 windows
+fn app_id(window: &WindowElement) -> String {
+    match window.underlying_surface() {
+        WindowSurface::Wayland(_) => window.xdg_app_id(),
+        #[cfg(feature = "xwayland")]
+        WindowSurface::X11(surface) => surface.class(),
+    }
+}
+
+fn resize_window(window: &WindowElement, width: i32, height: i32) {
+    match window.underlying_surface() {
+        WindowSurface::Wayland(toplevel) => {
+            toplevel.with_pending_state(|state| {
+                state.size = Some((width, height).into());
+            });
+            toplevel.send_configure();
+        }
+        #[cfg(feature = "xwayland")]
+        WindowSurface::X11(surface) => {
+            let mut geometry = surface.geometry();
+            geometry.size = (width, height).into();
+            let _ = surface.configure(geometry);
+        }
+    }
+}
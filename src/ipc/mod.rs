@@ -0,0 +1,428 @@
+//! JSON IPC socket for scripts and `ottoctl`
+//!
+//! The compositor listens on `$XDG_RUNTIME_DIR/otto-<display>.sock` and
+//! exports the path in `$OTTO_SOCKET`, so programs it starts find it. The
+//! protocol, newline delimited JSON, is defined in the `otto-ipc` crate.
+//! Subscribed clients and `[hooks]` receive events computed by comparing
+//! the windows, workspaces and outputs after each loop iteration with what
+//! they were last told.
+
+mod handler;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{ErrorKind, Read, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::PathBuf,
+};
+
+use otto_ipc::{Event, OutputInfo, WindowInfo, WorkspaceInfo, SOCKET_ENV};
+use serde::Serialize;
+use smithay::reexports::{
+    calloop::{generic::Generic, Interest, LoopHandle, Mode, PostAction, RegistrationToken},
+    wayland_server::backend::ObjectId,
+};
+use tracing::{info, warn};
+
+use crate::{config::Config, state::Backend, Otto};
+
+#[derive(Debug, Default)]
+pub struct IpcState {
+    path: Option<PathBuf>,
+    clients: HashMap<u64, IpcClient>,
+    next_client: u64,
+    /// Ids handed out to windows, kept while the window exists
+    window_ids: HashMap<ObjectId, u64>,
+    next_window: u64,
+    /// State last reported to subscribers, `None` when there are none
+    reported: Option<Snapshot>,
+}
+
+#[derive(Debug)]
+struct IpcClient {
+    stream: UnixStream,
+    token: Option<RegistrationToken>,
+    /// Bytes received after the last complete line
+    buffer: Vec<u8>,
+    subscribed: bool,
+}
+
+/// Windows, workspaces and outputs as seen by subscribers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    pub windows: BTreeMap<u64, WindowInfo>,
+    pub focused: Option<u64>,
+    pub workspaces: Vec<WorkspaceInfo>,
+    pub outputs: BTreeMap<String, OutputInfo>,
+}
+
+impl IpcState {
+    /// Listens on the IPC socket and exports its path in `$OTTO_SOCKET`
+    pub fn listen<BackendData: Backend + 'static>(
+        handle: &LoopHandle<'static, Otto<BackendData>>,
+        socket_name: Option<&str>,
+    ) -> Self {
+        let Some(path) = socket_path(socket_name) else {
+            warn!("XDG_RUNTIME_DIR is not set, the IPC socket is disabled");
+            return Self::default();
+        };
+        // A previous instance may have left its socket behind
+        let _ = std::fs::remove_file(&path);
+        let listener = match UnixListener::bind(&path).and_then(|listener| {
+            listener.set_nonblocking(true)?;
+            Ok(listener)
+        }) {
+            Ok(listener) => listener,
+            Err(err) => {
+                warn!(?err, path = %path.display(), "Failed to create the IPC socket");
+                return Self::default();
+            }
+        };
+        let result = handle.insert_source(
+            Generic::new(listener, Interest::READ, Mode::Level),
+            |_, listener, data| {
+                loop {
+                    match listener.as_ref().accept() {
+                        Ok((stream, _)) => data.add_ipc_client(stream),
+                        Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                        Err(err) => {
+                            warn!(?err, "Failed to accept IPC client");
+                            break;
+                        }
+                    }
+                }
+                Ok(PostAction::Continue)
+            },
+        );
+        if let Err(err) = result {
+            warn!(?err, "Failed to listen on the IPC socket");
+            return Self::default();
+        }
+
+        info!(path = %path.display(), "Listening on IPC socket");
+        std::env::set_var(SOCKET_ENV, &path);
+        Self {
+            path: Some(path),
+            ..Default::default()
+        }
+    }
+
+    /// Id of a window, assigning one the first time it is seen
+    pub fn window_id(&mut self, id: &ObjectId) -> u64 {
+        if let Some(window_id) = self.window_ids.get(id) {
+            return *window_id;
+        }
+        self.next_window += 1;
+        self.window_ids.insert(id.clone(), self.next_window);
+        self.next_window
+    }
+
+    /// Surface of the window with the id given to clients
+    pub fn window_surface(&self, window_id: u64) -> Option<ObjectId> {
+        self.window_ids
+            .iter()
+            .find(|(_, id)| **id == window_id)
+            .map(|(surface, _)| surface.clone())
+    }
+
+    fn has_subscribers(&self) -> bool {
+        self.clients.values().any(|client| client.subscribed)
+    }
+
+    /// Writes one JSON line, returns false if the client is gone or does
+    /// not keep up
+    fn send(&mut self, client: u64, message: &impl Serialize) -> bool {
+        let Some(client) = self.clients.get_mut(&client) else {
+            return false;
+        };
+        let Ok(mut line) = serde_json::to_vec(message) else {
+            return false;
+        };
+        line.push(b'\n');
+        client.stream.write_all(&line).is_ok()
+    }
+}
+
+impl Drop for IpcState {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn socket_path(socket_name: Option<&str>) -> Option<PathBuf> {
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")?;
+    let name = match socket_name {
+        Some(name) => format!("otto-{name}.sock"),
+        None => format!("otto-{}.sock", std::process::id()),
+    };
+    Some(PathBuf::from(runtime_dir).join(name))
+}
+
+/// Events turning `old` into `new`
+pub fn diff_snapshots(old: &Snapshot, new: &Snapshot) -> Vec<Event> {
+    let mut events = Vec::new();
+    for id in old.windows.keys() {
+        if !new.windows.contains_key(id) {
+            events.push(Event::WindowClosed { id: *id });
+        }
+    }
+    for (id, window) in &new.windows {
+        match old.windows.get(id) {
+            None => events.push(Event::WindowOpened {
+                window: window.clone(),
+            }),
+            // Focus is reported on its own
+            Some(previous) if !same_window(previous, window) => events.push(Event::WindowChanged {
+                window: window.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    let layout = |workspaces: &[WorkspaceInfo]| {
+        workspaces
+            .iter()
+            .map(|workspace| WorkspaceInfo {
+                active: false,
+                ..workspace.clone()
+            })
+            .collect::<Vec<_>>()
+    };
+    if layout(&old.workspaces) != layout(&new.workspaces) {
+        events.push(Event::WorkspacesChanged {
+            workspaces: new.workspaces.clone(),
+        });
+    }
+    let active = |workspaces: &[WorkspaceInfo]| workspaces.iter().position(|w| w.active);
+    if let Some(index) = active(&new.workspaces) {
+        if active(&old.workspaces) != Some(index) {
+            events.push(Event::WorkspaceActivated { index });
+        }
+    }
+
+    if old.focused != new.focused {
+        events.push(Event::WindowFocused { id: new.focused });
+    }
+
+    // Mode or scale changes are not reported
+    for name in old.outputs.keys() {
+        if !new.outputs.contains_key(name) {
+            events.push(Event::OutputDisconnected { name: name.clone() });
+        }
+    }
+    for (name, output) in &new.outputs {
+        if !old.outputs.contains_key(name) {
+            events.push(Event::OutputConnected {
+                output: output.clone(),
+            });
+        }
+    }
+    events
+}
+
+fn same_window(a: &WindowInfo, b: &WindowInfo) -> bool {
+    a == &WindowInfo {
+        focused: a.focused,
+        ..b.clone()
+    }
+}
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    fn add_ipc_client(&mut self, stream: UnixStream) {
+        let writer = match stream
+            .set_nonblocking(true)
+            .and_then(|_| stream.try_clone())
+        {
+            Ok(writer) => writer,
+            Err(err) => {
+                warn!(?err, "Failed to set up IPC client");
+                return;
+            }
+        };
+        self.ipc.next_client += 1;
+        let id = self.ipc.next_client;
+        let token = self.handle.insert_source(
+            Generic::new(stream, Interest::READ, Mode::Level),
+            move |_, stream, data| {
+                let closed = data.read_ipc_client(id, stream.as_ref());
+                if closed {
+                    data.ipc.clients.remove(&id);
+                    return Ok(PostAction::Remove);
+                }
+                Ok(PostAction::Continue)
+            },
+        );
+        match token {
+            Ok(token) => {
+                self.ipc.clients.insert(
+                    id,
+                    IpcClient {
+                        stream: writer,
+                        token: Some(token),
+                        buffer: Vec::new(),
+                        subscribed: false,
+                    },
+                );
+            }
+            Err(err) => warn!(?err, "Failed to watch IPC client"),
+        }
+    }
+
+    /// Handles the complete requests received, returns true once the client
+    /// disconnected
+    fn read_ipc_client(&mut self, id: u64, mut stream: &UnixStream) -> bool {
+        let mut chunk = [0u8; 4096];
+        let closed = loop {
+            match stream.read(&mut chunk) {
+                Ok(0) => break true,
+                Ok(read) => {
+                    if let Some(client) = self.ipc.clients.get_mut(&id) {
+                        client.buffer.extend_from_slice(&chunk[..read]);
+                    }
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break false,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => break true,
+            }
+        };
+
+        while let Some(line) = self.ipc.clients.get_mut(&id).and_then(|client| {
+            let end = client.buffer.iter().position(|byte| *byte == b'\n')?;
+            let line: Vec<u8> = client.buffer.drain(..=end).collect();
+            Some(line)
+        }) {
+            let line = String::from_utf8_lossy(&line);
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = self.handle_ipc_request(id, line.trim());
+            if !self.ipc.send(id, &response) {
+                return true;
+            }
+        }
+        closed
+    }
+
+    /// Sends the changes since the last call to subscribed clients and runs
+    /// the hooks watching them
+    pub fn dispatch_ipc_events(&mut self) {
+        let hooks = Config::with(|config| config.hooks.watches_desktop());
+        if !hooks && !self.ipc.has_subscribers() {
+            self.ipc.reported = None;
+            return;
+        }
+        let snapshot = self.ipc_snapshot();
+        if self.ipc.reported.as_ref() == Some(&snapshot) {
+            return;
+        }
+        let Some(reported) = self.ipc.reported.replace(snapshot.clone()) else {
+            return;
+        };
+        let events = diff_snapshots(&reported, &snapshot);
+        if hooks {
+            self.run_desktop_hooks(&events, &reported, &snapshot);
+        }
+
+        let subscribers: Vec<u64> = self
+            .ipc
+            .clients
+            .iter()
+            .filter(|(_, client)| client.subscribed)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in subscribers {
+            if !events.iter().all(|event| self.ipc.send(id, event)) {
+                self.remove_ipc_client(id);
+            }
+        }
+    }
+
+    fn remove_ipc_client(&mut self, id: u64) {
+        if let Some(token) = self.ipc.clients.remove(&id).and_then(|client| client.token) {
+            self.handle.remove(token);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(id: u64, title: &str) -> WindowInfo {
+        WindowInfo {
+            id,
+            app_id: "kitty".to_string(),
+            title: title.to_string(),
+            workspace: 0,
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+            focused: false,
+            minimized: false,
+            maximized: false,
+            fullscreen: false,
+        }
+    }
+
+    fn workspace(index: usize, active: bool) -> WorkspaceInfo {
+        WorkspaceInfo {
+            index,
+            name: format!("Workspace {}", index + 1),
+            active,
+            fullscreen: false,
+            windows: 0,
+        }
+    }
+
+    #[test]
+    fn window_changes_are_reported() {
+        let old = Snapshot {
+            windows: BTreeMap::from([(1, window(1, "a")), (2, window(2, "b"))]),
+            ..Default::default()
+        };
+        let new = Snapshot {
+            windows: BTreeMap::from([(2, window(2, "renamed")), (3, window(3, "c"))]),
+            ..Default::default()
+        };
+        assert_eq!(
+            diff_snapshots(&old, &new),
+            vec![
+                Event::WindowClosed { id: 1 },
+                Event::WindowChanged {
+                    window: window(2, "renamed")
+                },
+                Event::WindowOpened {
+                    window: window(3, "c")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn focus_and_workspace_switch_are_reported_alone() {
+        let mut focused = window(1, "a");
+        focused.focused = true;
+        let old = Snapshot {
+            windows: BTreeMap::from([(1, window(1, "a"))]),
+            focused: None,
+            workspaces: vec![workspace(0, true), workspace(1, false)],
+            ..Default::default()
+        };
+        let new = Snapshot {
+            windows: BTreeMap::from([(1, focused)]),
+            focused: Some(1),
+            workspaces: vec![workspace(0, false), workspace(1, true)],
+            ..Default::default()
+        };
+        assert_eq!(
+            diff_snapshots(&old, &new),
+            vec![
+                Event::WorkspaceActivated { index: 1 },
+                Event::WindowFocused { id: Some(1) },
+            ]
+        );
+    }
+}
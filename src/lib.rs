@@ -9,9 +9,11 @@
 pub mod cursor;
 pub mod drawing;
 pub mod focus;
+pub mod global_shortcuts;
 pub mod input;
 pub mod input_handler;
 pub mod interactive_view;
+pub mod ipc;
 pub mod render;
 pub mod render_elements;
 pub mod render_metrics;
@@ -25,11 +27,13 @@ pub mod state;
 pub mod textures_storage;
 #[cfg(feature = "udev")]
 pub mod udev;
+pub mod wallpaper_service;
 #[cfg(feature = "winit")]
 pub mod winit;
 #[cfg(feature = "x11")]
 pub mod x11;
 
+pub use config::check::run_check_config;
 pub use state::{CalloopData, ClientState, Otto};
 mod workspaces;
 
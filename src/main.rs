@@ -5,6 +5,7 @@ static POSSIBLE_BACKENDS: &[&str] = &[
     "--tty-udev : Run anvil as a tty udev client (requires root if without logind).",
     #[cfg(feature = "udev")]
     "--probe : Probe available displays and resolutions, then exit.",
+    "--check-config [path] : Validate the configuration, then exit.",
     #[cfg(feature = "x11")]
     "--x11 : Run anvil as an X11 client.",
 ];
@@ -38,8 +39,12 @@ async fn main() {
     #[cfg(feature = "profile-with-puffin")]
     profiling::puffin::set_scopes_on(true);
 
-    let arg = ::std::env::args().nth(1);
-    match arg.as_ref().map(|s| &s[..]) {
+    let mut args: Vec<String> = ::std::env::args().skip(1).collect();
+    if let Some(path) = take_config_arg(&mut args) {
+        std::env::set_var("OTTO_CONFIG", path);
+    }
+    let arg = args.first();
+    match arg.map(|s| &s[..]) {
         #[cfg(feature = "winit")]
         Some("--winit") => {
             tracing::info!("Starting otto with winit backend");
@@ -63,13 +68,19 @@ async fn main() {
             std::env::set_var("SCREEN_COMPOSER_BACKEND", "x11");
             otto::x11::run_x11();
         }
+        Some("--check-config") => {
+            let path = args.get(1).map(std::path::PathBuf::from);
+            if !otto::run_check_config(path) {
+                std::process::exit(1);
+            }
+        }
         Some(other) => {
             tracing::error!("Unknown backend: {}", other);
         }
         None => {
             #[allow(clippy::disallowed_macros)]
             {
-                println!("USAGE: otto --backend");
+                println!("USAGE: otto [--config <path>] --backend");
                 println!();
                 println!("Possible backends are:");
                 for b in POSSIBLE_BACKENDS {
@@ -79,3 +90,19 @@ async fn main() {
         }
     }
 }
+
+/// Removes `--config <path>` or `--config=<path>` from `args`
+fn take_config_arg(args: &mut Vec<String>) -> Option<String> {
+    let index = args
+        .iter()
+        .position(|arg| arg == "--config" || arg.starts_with("--config="))?;
+    let arg = args.remove(index);
+    match arg.strip_prefix("--config=") {
+        Some(path) => Some(path.to_string()),
+        None if index < args.len() => Some(args.remove(index)),
+        None => {
+            tracing::error!("--config needs a path");
+            None
+        }
+    }
+}
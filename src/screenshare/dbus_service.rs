@@ -532,7 +532,15 @@ impl CompositorHealthInterface {
 }
 
 /// Starts the D-Bus service on the session bus.
-pub async fn run_dbus_service(compositor_tx: Sender<CompositorCommand>) -> zbus::Result<()> {
+pub async fn run_dbus_service(
+    compositor_tx: Sender<CompositorCommand>,
+    shortcuts_tx: Sender<crate::global_shortcuts::GlobalShortcutsCommand>,
+    shortcut_signals: tokio::sync::mpsc::UnboundedReceiver<
+        crate::global_shortcuts::GlobalShortcutSignal,
+    >,
+    setting_changes: tokio::sync::mpsc::UnboundedReceiver<crate::settings_service::SettingChange>,
+    wallpaper_tx: Sender<crate::wallpaper_service::WallpaperCommand>,
+) -> zbus::Result<()> {
     let connection = Connection::session().await?;
 
     let screencast = ScreenCastInterface::new(compositor_tx.clone(), connection.clone());
@@ -554,7 +562,18 @@ pub async fn run_dbus_service(compositor_tx: Sender<CompositorCommand>) -> zbus:
     connection.request_name("org.otto.Compositor").await?;
 
     // Register the Settings interface
-    crate::settings_service::register_settings_interface(&connection).await?;
+    crate::settings_service::register_settings_interface(&connection, setting_changes).await?;
+
+    // Register the GlobalShortcuts interface
+    crate::global_shortcuts::register_global_shortcuts_interface(
+        &connection,
+        shortcuts_tx,
+        shortcut_signals,
+    )
+    .await?;
+
+    // Register the Wallpaper interface
+    crate::wallpaper_service::register_wallpaper_interface(&connection, wallpaper_tx).await?;
 
     info!("D-Bus service started at org.otto.ScreenCast");
 
@@ -127,6 +127,11 @@ pub struct StreamInfo {
 pub struct ScreenshareManager {
     /// Sender for commands from the D-Bus thread.
     pub command_sender: ChannelSender<CompositorCommand>,
+    /// Sender for GlobalShortcuts signals to the D-Bus thread.
+    pub global_shortcut_signals:
+        tokio::sync::mpsc::UnboundedSender<crate::global_shortcuts::GlobalShortcutSignal>,
+    /// Sender for Settings changes to the D-Bus thread.
+    pub setting_changes: tokio::sync::mpsc::UnboundedSender<crate::settings_service::SettingChange>,
 }
 
 impl ScreenshareManager {
@@ -148,6 +153,28 @@ impl ScreenshareManager {
             })
             .map_err(|e| format!("Failed to insert screenshare channel: {}", e))?;
 
+        // The GlobalShortcuts interface shares the D-Bus thread
+        let (shortcuts_sender, shortcuts_receiver) =
+            channel::<crate::global_shortcuts::GlobalShortcutsCommand>();
+        loop_handle
+            .insert_source(shortcuts_receiver, |event, _, state| {
+                if let ChannelEvent::Msg(cmd) = event {
+                    state.handle_global_shortcuts_command(cmd);
+                }
+            })
+            .map_err(|e| format!("Failed to insert global shortcuts channel: {}", e))?;
+        let (wallpaper_sender, wallpaper_receiver) =
+            channel::<crate::wallpaper_service::WallpaperCommand>();
+        loop_handle
+            .insert_source(wallpaper_receiver, |event, _, state| {
+                if let ChannelEvent::Msg(cmd) = event {
+                    state.handle_wallpaper_command(cmd);
+                }
+            })
+            .map_err(|e| format!("Failed to insert wallpaper channel: {}", e))?;
+        let (signal_sender, signal_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let (settings_sender, settings_receiver) = tokio::sync::mpsc::unbounded_channel();
+
         // Spawn the D-Bus service on a dedicated tokio thread
         let cmd_sender_clone = cmd_sender.clone();
         let _ = std::thread::Builder::new()
@@ -159,7 +186,15 @@ impl ScreenshareManager {
                     .expect("Failed to create tokio runtime for screenshare");
 
                 rt.block_on(async move {
-                    if let Err(e) = dbus_service::run_dbus_service(cmd_sender_clone).await {
+                    if let Err(e) = dbus_service::run_dbus_service(
+                        cmd_sender_clone,
+                        shortcuts_sender,
+                        signal_receiver,
+                        settings_receiver,
+                        wallpaper_sender,
+                    )
+                    .await
+                    {
                         tracing::error!("Screenshare D-Bus service failed: {}", e);
                     }
                 });
@@ -167,6 +202,8 @@ impl ScreenshareManager {
 
         Ok(Self {
             command_sender: cmd_sender,
+            global_shortcut_signals: signal_sender,
+            setting_changes: settings_sender,
         })
     }
 }
@@ -1,12 +1,62 @@
 //! D-Bus service implementation for `org.otto.Settings`.
 //!
-//! Exposes compositor settings like theme color scheme to the portal backend.
+//! Exposes compositor settings like theme color scheme and accent color to
+//! the portal backend, and signals their changes with `SettingChanged`.
 
-use tracing::info;
-use zbus::{interface, Connection};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::{info, warn};
+use zbus::zvariant::Value;
+use zbus::{interface, Connection, SignalContext};
 
-use crate::config::Config;
-use crate::theme::ThemeScheme;
+use crate::theme::{self, accent_color, ThemeScheme};
+
+const SETTINGS_PATH: &str = "/org/otto/Settings";
+
+/// Namespace of the settings in the Settings portal
+pub const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+
+/// A setting whose value changed, sent from the compositor to the D-Bus
+/// thread
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingChange {
+    ColorScheme(u32),
+    AccentColor((f64, f64, f64)),
+}
+
+impl SettingChange {
+    fn key(&self) -> &'static str {
+        match self {
+            SettingChange::ColorScheme(_) => "color-scheme",
+            SettingChange::AccentColor(_) => "accent-color",
+        }
+    }
+
+    fn value(&self) -> Value<'static> {
+        match self {
+            SettingChange::ColorScheme(scheme) => Value::from(*scheme),
+            SettingChange::AccentColor(color) => Value::new(*color),
+        }
+    }
+}
+
+/// The `color-scheme` value of `scheme`
+///
+/// - 0: No preference
+/// - 1: Prefer dark appearance
+/// - 2: Prefer light appearance
+pub fn color_scheme_value(scheme: ThemeScheme) -> u32 {
+    match scheme {
+        ThemeScheme::Dark => 1,
+        ThemeScheme::Light => 2,
+        ThemeScheme::Auto => 0,
+    }
+}
+
+/// The accent color as red, green and blue in `0.0..=1.0`
+pub fn accent_color_value() -> (f64, f64, f64) {
+    let color = accent_color().c4f();
+    (color.r as f64, color.g as f64, color.b as f64)
+}
 
 /// The main Settings D-Bus interface.
 ///
@@ -22,24 +72,60 @@ impl SettingsInterface {
     /// - 1: Prefer dark appearance
     /// - 2: Prefer light appearance
     async fn get_color_scheme(&self) -> u32 {
-        Config::with(|config| match config.theme_scheme {
-            ThemeScheme::Dark => 1,
-            ThemeScheme::Light => 2,
-        })
+        color_scheme_value(theme::scheme())
+    }
+
+    /// Returns the accent color as red, green and blue in `0.0..=1.0`.
+    async fn get_accent_color(&self) -> (f64, f64, f64) {
+        accent_color_value()
     }
+
+    /// Emitted when a setting changes, with the namespace and key it has in
+    /// the Settings portal.
+    #[zbus(signal)]
+    async fn setting_changed(
+        ctx: &SignalContext<'_>,
+        namespace: &str,
+        key: &str,
+        value: Value<'_>,
+    ) -> zbus::Result<()>;
 }
 
-/// Registers the Settings interface on the existing D-Bus connection.
-pub async fn register_settings_interface(connection: &Connection) -> zbus::Result<()> {
+/// Registers the Settings interface on the existing D-Bus connection and
+/// emits `SettingChanged` for the changes the compositor sends.
+pub async fn register_settings_interface(
+    connection: &Connection,
+    mut changes: UnboundedReceiver<SettingChange>,
+) -> zbus::Result<()> {
     let settings = SettingsInterface;
 
     connection
         .object_server()
-        .at("/org/otto/Settings", settings)
+        .at(SETTINGS_PATH, settings)
         .await?;
 
     connection.request_name("org.otto.Settings").await?;
 
+    let interface = connection
+        .object_server()
+        .interface::<_, SettingsInterface>(SETTINGS_PATH)
+        .await?;
+
+    tokio::spawn(async move {
+        while let Some(change) = changes.recv().await {
+            let result = SettingsInterface::setting_changed(
+                interface.signal_context(),
+                APPEARANCE_NAMESPACE,
+                change.key(),
+                change.value(),
+            )
+            .await;
+            if let Err(err) = result {
+                warn!(?err, "Failed to emit SettingChanged");
+            }
+        }
+    });
+
     info!("Settings D-Bus interface registered at org.otto.Settings");
 
     Ok(())
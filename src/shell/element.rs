@@ -444,6 +444,15 @@ impl WindowElement {
             })
             .unwrap_or_default()
     }
+
+    /// Title of a Wayland or X11 window
+    pub fn title(&self) -> String {
+        match self.underlying_surface() {
+            WindowSurface::Wayland(_) => self.xdg_title(),
+            #[cfg(feature = "xwayland")]
+            WindowSurface::X11(surface) => surface.title(),
+        }
+    }
 }
 
 impl IsAlive for WindowElement {
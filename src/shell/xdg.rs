@@ -32,7 +32,6 @@ use crate::{
     focus::KeyboardFocusTarget,
     shell::TouchResizeSurfaceGrab,
     state::{Backend, Otto},
-    workspaces::ApplicationsInfo,
 };
 
 use super::{
@@ -180,8 +179,9 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
             }
         }
 
-        if let Some(window) = self.workspaces.get_window_for_surface(&id) {
-            if window.is_fullscreen() {
+        if let Some(window) = self.workspaces.get_window_for_surface(&id).cloned() {
+            // A closed window of a split leaves the workspace to the other one
+            if window.is_fullscreen() && self.leave_split_view(&window).is_none() {
                 let fullscreen_workspace = window.get_fullscreen_workspace();
                 if let Some(workspace) = self.workspaces.get_workspace_at(fullscreen_workspace) {
                     workspace.set_fullscreen_mode(false);
@@ -552,23 +552,7 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
             self.workspaces.expose_set_visible(false);
 
             // Fetch app info asynchronously to get the proper display name
-            let app_id = window.display_app_id(&self.display_handle);
-            if !app_id.is_empty() {
-                let workspace_clone = next_workspace.clone();
-                tokio::spawn(async move {
-                    if let Some(app_info) = ApplicationsInfo::get_app_info_by_id(&app_id).await {
-                        if let Some(name) = app_info.desktop_name() {
-                            workspace_clone.set_name(Some(name));
-                        } else {
-                            // Fallback to app_id if no desktop name
-                            workspace_clone.set_name(Some(app_id));
-                        }
-                    } else {
-                        // Fallback to app_id if app info not found
-                        workspace_clone.set_name(Some(app_id));
-                    }
-                });
-            }
+            next_workspace.set_name_from_app(window.display_app_id(&self.display_handle));
 
             window.set_fullscreen(true, next_workspace_index);
 
@@ -673,6 +657,8 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
                 }
             }
             if let Some(we) = self.workspaces.get_window_for_surface(&id).cloned() {
+                // The other window of a split keeps the fullscreen workspace
+                let split_partner = self.leave_split_view(&we);
                 we.set_fullscreen(false, 0);
                 let scale = self
                     .workspaces
@@ -689,9 +675,11 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
 
                     // Get the fullscreen workspace index before switching away from it
                     let fullscreen_workspace_index = self.workspaces.get_current_workspace_index();
-                    let workspace = self.workspaces.get_current_workspace();
-                    workspace.set_fullscreen_mode(false);
-                    workspace.set_fullscreen_animating(false);
+                    if split_partner.is_none() {
+                        let workspace = self.workspaces.get_current_workspace();
+                        workspace.set_fullscreen_mode(false);
+                        workspace.set_fullscreen_animating(false);
+                    }
 
                     // Exit expose mode when exiting fullscreen
                     self.workspaces.expose_set_visible(false);
@@ -711,8 +699,10 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
                         .set_current_workspace_index(we.get_workspace(), Some(transition));
 
                     // Delete the temporary fullscreen workspace
-                    self.workspaces
-                        .remove_workspace_at(fullscreen_workspace_index);
+                    if split_partner.is_none() {
+                        self.workspaces
+                            .remove_workspace_at(fullscreen_workspace_index);
+                    }
 
                     // Animate size during unfullscreen transition
                     let current_element_geometry = self.workspaces.element_geometry(&we).unwrap();
@@ -927,19 +917,7 @@ impl<BackendData: Backend> XdgShellHandler for Otto<BackendData> {
         {
             let id = surface.wl_surface().id();
             let window = self.workspaces.get_window_for_surface(&id).unwrap().clone();
-
-            let current_element_geometry = self.workspaces.element_geometry(&window).unwrap();
-
-            if let Some(mut view) = self.workspaces.get_window_view(&id) {
-                view.unmaximised_rect = current_element_geometry;
-                self.workspaces.set_window_view(&id, view);
-            }
-
-            let next_focus = self.workspaces.minimize_window(&window);
-            match next_focus {
-                Some(wid) => self.set_keyboard_focus_on_surface(&wid),
-                None => self.clear_keyboard_focus(),
-            }
+            self.minimize_window(&window);
         }
 
         // The protocol demands us to always reply with a configure,
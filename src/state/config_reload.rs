@@ -0,0 +1,318 @@
+//! Live reload of the configuration file
+//!
+//! The directories of the configuration files are watched with inotify,
+//! editors often replace a file rather than write to it. When one of the
+//! files is edited the configuration is read again and only the sections
+//! that changed are applied. A file that fails to parse keeps the last good
+//! configuration active and is reported with a desktop notification.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    io::ErrorKind,
+    os::fd::{AsFd, OwnedFd},
+    path::{Path, PathBuf},
+};
+
+use inotify::{EventMask, Inotify, WatchDescriptor, WatchMask};
+use smithay::{
+    input::keyboard::XkbConfig,
+    reexports::calloop::{generic::Generic, Interest, LoopHandle, Mode, PostAction},
+};
+use tracing::{debug, info, warn};
+
+use crate::{
+    config::{Config, ConfigChanges},
+    state::Backend,
+    Otto,
+};
+
+/// Inotify watches on the directories of the configuration files
+#[derive(Debug, Default)]
+pub struct ConfigWatch {
+    inotify: Option<Inotify>,
+    /// Names of the configuration files in each watched directory
+    dirs: HashMap<WatchDescriptor, HashSet<OsString>>,
+}
+
+impl ConfigWatch {
+    pub fn new() -> Self {
+        Self::watching(Config::current().sources())
+    }
+
+    fn watching(sources: Vec<PathBuf>) -> Self {
+        let inotify = Inotify::init()
+            .map_err(|err| warn!(?err, "Failed to watch the configuration"))
+            .ok();
+        let mut watch = Self {
+            inotify,
+            dirs: HashMap::new(),
+        };
+        watch.watch_files(sources);
+        watch
+    }
+
+    /// Watches the directories of `sources` instead of the current ones
+    fn watch_files(&mut self, sources: Vec<PathBuf>) {
+        let Some(inotify) = &self.inotify else {
+            return;
+        };
+        let mut files: HashMap<PathBuf, HashSet<OsString>> = HashMap::new();
+        for path in sources {
+            let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+                continue;
+            };
+            let dir = if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                dir
+            };
+            files
+                .entry(dir.to_path_buf())
+                .or_default()
+                .insert(name.to_os_string());
+        }
+
+        let mask = WatchMask::CLOSE_WRITE
+            | WatchMask::CREATE
+            | WatchMask::DELETE
+            | WatchMask::MOVED_FROM
+            | WatchMask::MOVED_TO;
+        let mut watches = inotify.watches();
+        // A directory watched again keeps its descriptor
+        let mut dirs = HashMap::new();
+        for (dir, names) in files {
+            match watches.add(&dir, mask) {
+                Ok(descriptor) => {
+                    dirs.insert(descriptor, names);
+                }
+                Err(err) => {
+                    debug!(?err, dir = %dir.display(), "Not watching configuration directory")
+                }
+            }
+        }
+        for (descriptor, _) in self.dirs.drain() {
+            if !dirs.contains_key(&descriptor) {
+                let _ = watches.remove(descriptor);
+            }
+        }
+        self.dirs = dirs;
+    }
+
+    /// Whether a configuration file was created, edited or removed since
+    /// the last call
+    pub fn changed(&mut self) -> bool {
+        let Some(inotify) = &mut self.inotify else {
+            return false;
+        };
+        let mut buffer = [0; 4096];
+        let mut changed = false;
+        loop {
+            let events = match inotify.read_events(&mut buffer) {
+                Ok(events) => events,
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => {
+                    warn!(?err, "Failed to read configuration changes");
+                    break;
+                }
+            };
+            for event in events {
+                // Events were dropped, any file may have changed
+                changed |= event.mask.contains(EventMask::Q_OVERFLOW)
+                    || event.name.is_some_and(|name| {
+                        self.dirs
+                            .get(&event.wd)
+                            .is_some_and(|names| names.contains(name))
+                    });
+            }
+        }
+        changed
+    }
+
+    /// A descriptor readable when the watched directories change
+    fn fd(&self) -> Option<OwnedFd> {
+        let inotify = self.inotify.as_ref()?;
+        inotify
+            .as_fd()
+            .try_clone_to_owned()
+            .map_err(|err| warn!(?err, "Failed to watch the configuration"))
+            .ok()
+    }
+}
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// Reloads the configuration when `watch` reports a change, the files
+    /// included by the new configuration are watched from then on
+    pub(crate) fn watch_config(
+        handle: &LoopHandle<'static, Otto<BackendData>>,
+        watch: &ConfigWatch,
+    ) {
+        let Some(fd) = watch.fd() else {
+            return;
+        };
+        handle
+            .insert_source(
+                Generic::new(fd, Interest::READ, Mode::Level),
+                |_, _, data| {
+                    if data.config_watch.changed() {
+                        data.reload_config();
+                        data.config_watch.watch_files(Config::current().sources());
+                    }
+                    Ok(PostAction::Continue)
+                },
+            )
+            .map_err(|err| warn!(?err, "Failed to watch the configuration"))
+            .ok();
+    }
+
+    /// Reads the configuration again and applies what changed
+    pub fn reload_config(&mut self) {
+        let config = match Config::load() {
+            Ok(config) => config,
+            Err(err) => {
+                warn!("Keeping the current configuration: {err}");
+                notify_config_error(err.to_string());
+                return;
+            }
+        };
+        let previous = Config::replace(config);
+        let current = Config::current();
+        let changes = ConfigChanges::between(&previous, &current);
+        if changes.is_empty() {
+            return;
+        }
+        info!(?changes, "Configuration reloaded");
+        self.apply_config_changes(changes);
+    }
+
+    fn apply_config_changes(&mut self, changes: ConfigChanges) {
+        if changes.shortcuts {
+            // The active mode may no longer exist
+            self.exit_keyboard_mode();
+        }
+        if changes.keyboard {
+            self.reload_keymap();
+        }
+        if changes.cursor {
+            let (theme, size) = Config::with(|c| (c.cursor_theme.clone(), c.cursor_size));
+            self.cursor_manager.reload(&theme, size as u8);
+            self.cursor_texture_cache.clear();
+        }
+        if changes.dock {
+            self.workspaces.dock.reload_config();
+        }
+        // A scheme switch redraws the views itself
+        if changes.theme && !self.update_theme_scheme() {
+            crate::theme::reload();
+            self.workspaces.reload_theme();
+            self.signal_accent_color();
+        }
+        if changes.background {
+            self.workspaces.reload_backgrounds();
+        }
+        if changes.input_devices {
+            BackendData::reconfigure_input_devices(self);
+        }
+        if changes.displays {
+            BackendData::reapply_outputs(self);
+        }
+    }
+
+    /// Loads the global keymap and key repeat settings on the seat keyboard
+    fn reload_keymap(&mut self) {
+        let Some(keyboard) = self.seat.get_keyboard() else {
+            return;
+        };
+        let (xkb, delay, rate) = Config::with(|c| {
+            (
+                c.input.xkb_settings(),
+                c.keyboard_repeat_delay,
+                c.keyboard_repeat_rate,
+            )
+        });
+        let xkb_config = XkbConfig {
+            layout: &xkb.layout,
+            variant: &xkb.variant,
+            options: xkb.options_string(),
+            ..Default::default()
+        };
+        if let Err(err) = keyboard.set_xkb_config(self, xkb_config) {
+            warn!(?err, "Failed to load the configured keymap");
+        }
+        keyboard.change_repeat_info(rate, delay);
+        self.active_xkb_settings = xkb;
+        // Resolved again at the next key press of each keyboard
+        self.device_keymaps.clear();
+    }
+}
+
+/// Shows a desktop notification about a configuration that failed to load
+fn notify_config_error(message: String) {
+    tokio::spawn(async move {
+        let result: zbus::Result<()> = async {
+            let connection = zbus::Connection::session().await?;
+            connection
+                .call_method(
+                    Some("org.freedesktop.Notifications"),
+                    "/org/freedesktop/Notifications",
+                    Some("org.freedesktop.Notifications"),
+                    "Notify",
+                    &(
+                        "otto",
+                        0u32,
+                        "dialog-error",
+                        "Configuration not reloaded",
+                        message.as_str(),
+                        Vec::<&str>::new(),
+                        HashMap::<&str, zbus::zvariant::Value>::new(),
+                        -1i32,
+                    ),
+                )
+                .await?;
+            Ok(())
+        }
+        .await;
+        if let Err(err) = result {
+            warn!(?err, "Failed to show configuration error notification");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_config(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("otto-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("config.toml");
+        std::fs::write(&config, "").unwrap();
+        config
+    }
+
+    #[test]
+    fn watch_reports_no_change_without_edits() {
+        let config = temp_config("watch-idle");
+        let mut watch = ConfigWatch::watching(vec![config.clone()]);
+        assert!(!watch.changed());
+        std::fs::remove_dir_all(config.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn watch_reports_edits_of_the_watched_files_only() {
+        let config = temp_config("watch-edits");
+        let dir = config.parent().unwrap();
+        let mut watch = ConfigWatch::watching(vec![config.clone()]);
+
+        std::fs::write(dir.join("notes.txt"), "").unwrap();
+        assert!(!watch.changed());
+
+        // Replaced the way editors save
+        std::fs::write(dir.join("config.toml.new"), "[dock]\n").unwrap();
+        std::fs::rename(dir.join("config.toml.new"), &config).unwrap();
+        assert!(watch.changed());
+        assert!(!watch.changed());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
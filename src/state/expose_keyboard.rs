@@ -0,0 +1,135 @@
+//! Keyboard control of exposé
+//!
+//! While exposé is open, key presses without `Ctrl`, `Alt` or `Logo` move a
+//! highlight between the windows instead of reaching the focused app:
+//! arrows move it to the nearest window in that direction, `Tab` and
+//! `Shift+Tab` go through the windows in order, `Return` activates the
+//! highlighted window and `Delete` closes it. Before anything is typed, `w`
+//! closes the highlighted window and `1` to `9` switch workspace. In global
+//! exposé the highlight goes through the windows of every workspace.
+//!
+//! Any other character filters the windows by title and app name, the
+//! matching ones are laid out again and the others fade out. `BackSpace`
+//! edits the filter, `Esc` clears it or closes exposé.
+//!
+//! App Exposé takes key presses the same way, only `Esc` does something
+//! there: it closes App Exposé.
+
+use smithay::{
+    input::keyboard::{Keysym, ModifiersState},
+    utils::IsAlive,
+};
+
+use crate::{state::Backend, workspaces::SelectionDirection, Otto};
+
+/// Whether exposé takes a key press instead of the focused app
+pub fn is_expose_key(modifiers: &ModifiersState, keysym: Keysym) -> bool {
+    if modifiers.ctrl || modifiers.alt || modifiers.logo {
+        return false;
+    }
+    matches!(
+        keysym,
+        Keysym::Left
+            | Keysym::Right
+            | Keysym::Up
+            | Keysym::Down
+            | Keysym::Tab
+            | Keysym::ISO_Left_Tab
+            | Keysym::Return
+            | Keysym::KP_Enter
+            | Keysym::Delete
+            | Keysym::Escape
+            | Keysym::BackSpace
+    ) || keysym.key_char().is_some_and(|ch| !ch.is_control())
+}
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Whether key presses go to exposé
+    pub fn is_expose_keyboard_active(&self) -> bool {
+        self.workspaces.app_expose.alive()
+            || (self.workspaces.get_show_all()
+                && !self.workspaces.is_expose_transitioning()
+                && !self.workspaces.is_window_selector_dragging())
+    }
+
+    /// Handles a key press taken by exposé, see [`is_expose_key`]
+    pub(crate) fn expose_key(&mut self, keysym: Keysym) {
+        if self.workspaces.app_expose.alive() {
+            if keysym == Keysym::Escape {
+                self.workspaces.hide_app_expose();
+            }
+            return;
+        }
+        let workspaces = &self.workspaces;
+        let filter = workspaces.expose_filter();
+        match keysym {
+            Keysym::Left => workspaces.expose_move_selection(SelectionDirection::Left),
+            Keysym::Right => workspaces.expose_move_selection(SelectionDirection::Right),
+            Keysym::Up => workspaces.expose_move_selection(SelectionDirection::Up),
+            Keysym::Down => workspaces.expose_move_selection(SelectionDirection::Down),
+            Keysym::Tab => workspaces.expose_cycle_selection(true),
+            Keysym::ISO_Left_Tab => workspaces.expose_cycle_selection(false),
+            Keysym::Return | Keysym::KP_Enter => {
+                let Some(window_id) = workspaces.expose_selected_window() else {
+                    return;
+                };
+                self.workspaces.focus_app_with_window(&window_id);
+                self.set_keyboard_focus_on_surface(&window_id);
+                self.workspaces.expose_set_visible(false);
+            }
+            Keysym::Delete => self.close_expose_selection(),
+            Keysym::w if filter.is_empty() && workspaces.expose_selected_window().is_some() => {
+                self.close_expose_selection()
+            }
+            Keysym::Escape => {
+                if filter.is_empty() {
+                    self.workspaces.expose_set_visible(false);
+                } else {
+                    self.filter_expose(String::new());
+                }
+            }
+            Keysym::BackSpace => {
+                let mut filter = filter;
+                if filter.pop().is_some() {
+                    self.filter_expose(filter);
+                }
+            }
+            _ => {
+                let Some(ch) = keysym.key_char().filter(|ch| !ch.is_control()) else {
+                    return;
+                };
+                match ch.to_digit(10) {
+                    Some(digit @ 1..=9) if filter.is_empty() => {
+                        let index = digit as usize - 1;
+                        if self.workspaces.get_workspace_at(index).is_some() {
+                            self.set_current_workspace_index(index);
+                        }
+                    }
+                    _ => {
+                        let mut filter = filter;
+                        filter.push(ch);
+                        self.filter_expose(filter);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Filters the windows of exposé, highlighting the first match
+    fn filter_expose(&mut self, filter: String) {
+        self.workspaces.set_expose_filter(&filter);
+        if !filter.is_empty() {
+            self.workspaces.expose_select_first();
+        }
+    }
+
+    fn close_expose_selection(&mut self) {
+        let window = self
+            .workspaces
+            .expose_selected_window()
+            .and_then(|id| self.workspaces.get_window_for_surface(&id).cloned());
+        if let Some(window) = window {
+            self.close_window(&window);
+        }
+    }
+}
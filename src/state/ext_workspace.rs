@@ -0,0 +1,560 @@
+/// Handler for the ext-workspace-v1 protocol
+///
+/// Lets external bars and pagers such as Waybar show and switch workspaces.
+/// Every output shows all the workspaces, so a client gets one group per
+/// output, each with its own handles for the workspaces in the order of the
+/// workspace selector. A workspace is sent with its name, or its number when
+/// it has none, its position as coordinates, and the active, urgent and
+/// hidden states. Pagers can activate, create and remove workspaces; their
+/// requests are applied in order on `commit`.
+use std::sync::{Arc, Mutex};
+
+use smithay::{
+    output::Output,
+    reexports::wayland_protocols::ext::workspace::v1::server::{
+        ext_workspace_group_handle_v1::{self, ExtWorkspaceGroupHandleV1, GroupCapabilities},
+        ext_workspace_handle_v1::{self, ExtWorkspaceHandleV1, State, WorkspaceCapabilities},
+        ext_workspace_manager_v1::{self, ExtWorkspaceManagerV1},
+    },
+};
+use wayland_server::{
+    backend::ClientId, protocol::wl_output::WlOutput, Client, DataInit, Dispatch, DisplayHandle,
+    GlobalDispatch, New, Resource,
+};
+
+use crate::{
+    state::{Backend, Otto},
+    utils::Observer,
+    workspaces::WorkspacesModel,
+};
+
+/// A workspace as sent to clients
+#[derive(Debug, Clone, PartialEq)]
+struct WorkspaceInfo {
+    /// [`WorkspaceView::index`](crate::workspaces::workspace::WorkspaceView), the
+    /// workspace handles keep it as user data
+    view_index: usize,
+    name: String,
+    position: u32,
+    state: State,
+    capabilities: WorkspaceCapabilities,
+}
+
+impl WorkspaceInfo {
+    fn from_model(model: &WorkspacesModel) -> Vec<Self> {
+        model
+            .workspaces
+            .iter()
+            .enumerate()
+            .map(|(position, workspace)| {
+                let mut state = State::empty();
+                if position == model.current_workspace {
+                    state |= State::Active;
+                }
+                if workspace.is_urgent() {
+                    state |= State::Urgent;
+                }
+                // Pagers skip fullscreen workspaces while they are not shown
+                if workspace.get_fullscreen_mode() && position != model.current_workspace {
+                    state |= State::Hidden;
+                }
+                // Fullscreen workspaces go away with their window
+                let mut capabilities = WorkspaceCapabilities::Activate;
+                if !workspace.get_fullscreen_mode() {
+                    capabilities |= WorkspaceCapabilities::Remove;
+                }
+                WorkspaceInfo {
+                    view_index: workspace.index,
+                    name: workspace
+                        .get_name()
+                        .unwrap_or_else(|| (position + 1).to_string()),
+                    position: position as u32,
+                    state,
+                    capabilities,
+                }
+            })
+            .collect()
+    }
+
+    /// Sends what differs from `sent`, returns `false` if nothing did
+    fn send(&self, handle: &ExtWorkspaceHandleV1, sent: Option<&WorkspaceInfo>) -> bool {
+        let mut changed = false;
+        if sent.map(|sent| &sent.name) != Some(&self.name) {
+            handle.name(self.name.clone());
+            changed = true;
+        }
+        if sent.map(|sent| sent.position) != Some(self.position) {
+            handle.coordinates(self.position.to_ne_bytes().to_vec());
+            changed = true;
+        }
+        if sent.map(|sent| sent.state) != Some(self.state) {
+            handle.state(self.state);
+            changed = true;
+        }
+        if sent.map(|sent| sent.capabilities) != Some(self.capabilities) {
+            handle.capabilities(self.capabilities);
+            changed = true;
+        }
+        changed
+    }
+}
+
+/// A pager request, applied on `commit`
+#[derive(Debug, Clone)]
+enum WorkspaceRequest {
+    Activate(usize),
+    Remove(usize),
+    Create(String),
+}
+
+/// The group of one output and what its client was told about it
+struct GroupInstance {
+    output: Output,
+    group: ExtWorkspaceGroupHandleV1,
+    /// Bound outputs the group entered
+    wl_outputs: Vec<WlOutput>,
+    workspaces: Vec<(ExtWorkspaceHandleV1, WorkspaceInfo)>,
+}
+
+impl GroupInstance {
+    /// Enters `wl_output` unless done before, returns whether it did
+    fn enter(&mut self, wl_output: &WlOutput) -> bool {
+        self.wl_outputs.retain(|bound| bound.is_alive());
+        if self.wl_outputs.contains(wl_output) {
+            return false;
+        }
+        self.group.output_enter(wl_output);
+        self.wl_outputs.push(wl_output.clone());
+        true
+    }
+
+    /// Tells the client the group and its workspaces are gone
+    fn remove(&self) {
+        for (handle, _) in &self.workspaces {
+            self.group.workspace_leave(handle);
+            handle.removed();
+        }
+        for wl_output in &self.wl_outputs {
+            self.group.output_leave(wl_output);
+        }
+        self.group.removed();
+    }
+}
+
+/// A bound manager and what its client was told
+struct ManagerInstance {
+    manager: ExtWorkspaceManagerV1,
+    groups: Vec<GroupInstance>,
+    pending: Vec<WorkspaceRequest>,
+}
+
+/// Creates the resources for the dispatcher given to
+/// [`ExtWorkspaceManagerState::new`]
+struct ResourceFactory {
+    group: fn(&DisplayHandle, &Client, u32) -> Option<ExtWorkspaceGroupHandleV1>,
+    workspace: fn(&DisplayHandle, &Client, u32, usize) -> Option<ExtWorkspaceHandleV1>,
+}
+
+fn create_group<D>(
+    dh: &DisplayHandle,
+    client: &Client,
+    version: u32,
+) -> Option<ExtWorkspaceGroupHandleV1>
+where
+    D: Dispatch<ExtWorkspaceGroupHandleV1, ()> + 'static,
+{
+    client
+        .create_resource::<ExtWorkspaceGroupHandleV1, _, D>(dh, version, ())
+        .ok()
+}
+
+fn create_workspace<D>(
+    dh: &DisplayHandle,
+    client: &Client,
+    version: u32,
+    view_index: usize,
+) -> Option<ExtWorkspaceHandleV1>
+where
+    D: Dispatch<ExtWorkspaceHandleV1, usize> + 'static,
+{
+    client
+        .create_resource::<ExtWorkspaceHandleV1, _, D>(dh, version, view_index)
+        .ok()
+}
+
+struct ExtWorkspaceInner {
+    display: DisplayHandle,
+    factory: ResourceFactory,
+    instances: Vec<ManagerInstance>,
+    workspaces: Vec<WorkspaceInfo>,
+    outputs: Vec<Output>,
+}
+
+impl ExtWorkspaceInner {
+    /// Sends `instance` what changed since the last update
+    fn update(&self, instance: &mut ManagerInstance) {
+        let Some(client) = instance.manager.client() else {
+            return;
+        };
+        let mut changed = false;
+
+        instance.groups.retain(|group| {
+            let keep = self.outputs.contains(&group.output);
+            if !keep {
+                group.remove();
+                changed = true;
+            }
+            keep
+        });
+        for output in &self.outputs {
+            if instance.groups.iter().any(|group| &group.output == output) {
+                continue;
+            }
+            let version = instance.manager.version();
+            let Some(group) = (self.factory.group)(&self.display, &client, version) else {
+                continue;
+            };
+            instance.manager.workspace_group(&group);
+            group.capabilities(GroupCapabilities::CreateWorkspace);
+            let mut group = GroupInstance {
+                output: output.clone(),
+                group,
+                wl_outputs: Vec::new(),
+                workspaces: Vec::new(),
+            };
+            for wl_output in output.client_outputs(&client) {
+                group.enter(&wl_output);
+            }
+            instance.groups.push(group);
+            changed = true;
+        }
+
+        for group in instance.groups.iter_mut() {
+            changed |= self.update_group(&instance.manager, &client, group);
+        }
+
+        if changed {
+            instance.manager.done();
+        }
+    }
+
+    /// Sends the workspaces of `group` that changed, returns whether any did
+    fn update_group(
+        &self,
+        manager: &ExtWorkspaceManagerV1,
+        client: &Client,
+        group: &mut GroupInstance,
+    ) -> bool {
+        let mut changed = false;
+        let handle_group = &group.group;
+        group.workspaces.retain(|(handle, sent)| {
+            let keep = self
+                .workspaces
+                .iter()
+                .any(|info| info.view_index == sent.view_index);
+            if !keep {
+                handle_group.workspace_leave(handle);
+                handle.removed();
+                changed = true;
+            }
+            keep
+        });
+        for info in &self.workspaces {
+            let sent = group
+                .workspaces
+                .iter_mut()
+                .find(|(_, sent)| sent.view_index == info.view_index);
+            match sent {
+                Some((handle, sent)) => {
+                    changed |= info.send(handle, Some(sent));
+                    *sent = info.clone();
+                }
+                None => {
+                    let version = manager.version();
+                    let Some(handle) =
+                        (self.factory.workspace)(&self.display, client, version, info.view_index)
+                    else {
+                        continue;
+                    };
+                    manager.workspace(&handle);
+                    info.send(&handle, None);
+                    group.group.workspace_enter(&handle);
+                    group.workspaces.push((handle, info.clone()));
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+}
+
+/// Global state for ext workspace management, kept up to date as an
+/// observer of the workspaces
+pub struct ExtWorkspaceManagerState {
+    inner: Mutex<ExtWorkspaceInner>,
+}
+
+impl ExtWorkspaceManagerState {
+    pub fn new<D>(display: &DisplayHandle) -> Arc<Self>
+    where
+        D: GlobalDispatch<ExtWorkspaceManagerV1, ()>
+            + Dispatch<ExtWorkspaceManagerV1, ()>
+            + Dispatch<ExtWorkspaceGroupHandleV1, ()>
+            + Dispatch<ExtWorkspaceHandleV1, usize>
+            + 'static,
+    {
+        display.create_global::<D, ExtWorkspaceManagerV1, ()>(1, ());
+
+        Arc::new(Self {
+            inner: Mutex::new(ExtWorkspaceInner {
+                display: display.clone(),
+                factory: ResourceFactory {
+                    group: create_group::<D>,
+                    workspace: create_workspace::<D>,
+                },
+                instances: Vec::new(),
+                workspaces: Vec::new(),
+                outputs: Vec::new(),
+            }),
+        })
+    }
+
+    fn register_manager(&self, manager: ExtWorkspaceManagerV1) {
+        let mut inner = self.inner.lock().unwrap();
+        let mut instance = ManagerInstance {
+            manager,
+            groups: Vec::new(),
+            pending: Vec::new(),
+        };
+        inner.update(&mut instance);
+        inner.instances.push(instance);
+    }
+
+    /// Enters the group of `output` for a client binding it after the group
+    /// was sent
+    pub fn output_bound(&self, output: &Output, wl_output: &WlOutput) {
+        let mut inner = self.inner.lock().unwrap();
+        for instance in inner.instances.iter_mut() {
+            if !instance.manager.id().same_client_as(&wl_output.id()) {
+                continue;
+            }
+            let entered = instance
+                .groups
+                .iter_mut()
+                .filter(|group| &group.output == output)
+                .fold(false, |entered, group| group.enter(wl_output) || entered);
+            if entered {
+                instance.manager.done();
+            }
+        }
+    }
+
+    /// Tells the client no more events come, after a `stop` request
+    fn finish_manager(&self, manager: &ExtWorkspaceManagerV1) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.instances.retain(|instance| {
+            let keep = instance.manager.id() != manager.id();
+            if !keep {
+                instance.manager.finished();
+            }
+            keep
+        });
+    }
+
+    fn unregister_manager(&self, manager: &ExtWorkspaceManagerV1) {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .instances
+            .retain(|instance| instance.manager.id() != manager.id());
+    }
+
+    /// Queues a request of the group `group` until the next `commit`
+    fn queue_group_request(&self, group: &ExtWorkspaceGroupHandleV1, request: WorkspaceRequest) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(instance) = inner.instances.iter_mut().find(|instance| {
+            instance
+                .groups
+                .iter()
+                .any(|instance_group| instance_group.group.id() == group.id())
+        }) {
+            instance.pending.push(request);
+        }
+    }
+
+    /// Queues a request of the workspace `workspace` until the next `commit`
+    fn queue_workspace_request(&self, workspace: &ExtWorkspaceHandleV1, request: WorkspaceRequest) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(instance) = inner.instances.iter_mut().find(|instance| {
+            instance.groups.iter().any(|group| {
+                group
+                    .workspaces
+                    .iter()
+                    .any(|(handle, _)| handle.id() == workspace.id())
+            })
+        }) {
+            instance.pending.push(request);
+        }
+    }
+
+    fn take_requests(&self, manager: &ExtWorkspaceManagerV1) -> Vec<WorkspaceRequest> {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .instances
+            .iter_mut()
+            .find(|instance| instance.manager.id() == manager.id())
+            .map(|instance| std::mem::take(&mut instance.pending))
+            .unwrap_or_default()
+    }
+}
+
+impl Observer<WorkspacesModel> for ExtWorkspaceManagerState {
+    fn notify(&self, model: &WorkspacesModel) {
+        let mut inner = self.inner.lock().unwrap();
+        let workspaces = WorkspaceInfo::from_model(model);
+        if workspaces == inner.workspaces && model.outputs == inner.outputs {
+            return;
+        }
+        inner.workspaces = workspaces;
+        inner.outputs = model.outputs.clone();
+
+        let mut instances = std::mem::take(&mut inner.instances);
+        for instance in instances.iter_mut() {
+            inner.update(instance);
+        }
+        inner.instances = instances;
+    }
+}
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// Applies the requests a pager made since its last `commit`
+    fn apply_ext_workspace_requests(&mut self, manager: &ExtWorkspaceManagerV1) {
+        for request in self.ext_workspace_state.take_requests(manager) {
+            match request {
+                WorkspaceRequest::Activate(view_index) => {
+                    if let Some(index) =
+                        self.workspaces.workspace_position_by_view_index(view_index)
+                    {
+                        self.set_current_workspace_index(index);
+                    }
+                }
+                WorkspaceRequest::Remove(view_index) => {
+                    if let Some(index) =
+                        self.workspaces.workspace_position_by_view_index(view_index)
+                    {
+                        let _ = self.remove_workspace(index);
+                    }
+                }
+                WorkspaceRequest::Create(name) => {
+                    let (index, _) = self.workspaces.add_workspace();
+                    let name = Some(name.trim().to_string()).filter(|name| !name.is_empty());
+                    if name.is_some() {
+                        self.workspaces.rename_workspace(index, name);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Implement GlobalDispatch for manager
+impl<BackendData: Backend + 'static> GlobalDispatch<ExtWorkspaceManagerV1, (), Otto<BackendData>>
+    for Otto<BackendData>
+{
+    fn bind(
+        state: &mut Otto<BackendData>,
+        _handle: &DisplayHandle,
+        _client: &Client,
+        resource: New<ExtWorkspaceManagerV1>,
+        _global_data: &(),
+        data_init: &mut DataInit<'_, Otto<BackendData>>,
+    ) {
+        let manager = data_init.init(resource, ());
+        state.ext_workspace_state.register_manager(manager);
+    }
+}
+
+// Implement Dispatch for manager
+impl<BackendData: Backend + 'static> Dispatch<ExtWorkspaceManagerV1, (), Otto<BackendData>>
+    for Otto<BackendData>
+{
+    fn request(
+        state: &mut Otto<BackendData>,
+        _client: &Client,
+        resource: &ExtWorkspaceManagerV1,
+        request: ext_workspace_manager_v1::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, Otto<BackendData>>,
+    ) {
+        match request {
+            ext_workspace_manager_v1::Request::Commit => {
+                state.apply_ext_workspace_requests(resource);
+            }
+            ext_workspace_manager_v1::Request::Stop => {
+                state.ext_workspace_state.finish_manager(resource);
+            }
+            _ => {}
+        }
+    }
+
+    fn destroyed(
+        state: &mut Otto<BackendData>,
+        _client: ClientId,
+        resource: &ExtWorkspaceManagerV1,
+        _data: &(),
+    ) {
+        state.ext_workspace_state.unregister_manager(resource);
+    }
+}
+
+// Implement Dispatch for the group
+impl<BackendData: Backend + 'static> Dispatch<ExtWorkspaceGroupHandleV1, (), Otto<BackendData>>
+    for Otto<BackendData>
+{
+    fn request(
+        state: &mut Otto<BackendData>,
+        _client: &Client,
+        resource: &ExtWorkspaceGroupHandleV1,
+        request: ext_workspace_group_handle_v1::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, Otto<BackendData>>,
+    ) {
+        if let ext_workspace_group_handle_v1::Request::CreateWorkspace { workspace } = request {
+            state
+                .ext_workspace_state
+                .queue_group_request(resource, WorkspaceRequest::Create(workspace));
+        }
+    }
+}
+
+// Implement Dispatch for workspaces
+impl<BackendData: Backend + 'static> Dispatch<ExtWorkspaceHandleV1, usize, Otto<BackendData>>
+    for Otto<BackendData>
+{
+    fn request(
+        state: &mut Otto<BackendData>,
+        _client: &Client,
+        resource: &ExtWorkspaceHandleV1,
+        request: ext_workspace_handle_v1::Request,
+        view_index: &usize,
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, Otto<BackendData>>,
+    ) {
+        let request = match request {
+            ext_workspace_handle_v1::Request::Activate => WorkspaceRequest::Activate(*view_index),
+            ext_workspace_handle_v1::Request::Remove => WorkspaceRequest::Remove(*view_index),
+            // Every output shows all the workspaces, they do not move between
+            // groups
+            ext_workspace_handle_v1::Request::Deactivate
+            | ext_workspace_handle_v1::Request::Assign { .. } => {
+                tracing::debug!("ext workspace: unsupported request");
+                return;
+            }
+            _ => return,
+        };
+        state
+            .ext_workspace_state
+            .queue_workspace_request(resource, request);
+    }
+}
@@ -0,0 +1,289 @@
+//! Running the `[hooks]` commands
+//!
+//! Hooks are spawned without waiting for them, a thread reaps each one. The
+//! hooks of focus and workspace changes run at most once per
+//! `hooks.min_interval_ms`; changes in between are coalesced and run once
+//! the interval is over with the details of the latest one.
+//!
+//! `session_locked` and `session_unlocked` follow the `Lock` and `Unlock`
+//! signals of logind's `session/auto`, the session Otto was started in;
+//! locking another session doesn't run them.
+
+use std::{
+    collections::HashMap,
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use otto_ipc::{Event, WindowInfo};
+use smithay::reexports::calloop::{
+    channel::{channel, Event as ChannelEvent, Sender},
+    timer::{TimeoutAction, Timer},
+    LoopHandle,
+};
+use tracing::{debug, warn};
+
+use crate::{
+    config::{hooks::HookEvent, Config},
+    ipc::Snapshot,
+    state::Backend,
+    Otto,
+};
+
+/// Hooks still running above which new ones are skipped
+const MAX_RUNNING: usize = 16;
+
+/// Environment variables describing an event
+pub type HookEnv = Vec<(&'static str, String)>;
+
+#[derive(Debug, Default)]
+pub struct HookState {
+    last_run: HashMap<HookEvent, Instant>,
+    /// Latest details of events waiting for the interval to pass
+    pending: HashMap<HookEvent, HookEnv>,
+    running: Arc<AtomicUsize>,
+}
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// Runs the hooks of `event`, or defers them if they ran recently and
+    /// the event is rate limited
+    pub fn run_hooks(&mut self, event: HookEvent, env: HookEnv) {
+        let (configured, interval) = Config::with(|config| {
+            (
+                !config.hooks.commands(event).is_empty(),
+                Duration::from_millis(config.hooks.min_interval_ms),
+            )
+        });
+        if !configured {
+            return;
+        }
+        if !event.is_rate_limited() {
+            self.spawn_hooks(event, env);
+            return;
+        }
+
+        let elapsed = self
+            .hooks
+            .last_run
+            .get(&event)
+            .map(|last_run| last_run.elapsed());
+        match elapsed {
+            Some(elapsed) if elapsed < interval => {
+                // A timer is already waiting if an event is pending
+                if self.hooks.pending.insert(event, env).is_none() {
+                    self.handle
+                        .insert_source(
+                            Timer::from_duration(interval - elapsed),
+                            move |_, _, data| {
+                                if let Some(env) = data.hooks.pending.remove(&event) {
+                                    data.spawn_hooks(event, env);
+                                }
+                                TimeoutAction::Drop
+                            },
+                        )
+                        .map_err(|err| warn!(?err, "Failed to defer hooks"))
+                        .ok();
+                }
+            }
+            _ => self.spawn_hooks(event, env),
+        }
+    }
+
+    fn spawn_hooks(&mut self, event: HookEvent, env: HookEnv) {
+        self.hooks.last_run.insert(event, Instant::now());
+        let commands = Config::with(|config| config.hooks.commands(event).to_vec());
+        for hook in commands {
+            if self.hooks.running.load(Ordering::Relaxed) >= MAX_RUNNING {
+                warn!(%event, "Too many hooks running, skipping");
+                return;
+            }
+            let (cmd, args) = hook.command_line();
+            debug!(%event, program = %cmd, "Running hook");
+            let child = Command::new(&cmd)
+                .args(&args)
+                .envs(self.client_env())
+                .env("OTTO_EVENT", event.name())
+                .envs(env.iter().map(|(key, value)| (*key, value)))
+                .stdin(Stdio::null())
+                .spawn();
+            match child {
+                Ok(mut child) => {
+                    let running = self.hooks.running.clone();
+                    running.fetch_add(1, Ordering::Relaxed);
+                    let reaper = std::thread::Builder::new()
+                        .name("hook-reaper".into())
+                        .spawn(move || {
+                            let _ = child.wait();
+                            running.fetch_sub(1, Ordering::Relaxed);
+                        });
+                    if let Err(err) = reaper {
+                        warn!(?err, "Failed to wait for hook");
+                    }
+                }
+                Err(err) => warn!(%event, program = %cmd, %err, "Failed to run hook"),
+            }
+        }
+    }
+
+    /// Runs the hooks of window, focus, workspace and output changes
+    pub(crate) fn run_desktop_hooks(&mut self, events: &[Event], old: &Snapshot, new: &Snapshot) {
+        for event in events {
+            if let Some((hook, env)) = desktop_hook(event, old, new) {
+                self.run_hooks(hook, env);
+            }
+        }
+    }
+
+    /// Runs the session lock hooks when logind asks to lock or unlock the
+    /// session, e.g. on `loginctl lock-session`. Only the signals of
+    /// `session/auto` are followed.
+    pub(crate) fn watch_session_lock(handle: &LoopHandle<'static, Otto<BackendData>>) {
+        let (sender, receiver) = channel::<bool>();
+        let result = handle.insert_source(receiver, |event, _, data| {
+            if let ChannelEvent::Msg(locked) = event {
+                let hook = if locked {
+                    HookEvent::SessionLocked
+                } else {
+                    HookEvent::SessionUnlocked
+                };
+                data.run_hooks(hook, Vec::new());
+            }
+        });
+        if let Err(err) = result {
+            warn!(?err, "Failed to watch session lock");
+            return;
+        }
+        for (signal, locked) in [("Lock", true), ("Unlock", false)] {
+            let sender = sender.clone();
+            let _ = std::thread::Builder::new()
+                .name("logind-lock".into())
+                .spawn(move || {
+                    if let Err(err) = forward_logind_signal(signal, locked, &sender) {
+                        debug!(?err, signal, "Not watching logind session signal");
+                    }
+                });
+        }
+    }
+}
+
+fn forward_logind_signal(
+    signal: &'static str,
+    locked: bool,
+    sender: &Sender<bool>,
+) -> zbus::Result<()> {
+    let connection = zbus::blocking::Connection::system()?;
+    let proxy = zbus::blocking::Proxy::new(
+        &connection,
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1/session/auto",
+        "org.freedesktop.login1.Session",
+    )?;
+    for _ in proxy.receive_signal(signal)? {
+        if sender.send(locked).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Hook and details for an IPC event
+pub fn desktop_hook(event: &Event, old: &Snapshot, new: &Snapshot) -> Option<(HookEvent, HookEnv)> {
+    match event {
+        Event::WindowOpened { window } => Some((HookEvent::WindowOpened, window_env(Some(window)))),
+        Event::WindowClosed { id } => {
+            Some((HookEvent::WindowClosed, window_env(old.windows.get(id))))
+        }
+        Event::WindowFocused { id } => Some((
+            HookEvent::FocusChanged,
+            window_env(id.and_then(|id| new.windows.get(&id))),
+        )),
+        Event::WorkspaceActivated { index } => {
+            let name = new
+                .workspaces
+                .get(*index)
+                .map(|workspace| workspace.name.clone())
+                .unwrap_or_default();
+            Some((
+                HookEvent::WorkspaceChanged,
+                vec![
+                    ("OTTO_WORKSPACE", index.to_string()),
+                    ("OTTO_WORKSPACE_NAME", name),
+                ],
+            ))
+        }
+        Event::OutputConnected { output } => Some((
+            HookEvent::OutputConnected,
+            vec![
+                ("OTTO_OUTPUT", output.name.clone()),
+                ("OTTO_OUTPUT_MAKE", output.make.clone()),
+                ("OTTO_OUTPUT_MODEL", output.model.clone()),
+            ],
+        )),
+        Event::OutputDisconnected { name } => Some((
+            HookEvent::OutputDisconnected,
+            vec![("OTTO_OUTPUT", name.clone())],
+        )),
+        Event::WindowChanged { .. } | Event::WorkspacesChanged { .. } => None,
+    }
+}
+
+/// Details of a window, empty values when there is none
+fn window_env(window: Option<&WindowInfo>) -> HookEnv {
+    let field = |value: fn(&WindowInfo) -> String| window.map(value).unwrap_or_default();
+    vec![
+        ("OTTO_WINDOW_ID", field(|w| w.id.to_string())),
+        ("OTTO_APP_ID", field(|w| w.app_id.clone())),
+        ("OTTO_WINDOW_TITLE", field(|w| w.title.clone())),
+        ("OTTO_WORKSPACE", field(|w| w.workspace.to_string())),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn closed_windows_are_described_from_the_previous_state() {
+        let window = WindowInfo {
+            id: 4,
+            app_id: "org.gnome.Nautilus".to_string(),
+            title: "Home".to_string(),
+            workspace: 1,
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+            focused: false,
+            minimized: false,
+            maximized: false,
+            fullscreen: false,
+        };
+        let old = Snapshot {
+            windows: BTreeMap::from([(4, window)]),
+            ..Default::default()
+        };
+        let (hook, env) =
+            desktop_hook(&Event::WindowClosed { id: 4 }, &old, &Snapshot::default()).unwrap();
+        assert_eq!(hook, HookEvent::WindowClosed);
+        assert!(env.contains(&("OTTO_APP_ID", "org.gnome.Nautilus".to_string())));
+        assert!(env.contains(&("OTTO_WORKSPACE", "1".to_string())));
+    }
+
+    #[test]
+    fn focus_lost_clears_the_window_details() {
+        let (hook, env) = desktop_hook(
+            &Event::WindowFocused { id: None },
+            &Snapshot::default(),
+            &Snapshot::default(),
+        )
+        .unwrap();
+        assert_eq!(hook, HookEvent::FocusChanged);
+        assert!(env.iter().all(|(_, value)| value.is_empty()));
+    }
+}
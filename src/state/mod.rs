@@ -46,7 +46,9 @@ use smithay::{
         wayland_protocols::xdg::shell::server::xdg_toplevel,
         wayland_server::{
             backend::{ClientData, ClientId, DisconnectReason, ObjectId},
-            protocol::{wl_data_device_manager::DndAction, wl_surface::WlSurface},
+            protocol::{
+                wl_data_device_manager::DndAction, wl_output::WlOutput, wl_surface::WlSurface,
+            },
             Display, DisplayHandle, Resource,
         },
     },
@@ -92,11 +94,16 @@ use smithay::{
 
 use crate::cursor::{CursorManager, CursorTextureCache};
 use crate::{
-    config::Config,
+    config::{
+        gestures::{ContinuousAction, GestureDirection},
+        Config,
+    },
     focus::KeyboardFocusTarget,
+    input::actions::KeyAction,
     render_elements::scene_element::SceneElement,
     shell::{LayerShellSurface, WindowElement},
     skia_renderer::SkiaTextureImage,
+    utils::{Observable, Observer},
     workspaces::{WindowViewBaseModel, WindowViewSurface, Workspaces},
 };
 #[cfg(feature = "xwayland")]
@@ -192,6 +199,7 @@ pub struct Otto<BackendData: Backend + 'static> {
     pub xdg_foreign_state: XdgForeignState,
     pub foreign_toplevel_list_state: ForeignToplevelListState,
     pub wlr_foreign_toplevel_state: wlr_foreign_toplevel::WlrForeignToplevelManagerState,
+    pub ext_workspace_state: Arc<ext_workspace::ExtWorkspaceManagerState>,
     pub cursor_shape_manager_state: CursorShapeManagerState,
 
     #[cfg(feature = "xwayland")]
@@ -201,8 +209,15 @@ pub struct Otto<BackendData: Backend + 'static> {
 
     // input-related fields
     pub suppressed_keys: Vec<Keysym>,
+    /// Last pressed key while it can still trigger a release shortcut
+    pub key_tap: Option<Keysym>,
     pub current_modifiers: ModifiersState,
     pub app_switcher_hold_modifiers: Option<ModifiersState>,
+    /// Keymap currently loaded on the seat keyboard, see `update_keymap_for_device`
+    pub active_xkb_settings: crate::config::XkbSettings,
+    /// Keymap configured for each keyboard by device id, resolved when the
+    /// device is added
+    pub device_keymaps: HashMap<String, crate::config::XkbSettings>,
     pub cursor_status: Arc<Mutex<CursorImageStatus>>,
     pub cursor_manager: CursorManager,
     pub cursor_texture_cache: CursorTextureCache,
@@ -225,15 +240,33 @@ pub struct Otto<BackendData: Backend + 'static> {
     pub layers_engine: Arc<Engine>,
 
     pub show_desktop: bool,
-    pub swipe_gesture: SwipeGestureState,
-    pub is_pinching: bool,
+    pub swipe_gesture: GestureState,
+    pub pinch_gesture: GestureState,
     pub pinch_last_scale: f64,
+    /// Finger count and start time of the current hold gesture
+    pub hold_gesture: Option<(u32, u32)>,
     pub is_resizing: bool,
+    pub scroll_state: crate::input::scroll::ScrollState,
+    /// Unfinished multi-key shortcut and active keyboard mode
+    pub key_sequences: crate::input::key_sequences::KeySequenceState,
+    pub pointer_shortcuts: crate::input::pointer_shortcuts::PointerShortcutState,
+    pub shortcut_inhibit: crate::input::shortcut_inhibit::ShortcutInhibitState,
+    /// Watches on the configuration files, see `watch_config`
+    pub config_watch: config_reload::ConfigWatch,
+    /// IPC socket clients, see `crate::ipc`
+    pub ipc: crate::ipc::IpcState,
+    pub hooks: hooks::HookState,
+    /// Manual scheme override and palette animation, see `theme_switch`
+    pub theme_switch: theme_switch::ThemeSwitchState,
 
     // screenshare
     pub screenshare_sessions: HashMap<String, crate::screenshare::ScreencastSession>,
     /// Manager for the screenshare D-Bus service (started lazily when needed).
     pub screenshare_manager: Option<crate::screenshare::ScreenshareManager>,
+    /// Shortcuts registered by apps through the GlobalShortcuts portal
+    pub global_shortcuts: crate::global_shortcuts::GlobalShortcuts,
+    /// Wallpaper set through the Wallpaper portal waiting to be kept
+    pub wallpaper_preview: Option<wallpapers::WallpaperPreview>,
 
     // foreign toplevel list - maps surface ObjectId to unified toplevel handles (both protocols)
     pub foreign_toplevels: HashMap<ObjectId, foreign_toplevel_shared::ForeignToplevelHandles>,
@@ -253,23 +286,35 @@ pub struct Otto<BackendData: Backend + 'static> {
     pub render_metrics: Arc<crate::render_metrics::RenderMetrics>,
 }
 
+pub mod config_reload;
 pub mod data_device_handler;
 pub mod dnd_grab_handler;
+pub mod expose_keyboard;
+pub mod ext_workspace;
 pub mod foreign_toplevel_list_handler;
 pub mod foreign_toplevel_shared;
 pub mod fractional_scale_handler;
+pub mod hooks;
 pub mod input_method_handler;
+pub mod recent_documents;
 pub mod seat_handler;
 pub mod security_context_handler;
 pub mod selection_handler;
+pub mod split_view;
+pub mod theme_switch;
+pub mod wallpapers;
 pub mod wlr_foreign_toplevel;
+pub mod workspace_actions;
 pub mod xdg_activation_handler;
 pub mod xdg_decoration_handler;
 pub mod xwayland_handler;
 
 // Gesture constants
 pub const DIRECTION_THRESHOLD: f64 = 5.0;
+pub const PINCH_THRESHOLD: f64 = 0.05;
 pub const EXPOSE_DELTA_MULTIPLIER: f64 = 500.0;
+pub const PINCH_DELTA_MULTIPLIER: f64 = 1.5 * EXPOSE_DELTA_MULTIPLIER;
+pub const HOLD_MIN_DURATION_MS: u32 = 400;
 pub const VELOCITY_SAMPLE_COUNT: usize = 4;
 
 /// Swipe gesture direction detected from accumulated deltas
@@ -282,43 +327,67 @@ pub enum SwipeDirection {
 
 impl SwipeDirection {
     pub fn from_accumulated(horiz: f64, vert: f64) -> Self {
-        if horiz > DIRECTION_THRESHOLD && horiz > vert {
+        if horiz.abs() > DIRECTION_THRESHOLD && horiz.abs() > vert.abs() {
             Self::Horizontal(horiz)
-        } else if vert > DIRECTION_THRESHOLD {
+        } else if vert.abs() > DIRECTION_THRESHOLD {
             Self::Vertical(vert)
         } else {
             Self::Undetermined
         }
     }
 
-    pub fn to_expose_delta(&self) -> Option<f32> {
+    pub fn to_gesture_direction(self) -> Option<GestureDirection> {
         match self {
-            Self::Vertical(delta) => Some(*delta as f32 / EXPOSE_DELTA_MULTIPLIER as f32),
-            _ => None,
+            Self::Horizontal(delta) if delta < 0.0 => Some(GestureDirection::SwipeLeft),
+            Self::Horizontal(_) => Some(GestureDirection::SwipeRight),
+            Self::Vertical(delta) if delta < 0.0 => Some(GestureDirection::SwipeUp),
+            Self::Vertical(_) => Some(GestureDirection::SwipeDown),
+            Self::Undetermined => None,
         }
     }
 }
 
-/// State machine for 3-finger swipe gestures
+/// State machine for touchpad swipe and pinch gestures
 #[derive(Debug, Clone)]
-pub enum SwipeGestureState {
+pub enum GestureState {
     Idle,
-    Detecting { accumulated: (f64, f64) },
-    WorkspaceSwitching { velocity_samples: Vec<f64> },
-    Expose { velocity_samples: Vec<f64> },
+    /// Waiting for the movement to pick a direction
+    Detecting {
+        fingers: u32,
+        accumulated: (f64, f64),
+    },
+    /// A continuous action follows the fingers, `direction` is the one
+    /// that opens it
+    Continuous {
+        action: ContinuousAction,
+        direction: GestureDirection,
+        velocity_samples: Vec<f64>,
+    },
+    /// A one-shot action already ran, ignore the rest of the gesture
+    Triggered,
 }
 
-impl SwipeGestureState {
+impl GestureState {
     pub fn is_active(&self) -> bool {
         !matches!(self, Self::Idle)
     }
 
     pub fn is_expose(&self) -> bool {
-        matches!(self, Self::Expose { .. })
+        matches!(
+            self,
+            Self::Continuous {
+                action: ContinuousAction::Expose | ContinuousAction::ExposeAllWorkspaces,
+                ..
+            }
+        )
     }
 }
 
-impl<BackendData: Backend> OutputHandler for Otto<BackendData> {}
+impl<BackendData: Backend> OutputHandler for Otto<BackendData> {
+    fn output_bound(&mut self, output: Output, wl_output: WlOutput) {
+        self.ext_workspace_state.output_bound(&output, &wl_output);
+    }
+}
 
 impl<BackendData: Backend> ShmHandler for Otto<BackendData> {
     fn shm_state(&self) -> &ShmState {
@@ -332,8 +401,11 @@ impl<BackendData: Backend> KeyboardShortcutsInhibitHandler for Otto<BackendData>
     }
 
     fn new_inhibitor(&mut self, inhibitor: KeyboardShortcutsInhibitor) {
-        // Just grant the wish for everyone
-        inhibitor.activate();
+        self.request_shortcut_inhibit(inhibitor);
+    }
+
+    fn inhibitor_destroyed(&mut self, inhibitor: KeyboardShortcutsInhibitor) {
+        self.shortcut_inhibitor_destroyed(&inhibitor);
     }
 }
 
@@ -406,6 +478,7 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
         } else {
             None
         };
+        let ipc = crate::ipc::IpcState::listen(&handle, socket_name.as_deref());
         handle
             .insert_source(
                 Generic::new(display, Interest::READ, Mode::Level),
@@ -431,6 +504,12 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
             })
             .expect("Failed to insert loop wake channel");
 
+        let config_watch = config_reload::ConfigWatch::new();
+        Self::watch_config(&handle, &config_watch);
+        Self::watch_theme_schedule(&handle);
+        Self::watch_wallpaper_slideshows(&handle);
+        Self::watch_session_lock(&handle);
+
         // init globals
         let compositor_state = CompositorState::new::<Self>(&dh);
         let data_device_state = DataDeviceState::new::<Self>(&dh);
@@ -481,26 +560,17 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
         let cursor_manager = CursorManager::new(&cursor_theme, cursor_size as u8);
         let cursor_texture_cache = CursorTextureCache::default();
         let pointer = seat.add_pointer();
-        let (layout, variant, options, repeat_delay, repeat_rate) = Config::with(|c| {
-            let layout = c.input.xkb_layout.clone().unwrap_or_default();
-            let variant = c.input.xkb_variant.clone().unwrap_or_default();
-            let options = if c.input.xkb_options.is_empty() {
-                None
-            } else {
-                Some(c.input.xkb_options.join(","))
-            };
+        let (xkb_settings, repeat_delay, repeat_rate) = Config::with(|c| {
             (
-                layout,
-                variant,
-                options,
+                c.input.xkb_settings(),
                 c.keyboard_repeat_delay,
                 c.keyboard_repeat_rate,
             )
         });
         let xkb_config = XkbConfig {
-            layout: &layout,
-            variant: &variant,
-            options,
+            layout: &xkb_settings.layout,
+            variant: &xkb_settings.variant,
+            options: xkb_settings.options_string(),
             ..Default::default()
         };
         seat.add_keyboard(xkb_config, repeat_delay, repeat_rate)
@@ -524,7 +594,12 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
         });
         layers_engine.add_layer(&root_layer);
         let scene_element = SceneElement::with_engine(layers_engine.clone());
-        let workspaces = Workspaces::new(layers_engine.clone(), dh.clone());
+        let mut workspaces = Workspaces::new(layers_engine.clone(), dh.clone());
+        Self::watch_wallpaper_decoding(&handle, &workspaces);
+        Self::watch_recent_documents(&handle, &workspaces);
+        let ext_workspace_state = ext_workspace::ExtWorkspaceManagerState::new::<Self>(&dh);
+        workspaces.with_model(|model| ext_workspace_state.notify(model));
+        workspaces.add_listener(ext_workspace_state.clone());
 
         #[cfg(feature = "debugger")]
         layers_engine.start_debugger();
@@ -563,11 +638,15 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
             xdg_foreign_state,
             foreign_toplevel_list_state,
             wlr_foreign_toplevel_state,
+            ext_workspace_state,
             cursor_shape_manager_state,
             dnd_icon: None,
             suppressed_keys: Vec::new(),
+            key_tap: None,
             current_modifiers: ModifiersState::default(),
             app_switcher_hold_modifiers: None,
+            active_xkb_settings: xkb_settings,
+            device_keymaps: HashMap::new(),
             cursor_status,
             cursor_manager,
             cursor_texture_cache,
@@ -590,14 +669,25 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
 
             show_desktop: false,
             // support variables for gestures
-            swipe_gesture: SwipeGestureState::Idle,
-            is_pinching: false,
+            swipe_gesture: GestureState::Idle,
+            pinch_gesture: GestureState::Idle,
             pinch_last_scale: 1.0,
+            hold_gesture: None,
             is_resizing: false,
+            scroll_state: Default::default(),
+            key_sequences: Default::default(),
+            pointer_shortcuts: Default::default(),
+            shortcut_inhibit: Default::default(),
+            config_watch,
+            ipc,
+            hooks: Default::default(),
+            theme_switch: Default::default(),
 
             // screenshare
             screenshare_sessions: HashMap::new(),
             screenshare_manager: None,
+            global_shortcuts: Default::default(),
+            wallpaper_preview: None,
 
             // foreign toplevel list
             foreign_toplevels: HashMap::new(),
@@ -1172,19 +1262,22 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
         self.workspaces.quit_appswitcher_app();
         // FIXME focus the previous window
     }
-    pub fn toggle_maximize_focused_window(&mut self) {
-        let Some(window) = self
-            .seat
+    /// Window with keyboard focus
+    pub fn focused_window(&self) -> Option<WindowElement> {
+        self.seat
             .get_keyboard()
             .and_then(|keyboard| keyboard.current_focus())
             .and_then(|focus| match focus {
                 KeyboardFocusTarget::Window(window) => Some(window),
                 _ => None,
             })
-        else {
-            return;
-        };
-
+    }
+    pub fn toggle_maximize_focused_window(&mut self) {
+        if let Some(window) = self.focused_window() {
+            self.toggle_maximize_window(&window);
+        }
+    }
+    pub fn toggle_maximize_window(&mut self, window: &WindowElement) {
         match window.underlying_surface() {
             smithay::desktop::WindowSurface::Wayland(_) => {
                 if let Some(toplevel) = window.toplevel() {
@@ -1214,19 +1307,68 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
             }
         }
     }
-    pub fn close_focused_window(&mut self) {
-        if let Some(keyboard) = self.seat.get_keyboard() {
-            if let Some(KeyboardFocusTarget::Window(window)) = keyboard.current_focus() {
-                match window.underlying_surface() {
-                    smithay::desktop::WindowSurface::Wayland(toplevel) => toplevel.send_close(),
-                    #[cfg(feature = "xwayland")]
-                    smithay::desktop::WindowSurface::X11(surface) => {
-                        let _ = surface.close();
-                    }
+    /// Moves a window to a new fullscreen workspace
+    pub fn fullscreen_window(&mut self, window: &WindowElement) {
+        match window.underlying_surface().clone() {
+            smithay::desktop::WindowSurface::Wayland(toplevel) => {
+                <Self as smithay::wayland::shell::xdg::XdgShellHandler>::fullscreen_request(
+                    self, toplevel, None,
+                );
+            }
+            #[cfg(feature = "xwayland")]
+            smithay::desktop::WindowSurface::X11(surface) => {
+                if let Some(xwm) = surface.xwm_id() {
+                    <Self as smithay::xwayland::XwmHandler>::fullscreen_request(self, xwm, surface);
                 }
             }
         }
     }
+    pub fn close_focused_window(&mut self) {
+        if let Some(window) = self.focused_window() {
+            self.close_window(&window);
+        }
+    }
+    pub fn close_window(&mut self, window: &WindowElement) {
+        match window.underlying_surface() {
+            smithay::desktop::WindowSurface::Wayland(toplevel) => toplevel.send_close(),
+            #[cfg(feature = "xwayland")]
+            smithay::desktop::WindowSurface::X11(surface) => {
+                let _ = surface.close();
+            }
+        }
+    }
+    /// Minimizes a window, remembering its geometry, and focuses the next one
+    pub fn minimize_window(&mut self, window: &WindowElement) {
+        let id = window.id();
+        if let Some(geometry) = self.workspaces.element_geometry(window) {
+            if let Some(mut view) = self.workspaces.get_window_view(&id) {
+                view.unmaximised_rect = geometry;
+                self.workspaces.set_window_view(&id, view);
+            }
+        }
+
+        match self.workspaces.minimize_window(window) {
+            Some(wid) => self.set_keyboard_focus_on_surface(&wid),
+            None => self.clear_keyboard_focus(),
+        }
+    }
+    /// Raises and focuses a window, restoring it if it is minimized
+    pub fn activate_window(&mut self, wid: &ObjectId) {
+        let minimized = self
+            .workspaces
+            .get_window_for_surface(wid)
+            .is_some_and(|window| window.is_minimised());
+        let wid = if minimized {
+            match self.workspaces.unminimize_window(wid) {
+                Some(wid) => wid,
+                None => return,
+            }
+        } else {
+            wid.clone()
+        };
+        self.workspaces.focus_app_with_window(&wid);
+        self.set_keyboard_focus_on_surface(&wid);
+    }
     pub fn raise_next_app_window(&mut self) {
         if let Some(wid) = self.workspaces.raise_next_app_window() {
             self.set_keyboard_focus_on_surface(&wid);
@@ -1238,6 +1380,7 @@ impl<BackendData: Backend + 'static> Otto<BackendData> {
         }
     }
     pub fn set_current_workspace_index(&mut self, index: usize) {
+        let index = self.workspaces.remove_empty_workspaces(index);
         self.workspaces.set_current_workspace_index(index, None);
         // Focus the top window of the new workspace, or clear focus if empty
         if let Some(top_wid) = self.workspaces.get_top_window_of_workspace(index) {
@@ -1440,6 +1583,8 @@ pub fn take_presentation_feedback<'a>(
 pub trait Backend {
     const HAS_RELATIVE_MOTION: bool = false;
     const HAS_GESTURES: bool = false;
+    /// Input devices reported by the backend
+    type InputDevice: smithay::backend::input::Device;
     fn seat_name(&self) -> String;
     fn backend_name(&self) -> &'static str;
     fn reset_buffers(&mut self, output: &Output);
@@ -1469,4 +1614,33 @@ pub trait Backend {
     fn prefers_dmabuf_screenshare(&self) -> bool {
         false
     }
+    /// Runs an action that comes without an input event, e.g. from IPC
+    fn process_key_action(state: &mut Otto<Self>, action: KeyAction)
+    where
+        Self: Sized + 'static;
+    /// Applies `[input.devices]` again after the configuration changed
+    fn reconfigure_input_devices(_state: &mut Otto<Self>)
+    where
+        Self: Sized + 'static,
+    {
+    }
+    /// Sets up an input device plugged in while running
+    fn input_device_added(_state: &mut Otto<Self>, _device: &mut Self::InputDevice)
+    where
+        Self: Sized + 'static,
+    {
+    }
+    /// Forgets an input device that was unplugged
+    fn input_device_removed(_state: &mut Otto<Self>, _device: &Self::InputDevice)
+    where
+        Self: Sized + 'static,
+    {
+    }
+    /// Applies `[displays]` again to the outputs after the configuration
+    /// changed
+    fn reapply_outputs(_state: &mut Otto<Self>)
+    where
+        Self: Sized + 'static,
+    {
+    }
 }
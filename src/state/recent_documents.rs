@@ -0,0 +1,32 @@
+//! Reading the recent documents listed by App Exposé off the main loop
+
+use smithay::reexports::calloop::{
+    channel::{channel, Event as ChannelEvent},
+    LoopHandle,
+};
+use tracing::warn;
+
+use crate::{
+    state::Backend,
+    workspaces::{recent_documents::Bookmark, Workspaces},
+    Otto,
+};
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// Keeps the recent documents once read off the main loop
+    pub(crate) fn watch_recent_documents(
+        handle: &LoopHandle<'static, Otto<BackendData>>,
+        workspaces: &Workspaces,
+    ) {
+        let (sender, receiver) = channel::<Vec<Bookmark>>();
+        let result = handle.insert_source(receiver, |event, _, data| {
+            if let ChannelEvent::Msg(bookmarks) = event {
+                data.workspaces.recent_documents_loaded(bookmarks);
+            }
+        });
+        match result {
+            Ok(_) => workspaces.set_recent_documents_loader(sender),
+            Err(err) => warn!(?err, "Failed to watch the recent documents"),
+        }
+    }
+}
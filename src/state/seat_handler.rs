@@ -32,9 +32,11 @@ impl<BackendData: Backend> SeatHandler for Otto<BackendData> {
 
         let wl_surface = target.and_then(WaylandFocus::wl_surface);
 
-        let focus = wl_surface.and_then(|s| dh.get_client(s.id()).ok());
+        let focus = wl_surface.as_ref().and_then(|s| dh.get_client(s.id()).ok());
         set_data_device_focus(dh, seat, focus.clone());
         set_primary_focus(dh, seat, focus);
+
+        self.update_inhibit_indicator_for(wl_surface.as_deref());
     }
 
     fn cursor_image(&mut self, _seat: &smithay::input::Seat<Self>, image: CursorImageStatus) {
@@ -0,0 +1,242 @@
+//! Split View: a second window joining a fullscreen workspace
+//!
+//! A window dropped on a fullscreen workspace in the workspace selector, or
+//! sent there with `ToggleSplitView`, shares the workspace with the
+//! fullscreen window. Both keep the fullscreen state and are told they are
+//! tiled on every edge; the divider between them is dragged to resize them.
+//! When either window leaves fullscreen, or closes, the other one takes the
+//! whole workspace again.
+
+use layers::prelude::Transition;
+use smithay::{
+    reexports::wayland_protocols::xdg::shell::server::xdg_toplevel,
+    utils::{IsAlive, Logical, Rectangle},
+    wayland::shell::xdg::XdgShellHandler,
+};
+use tracing::info;
+
+use crate::{
+    shell::{FullscreenSurface, WindowElement},
+    state::Backend,
+    workspaces::{
+        split_view::{ratio_at, split_layout},
+        SplitView,
+    },
+    Otto,
+};
+
+const TILED_STATES: [xdg_toplevel::State; 4] = [
+    xdg_toplevel::State::TiledLeft,
+    xdg_toplevel::State::TiledRight,
+    xdg_toplevel::State::TiledTop,
+    xdg_toplevel::State::TiledBottom,
+];
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Whether `window` can join the fullscreen workspace at `index`
+    pub fn can_split_with(&self, window: &WindowElement, index: usize) -> bool {
+        let Some(workspace) = self.workspaces.get_workspace_at(index) else {
+            return false;
+        };
+        if !workspace.get_fullscreen_mode()
+            || workspace.get_fullscreen_animating()
+            || workspace.get_split_view().is_some()
+        {
+            return false;
+        }
+        !window.is_fullscreen()
+            && window.toplevel().is_some()
+            && self.workspaces.fullscreen_window_at(index).is_some()
+    }
+
+    /// Puts `window` next to the fullscreen window of the workspace at
+    /// `index` and switches to it
+    pub fn join_split_view(&mut self, window: &WindowElement, index: usize) -> bool {
+        if !self.can_split_with(window, index) {
+            return false;
+        }
+        let Some(fullscreen) = self.workspaces.fullscreen_window_at(index) else {
+            return false;
+        };
+        info!(workspace = index, "Joining split view");
+
+        let id = window.id();
+        if let Some(mut view) = self.workspaces.get_window_view(&id) {
+            if let Some(geometry) = self.workspaces.element_geometry(window) {
+                view.unmaximised_rect = geometry;
+                self.workspaces.set_window_view(&id, view);
+            }
+        }
+        self.workspaces.expose_set_visible(false);
+
+        let origin = window.get_workspace();
+        window.set_fullscreen(true, index);
+        self.workspaces
+            .move_window_to_workspace(window, index, (0, 0));
+        window.set_workspace(origin);
+
+        // Only whole-output windows can be scanned out directly
+        for output in self.workspaces.outputs() {
+            if let Some(surface) = output.user_data().get::<FullscreenSurface>() {
+                surface.clear();
+            }
+            self.backend_data.reset_buffers(output);
+        }
+
+        let workspace = self.workspaces.get_workspace_at(index).unwrap();
+        workspace.set_split_view(Some(SplitView::new(fullscreen.id(), id.clone())));
+        let transition = Transition::ease_in_out_quad(0.6);
+        self.apply_split_layout(index, Some(transition));
+
+        self.workspaces
+            .set_current_workspace_index(index, Some(transition));
+        self.set_keyboard_focus_on_surface(&id);
+        true
+    }
+
+    /// Sizes the windows of the split at `index` around its divider
+    pub(crate) fn apply_split_layout(&mut self, index: usize, transition: Option<Transition>) {
+        let Some(workspace) = self.workspaces.get_workspace_at(index) else {
+            return;
+        };
+        let Some(split) = workspace.get_split_view() else {
+            return;
+        };
+        let layout = split_layout(self.split_area(), split.ratio);
+        for (id, rect) in [(&split.left, layout.left), (&split.right, layout.right)] {
+            let Some(window) = self.workspaces.get_window_for_surface(id).cloned() else {
+                continue;
+            };
+            self.workspaces
+                .place_window(&window, index, rect.loc, transition);
+            if let Some(toplevel) = window.toplevel() {
+                toplevel.with_pending_state(|state| {
+                    state.states.set(xdg_toplevel::State::Fullscreen);
+                    for tiled in TILED_STATES {
+                        state.states.set(tiled);
+                    }
+                    state.size = Some(rect.size);
+                });
+                toplevel.send_pending_configure();
+            }
+        }
+        workspace.split_divider.show(layout.divider);
+    }
+
+    /// Takes `window` out of its split, giving the whole workspace back to
+    /// the other window. Returns the other window.
+    pub(crate) fn leave_split_view(&mut self, window: &WindowElement) -> Option<WindowElement> {
+        let index = window.get_fullscreen_workspace();
+        let workspace = self.workspaces.get_workspace_at(index)?;
+        let split = workspace.get_split_view()?;
+        let id = window.id();
+        let partner_id = split.other(&id)?.clone();
+        info!(workspace = index, "Leaving split view");
+
+        workspace.set_split_view(None);
+        workspace.split_divider.hide();
+
+        if let Some(toplevel) = window.toplevel() {
+            if toplevel.alive() {
+                toplevel.with_pending_state(|state| {
+                    for tiled in TILED_STATES {
+                        state.states.unset(tiled);
+                    }
+                });
+            }
+        }
+
+        let partner = self
+            .workspaces
+            .get_window_for_surface(&partner_id)
+            .cloned()?;
+        let area = self.split_area();
+        self.workspaces.place_window(
+            &partner,
+            index,
+            area.loc,
+            Some(Transition::ease_in_out_quad(0.6)),
+        );
+        if let Some(toplevel) = partner.toplevel() {
+            toplevel.with_pending_state(|state| {
+                for tiled in TILED_STATES {
+                    state.states.unset(tiled);
+                }
+                state.size = Some(area.size);
+            });
+            toplevel.send_pending_configure();
+        }
+        // The workspace keeps the whole output it is on
+        if let Some(output) = self
+            .workspaces
+            .workspace_output_for_element(index, &partner)
+        {
+            output
+                .user_data()
+                .insert_if_missing(FullscreenSurface::default);
+            output
+                .user_data()
+                .get::<FullscreenSurface>()
+                .unwrap()
+                .set(partner.clone());
+            self.backend_data.reset_buffers(&output);
+        }
+        workspace.set_name_from_app(partner.display_app_id(&self.display_handle));
+        Some(partner)
+    }
+
+    /// Moves the divider of the current workspace to `x`
+    pub fn resize_split_view(&mut self, x: f64) {
+        let index = self.workspaces.get_current_workspace_index();
+        let workspace = self.workspaces.get_current_workspace();
+        let Some(mut split) = workspace.get_split_view() else {
+            return;
+        };
+        split.ratio = ratio_at(self.split_area(), x);
+        workspace.set_split_view(Some(split));
+        self.apply_split_layout(index, None);
+    }
+
+    /// Splits the focused fullscreen window with the top window of the
+    /// workspace it came from, or a focused window with the nearest
+    /// fullscreen workspace. A window already split leaves its split.
+    pub fn toggle_split_view(&mut self) {
+        let Some(window) = self.focused_window() else {
+            return;
+        };
+        if window.is_fullscreen() {
+            let index = window.get_fullscreen_workspace();
+            let Some(workspace) = self.workspaces.get_workspace_at(index) else {
+                return;
+            };
+            if workspace.get_split_view().is_some() {
+                if let Some(toplevel) = window.toplevel().cloned() {
+                    XdgShellHandler::unfullscreen_request(self, toplevel);
+                }
+                return;
+            }
+            let candidate = self
+                .workspaces
+                .get_top_window_of_workspace(window.get_workspace())
+                .and_then(|id| self.workspaces.get_window_for_surface(&id).cloned());
+            if let Some(candidate) = candidate {
+                self.join_split_view(&candidate, index);
+            }
+            return;
+        }
+
+        let current = self.workspaces.get_current_workspace_index();
+        let count = self.workspaces.with_model(|model| model.workspaces.len());
+        let nearest = (0..count)
+            .filter(|index| self.can_split_with(&window, *index))
+            .min_by_key(|index| index.abs_diff(current));
+        if let Some(index) = nearest {
+            self.join_split_view(&window, index);
+        }
+    }
+
+    /// The area shared by the windows of a split, in workspace coordinates
+    fn split_area(&self) -> Rectangle<i32, Logical> {
+        Rectangle::from_loc_and_size((0, 0), self.workspaces.get_logical_rect().size)
+    }
+}
@@ -0,0 +1,154 @@
+//! Switching between the light and dark scheme
+//!
+//! With `theme_scheme = "Auto"` the schedule is checked every minute. The
+//! `ToggleThemeScheme` action overrides the scheme until the schedule or
+//! the configuration next asks for a different one. Every switch animates
+//! the palette of the compositor views and is signalled to applications
+//! through the Settings portal.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use smithay::reexports::calloop::{
+    timer::{TimeoutAction, Timer},
+    LoopHandle, RegistrationToken,
+};
+use tracing::{info, warn};
+
+use crate::{
+    config::{hooks::HookEvent, Config},
+    settings_service::{accent_color_value, color_scheme_value, SettingChange},
+    state::Backend,
+    theme::{self, ThemeColors, ThemeScheme},
+    Otto,
+};
+
+/// How often the schedule of `theme_scheme = "Auto"` is checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Time between the frames of the palette animation; every frame renders
+/// all the themed views again, so it runs slower than the display
+const FRAME_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Default)]
+pub struct ThemeSwitchState {
+    /// Scheme picked with `ToggleThemeScheme`, and the scheduled one it
+    /// overrides
+    manual: Option<(ThemeScheme, ThemeScheme)>,
+    /// Accent color last signalled to the Settings portal
+    accent: Option<(f64, f64, f64)>,
+    /// Timer of the palette animation in progress
+    transition: Option<RegistrationToken>,
+}
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// Follows the schedule of `theme_scheme = "Auto"`
+    pub(crate) fn watch_theme_schedule(handle: &LoopHandle<'static, Otto<BackendData>>) {
+        handle
+            .insert_source(Timer::from_duration(CHECK_INTERVAL), |_, _, data| {
+                data.update_theme_scheme();
+                TimeoutAction::ToDuration(CHECK_INTERVAL)
+            })
+            .map_err(|err| warn!(?err, "Failed to watch the theme schedule"))
+            .ok();
+    }
+
+    /// Switches to the scheme the configuration and its schedule ask for,
+    /// unless a manual toggle overrides it. Returns whether it switched.
+    pub fn update_theme_scheme(&mut self) -> bool {
+        let scheduled = scheduled_scheme();
+        let wanted = match self.theme_switch.manual {
+            Some((manual, overridden)) if overridden == scheduled => manual,
+            _ => {
+                self.theme_switch.manual = None;
+                scheduled
+            }
+        };
+        if wanted == theme::scheme() {
+            return false;
+        }
+        self.switch_theme_scheme(wanted);
+        true
+    }
+
+    /// Switches between light and dark until the schedule next changes
+    pub fn toggle_theme_scheme(&mut self) {
+        let scheduled = scheduled_scheme();
+        let scheme = theme::scheme().toggled();
+        self.theme_switch.manual = (scheme != scheduled).then_some((scheme, scheduled));
+        self.switch_theme_scheme(scheme);
+    }
+
+    fn switch_theme_scheme(&mut self, scheme: ThemeScheme) {
+        info!(?scheme, "Switching the theme scheme");
+        let from = theme::theme_colors();
+        theme::set_scheme(scheme);
+        self.animate_palette(from);
+        // Light and dark wallpapers
+        self.workspaces.reload_backgrounds();
+
+        self.send_setting_change(SettingChange::ColorScheme(color_scheme_value(scheme)));
+        self.run_hooks(
+            HookEvent::ThemeChanged,
+            vec![("OTTO_THEME_SCHEME", format!("{scheme:?}"))],
+        );
+    }
+
+    /// Redraws the views with the palette of the new scheme, blending from
+    /// `from` over `theme_schedule.transition_ms`
+    fn animate_palette(&mut self, from: Arc<ThemeColors>) {
+        if let Some(token) = self.theme_switch.transition.take() {
+            self.handle.remove(token);
+        }
+        let duration = Config::with(|c| Duration::from_millis(c.theme_schedule.transition_ms));
+        if duration.is_zero() {
+            theme::reload();
+            self.workspaces.reload_theme();
+            self.signal_accent_color();
+            return;
+        }
+
+        let to = theme::scheme_colors();
+        let start = Instant::now();
+        self.theme_switch.transition = self
+            .handle
+            .insert_source(Timer::immediate(), move |_, _, data| {
+                let progress = start.elapsed().as_secs_f32() / duration.as_secs_f32();
+                let action = if progress < 1.0 {
+                    // Smoothstep
+                    let eased = progress * progress * (3.0 - 2.0 * progress);
+                    theme::show_transition(&from, &to, eased);
+                    TimeoutAction::ToDuration(FRAME_INTERVAL)
+                } else {
+                    theme::reload();
+                    data.theme_switch.transition = None;
+                    data.signal_accent_color();
+                    TimeoutAction::Drop
+                };
+                data.workspaces.reload_theme();
+                action
+            })
+            .map_err(|err| warn!(?err, "Failed to animate the theme palette"))
+            .ok();
+    }
+
+    /// Signals the accent color if the palette or the theme file changed it
+    pub fn signal_accent_color(&mut self) {
+        let accent = accent_color_value();
+        if self.theme_switch.accent != Some(accent) {
+            self.theme_switch.accent = Some(accent);
+            self.send_setting_change(SettingChange::AccentColor(accent));
+        }
+    }
+
+    fn send_setting_change(&self, change: SettingChange) {
+        if let Some(manager) = &self.screenshare_manager {
+            let _ = manager.setting_changes.send(change);
+        }
+    }
+}
+
+fn scheduled_scheme() -> ThemeScheme {
+    Config::with(|config| config.theme_scheme.resolve(&config.theme_schedule))
+}
@@ -0,0 +1,204 @@
+//! Timed wallpaper slideshows, see `[wallpaper]`, and wallpapers set
+//! through the Wallpaper portal
+//!
+//! A wallpaper set by an app is kept in `$XDG_STATE_HOME/otto/wallpaper.toml`.
+//! When the app asks for a preview, the wallpaper is shown with a prompt:
+//! `Return` keeps it, `Esc` goes back to the previous one. The
+//! `ResetWallpaper` action forgets it and shows `[wallpaper]` again.
+
+use std::time::Duration;
+
+use smithay::{
+    input::keyboard::{Keysym, ModifiersState},
+    reexports::calloop::{
+        channel::{channel, Event as ChannelEvent},
+        timer::{TimeoutAction, Timer},
+        LoopHandle,
+    },
+};
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+
+use crate::{
+    config::wallpaper::PickedWallpaper,
+    state::Backend,
+    wallpaper_service::{WallpaperCommand, WallpaperError},
+    workspaces::{wallpaper::DecodedWallpaper, PromptLine, PromptModel, Workspaces},
+    Otto,
+};
+
+/// How often the slideshow intervals are checked
+const SLIDESHOW_TICK: Duration = Duration::from_secs(1);
+
+/// A wallpaper shown until the user keeps or reverts it
+#[derive(Debug)]
+pub struct WallpaperPreview {
+    app_id: String,
+    picked: PickedWallpaper,
+    /// Wallpaper picked before, shown again when reverting
+    previous: Option<PickedWallpaper>,
+    response_tx: oneshot::Sender<Result<bool, WallpaperError>>,
+}
+
+impl WallpaperPreview {
+    fn model(&self) -> PromptModel {
+        let app = if self.app_id.is_empty() {
+            "An application"
+        } else {
+            self.app_id.as_str()
+        };
+        let image = &self.picked.image;
+        PromptModel {
+            title: format!("{app} wants to change the wallpaper"),
+            lines: vec![PromptLine {
+                text: image
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| image.display().to_string()),
+                detail: image
+                    .parent()
+                    .map(|dir| dir.display().to_string())
+                    .unwrap_or_default(),
+                highlighted: true,
+            }],
+            hint: "Return keep · Esc revert".to_string(),
+        }
+    }
+}
+
+impl<BackendData: Backend + 'static> Otto<BackendData> {
+    /// Moves the slideshows to their next image when their interval is over
+    pub(crate) fn watch_wallpaper_slideshows(handle: &LoopHandle<'static, Otto<BackendData>>) {
+        handle
+            .insert_source(Timer::from_duration(SLIDESHOW_TICK), |_, _, data| {
+                data.workspaces.advance_slideshows();
+                TimeoutAction::ToDuration(SLIDESHOW_TICK)
+            })
+            .map_err(|err| warn!(?err, "Failed to run the wallpaper slideshows"))
+            .ok();
+    }
+
+    /// Shows the wallpaper images once decoded off the main loop
+    pub(crate) fn watch_wallpaper_decoding(
+        handle: &LoopHandle<'static, Otto<BackendData>>,
+        workspaces: &Workspaces,
+    ) {
+        let (sender, receiver) = channel::<DecodedWallpaper>();
+        let result = handle.insert_source(receiver, |event, _, data| {
+            if let ChannelEvent::Msg(decoded) = event {
+                data.workspaces.wallpaper_decoded(decoded);
+            }
+        });
+        match result {
+            Ok(_) => workspaces.set_wallpaper_decoder(sender),
+            Err(err) => warn!(?err, "Failed to watch wallpaper decoding"),
+        }
+    }
+
+    pub(crate) fn handle_wallpaper_command(&mut self, cmd: WallpaperCommand) {
+        match cmd {
+            WallpaperCommand::SetWallpaper {
+                app_id,
+                image,
+                preview,
+                response_tx,
+            } => {
+                if !image.is_file() {
+                    let _ = response_tx.send(Err(WallpaperError::NotAFile(image)));
+                    return;
+                }
+                // A newer request replaces a preview still shown
+                let previous = match self.wallpaper_preview.take() {
+                    Some(pending) => {
+                        let _ = pending.response_tx.send(Ok(false));
+                        pending.previous
+                    }
+                    None => self.workspaces.picked_wallpaper(),
+                };
+                let picked = PickedWallpaper { image };
+                self.workspaces.set_picked_wallpaper(Some(picked.clone()));
+
+                if preview {
+                    info!(%app_id, image = %picked.image.display(), "Previewing wallpaper");
+                    self.wallpaper_preview = Some(WallpaperPreview {
+                        app_id,
+                        picked,
+                        previous,
+                        response_tx,
+                    });
+                } else {
+                    info!(%app_id, image = %picked.image.display(), "Setting wallpaper");
+                    save_picked_wallpaper(&picked);
+                    let _ = response_tx.send(Ok(true));
+                }
+                self.update_wallpaper_prompt();
+            }
+        }
+    }
+
+    pub fn is_wallpaper_prompt_active(&self) -> bool {
+        self.wallpaper_preview.is_some()
+    }
+
+    /// Shows the wallpaper preview, or hides the prompt.
+    ///
+    /// Global shortcut and shortcut inhibitor requests share the prompt and
+    /// are shown first.
+    pub(crate) fn update_wallpaper_prompt(&self) {
+        if self.is_shortcut_prompt_active() || self.is_inhibit_prompt_active() {
+            return;
+        }
+        match &self.wallpaper_preview {
+            Some(preview) => self.workspaces.prompt.show(preview.model()),
+            None => {
+                if self.workspaces.prompt.alive() {
+                    self.workspaces.prompt.hide();
+                }
+            }
+        }
+    }
+
+    /// Handles a key press while a wallpaper preview is shown
+    pub(crate) fn wallpaper_prompt_key(&mut self, modifiers: &ModifiersState, keysym: Keysym) {
+        let plain = !(modifiers.ctrl || modifiers.alt || modifiers.logo || modifiers.shift);
+        match keysym {
+            Keysym::Return | Keysym::KP_Enter if plain => self.finish_wallpaper_preview(true),
+            Keysym::Escape if plain => self.finish_wallpaper_preview(false),
+            _ => {}
+        }
+    }
+
+    /// Forgets the wallpaper picked through the portal, cross-fading back to
+    /// the configured ones; a preview still shown is reverted
+    pub fn reset_picked_wallpaper(&mut self) {
+        if let Some(preview) = self.wallpaper_preview.take() {
+            let _ = preview.response_tx.send(Ok(false));
+            self.update_wallpaper_prompt();
+        }
+        if let Err(err) = PickedWallpaper::state_file().remove() {
+            warn!(?err, "Failed to forget the wallpaper");
+        }
+        info!("Resetting the wallpaper");
+        self.workspaces.set_picked_wallpaper(None);
+    }
+
+    fn finish_wallpaper_preview(&mut self, kept: bool) {
+        let Some(preview) = self.wallpaper_preview.take() else {
+            return;
+        };
+        info!(app_id = %preview.app_id, kept, "Wallpaper preview answered");
+        if kept {
+            save_picked_wallpaper(&preview.picked);
+        } else {
+            self.workspaces.set_picked_wallpaper(preview.previous);
+        }
+        let _ = preview.response_tx.send(Ok(kept));
+        self.update_wallpaper_prompt();
+    }
+}
+
+fn save_picked_wallpaper(picked: &PickedWallpaper) {
+    if let Err(err) = PickedWallpaper::state_file().save(picked) {
+        warn!(?err, "Failed to remember the wallpaper");
+    }
+}
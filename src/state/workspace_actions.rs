@@ -0,0 +1,171 @@
+//! Workspace builtins: moving the focused window between workspaces,
+//! creating and removing workspaces, and renaming the current one
+//!
+//! `WorkspaceRename` opens the workspace selector with a text field in place
+//! of the name of the current workspace. It takes all key presses: `Return`
+//! keeps the name, an empty name restores the default one, `Esc` cancels.
+
+use smithay::input::keyboard::{Keysym, ModifiersState};
+use tracing::info;
+
+use crate::{state::Backend, Otto};
+
+impl<BackendData: Backend> Otto<BackendData> {
+    /// Moves the focused window to the workspace at `index`, switching to
+    /// it with `follow`. Fullscreen windows and fullscreen workspaces are
+    /// left alone.
+    pub fn move_focused_window_to_workspace(&mut self, index: usize, follow: bool) {
+        let Some(window) = self.focused_window() else {
+            return;
+        };
+        if window.is_fullscreen() {
+            return;
+        }
+        let Some(source) = self.workspaces.window_workspace_index(&window) else {
+            return;
+        };
+        let Some(target) = self.workspaces.get_workspace_at(index) else {
+            return;
+        };
+        if index == source || target.get_fullscreen_mode() {
+            return;
+        }
+        info!(
+            from = source,
+            to = index,
+            follow,
+            "Moving window to workspace"
+        );
+
+        let location = self.workspaces.window_location(&window).unwrap_or_default();
+        self.workspaces
+            .move_window_to_workspace(&window, index, location);
+
+        if follow {
+            self.workspaces.set_current_workspace_index(index, None);
+            self.set_keyboard_focus_on_surface(&window.id());
+        } else {
+            // Focus what is left on the current workspace
+            let current = self.workspaces.get_current_workspace_index();
+            match self.workspaces.get_top_window_of_workspace(current) {
+                Some(top_wid) => self.set_keyboard_focus_on_surface(&top_wid),
+                None => self.clear_keyboard_focus(),
+            }
+        }
+    }
+
+    /// Moves the focused window to the next workspace taking windows, in
+    /// the order of the workspace selector and going round past the ends
+    pub fn move_focused_window_to_adjacent_workspace(&mut self, forward: bool, follow: bool) {
+        let Some(window) = self.focused_window() else {
+            return;
+        };
+        let Some(source) = self.workspaces.window_workspace_index(&window) else {
+            return;
+        };
+        let count = self.workspaces.with_model(|m| m.workspaces.len());
+        let mut index = source;
+        for _ in 1..count {
+            index = crate::input::actions::wrapping_workspace_index(index, count, forward);
+            let fullscreen = self
+                .workspaces
+                .get_workspace_at(index)
+                .is_some_and(|workspace| workspace.get_fullscreen_mode());
+            if !fullscreen {
+                self.move_focused_window_to_workspace(index, follow);
+                return;
+            }
+        }
+    }
+
+    /// Adds a workspace after the last one and switches to it
+    pub fn create_workspace(&mut self) {
+        let (index, _) = self.workspaces.add_workspace();
+        self.set_current_workspace_index(index);
+    }
+
+    /// Removes the current workspace, its windows go to the workspace
+    /// taking its place
+    pub fn remove_current_workspace(&mut self) {
+        let index = self.workspaces.get_current_workspace_index();
+        let _ = self.remove_workspace(index);
+    }
+
+    /// Removes the workspace at `index`, its windows go to the workspace
+    /// that is current afterwards. The last workspace and fullscreen
+    /// workspaces stay, the reason is returned.
+    pub fn remove_workspace(&mut self, index: usize) -> Result<(), &'static str> {
+        let count = self.workspaces.with_model(|m| m.workspaces.len());
+        let fullscreen = self
+            .workspaces
+            .get_workspace_at(index)
+            .is_some_and(|workspace| workspace.get_fullscreen_mode());
+        let refused = if count == 1 {
+            Some("cannot remove the last workspace")
+        } else if fullscreen {
+            Some("cannot remove a fullscreen workspace")
+        } else {
+            None
+        };
+        if let Some(reason) = refused {
+            info!(index, reason, "Workspace not removed");
+            return Err(reason);
+        }
+        self.workspaces.remove_workspace_at(index);
+        let current = self.workspaces.get_current_workspace_index();
+        self.set_current_workspace_index(current);
+        Ok(())
+    }
+
+    /// Opens the workspace selector to edit the name of the current workspace
+    pub fn start_workspace_rename(&mut self) {
+        if !self.workspaces.get_show_all() {
+            self.dismiss_all_popups();
+            self.workspaces.expose_set_visible(true);
+        }
+        let workspace = self.workspaces.get_current_workspace();
+        self.workspaces
+            .workspace_selector_view
+            .start_rename(workspace.index, workspace.get_name().unwrap_or_default());
+    }
+
+    pub fn is_workspace_rename_active(&self) -> bool {
+        self.workspaces.get_show_all() && self.workspaces.workspace_selector_view.is_renaming()
+    }
+
+    /// Handles a key press while a workspace name is edited
+    pub(crate) fn workspace_rename_key(&mut self, modifiers: &ModifiersState, keysym: Keysym) {
+        let selector = &self.workspaces.workspace_selector_view;
+        match keysym {
+            Keysym::Return | Keysym::KP_Enter => {
+                let Some((view_index, text)) = selector.stop_rename() else {
+                    return;
+                };
+                let Some(index) = self.workspaces.workspace_position_by_view_index(view_index)
+                else {
+                    return;
+                };
+                let name = Some(text.trim().to_string()).filter(|name| !name.is_empty());
+                self.workspaces.rename_workspace(index, name);
+            }
+            Keysym::Escape => {
+                selector.stop_rename();
+            }
+            Keysym::BackSpace => {
+                if let Some(mut text) = selector.rename_text() {
+                    text.pop();
+                    selector.set_rename_text(text);
+                }
+            }
+            _ if modifiers.ctrl || modifiers.alt || modifiers.logo => {}
+            _ => {
+                if let (Some(ch), Some(mut text)) = (keysym.key_char(), selector.rename_text()) {
+                    if !ch.is_control() {
+                        text.push(ch);
+                        selector.set_rename_text(text);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -35,6 +35,11 @@ impl<BackendData: Backend> XdgActivationHandler for Otto<BackendData> {
         if token_data.timestamp.elapsed().as_secs() < 10 {
             self.workspaces.focus_app_with_window(&surface.id());
             self.set_keyboard_focus_on_surface(&surface.id());
+        } else if let Some(window) = self.workspaces.get_window_for_surface(&surface.id()) {
+            // Too late to take the focus, pagers show the workspace as urgent
+            if let Some(index) = self.workspaces.window_workspace_index(window) {
+                self.workspaces.set_workspace_urgent(index);
+            }
         }
     }
 }
@@ -0,0 +1,219 @@
+//! Theme files, set with `theme_file` in the configuration
+//!
+//! ```toml
+//! accent_color = "#FF9F0A"
+//! font_family = "IBM Plex Sans"
+//!
+//! # Palette colors, for both schemes or for one
+//! [colors]
+//! materials_medium = "#F2F2F2B3"
+//! [colors_dark]
+//! materials_medium = "#1E1E1EB3"
+//!
+//! [dock]
+//! corner_radius = 12
+//! blur = false
+//!
+//! [expose]
+//! font_size = 12
+//! font_weight = "bold"
+//! background_color = "#000000AA"
+//! text_color = "#FFFFFF"
+//! ```
+//!
+//! Colors are `#RRGGBB` or `#RRGGBBAA`, palette names are the fields of
+//! [`ThemeColors`](super::ThemeColors).
+
+use std::{collections::BTreeMap, path::Path};
+
+use serde::Deserialize;
+
+use super::{colors_light, parse_color};
+use crate::config::ConfigError;
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeFile {
+    pub accent_color: Option<String>,
+    /// Font of every component that does not set its own
+    pub font_family: Option<String>,
+    pub colors: BTreeMap<String, String>,
+    pub colors_light: BTreeMap<String, String>,
+    pub colors_dark: BTreeMap<String, String>,
+    pub dock: ComponentTheme,
+    pub app_switcher: ComponentTheme,
+    /// Window title labels of the exposé
+    pub expose: ComponentTheme,
+    pub workspace_selector: ComponentTheme,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ComponentTheme {
+    pub corner_radius: Option<f32>,
+    /// Whether the content behind the component is blurred, it is by
+    /// default
+    pub blur: Option<bool>,
+    pub font_family: Option<String>,
+    pub font_size: Option<f32>,
+    pub font_weight: Option<FontWeight>,
+    pub background_color: Option<String>,
+    pub border_color: Option<String>,
+    pub text_color: Option<String>,
+    pub shadow_color: Option<String>,
+}
+
+/// `400`, `700`, or a name like `"semibold"`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum FontWeight {
+    Number(i32),
+    Name(String),
+}
+
+impl FontWeight {
+    /// The CSS weight, `None` if it is not one
+    pub fn value(&self) -> Option<i32> {
+        match self {
+            FontWeight::Number(weight) => (1..=1000).contains(weight).then_some(*weight),
+            FontWeight::Name(name) => {
+                let weight = match name
+                    .to_ascii_lowercase()
+                    .replace(['-', '_', ' '], "")
+                    .as_str()
+                {
+                    "thin" => 100,
+                    "extralight" | "ultralight" => 200,
+                    "light" => 300,
+                    "normal" | "regular" => 400,
+                    "medium" => 500,
+                    "semibold" | "demibold" => 600,
+                    "bold" => 700,
+                    "extrabold" | "ultrabold" => 800,
+                    "black" | "heavy" => 900,
+                    _ => return None,
+                };
+                Some(weight)
+            }
+        }
+    }
+}
+
+impl ThemeFile {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let display = path.display().to_string();
+        let content = std::fs::read_to_string(path).map_err(|source| match source.kind() {
+            std::io::ErrorKind::NotFound => ConfigError::NotFound {
+                path: display.clone(),
+            },
+            _ => ConfigError::Io {
+                path: display.clone(),
+                source,
+            },
+        })?;
+        let theme: ThemeFile = toml::from_str(&content).map_err(|source| ConfigError::Parse {
+            path: display.clone(),
+            source,
+        })?;
+        theme.validate().map_err(|message| ConfigError::Theme {
+            path: display,
+            message,
+        })?;
+        Ok(theme)
+    }
+
+    /// Checks color values, palette names and font weights
+    pub fn validate(&self) -> Result<(), String> {
+        let mut palette = colors_light::COLORS.clone();
+        let tables = [
+            ("colors", &self.colors),
+            ("colors_light", &self.colors_light),
+            ("colors_dark", &self.colors_dark),
+        ];
+        for (table, colors) in tables {
+            for (name, value) in colors {
+                if palette.color_mut(name).is_none() {
+                    return Err(format!("unknown color '{name}' in [{table}]"));
+                }
+                check_color(&format!("{table}.{name}"), value)?;
+            }
+        }
+        if let Some(accent) = &self.accent_color {
+            check_color("accent_color", accent)?;
+        }
+
+        let components = [
+            ("dock", &self.dock),
+            ("app_switcher", &self.app_switcher),
+            ("expose", &self.expose),
+            ("workspace_selector", &self.workspace_selector),
+        ];
+        for (section, component) in components {
+            let colors = [
+                ("background_color", &component.background_color),
+                ("border_color", &component.border_color),
+                ("text_color", &component.text_color),
+                ("shadow_color", &component.shadow_color),
+            ];
+            for (key, value) in colors {
+                if let Some(value) = value {
+                    check_color(&format!("{section}.{key}"), value)?;
+                }
+            }
+            if let Some(weight) = &component.font_weight {
+                if weight.value().is_none() {
+                    return Err(format!("invalid font weight {weight:?} in [{section}]"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_color(key: &str, value: &str) -> Result<(), String> {
+    match parse_color(value) {
+        Some(_) => Ok(()),
+        None => Err(format!(
+            "{key} = '{value}' is not a #RRGGBB or #RRGGBBAA color"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_files_override_palette_and_components() {
+        let theme: ThemeFile = toml::from_str(
+            r##"
+            accent_color = "#FF9F0A"
+            [colors_dark]
+            materials_medium = "#1E1E1EB3"
+            [expose]
+            font_weight = "Semi-Bold"
+            blur = false
+            "##,
+        )
+        .unwrap();
+        assert_eq!(theme.validate(), Ok(()));
+        assert_eq!(theme.colors_dark.len(), 1);
+        assert_eq!(theme.expose.font_weight.unwrap().value(), Some(600));
+        assert_eq!(theme.expose.blur, Some(false));
+        assert_eq!(theme.dock, ComponentTheme::default());
+    }
+
+    #[test]
+    fn unknown_colors_and_bad_values_are_rejected() {
+        let unknown: ThemeFile = toml::from_str("[colors]\nbackground = \"#000000\"").unwrap();
+        assert!(unknown.validate().unwrap_err().contains("'background'"));
+
+        let bad: ThemeFile = toml::from_str("[dock]\ntext_color = \"white\"").unwrap();
+        assert!(bad.validate().unwrap_err().contains("dock.text_color"));
+
+        assert!(toml::from_str::<ThemeFile>("[dock]\nradius = 3").is_err());
+
+        // The engine blurs with a radius of its own
+        assert!(toml::from_str::<ThemeFile>("[expose]\nblur = 4").is_err());
+    }
+}
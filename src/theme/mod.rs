@@ -1,13 +1,20 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, RwLock,
+};
+
+use layers::prelude::BlendMode;
 use layers::skia::{
-    font_style::{Slant, Width},
+    font_style::{Slant, Weight, Width},
     textlayout::TextStyle,
-    FontStyle,
+    FontStyle, Paint,
 };
 use layers::types::Color;
-use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
 use crate::config::Config;
+use file::ComponentTheme;
+use schedule::ThemeScheduleConfig;
 
 // Macro to define a Lazy group of colors
 macro_rules! define_colors {
@@ -46,87 +53,379 @@ macro_rules! define_text_styles {
         }
     };
 }
-#[allow(unused)]
-pub struct ThemeColors {
-    pub accents_red: Color,
-    pub accents_orange: Color,
-    pub accents_yellow: Color,
-    pub accents_green: Color,
-    pub accents_mint: Color,
-    pub accents_teal: Color,
-    pub accents_cyan: Color,
-    pub accents_blue: Color,
-    pub accents_indigo: Color,
-    pub accents_purple: Color,
-    pub accents_pink: Color,
-    pub accents_gray: Color,
-    pub accents_brown: Color,
-    pub accents_vibrant_red: Color,
-    pub accents_vibrant_orange: Color,
-    pub accents_vibrant_yellow: Color,
-    pub accents_vibrant_green: Color,
-    pub accents_vibrant_mint: Color,
-    pub accents_vibrant_teal: Color,
-    pub accents_vibrant_cyan: Color,
-    pub accents_vibrant_blue: Color,
-    pub accents_vibrant_indigo: Color,
-    pub accents_vibrant_purple: Color,
-    pub accents_vibrant_pink: Color,
-    pub accents_vibrant_brown: Color,
-    pub accents_vibrant_gray: Color,
-    pub fills_primary: Color,
-    pub fills_secondary: Color,
-    pub fills_tertiary: Color,
-    pub fills_quaternary: Color,
-    pub fills_quinary: Color,
-    pub fills_vibrant_primary: Color,
-    pub fills_vibrant_secondary: Color,
-    pub fills_vibrant_tertiary: Color,
-    pub fills_vibrant_quaternary: Color,
-    pub fills_vibrant_quinary: Color,
-    pub text_primary: Color,
-    pub text_secondary: Color,
-    pub text_tertiary: Color,
-    pub text_quaternary: Color,
-    pub text_quinary: Color,
-    pub text_vibrant_primary: Color,
-    pub text_vibrant_secondary: Color,
-    pub text_vibrant_tertiary: Color,
-    pub text_vibrant_quaternary: Color,
-    pub text_vibrant_quinary: Color,
-    pub materials_ultrathick: Color,
-    pub materials_thick: Color,
-    pub materials_medium: Color,
-    pub materials_thin: Color,
-    pub materials_ultrathin: Color,
-    pub materials_highlight: Color,
-    pub materials_controls_menu: Color,
-    pub materials_controls_popover: Color,
-    pub materials_controls_title_bar: Color,
-    pub materials_controls_sidebar: Color,
-    pub materials_controls_selection_focused: Color,
-    pub materials_controls_selection_unfocused: Color,
-    pub materials_controls_header_view: Color,
-    pub materials_controls_tooltip: Color,
-    pub materials_controls_under_window_background: Color,
-    pub materials_controls_fullscreen: Color,
-    pub materials_controls_hud: Color,
-    pub shadow_color: Color,
+// Macro to declare the palette and look up its colors by name
+macro_rules! theme_colors {
+    ($($name:ident),* $(,)?) => {
+        #[allow(unused)]
+        #[derive(Clone)]
+        pub struct ThemeColors {
+            $(pub $name: Color,)*
+        }
+
+        impl ThemeColors {
+            /// The color called `name` in theme files
+            pub fn color_mut(&mut self, name: &str) -> Option<&mut Color> {
+                match name {
+                    $(stringify!($name) => Some(&mut self.$name),)*
+                    _ => None,
+                }
+            }
+
+            /// Every color `progress` of the way from `self` to `target`
+            pub fn mix(&self, target: &ThemeColors, progress: f32) -> ThemeColors {
+                ThemeColors {
+                    $($name: mix_color(self.$name, target.$name, progress),)*
+                }
+            }
+        }
+    };
+}
+
+theme_colors! {
+    accents_red,
+    accents_orange,
+    accents_yellow,
+    accents_green,
+    accents_mint,
+    accents_teal,
+    accents_cyan,
+    accents_blue,
+    accents_indigo,
+    accents_purple,
+    accents_pink,
+    accents_gray,
+    accents_brown,
+    accents_vibrant_red,
+    accents_vibrant_orange,
+    accents_vibrant_yellow,
+    accents_vibrant_green,
+    accents_vibrant_mint,
+    accents_vibrant_teal,
+    accents_vibrant_cyan,
+    accents_vibrant_blue,
+    accents_vibrant_indigo,
+    accents_vibrant_purple,
+    accents_vibrant_pink,
+    accents_vibrant_brown,
+    accents_vibrant_gray,
+    fills_primary,
+    fills_secondary,
+    fills_tertiary,
+    fills_quaternary,
+    fills_quinary,
+    fills_vibrant_primary,
+    fills_vibrant_secondary,
+    fills_vibrant_tertiary,
+    fills_vibrant_quaternary,
+    fills_vibrant_quinary,
+    text_primary,
+    text_secondary,
+    text_tertiary,
+    text_quaternary,
+    text_quinary,
+    text_vibrant_primary,
+    text_vibrant_secondary,
+    text_vibrant_tertiary,
+    text_vibrant_quaternary,
+    text_vibrant_quinary,
+    materials_ultrathick,
+    materials_thick,
+    materials_medium,
+    materials_thin,
+    materials_ultrathin,
+    materials_highlight,
+    materials_controls_menu,
+    materials_controls_popover,
+    materials_controls_title_bar,
+    materials_controls_sidebar,
+    materials_controls_selection_focused,
+    materials_controls_selection_unfocused,
+    materials_controls_header_view,
+    materials_controls_tooltip,
+    materials_controls_under_window_background,
+    materials_controls_fullscreen,
+    materials_controls_hud,
+    shadow_color,
 }
 
 mod colors_dark;
 mod colors_light;
+pub mod file;
+pub mod schedule;
 pub mod text_styles;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ThemeScheme {
     Light,
     Dark,
+    /// Light or dark following `[theme_schedule]`
+    Auto,
+}
+
+impl ThemeScheme {
+    /// Light or dark, the schedule decides for `Auto`
+    pub fn resolve(self, schedule: &ThemeScheduleConfig) -> ThemeScheme {
+        match self {
+            ThemeScheme::Auto => schedule.scheme_now(),
+            scheme => scheme,
+        }
+    }
+
+    pub fn toggled(self) -> ThemeScheme {
+        match self {
+            ThemeScheme::Dark => ThemeScheme::Light,
+            _ => ThemeScheme::Dark,
+        }
+    }
+}
+
+/// Palette resolved from the scheme and the theme file, until the next reload
+static ACTIVE_COLORS: RwLock<Option<Arc<ThemeColors>>> = RwLock::new(None);
+/// Scheme shown, set when the compositor switches it
+static ACTIVE_SCHEME: RwLock<Option<ThemeScheme>> = RwLock::new(None);
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// The scheme shown, `Light` or `Dark`; the configured one until the
+/// compositor switches it
+pub fn scheme() -> ThemeScheme {
+    if let Some(scheme) = *ACTIVE_SCHEME.read().unwrap() {
+        return scheme;
+    }
+    let configured = Config::with(|config| config.theme_scheme.resolve(&config.theme_schedule));
+    *ACTIVE_SCHEME.write().unwrap().get_or_insert(configured)
+}
+
+/// Shows `scheme` from the next [`reload`] on
+pub fn set_scheme(scheme: ThemeScheme) {
+    *ACTIVE_SCHEME.write().unwrap() = Some(scheme);
+}
+
+/// Palette of the active scheme with the theme file overrides
+pub fn theme_colors() -> Arc<ThemeColors> {
+    if let Some(colors) = ACTIVE_COLORS.read().unwrap().as_ref() {
+        return colors.clone();
+    }
+    let colors = Arc::new(Config::with(resolve_colors));
+    *ACTIVE_COLORS.write().unwrap() = Some(colors.clone());
+    colors
+}
+
+/// Resolves the palette again after the scheme or the theme file changed.
+///
+/// Views rendered before keep their colors until they render again, see
+/// [`generation`].
+pub fn reload() {
+    *ACTIVE_COLORS.write().unwrap() = None;
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Palette of the active scheme, ignoring a transition being shown
+pub fn scheme_colors() -> ThemeColors {
+    Config::with(resolve_colors)
+}
+
+/// Shows a palette `progress` of the way from `from` to `to`, for animating
+/// a scheme switch; [`reload`] ends it
+pub fn show_transition(from: &ThemeColors, to: &ThemeColors, progress: f32) {
+    *ACTIVE_COLORS.write().unwrap() = Some(Arc::new(from.mix(to, progress)));
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Increases on every [`reload`]; views keep it in their state so a theme
+/// change renders them again
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::Relaxed)
 }
 
-pub fn theme_colors() -> &'static Lazy<ThemeColors> {
-    Config::with(|c| match c.theme_scheme {
-        ThemeScheme::Light => &colors_light::COLORS,
-        ThemeScheme::Dark => &colors_dark::COLORS,
+fn resolve_colors(config: &Config) -> ThemeColors {
+    let theme = config.theme();
+    let (mut colors, scheme_overrides) = match scheme() {
+        ThemeScheme::Dark => (colors_dark::COLORS.clone(), &theme.colors_dark),
+        _ => (colors_light::COLORS.clone(), &theme.colors_light),
+    };
+    for (name, value) in theme.colors.iter().chain(scheme_overrides) {
+        if let (Some(color), Some(value)) = (colors.color_mut(name), parse_color(value)) {
+            *color = value;
+        }
+    }
+    colors
+}
+
+/// Color used for selections and highlights, also given to applications
+/// through the Settings portal
+pub fn accent_color() -> Color {
+    Config::with(|config| config.theme().accent_color.as_deref().and_then(parse_color))
+        .unwrap_or_else(|| theme_colors().accents_blue)
+}
+
+fn mix_color(from: Color, to: Color, progress: f32) -> Color {
+    let (from, to) = (from.c4f(), to.c4f());
+    let mix = |a: f32, b: f32| a + (b - a) * progress;
+    Color::new_rgba(
+        mix(from.r, to.r),
+        mix(from.g, to.g),
+        mix(from.b, to.b),
+        mix(from.a, to.a),
+    )
+}
+
+/// Parses `#RRGGBB` or `#RRGGBBAA`
+pub fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Color::new_rgba255(
+        channel(0)?,
+        channel(2)?,
+        channel(4)?,
+        alpha,
+    ))
+}
+
+/// Parts of the interface a theme file can style separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component {
+    Dock,
+    AppSwitcher,
+    Expose,
+    WorkspaceSelector,
+}
+
+/// Style of a component from the theme file.
+///
+/// Each getter takes the built-in value, used when the theme does not set
+/// one. Sizes in theme files are logical pixels.
+#[derive(Debug, Clone)]
+pub struct ComponentStyle {
+    theme: ComponentTheme,
+    font_family: String,
+    scale: f32,
+}
+
+pub fn component_style(component: Component) -> ComponentStyle {
+    Config::with(|config| {
+        let theme = config.theme();
+        let component_theme = match component {
+            Component::Dock => &theme.dock,
+            Component::AppSwitcher => &theme.app_switcher,
+            Component::Expose => &theme.expose,
+            Component::WorkspaceSelector => &theme.workspace_selector,
+        };
+        let font_family = component_theme
+            .font_family
+            .as_ref()
+            .or(theme.font_family.as_ref())
+            .unwrap_or(&config.font_family)
+            .clone();
+        ComponentStyle {
+            theme: component_theme.clone(),
+            font_family,
+            scale: config.screen_scale as f32,
+        }
     })
 }
+
+impl ComponentStyle {
+    pub fn corner_radius(&self, default: f32) -> f32 {
+        self.theme
+            .corner_radius
+            .map_or(default, |radius| radius * self.scale)
+    }
+
+    /// Background blur, unless the theme turns it off
+    pub fn blend_mode(&self) -> BlendMode {
+        if self.theme.blur == Some(false) {
+            BlendMode::default()
+        } else {
+            BlendMode::BackgroundBlur
+        }
+    }
+
+    pub fn font_family(&self) -> &str {
+        &self.font_family
+    }
+
+    pub fn font_size(&self, default: f32) -> f32 {
+        self.theme
+            .font_size
+            .map_or(default, |size| size * self.scale)
+    }
+
+    pub fn font_weight(&self, default: Weight) -> Weight {
+        self.theme
+            .font_weight
+            .as_ref()
+            .and_then(|weight| weight.value())
+            .map_or(default, Weight::from)
+    }
+
+    pub fn background_color(&self, default: Color) -> Color {
+        themed_color(&self.theme.background_color, default)
+    }
+
+    pub fn border_color(&self, default: Color) -> Color {
+        themed_color(&self.theme.border_color, default)
+    }
+
+    pub fn text_color(&self, default: Color) -> Color {
+        themed_color(&self.theme.text_color, default)
+    }
+
+    pub fn shadow_color(&self, default: Color) -> Color {
+        themed_color(&self.theme.shadow_color, default)
+    }
+
+    /// Text style with the themed family, weight, size and color
+    pub fn text_style(&self, size: f32, weight: Weight, color: Color) -> TextStyle {
+        let mut style = TextStyle::new();
+        style.set_font_size(self.font_size(size));
+        style.set_font_style(FontStyle::new(
+            self.font_weight(weight),
+            Width::NORMAL,
+            Slant::Upright,
+        ));
+        style.set_font_families(&[self.font_family()]);
+        style.set_foreground_paint(&Paint::new(self.text_color(color).c4f(), None));
+        style
+    }
+}
+
+fn themed_color(value: &Option<String>, default: Color) -> Color {
+    value.as_deref().and_then(parse_color).unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_are_parsed_with_optional_alpha() {
+        let color = parse_color("#FF800040").unwrap().c4f();
+        assert!((color.r - 1.0).abs() < 1e-6);
+        assert!((color.a - 64.0 / 255.0).abs() < 1e-6);
+        assert!((parse_color("#FF8000").unwrap().c4f().a - 1.0).abs() < 1e-6);
+        assert!(parse_color("FF8000").is_none());
+        assert!(parse_color("#FF80").is_none());
+        assert!(parse_color("#GG8000").is_none());
+    }
+
+    #[test]
+    fn palettes_mix_halfway() {
+        let light = colors_light::COLORS.clone();
+        let dark = colors_dark::COLORS.clone();
+        let half = light.mix(&dark, 0.5).materials_medium.c4f();
+        let (light, dark) = (light.materials_medium.c4f(), dark.materials_medium.c4f());
+        assert!((half.r - (light.r + dark.r) / 2.0).abs() < 1e-3);
+        assert!((half.a - (light.a + dark.a) / 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn palette_colors_are_found_by_name() {
+        let mut colors = colors_dark::COLORS.clone();
+        assert!(colors.color_mut("materials_medium").is_some());
+        assert!(colors.color_mut("accent").is_none());
+    }
+}
@@ -0,0 +1,226 @@
+//! When `theme_scheme = "Auto"` switches between light and dark
+//!
+//! ```toml
+//! theme_scheme = "Auto"
+//!
+//! [theme_schedule]
+//! mode = "Time"     # or "Sun"
+//! light_at = "07:00"
+//! dark_at = "19:00"
+//! # Used by "Sun": light from sunrise to sunset at this location
+//! latitude = 45.46
+//! longitude = 9.19
+//! ```
+
+use std::fmt;
+
+use chrono::{Datelike, Local, Timelike};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::ThemeScheme;
+
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduleMode {
+    /// Fixed times of day, `light_at` and `dark_at`
+    #[default]
+    Time,
+    /// Sunrise and sunset at `latitude` and `longitude`
+    Sun,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeScheduleConfig {
+    pub mode: ScheduleMode,
+    pub light_at: TimeOfDay,
+    pub dark_at: TimeOfDay,
+    /// Degrees, north is positive
+    pub latitude: f64,
+    /// Degrees, east is positive
+    pub longitude: f64,
+    /// Duration of the palette animation when the scheme switches
+    pub transition_ms: u64,
+}
+
+impl Default for ThemeScheduleConfig {
+    fn default() -> Self {
+        Self {
+            mode: ScheduleMode::Time,
+            light_at: TimeOfDay::new(7, 0),
+            dark_at: TimeOfDay::new(19, 0),
+            latitude: 0.0,
+            longitude: 0.0,
+            transition_ms: 400,
+        }
+    }
+}
+
+impl ThemeScheduleConfig {
+    /// Scheme for the current local time
+    pub fn scheme_now(&self) -> ThemeScheme {
+        let now = Local::now();
+        let minute = now.hour() * 60 + now.minute();
+        let utc_offset = now.offset().local_minus_utc() / 60;
+        self.scheme_at(now.ordinal(), minute, utc_offset)
+    }
+
+    /// Scheme at `minute` of the local day `day_of_year`, with the local
+    /// time `utc_offset` minutes ahead of UTC
+    pub fn scheme_at(&self, day_of_year: u32, minute: u32, utc_offset: i32) -> ThemeScheme {
+        let (light_at, dark_at) = match self.mode {
+            ScheduleMode::Time => (self.light_at.minutes, self.dark_at.minutes),
+            ScheduleMode::Sun => {
+                match sun_times(day_of_year, self.latitude, self.longitude, utc_offset) {
+                    SunTimes::Daily { sunrise, sunset } => (sunrise, sunset),
+                    SunTimes::AlwaysUp => return ThemeScheme::Light,
+                    SunTimes::AlwaysDown => return ThemeScheme::Dark,
+                }
+            }
+        };
+        let light = if light_at <= dark_at {
+            (light_at..dark_at).contains(&minute)
+        } else {
+            !(dark_at..light_at).contains(&minute)
+        };
+        if light {
+            ThemeScheme::Light
+        } else {
+            ThemeScheme::Dark
+        }
+    }
+}
+
+/// Sunrise and sunset in local minutes of the day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SunTimes {
+    Daily {
+        sunrise: u32,
+        sunset: u32,
+    },
+    /// Polar day
+    AlwaysUp,
+    /// Polar night
+    AlwaysDown,
+}
+
+/// Sunrise and sunset with the NOAA approximation, within a few minutes
+pub fn sun_times(day_of_year: u32, latitude: f64, longitude: f64, utc_offset: i32) -> SunTimes {
+    let gamma = 2.0 * std::f64::consts::PI / 365.0 * (day_of_year as f64 - 1.0);
+    let equation_of_time = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+    let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = 90.833_f64.to_radians().cos() / (latitude.cos() * declination.cos())
+        - latitude.tan() * declination.tan();
+    if cos_hour_angle < -1.0 {
+        return SunTimes::AlwaysUp;
+    }
+    if cos_hour_angle > 1.0 {
+        return SunTimes::AlwaysDown;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+
+    let local = |utc: f64| (utc.round() as i32 + utc_offset).rem_euclid(MINUTES_PER_DAY) as u32;
+    SunTimes::Daily {
+        sunrise: local(720.0 - 4.0 * (longitude + hour_angle) - equation_of_time),
+        sunset: local(720.0 - 4.0 * (longitude - hour_angle) - equation_of_time),
+    }
+}
+
+/// `"HH:MM"` in the configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeOfDay {
+    minutes: u32,
+}
+
+impl TimeOfDay {
+    pub const fn new(hour: u32, minute: u32) -> Self {
+        Self {
+            minutes: hour * 60 + minute,
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let (hour, minute) = value.split_once(':')?;
+        let hour: u32 = hour.parse().ok()?;
+        let minute: u32 = minute.parse().ok()?;
+        (hour < 24 && minute < 60).then(|| Self::new(hour, minute))
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.minutes / 60, self.minutes % 60)
+    }
+}
+
+impl Serialize for TimeOfDay {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeOfDay {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        TimeOfDay::parse(&value).ok_or_else(|| {
+            serde::de::Error::custom(format!("'{value}' is not a time of day like \"07:30\""))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_times_switch_at_light_and_dark() {
+        let schedule = ThemeScheduleConfig::default();
+        assert_eq!(schedule.scheme_at(1, 6 * 60 + 59, 0), ThemeScheme::Dark);
+        assert_eq!(schedule.scheme_at(1, 7 * 60, 0), ThemeScheme::Light);
+        assert_eq!(schedule.scheme_at(1, 19 * 60, 0), ThemeScheme::Dark);
+
+        // Light over midnight
+        let night_shift = ThemeScheduleConfig {
+            light_at: TimeOfDay::new(22, 0),
+            dark_at: TimeOfDay::new(6, 0),
+            ..Default::default()
+        };
+        assert_eq!(night_shift.scheme_at(1, 23 * 60, 0), ThemeScheme::Light);
+        assert_eq!(night_shift.scheme_at(1, 12 * 60, 0), ThemeScheme::Dark);
+    }
+
+    #[test]
+    fn sun_times_match_known_days() {
+        // Greenwich, spring equinox: about 06:04 and 18:14 UTC
+        let SunTimes::Daily { sunrise, sunset } = sun_times(79, 51.48, 0.0, 0) else {
+            panic!("the sun rises at the equinox");
+        };
+        assert!((355..=375).contains(&sunrise), "sunrise {sunrise}");
+        assert!((1085..=1105).contains(&sunset), "sunset {sunset}");
+
+        // Tromsø: midnight sun in June, polar night in December
+        assert_eq!(sun_times(172, 69.65, 18.96, 120), SunTimes::AlwaysUp);
+        assert_eq!(sun_times(355, 69.65, 18.96, 60), SunTimes::AlwaysDown);
+    }
+
+    #[test]
+    fn times_of_day_parse_from_config() {
+        let schedule: ThemeScheduleConfig =
+            toml::from_str("mode = \"Sun\"\nlight_at = \"6:30\"").unwrap();
+        assert_eq!(schedule.mode, ScheduleMode::Sun);
+        assert_eq!(schedule.light_at, TimeOfDay::new(6, 30));
+        assert_eq!(schedule.light_at.to_string(), "06:30");
+        assert!(toml::from_str::<ThemeScheduleConfig>("dark_at = \"25:00\"").is_err());
+    }
+}
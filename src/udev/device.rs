@@ -30,7 +30,10 @@ use smithay::{
 use smithay_drm_extras::drm_scanner::DrmScanEvent;
 use tracing::{debug, error, info, warn};
 
-use crate::{config::Config, state::Otto};
+use crate::{
+    config::{Config, DisplayProfile},
+    state::Otto,
+};
 
 use super::{
     feedback::get_surface_dmabuf_feedback,
@@ -193,6 +196,40 @@ impl Otto<UdevData> {
         crate::shell::fixup_positions(&mut self.workspaces, self.pointer.current_location());
     }
 
+    /// Sets up again the connectors whose `[displays]` profile changed
+    pub(crate) fn reapply_display_profiles(&mut self) {
+        let connectors: Vec<_> = self
+            .backend_data
+            .backends
+            .iter()
+            .flat_map(|(node, device)| {
+                device
+                    .drm_scanner
+                    .crtcs()
+                    .filter(|(info, crtc)| {
+                        // Connectors without a surface are not desktop outputs
+                        device.surfaces.get(crtc).is_some_and(|surface| {
+                            let (output_name, make, model) = connector_output_info(info);
+                            surface.display_profile != display_profile(&output_name, &make, &model)
+                        })
+                    })
+                    .map(|(info, crtc)| (*node, info.clone(), crtc))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        if connectors.is_empty() {
+            return;
+        }
+
+        for (node, connector, crtc) in connectors {
+            info!(connector = ?connector.interface(), "Applying the changed display profile");
+            self.connector_disconnected(node, connector.clone(), crtc);
+            self.connector_connected(node, connector, crtc);
+        }
+
+        crate::shell::fixup_positions(&mut self.workspaces, self.pointer.current_location());
+    }
+
     /// Handles connector connection events
     pub(super) fn connector_connected(
         &mut self,
@@ -217,11 +254,7 @@ impl Otto<UdevData> {
             .dmabuf_render_formats()
             .clone();
 
-        let output_name = format!(
-            "{}-{}",
-            connector.interface().as_str(),
-            connector.interface_id()
-        );
+        let (output_name, make, model) = connector_output_info(&connector);
         info!(?crtc, "Trying to setup connector {}", output_name,);
 
         let non_desktop = device
@@ -241,13 +274,6 @@ impl Otto<UdevData> {
             })
             .unwrap_or(false);
 
-        // EDID info is no longer available in smithay-drm-extras
-        // Using connector info instead
-        let (make, model) = (
-            format!("{:?}", connector.interface()),
-            format!("{:?}", connector.interface()),
-        );
-
         if non_desktop {
             info!(
                 "Connector {} is non-desktop, setting up for leasing",
@@ -296,15 +322,7 @@ impl Otto<UdevData> {
         let device = self.backend_data.backends.get_mut(&node).unwrap();
 
         // Try to get mode from config first
-        let config_profile = Config::with(|config| {
-            let descriptor = crate::config::DisplayDescriptor {
-                connector: output_name,
-                vendor: Some(make),
-                model: Some(model),
-                kind: None,
-            };
-            config.displays.resolve(output_name, &descriptor)
-        });
+        let config_profile = display_profile(output_name, make, model);
 
         let mode_id = if let Some(ref profile) = config_profile {
             // Try to find matching resolution from config
@@ -481,6 +499,7 @@ impl Otto<UdevData> {
                 dmabuf_feedback,
                 was_direct_scanout: false,
                 render_metrics: Some(self.render_metrics.clone()),
+                display_profile: config_profile,
             };
 
             let device = self.backend_data.backends.get_mut(&node).unwrap();
@@ -607,3 +626,29 @@ impl Otto<UdevData> {
         }
     }
 }
+
+/// Output name, make and model of a connector
+fn connector_output_info(connector: &connector::Info) -> (String, String, String) {
+    let output_name = format!(
+        "{}-{}",
+        connector.interface().as_str(),
+        connector.interface_id()
+    );
+    // EDID info is no longer available in smithay-drm-extras
+    // Using connector info instead
+    let make = format!("{:?}", connector.interface());
+    (output_name, make.clone(), make)
+}
+
+/// The `[displays]` profile of an output
+fn display_profile(output_name: &str, make: &str, model: &str) -> Option<DisplayProfile> {
+    Config::with(|config| {
+        let descriptor = crate::config::DisplayDescriptor {
+            connector: output_name,
+            vendor: Some(make),
+            model: Some(model),
+            kind: None,
+        };
+        config.displays.resolve(output_name, &descriptor)
+    })
+}
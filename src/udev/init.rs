@@ -22,7 +22,10 @@ use smithay::{
 };
 use tracing::{error, info, warn};
 
-use crate::{config::Config, state::Otto};
+use crate::{
+    config::{self, Config, InputDeviceSettings},
+    state::Otto,
+};
 
 use super::{
     feedback::get_surface_dmabuf_feedback,
@@ -30,7 +33,12 @@ use super::{
 };
 
 /// Configures all libinput devices based on Otto's configuration
-fn configure_libinput_devices(libinput: &mut Libinput, config: &Config) {
+///
+/// Returns the configured devices.
+fn configure_libinput_devices(
+    libinput: &mut Libinput,
+    config: &Config,
+) -> Vec<smithay::reexports::input::Device> {
     use smithay::reexports::input::{
         event::{DeviceEvent, EventTrait},
         Event,
@@ -39,23 +47,37 @@ fn configure_libinput_devices(libinput: &mut Libinput, config: &Config) {
     // Process initial devices
     libinput.dispatch().ok();
 
+    let mut devices = Vec::new();
     for event in libinput.by_ref() {
         if let Event::Device(DeviceEvent::Added(added_event)) = event {
             let mut device = added_event.device();
             apply_device_config(&mut device, config);
+            devices.push(device);
         }
     }
+    devices
 }
 
-/// Applies configuration to an individual input device
-fn apply_device_config(device: &mut smithay::reexports::input::Device, config: &Config) {
-    // Only configure pointer devices (touchpads)
-    if !device.has_capability(smithay::reexports::input::DeviceCapability::Pointer) {
-        return;
+impl Otto<UdevData> {
+    /// Applies the input configuration again to every known device
+    pub(crate) fn reconfigure_input_devices(&mut self) {
+        Config::with(|config| {
+            for device in self.backend_data.input_devices.iter_mut() {
+                apply_device_config(device, config);
+            }
+        });
     }
+}
 
+/// Applies configuration to an individual input device
+///
+/// Called for every device present at startup and again from the
+/// `input_device_added` hook when a device is hotplugged.
+pub(crate) fn apply_device_config(device: &mut smithay::reexports::input::Device, config: &Config) {
     // Check if it's a touchpad
-    if device.config_tap_finger_count() > 0 {
+    if device.has_capability(smithay::reexports::input::DeviceCapability::Pointer)
+        && device.config_tap_finger_count() > 0
+    {
         // Configure tap-to-click
         if device
             .config_tap_set_enabled(config.input.tap_enabled)
@@ -165,6 +187,79 @@ fn apply_device_config(device: &mut smithay::reexports::input::Device, config: &
             config.input.touchpad_natural_scroll_enabled
         );
     }
+
+    // Per-device overrides from [[input.devices]]
+    let descriptor = crate::input::devices::device_descriptor(&*device);
+    let settings = config.input.device_settings(&descriptor);
+    apply_device_settings(device, &settings);
+}
+
+/// Applies the libinput options of an `[[input.devices]]` entry
+fn apply_device_settings(
+    device: &mut smithay::reexports::input::Device,
+    settings: &InputDeviceSettings,
+) {
+    use smithay::reexports::input::{AccelProfile, ScrollMethod};
+
+    if let Some(profile) = settings.accel_profile {
+        let profile = match profile {
+            config::AccelProfile::Flat => AccelProfile::Flat,
+            config::AccelProfile::Adaptive => AccelProfile::Adaptive,
+        };
+        if device.config_accel_set_profile(profile).is_ok() {
+            tracing::debug!(device = device.name(), ?profile, "Set acceleration profile");
+        }
+    }
+
+    if let Some(speed) = settings.accel_speed {
+        if device
+            .config_accel_set_speed(speed.clamp(-1.0, 1.0))
+            .is_ok()
+        {
+            tracing::debug!(device = device.name(), speed, "Set acceleration speed");
+        }
+    }
+
+    if let Some(method) = settings.scroll_method {
+        let method = match method {
+            config::ScrollMethod::NoScroll => ScrollMethod::NoScroll,
+            config::ScrollMethod::TwoFinger => ScrollMethod::TwoFinger,
+            config::ScrollMethod::Edge => ScrollMethod::Edge,
+            config::ScrollMethod::OnButtonDown => ScrollMethod::OnButtonDown,
+        };
+        if device.config_scroll_set_method(method).is_ok() {
+            tracing::debug!(device = device.name(), ?method, "Set scroll method");
+        }
+    }
+
+    if let Some(button) = settings.scroll_button {
+        if device.config_scroll_set_button(button).is_ok() {
+            tracing::debug!(device = device.name(), button, "Set scroll button");
+        }
+    }
+
+    if let Some(enabled) = settings.natural_scroll {
+        if device
+            .config_scroll_set_natural_scroll_enabled(enabled)
+            .is_ok()
+        {
+            tracing::debug!(device = device.name(), enabled, "Set natural scroll");
+        }
+    }
+
+    if let Some(enabled) = settings.left_handed {
+        if device.config_left_handed_set(enabled).is_ok() {
+            tracing::debug!(device = device.name(), enabled, "Set left-handed mode");
+        }
+    }
+
+    if let Some(matrix) = settings.calibration_matrix {
+        if device.config_calibration_has_matrix()
+            && device.config_calibration_set_matrix(matrix).is_ok()
+        {
+            tracing::debug!(device = device.name(), ?matrix, "Set calibration matrix");
+        }
+    }
 }
 
 /// Main entry point for the udev backend
@@ -223,6 +318,7 @@ pub fn run_udev() {
         #[cfg(feature = "fps_ticker")]
         fps_texture: None,
         debug_flags: DebugFlags::empty(),
+        input_devices: Vec::new(),
     };
     let mut state = Otto::init(display, event_loop.handle(), data, true);
 
@@ -246,9 +342,11 @@ pub fn run_udev() {
     libinput_context.udev_assign_seat(&state.seat_name).unwrap();
 
     // Configure input devices based on config
-    Config::with(|config| {
-        configure_libinput_devices(&mut libinput_context, config);
-    });
+    state.backend_data.input_devices =
+        Config::with(|config| configure_libinput_devices(&mut libinput_context, config));
+    for device in state.backend_data.input_devices.clone() {
+        state.cache_device_keymap(&device);
+    }
 
     let libinput_backend = LibinputInputBackend::new(libinput_context.clone());
 
@@ -440,6 +538,9 @@ pub fn run_udev() {
      */
     match crate::screenshare::ScreenshareManager::start(&event_loop.handle()) {
         Ok(manager) => {
+            state
+                .global_shortcuts
+                .set_signal_sender(manager.global_shortcut_signals.clone());
             state.screenshare_manager = Some(manager);
             tracing::info!("Screenshare D-Bus service started");
         }
@@ -459,6 +560,7 @@ pub fn run_udev() {
             state.running.store(false, Ordering::SeqCst);
         } else {
             display_handle.flush_clients().unwrap();
+            state.dispatch_ipc_events();
             // Log rendering metrics periodically
             state.render_metrics.maybe_log_stats(false);
         }
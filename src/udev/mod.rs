@@ -10,6 +10,7 @@ pub mod render;
 pub mod types;
 
 // Re-export public API
+pub(crate) use init::apply_device_config;
 pub use init::run_udev;
 
 // Re-export public types
@@ -20,6 +21,8 @@ pub use types::{
 
 use crate::skia_renderer::SkiaTextureImage;
 use crate::{
+    config::Config,
+    input::actions::KeyAction,
     skia_renderer::SkiaRenderer,
     state::{Backend, Otto},
 };
@@ -82,6 +85,32 @@ delegate_dmabuf!(Otto<UdevData>);
 impl Backend for UdevData {
     const HAS_RELATIVE_MOTION: bool = true;
     const HAS_GESTURES: bool = true;
+    type InputDevice = smithay::reexports::input::Device;
+
+    fn process_key_action(state: &mut Otto<Self>, action: KeyAction) {
+        state.process_key_action(action);
+    }
+
+    fn input_device_added(state: &mut Otto<Self>, device: &mut Self::InputDevice) {
+        // Hotplugged devices miss the configuration applied at startup
+        Config::with(|config| apply_device_config(device, config));
+        state.backend_data.input_devices.push(device.clone());
+    }
+
+    fn input_device_removed(state: &mut Otto<Self>, device: &Self::InputDevice) {
+        state
+            .backend_data
+            .input_devices
+            .retain(|known| known != device);
+    }
+
+    fn reconfigure_input_devices(state: &mut Otto<Self>) {
+        state.reconfigure_input_devices();
+    }
+
+    fn reapply_outputs(state: &mut Otto<Self>) {
+        state.reapply_display_profiles();
+    }
 
     fn seat_name(&self) -> String {
         self.session.seat()
@@ -308,8 +308,10 @@ impl Otto<UdevData> {
         // - Current workspace must be in fullscreen mode and not animating
         // - Disable during expose gesture
         // - Disable during workspace swipe gesture
-        let allow_direct_scanout =
-            self.workspaces.is_fullscreen_and_stable() && !self.swipe_gesture.is_active();
+        // - Disable during pinch gesture
+        let allow_direct_scanout = self.workspaces.is_fullscreen_and_stable()
+            && !self.swipe_gesture.is_active()
+            && !self.pinch_gesture.is_active();
 
         // Only fetch the fullscreen window if direct scanout is allowed
         let fullscreen_window = if allow_direct_scanout {
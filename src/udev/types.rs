@@ -86,6 +86,8 @@ pub struct UdevData {
     #[cfg(feature = "fps_ticker")]
     pub(super) fps_texture: Option<smithay::backend::renderer::multigpu::MultiTexture>,
     pub(super) debug_flags: DebugFlags,
+    /// Libinput devices, configured again when the config changes
+    pub(crate) input_devices: Vec<smithay::reexports::input::Device>,
 }
 
 impl UdevData {
@@ -137,6 +139,8 @@ pub struct SurfaceData {
     pub(super) was_direct_scanout: bool,
     /// Rendering metrics
     pub(super) render_metrics: Option<Arc<crate::render_metrics::RenderMetrics>>,
+    /// `[displays]` profile the output was set up with
+    pub(super) display_profile: Option<crate::config::DisplayProfile>,
 }
 
 impl Drop for SurfaceData {
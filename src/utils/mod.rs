@@ -69,8 +69,7 @@ pub fn image_from_path(path: &str, size: impl Into<skia::ISize>) -> Option<layer
         load_svg_image(path, size).ok()?
     } else {
         let image_data = std::fs::read(image_path).ok()?;
-        layers::skia::Image::from_encoded(layers::skia::Data::new_copy(image_data.as_slice()))
-            .unwrap()
+        layers::skia::Image::from_encoded(layers::skia::Data::new_copy(image_data.as_slice()))?
     };
 
     Some(image)
@@ -178,18 +177,13 @@ pub trait Observer<T>: Sync + Send {
     fn notify(&self, event: &T);
 }
 
+/// Draws one line of text, `text_style` gives its font and color
 pub fn draw_text_content(
     text: impl Into<String>,
     text_style: skia::textlayout::TextStyle,
     text_align: skia::textlayout::TextAlign,
 ) -> Option<ContentDrawFunction> {
     let text = text.into();
-    let foreground_paint =
-        layers::skia::Paint::new(layers::skia::Color4f::new(0.0, 0.0, 0.0, 0.5), None);
-    let mut text_style = text_style.clone();
-    text_style.set_foreground_paint(&foreground_paint);
-    let ff = Config::with(|c| c.font_family.clone());
-    text_style.set_font_families(&[ff]);
 
     let mut paragraph_style = layers::skia::textlayout::ParagraphStyle::new();
     paragraph_style.set_text_direction(layers::skia::textlayout::TextDirection::LTR);
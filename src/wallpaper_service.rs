@@ -0,0 +1,93 @@
+//! D-Bus service implementation for `org.otto.Wallpaper`.
+//!
+//! The portal backend forwards `SetWallpaperURI` requests here with the
+//! image as a local path. The compositor cross-fades to the image, asks the
+//! user to keep it when a preview was requested, and remembers the choice
+//! across restarts.
+
+use std::path::PathBuf;
+
+use smithay::reexports::calloop::channel::Sender;
+use thiserror::Error;
+use tokio::sync::oneshot;
+use tracing::info;
+use zbus::{fdo, interface, Connection};
+
+const WALLPAPER_PATH: &str = "/org/otto/Wallpaper";
+
+#[derive(Debug, Error)]
+pub enum WallpaperError {
+    #[error("{} is not a file", .0.display())]
+    NotAFile(PathBuf),
+}
+
+/// Commands sent from the D-Bus service to the compositor main loop.
+#[derive(Debug)]
+pub enum WallpaperCommand {
+    /// Show `image` on every workspace, asking the user to keep it first if
+    /// `preview` is set.
+    SetWallpaper {
+        app_id: String,
+        image: PathBuf,
+        preview: bool,
+        /// Whether the wallpaper was kept
+        response_tx: oneshot::Sender<Result<bool, WallpaperError>>,
+    },
+}
+
+/// The Wallpaper D-Bus interface.
+///
+/// Implements `org.otto.Wallpaper` at `/org/otto/Wallpaper`.
+pub struct WallpaperInterface {
+    /// Channel to send commands to the compositor's main loop.
+    compositor_tx: Sender<WallpaperCommand>,
+}
+
+#[interface(name = "org.otto.Wallpaper")]
+impl WallpaperInterface {
+    /// Sets the image at `path` as the wallpaper.
+    ///
+    /// With `show_preview` the image is shown until the user keeps or
+    /// reverts it. Returns whether the wallpaper was kept.
+    async fn set_wallpaper(
+        &self,
+        app_id: String,
+        path: String,
+        show_preview: bool,
+    ) -> fdo::Result<bool> {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.compositor_tx
+            .send(WallpaperCommand::SetWallpaper {
+                app_id,
+                image: PathBuf::from(path),
+                preview: show_preview,
+                response_tx,
+            })
+            .map_err(|e| fdo::Error::Failed(format!("Failed to send command: {e}")))?;
+
+        match response_rx.await {
+            Ok(Ok(kept)) => Ok(kept),
+            Ok(Err(err)) => Err(fdo::Error::InvalidArgs(err.to_string())),
+            Err(_) => Err(fdo::Error::Failed(
+                "Compositor dropped the request".to_string(),
+            )),
+        }
+    }
+}
+
+/// Registers the Wallpaper interface on the existing D-Bus connection.
+pub async fn register_wallpaper_interface(
+    connection: &Connection,
+    compositor_tx: Sender<WallpaperCommand>,
+) -> zbus::Result<()> {
+    connection
+        .object_server()
+        .at(WALLPAPER_PATH, WallpaperInterface { compositor_tx })
+        .await?;
+
+    connection.request_name("org.otto.Wallpaper").await?;
+
+    info!("Wallpaper D-Bus interface registered at org.otto.Wallpaper");
+
+    Ok(())
+}
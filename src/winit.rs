@@ -52,6 +52,7 @@ use tracing::{error, info, warn};
 
 use crate::{
     config::{Config, DisplayDescriptor, DisplayKind, DisplayResolution, WINIT_DISPLAY_ID},
+    input::actions::KeyAction,
     render::*,
     render_elements::workspace_render_elements::WorkspaceRenderElements,
     shell::WindowElement,
@@ -183,12 +184,17 @@ impl DmabufHandler for Otto<WinitData> {
 delegate_dmabuf!(Otto<WinitData>);
 
 impl Backend for WinitData {
+    type InputDevice = winit::WinitVirtualDevice;
+
     fn seat_name(&self) -> String {
         String::from("winit")
     }
     fn backend_name(&self) -> &'static str {
         "winit"
     }
+    fn process_key_action(state: &mut Otto<Self>, action: KeyAction) {
+        state.process_key_action_on_first_output(action);
+    }
     fn reset_buffers(&mut self, _output: &Output) {
         self.full_redraw = 4;
     }
@@ -369,6 +375,9 @@ pub fn run_winit() {
     // Start the screenshare D-Bus service
     match crate::screenshare::ScreenshareManager::start(&event_loop.handle()) {
         Ok(manager) => {
+            state
+                .global_shortcuts
+                .set_signal_sender(manager.global_shortcut_signals.clone());
             state.screenshare_manager = Some(manager);
             info!("Screenshare D-Bus service started");
         }
@@ -694,6 +703,7 @@ pub fn run_winit() {
             state.workspaces.refresh_space();
             state.popups.cleanup();
             display_handle.flush_clients().unwrap();
+            state.dispatch_ipc_events();
         }
     }
 }
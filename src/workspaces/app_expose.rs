@@ -0,0 +1,607 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use layers::{
+    engine::{animation::Transition, Engine},
+    prelude::*,
+    skia,
+    types::Size,
+};
+use smithay::{
+    backend::input::ButtonState,
+    input::pointer::{CursorIcon, CursorImageStatus},
+    reexports::wayland_server::backend::ObjectId,
+    utils::IsAlive,
+};
+
+use crate::{
+    config::Config,
+    interactive_view::ViewInteractions,
+    theme::accent_color,
+    utils::natural_layout::{natural_layout, LayoutRect},
+};
+
+use super::{
+    recent_documents::RecentDocument, window_selector::get_paragraph_for_text, Application,
+};
+
+// Logical (unscaled) values - will be multiplied by screen scale when used
+const PADDING: f32 = 40.0;
+const HEADER_HEIGHT: f32 = 60.0;
+const MINIMIZED_ROW_HEIGHT: f32 = 150.0;
+const DOCUMENT_WIDTH: f32 = 220.0;
+const DOCUMENT_HEIGHT: f32 = 56.0;
+const GAP: f32 = 20.0;
+
+/// Documents listed at most in the strip at the bottom
+pub(super) const MAX_DOCUMENTS: usize = 8;
+
+/// A window of the app, as given to [`AppExposeView::show`]
+#[derive(Clone)]
+pub struct AppExposeEntry {
+    pub window_id: ObjectId,
+    pub title: String,
+    /// Geometry of the window on its workspace, in physical pixels
+    pub geometry: LayoutRect,
+    pub minimized: bool,
+    /// Whether the window is on the current workspace, its preview then
+    /// starts from the window itself
+    pub on_screen: bool,
+    /// Mirror of the window and the layer it is borrowed from
+    pub mirror: Layer,
+    pub parent: Layer,
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct AppExposeWindow {
+    pub window_id: ObjectId,
+    pub title: String,
+    /// Where the preview of the window is drawn
+    pub rect: LayoutRect,
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct AppExposeDocument {
+    pub document: RecentDocument,
+    pub rect: LayoutRect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppExposeTarget {
+    Window(usize),
+    Document(usize),
+}
+
+#[derive(Debug, Clone, Default, Hash)]
+pub struct AppExposeState {
+    pub app_name: String,
+    pub windows: Vec<AppExposeWindow>,
+    pub documents: Vec<AppExposeDocument>,
+    pub hover: Option<AppExposeTarget>,
+}
+
+impl AppExposeState {
+    fn target_at(&self, x: f32, y: f32) -> Option<AppExposeTarget> {
+        let contains = |rect: &LayoutRect| {
+            rect.x <= x && x < rect.x + rect.width && rect.y <= y && y < rect.y + rect.height
+        };
+        self.windows
+            .iter()
+            .position(|window| contains(&window.rect))
+            .map(AppExposeTarget::Window)
+            .or_else(|| {
+                self.documents
+                    .iter()
+                    .position(|document| contains(&document.rect))
+                    .map(AppExposeTarget::Document)
+            })
+    }
+}
+
+struct BorrowedMirror {
+    layer: Layer,
+    parent: Layer,
+    hidden: bool,
+}
+
+/// The windows of one app across all workspaces, its minimized windows in
+/// a row below them and its recent documents at the bottom
+///
+/// The previews are the mirror layers of exposé, borrowed while the view
+/// is shown and given back when it is hidden.
+///
+/// # AppExposeView Layer Structure
+///
+/// ```diagram
+/// AppExposeView
+/// └── app_expose `app_expose`
+///     ├── app_expose_backdrop `app_expose_backdrop`
+///     ├── app_expose_windows `app_expose_windows`
+///     │   ├── mirror_window_1
+///     │   ...
+///     └── app_expose_view `app_expose_view`
+/// ```
+#[derive(Clone)]
+pub struct AppExposeView {
+    pub wrap_layer: Layer,
+    windows_layer: Layer,
+    pub view: View<AppExposeState>,
+    app: Arc<RwLock<Option<Application>>>,
+    borrowed: Arc<RwLock<HashMap<ObjectId, BorrowedMirror>>>,
+    active: Arc<AtomicBool>,
+}
+
+impl IsAlive for AppExposeView {
+    fn alive(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+}
+
+impl AppExposeView {
+    pub fn new(layers_engine: Arc<Engine>) -> Self {
+        let fill = taffy::Style {
+            position: taffy::Position::Absolute,
+            ..Default::default()
+        };
+        let wrap = layers_engine.new_layer();
+        wrap.set_key("app_expose");
+        wrap.set_layout_style(fill.clone());
+        wrap.set_size(Size::percent(1.0, 1.0), None);
+        wrap.set_opacity(0.0, None);
+        wrap.set_hidden(true);
+        wrap.set_pointer_events(false);
+        layers_engine.add_layer(&wrap);
+
+        let backdrop = layers_engine.new_layer();
+        backdrop.set_key("app_expose_backdrop");
+        backdrop.set_layout_style(fill.clone());
+        backdrop.set_size(Size::percent(1.0, 1.0), None);
+        backdrop.set_background_color(Color::new_rgba(0.0, 0.0, 0.0, 0.5), None);
+        backdrop.set_pointer_events(false);
+        wrap.add_sublayer(&backdrop);
+
+        let windows_layer = layers_engine.new_layer();
+        windows_layer.set_key("app_expose_windows");
+        windows_layer.set_layout_style(fill.clone());
+        windows_layer.set_size(Size::percent(1.0, 1.0), None);
+        windows_layer.set_pointer_events(false);
+        wrap.add_sublayer(&windows_layer);
+
+        let layer = layers_engine.new_layer();
+        layer.set_layout_style(fill);
+        layer.set_pointer_events(false);
+        wrap.add_sublayer(&layer);
+        let view = View::new(
+            "app_expose_view",
+            AppExposeState::default(),
+            Box::new(render_app_expose_view),
+        );
+        view.mount_layer(layer);
+
+        Self {
+            wrap_layer: wrap,
+            windows_layer,
+            view,
+            app: Arc::new(RwLock::new(None)),
+            borrowed: Arc::new(RwLock::new(HashMap::new())),
+            active: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Lays out `windows` and `documents` of the app on a screen of
+    /// `screen_size` and fades the view in
+    pub fn show(
+        &self,
+        app_name: String,
+        app: Option<Application>,
+        windows: Vec<AppExposeEntry>,
+        documents: Vec<RecentDocument>,
+        screen_size: (f32, f32),
+    ) {
+        self.restore_mirrors();
+        let draw_scale = Config::with(|config| config.screen_scale) as f32;
+        let (open, minimized): (Vec<_>, Vec<_>) =
+            windows.into_iter().partition(|window| !window.minimized);
+        let (windows_area, minimized_area, documents_area) = layout_areas(
+            screen_size,
+            draw_scale,
+            !minimized.is_empty(),
+            !documents.is_empty(),
+        );
+
+        let mut slots = HashMap::new();
+        natural_layout(
+            &mut slots,
+            open.iter()
+                .map(|window| (window.window_id.clone(), window.geometry)),
+            &windows_area,
+            false,
+        );
+        let mut placed: Vec<(AppExposeEntry, LayoutRect)> = open
+            .into_iter()
+            .filter_map(|window| {
+                let slot = slots.get(&window.window_id).copied()?;
+                Some((window, slot))
+            })
+            .collect();
+        if let Some(area) = minimized_area {
+            let sizes: Vec<_> = minimized
+                .iter()
+                .map(|window| (window.geometry.width, window.geometry.height))
+                .collect();
+            let rects = row_layout(&sizes, &area, GAP * draw_scale);
+            placed.extend(minimized.into_iter().zip(rects));
+        }
+
+        let transition = Transition::spring(0.3, 0.1);
+        let mut borrowed = self.borrowed.write().unwrap();
+        let mut state_windows = Vec::new();
+        for (window, slot) in placed {
+            let mirror = window.mirror.clone();
+            borrowed.insert(
+                window.window_id.clone(),
+                BorrowedMirror {
+                    layer: mirror.clone(),
+                    parent: window.parent.clone(),
+                    hidden: mirror.hidden(),
+                },
+            );
+            self.windows_layer.add_sublayer(&mirror);
+            mirror.set_hidden(false);
+            mirror.set_opacity(1.0, None);
+            mirror.set_size(
+                Size::points(window.geometry.width, window.geometry.height),
+                None,
+            );
+
+            let scale = (slot.width / window.geometry.width)
+                .min(slot.height / window.geometry.height)
+                .min(1.0);
+            let rect = LayoutRect::new(
+                slot.x + (slot.width - window.geometry.width * scale) / 2.0,
+                slot.y + (slot.height - window.geometry.height * scale) / 2.0,
+                window.geometry.width * scale,
+                window.geometry.height * scale,
+            );
+            let position = layers::types::Point {
+                x: rect.x,
+                y: rect.y,
+            };
+            let scale = layers::types::Point { x: scale, y: scale };
+            if window.on_screen && !window.minimized {
+                // Grow out of the window itself
+                mirror.set_position(
+                    layers::types::Point {
+                        x: window.geometry.x,
+                        y: window.geometry.y,
+                    },
+                    None,
+                );
+                mirror.set_scale(layers::types::Point { x: 1.0, y: 1.0 }, None);
+                mirror.set_position(position, Some(transition));
+                mirror.set_scale(scale, Some(transition));
+            } else {
+                mirror.set_position(position, None);
+                mirror.set_scale(scale, None);
+            }
+            state_windows.push(AppExposeWindow {
+                window_id: window.window_id,
+                title: window.title,
+                rect,
+            });
+        }
+        drop(borrowed);
+
+        let documents = documents_area
+            .map(|area| {
+                let sizes = vec![(DOCUMENT_WIDTH, DOCUMENT_HEIGHT); documents.len()];
+                let rects = row_layout(&sizes, &area, GAP * draw_scale);
+                documents
+                    .into_iter()
+                    .zip(rects)
+                    .map(|(document, rect)| AppExposeDocument { document, rect })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        *self.app.write().unwrap() = app;
+        self.view.update_state(&AppExposeState {
+            app_name,
+            windows: state_windows,
+            documents,
+            hover: None,
+        });
+
+        self.active.store(true, Ordering::Relaxed);
+        self.wrap_layer.set_hidden(false);
+        self.wrap_layer
+            .set_opacity(1.0, Some(Transition::ease_out_quad(0.2)));
+    }
+
+    /// Fades the view out, the previews go back to exposé afterwards
+    pub fn hide(&self) {
+        if !self.alive() {
+            return;
+        }
+        self.active.store(false, Ordering::Relaxed);
+        let active = self.active.clone();
+        let borrowed = self.borrowed.clone();
+        self.wrap_layer
+            .set_opacity(0.0, Some(Transition::ease_in_quad(0.15)))
+            .on_finish(
+                move |layer: &Layer, _p: f32| {
+                    // Shown again in the meantime
+                    if active.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    restore_mirrors(&borrowed);
+                    layer.set_hidden(true);
+                },
+                true,
+            );
+    }
+
+    /// Gives the previews back to exposé right away
+    pub fn restore_mirrors(&self) {
+        restore_mirrors(&self.borrowed);
+    }
+
+    /// Drops the preview of a window that is going away, its mirror layer is
+    /// removed with the window
+    pub fn forget_window(&self, window_id: &ObjectId) {
+        self.borrowed.write().unwrap().remove(window_id);
+    }
+
+    /// The app whose documents are listed
+    pub fn app(&self) -> Option<Application> {
+        self.app.read().unwrap().clone()
+    }
+}
+
+fn restore_mirrors(borrowed: &RwLock<HashMap<ObjectId, BorrowedMirror>>) {
+    for (_, mirror) in borrowed.write().unwrap().drain() {
+        mirror.parent.add_sublayer(&mirror.layer);
+        mirror.layer.set_hidden(mirror.hidden);
+    }
+}
+
+/// Areas of the open windows, of the row of minimized windows and of the
+/// strip of documents on a screen of `screen_size`
+fn layout_areas(
+    screen_size: (f32, f32),
+    draw_scale: f32,
+    minimized: bool,
+    documents: bool,
+) -> (LayoutRect, Option<LayoutRect>, Option<LayoutRect>) {
+    let padding = PADDING * draw_scale;
+    let (width, mut bottom) = (screen_size.0 - padding * 2.0, screen_size.1 - padding);
+    let documents = documents.then(|| {
+        let height = DOCUMENT_HEIGHT * draw_scale;
+        bottom -= height;
+        let area = LayoutRect::new(padding, bottom, width, height);
+        bottom -= padding;
+        area
+    });
+    let minimized = minimized.then(|| {
+        let height = MINIMIZED_ROW_HEIGHT * draw_scale;
+        bottom -= height;
+        let area = LayoutRect::new(padding, bottom, width, height);
+        bottom -= padding;
+        area
+    });
+    let top = padding + HEADER_HEIGHT * draw_scale;
+    let windows = LayoutRect::new(padding, top, width, (bottom - top).max(0.0));
+    (windows, minimized, documents)
+}
+
+/// Places items of `sizes` side by side in `area`, as tall as the area and
+/// centred, shrunk together when they don't fit its width
+fn row_layout(sizes: &[(f32, f32)], area: &LayoutRect, gap: f32) -> Vec<LayoutRect> {
+    let widths: Vec<f32> = sizes
+        .iter()
+        .map(|(width, height)| {
+            if *height > 0.0 {
+                width * area.height / height
+            } else {
+                area.height
+            }
+        })
+        .collect();
+    let total = widths.iter().sum::<f32>() + gap * widths.len().saturating_sub(1) as f32;
+    let scale = if total > area.width {
+        area.width / total
+    } else {
+        1.0
+    };
+    let height = area.height * scale;
+    let mut x = area.x + (area.width - total * scale) / 2.0;
+    let y = area.y + (area.height - height) / 2.0;
+    widths
+        .iter()
+        .map(|width| {
+            let rect = LayoutRect::new(x, y, width * scale, height);
+            x += (width + gap) * scale;
+            rect
+        })
+        .collect()
+}
+
+fn draw_label(canvas: &skia::Canvas, text: &str, rect: skia::Rect, font_size: f32, color: Color) {
+    let radius = rect.height() / 2.0;
+    let mut paint = skia::Paint::new(color.c4f(), None);
+    paint.set_anti_alias(true);
+    canvas.draw_rrect(skia::RRect::new_rect_xy(rect, radius, radius), &paint);
+    let mut paragraph = get_paragraph_for_text(text, font_size);
+    paragraph.layout(rect.width() - radius);
+    let y = rect.y() + (rect.height() - paragraph.height()) / 2.0;
+    paragraph.paint(canvas, (rect.x() + radius / 2.0, y));
+}
+
+pub fn render_app_expose_view(state: &AppExposeState, view: &View<AppExposeState>) -> LayerTree {
+    let draw_scale = Config::with(|config| config.screen_scale) as f32;
+    let font_size = 16.0 * draw_scale;
+    let state = state.clone();
+    let accent = accent_color();
+    let label_color = Color::new_rgba(1.0, 1.0, 1.0, 0.6);
+    let draw = move |canvas: &skia::Canvas, w: f32, h: f32| {
+        if !state.app_name.is_empty() {
+            let width = 300.0 * draw_scale;
+            let height = 40.0 * draw_scale;
+            let rect = skia::Rect::from_xywh(
+                (w - width) / 2.0,
+                PADDING * draw_scale + (HEADER_HEIGHT * draw_scale - height) / 2.0,
+                width,
+                height,
+            );
+            draw_label(canvas, &state.app_name, rect, font_size * 1.2, label_color);
+        }
+
+        if let Some(AppExposeTarget::Window(index)) = state.hover {
+            if let Some(window) = state.windows.get(index) {
+                let rect = window.rect;
+                let mut paint = skia::Paint::new(accent.c4f(), None);
+                paint.set_anti_alias(true);
+                paint.set_stroke(true);
+                paint.set_stroke_width(8.0 * draw_scale);
+                let rrect = skia::RRect::new_rect_xy(
+                    skia::Rect::from_xywh(rect.x, rect.y, rect.width, rect.height)
+                        .with_outset((4.0 * draw_scale, 4.0 * draw_scale)),
+                    10.0 * draw_scale,
+                    10.0 * draw_scale,
+                );
+                canvas.draw_rrect(rrect, &paint);
+
+                let width = rect.width.clamp(160.0 * draw_scale, 400.0 * draw_scale);
+                let height = 32.0 * draw_scale;
+                let title = skia::Rect::from_xywh(
+                    rect.x + (rect.width - width) / 2.0,
+                    rect.y + (rect.height - height) / 2.0,
+                    width,
+                    height,
+                );
+                draw_label(canvas, &window.title, title, font_size, label_color);
+            }
+        }
+
+        for (index, document) in state.documents.iter().enumerate() {
+            let rect = document.rect;
+            let color = if state.hover == Some(AppExposeTarget::Document(index)) {
+                accent
+            } else {
+                label_color
+            };
+            let rect = skia::Rect::from_xywh(rect.x, rect.y, rect.width, rect.height);
+            draw_label(canvas, &document.document.name, rect, font_size, color);
+        }
+        skia::Rect::from_xywh(0.0, 0.0, w, h)
+    };
+
+    LayerTreeBuilder::default()
+        .key(view.get_key())
+        .position(((0.0, 0.0).into(), None))
+        .size(Size::percent(1.0, 1.0))
+        .content(Some(draw))
+        .build()
+        .unwrap()
+}
+
+impl<Backend: crate::state::Backend> ViewInteractions<Backend> for AppExposeView {
+    fn id(&self) -> Option<usize> {
+        Some(self.wrap_layer.id.0.into())
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive()
+    }
+
+    fn on_motion(
+        &self,
+        _seat: &smithay::input::Seat<crate::Otto<Backend>>,
+        otto: &mut crate::Otto<Backend>,
+        event: &smithay::input::pointer::MotionEvent,
+    ) {
+        let screen_scale = Config::with(|config| config.screen_scale);
+        let location = event.location.to_physical(screen_scale);
+        let state = self.view.get_state();
+        let hover = state.target_at(location.x as f32, location.y as f32);
+        let cursor = if hover.is_some() {
+            CursorIcon::Pointer
+        } else {
+            CursorIcon::default()
+        };
+        otto.set_cursor(&CursorImageStatus::Named(cursor));
+        if hover != state.hover {
+            self.view.update_state(&AppExposeState { hover, ..state });
+        }
+    }
+
+    fn on_button(
+        &self,
+        _seat: &smithay::input::Seat<crate::Otto<Backend>>,
+        otto: &mut crate::Otto<Backend>,
+        event: &smithay::input::pointer::ButtonEvent,
+    ) {
+        if event.state != ButtonState::Released {
+            return;
+        }
+        let state = self.view.get_state();
+        match state.hover {
+            Some(AppExposeTarget::Window(index)) => {
+                if let Some(window) = state.windows.get(index) {
+                    if let Some(wid) = otto.workspaces.app_expose_activate(&window.window_id) {
+                        otto.set_keyboard_focus_on_surface(&wid);
+                    }
+                }
+            }
+            Some(AppExposeTarget::Document(index)) => {
+                if let Some(document) = state.documents.get(index) {
+                    let path = document.document.path.to_string_lossy().to_string();
+                    let command = self
+                        .app()
+                        .and_then(|app| app.command(std::slice::from_ref(&path)))
+                        .unwrap_or_else(|| ("xdg-open".to_string(), vec![path]));
+                    otto.launch_program(command.0, command.1);
+                }
+                otto.workspaces.hide_app_expose();
+            }
+            None => otto.workspaces.hide_app_expose(),
+        }
+        otto.set_cursor(&CursorImageStatus::default_named());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_is_centred_when_it_fits() {
+        let area = LayoutRect::new(0.0, 0.0, 1000.0, 100.0);
+        let rects = row_layout(&[(200.0, 100.0), (100.0, 100.0)], &area, 20.0);
+        assert_eq!(
+            rects,
+            [
+                LayoutRect::new(340.0, 0.0, 200.0, 100.0),
+                LayoutRect::new(560.0, 0.0, 100.0, 100.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn row_shrinks_to_fit_the_area() {
+        let area = LayoutRect::new(0.0, 0.0, 205.0, 100.0);
+        let rects = row_layout(&[(400.0, 200.0), (200.0, 100.0)], &area, 10.0);
+        assert_eq!(
+            rects,
+            [
+                LayoutRect::new(0.0, 25.0, 100.0, 50.0),
+                LayoutRect::new(105.0, 25.0, 100.0, 50.0),
+            ]
+        );
+    }
+}
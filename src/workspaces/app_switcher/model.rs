@@ -7,6 +7,8 @@ pub struct AppSwitcherModel {
     pub apps: Vec<Application>,
     pub current_app: usize,
     pub width: i32,
+    /// [`theme::generation`](crate::theme::generation) of the rendering
+    pub theme: u64,
 }
 
 impl Hash for AppSwitcherModel {
@@ -14,6 +16,7 @@ impl Hash for AppSwitcherModel {
         self.apps.hash(state);
         self.current_app.hash(state);
         self.width.hash(state);
+        self.theme.hash(state);
     }
 }
 
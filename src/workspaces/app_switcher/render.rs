@@ -1,7 +1,11 @@
 use layers::{prelude::*, types::Size};
 use taffy::FromLength;
 
-use crate::{config::Config, theme::theme_colors, workspaces::utils::FONT_CACHE};
+use crate::{
+    config::Config,
+    theme::{component_style, theme_colors, Component},
+    workspaces::utils::FONT_CACHE,
+};
 
 use super::render_app::render_app_view;
 
@@ -36,11 +40,15 @@ pub fn render_appswitcher_view(
         (available_width - total_padding - COMPONENT_PADDING_H * 2.0) / state.apps.len() as f32;
     let available_icon_size = ICON_SIZE.min(available_icon_size);
 
-    let FONT_SIZE: f32 = available_icon_size / 8.0;
+    let style = component_style(Component::AppSwitcher);
+    let FONT_SIZE: f32 = style.font_size(available_icon_size / 8.0);
     let component_width =
         apps_len * available_icon_size + total_padding + COMPONENT_PADDING_H * 2.0;
     let component_height = available_icon_size + ICON_PADDING * 2.0 + COMPONENT_PADDING_V * 2.0;
-    let background_color = theme_colors().materials_thin;
+    let background_color = style.background_color(theme_colors().materials_thin);
+    let text_color = style.text_color(theme_colors().text_secondary).c4f();
+    let corner_radius = style.corner_radius(component_height / 8.0);
+    let blend_mode = style.blend_mode();
     let current_app = state.current_app as f32;
     let mut app_name = "".to_string();
     if !state.apps.is_empty() && state.current_app < state.apps.len() {
@@ -74,19 +82,17 @@ pub fn render_appswitcher_view(
             canvas.draw_rrect(rrect, &paint);
 
             // Create font with subpixel rendering
-            let font_family = Config::with(|c| c.font_family.clone());
             let font_style = layers::skia::FontStyle::new(
-                layers::skia::font_style::Weight::MEDIUM,
+                style.font_weight(layers::skia::font_style::Weight::MEDIUM),
                 layers::skia::font_style::Width::CONDENSED,
                 layers::skia::font_style::Slant::Upright,
             );
             let font = FONT_CACHE.with(|font_cache| {
-                font_cache.make_font_with_fallback(font_family, font_style, FONT_SIZE)
+                font_cache.make_font_with_fallback(style.font_family(), font_style, FONT_SIZE)
             });
 
             // Draw text with improved rendering
-            let mut text_paint =
-                layers::skia::Paint::new(theme_colors().text_secondary.c4f(), None);
+            let mut text_paint = layers::skia::Paint::new(text_color, None);
             text_paint.set_anti_alias(true);
 
             let text_bounds = font.measure_str(&app_name, Some(&text_paint)).1;
@@ -106,7 +112,7 @@ pub fn render_appswitcher_view(
             },
             Some(Transition::ease_out_quad(0.35)),
         ))
-        .blend_mode(BlendMode::BackgroundBlur)
+        .blend_mode(blend_mode)
         .background_color((
             PaintColor::Solid {
                 color: background_color,
@@ -114,7 +120,7 @@ pub fn render_appswitcher_view(
             None,
         ))
         .content(Some(draw_container))
-        .border_corner_radius((BorderRadius::new_single(component_height / 8.0), None))
+        .border_corner_radius((BorderRadius::new_single(corner_radius), None))
         .layout_style(taffy::Style {
             position: taffy::Position::Relative,
             display: taffy::Display::Flex,
@@ -19,6 +19,7 @@ use tokio::sync::mpsc;
 
 use crate::{
     interactive_view::ViewInteractions,
+    theme,
     utils::Observer,
     workspaces::{apps_info::ApplicationsInfo, Application, WorkspacesModel},
 };
@@ -173,6 +174,15 @@ impl AppSwitcherView {
         tr
     }
 
+    /// Renders the switcher again with the current theme
+    pub fn reload_theme(&self) {
+        let state = self.view.get_state();
+        self.view.update_state(&AppSwitcherModel {
+            theme: theme::generation(),
+            ..state
+        });
+    }
+
     pub fn reset(&self) {
         let state = self.view.get_state();
         self.view.update_state(&AppSwitcherModel {
@@ -233,6 +243,7 @@ impl AppSwitcherView {
                         current_app,
                         apps,
                         width: workspace.width,
+                        theme: theme::generation(),
                         // ..switcher_state
                     });
                 }
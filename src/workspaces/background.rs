@@ -2,15 +2,42 @@ use std::hash::{Hash, Hasher};
 
 use layers::{prelude::*, skia};
 
-#[derive(Clone, Debug)]
+use super::wallpaper::{draw_wallpaper, WallpaperContent};
+
+/// The wallpaper of one output. It has two slots: the top one fades in over
+/// the bottom one, or fades out to reveal it, so a new wallpaper always
+/// cross-fades.
+#[derive(Clone)]
+pub struct OutputBackground {
+    pub output: String,
+    /// Where the output is in the workspace, in pixels
+    pub frame: skia::Rect,
+    pub slots: [Option<WallpaperContent>; 2],
+    /// Slot of the wallpaper shown
+    pub front: usize,
+}
+
+#[derive(Clone)]
 pub struct BackgroundViewState {
-    pub image: Option<skia::Image>,
+    pub outputs: Vec<OutputBackground>,
+    /// Duration of the cross-fade in seconds
+    pub transition: f32,
     pub debug_string: String,
 }
 impl Hash for BackgroundViewState {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        if let Some(image) = self.image.as_ref() {
-            image.unique_id().hash(state);
+        for output in &self.outputs {
+            output.output.hash(state);
+            for value in [output.frame.x(), output.frame.y()] {
+                value.to_bits().hash(state);
+            }
+            for value in [output.frame.width(), output.frame.height()] {
+                value.to_bits().hash(state);
+            }
+            for slot in &output.slots {
+                slot.as_ref().map(|content| &content.key).hash(state);
+            }
+            output.front.hash(state);
         }
         self.debug_string.hash(state);
     }
@@ -26,7 +53,8 @@ pub struct BackgroundView {
 impl BackgroundView {
     pub fn new(index: usize, layer: Layer) -> Self {
         let state = BackgroundViewState {
-            image: None,
+            outputs: Vec::new(),
+            transition: 0.0,
             debug_string: "Screen composer 0.1".to_string(),
         };
         let view = layers::prelude::View::new(
@@ -48,10 +76,44 @@ impl BackgroundView {
         });
     }
 
-    pub fn set_image(&self, image: skia::Image) {
+    /// Shows one wallpaper per output, cross-fading over `transition`
+    /// seconds where it changed. An output without content keeps the one
+    /// it shows; outputs not listed are dropped.
+    pub fn set_wallpapers(
+        &self,
+        wallpapers: Vec<(String, skia::Rect, Option<WallpaperContent>)>,
+        transition: f32,
+    ) {
+        let state = self.view.get_state();
+        let outputs = wallpapers
+            .into_iter()
+            .map(|(output, frame, content)| {
+                let mut background = state
+                    .outputs
+                    .iter()
+                    .find(|background| background.output == output)
+                    .cloned()
+                    .unwrap_or(OutputBackground {
+                        output,
+                        frame,
+                        slots: [None, None],
+                        front: 0,
+                    });
+                background.frame = frame;
+                let shown = background.slots[background.front].as_ref();
+                if let Some(content) = content {
+                    if shown.is_none_or(|shown| shown.key != content.key) {
+                        background.front = 1 - background.front;
+                        background.slots[background.front] = Some(content);
+                    }
+                }
+                background
+            })
+            .collect();
         self.view.update_state(&BackgroundViewState {
-            image: Some(image),
-            ..self.view.get_state()
+            outputs,
+            transition,
+            ..state
         });
     }
 }
@@ -61,60 +123,59 @@ pub fn view_background(
     state: &BackgroundViewState,
     _view: &View<BackgroundViewState>,
 ) -> LayerTree {
-    let image = state.image.clone();
-
-    // let debug_text = state.debug_string.clone();
-
-    let draw_container = move |canvas: &skia::Canvas, w, h| {
-        let color = skia::Color4f::new(1.0, 1.0, 1.0, 1.0);
-        let mut paint = skia::Paint::new(color, None);
-
-        if let Some(image) = image.as_ref() {
-            let mut matrix = skia::Matrix::new_identity();
-            let image_width = image.width() as f32;
-            let image_height = image.height() as f32;
-            let scale_x: f32 = w / image_width;
-            let scale_y: f32 = h / image_height;
-            let scale = scale_x.max(scale_y); // Choose the smaller scale to maintain aspect ratio
-
-            // Calculate the offsets for centering the image
-            let offset_x = (w - image_width * scale) / 2.0;
-            let offset_y = (h - image_height * scale) / 2.0;
-
-            matrix.set_scale_translate((scale, scale), (offset_x, offset_y)); // canvas.concat(&matrix);
-                                                                              // canvas.draw_image_rect(image, None, rect, &paint);
-            paint.set_shader(image.to_shader(
-                (skia::TileMode::Repeat, skia::TileMode::Repeat),
-                skia::SamplingOptions::default(),
-                &matrix,
-            ));
-        }
-
-        let split = 1;
-        let rect_size_w = w / split as f32;
-        let rect_size_h = h / split as f32;
-
-        for i in 0..split {
-            for j in 0..split {
-                let rect = skia::Rect::from_xywh(
-                    i as f32 * rect_size_w,
-                    j as f32 * rect_size_h,
-                    rect_size_w,
-                    rect_size_h,
-                );
-                canvas.draw_rect(rect, &paint);
+    // The bottom slot stays opaque, the top one fades
+    let fade = (state.transition > 0.0).then(|| Transition {
+        delay: 0.0,
+        timing: TimingFunction::ease_out_quad(state.transition),
+    });
+    let slot = |background: &OutputBackground, index: usize| {
+        let content = background.slots[index].clone();
+        let draw_wallpaper = move |canvas: &skia::Canvas, w, h| {
+            if let Some(content) = content.as_ref() {
+                draw_wallpaper(canvas, w, h, content);
             }
-        }
-
-        // let color = skia::Color4f::new(0.0, 0.0, 0.0, 1.0);
-        // let paint = skia::Paint::new(color, None);
-        // let mut font = skia::Font::default();
-        // let font_size = 26.0;
-        // font.set_size(font_size);
-        // canvas.draw_str("test string string", (80.0, 100.0), &font, &paint);
-        // canvas.draw_rect(skia::Rect::from_xywh(80.0, 100.0, 200.0, 100.0), &paint);
-        skia::Rect::from_xywh(0.0, 0.0, w, h)
+            skia::Rect::from_xywh(0.0, 0.0, w, h)
+        };
+        let (opacity, transition) = match index {
+            0 => (1.0, None),
+            _ if background.front == 1 => (1.0, fade),
+            _ => (0.0, fade),
+        };
+        LayerTreeBuilder::default()
+            .key(format!(
+                "background_{}_wallpaper_{index}",
+                background.output
+            ))
+            .layout_style(taffy::Style {
+                position: taffy::Position::Absolute,
+                ..Default::default()
+            })
+            .size(layers::types::Size::percent(1.0, 1.0))
+            .opacity((opacity, transition))
+            .content(Some(draw_wallpaper))
+            .pointer_events(false)
+            .build()
+            .unwrap()
     };
+    let outputs = state
+        .outputs
+        .iter()
+        .map(|background| {
+            let frame = background.frame;
+            LayerTreeBuilder::default()
+                .key(format!("background_{}", background.output))
+                .layout_style(taffy::Style {
+                    position: taffy::Position::Absolute,
+                    ..Default::default()
+                })
+                .position(((frame.x(), frame.y()).into(), None))
+                .size(layers::types::Size::points(frame.width(), frame.height()))
+                .pointer_events(false)
+                .children(vec![slot(background, 0), slot(background, 1)])
+                .build()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
 
     LayerTreeBuilder::default()
         .key("background_view")
@@ -126,10 +187,11 @@ pub fn view_background(
             }),
         ))
         .border_corner_radius(BorderRadius::new_single(24.0))
-        .content(Some(draw_container))
         // .image_cache(true)
         .background_color(layers::prelude::Color::new_rgba(0.0, 0.0, 0.0, 1.0))
+        .clip_children(true)
         .pointer_events(false)
+        .children(outputs)
         .build()
         .unwrap()
 }
@@ -6,6 +6,8 @@ use tracing::warn;
 
 use super::DockView;
 
+const BTN_RIGHT: u32 = 0x111;
+
 // Dock view interactions
 impl<Backend: crate::state::Backend> ViewInteractions<Backend> for DockView {
     fn id(&self) -> Option<usize> {
@@ -49,8 +51,12 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for DockView {
             ButtonState::Released => {
                 if let Some(layer_id) = state.layers_engine.current_hover() {
                     if let Some((identifier, match_id)) = self.get_app_from_layer(&layer_id) {
-                        // if we click on an app icon, focus the app
-                        if let Some(wid) = state.workspaces.focus_app(&identifier) {
+                        // a right click shows App Exposé for the app,
+                        // a click on an app icon focuses the app
+                        if event.button == BTN_RIGHT {
+                            state.dismiss_all_popups();
+                            state.workspaces.show_app_expose(&identifier);
+                        } else if let Some(wid) = state.workspaces.focus_app(&identifier) {
                             state.set_keyboard_focus_on_surface(&wid);
                         } else if let Some(bookmark) = self.bookmark_config_for(&match_id) {
                             if let Some(app) = self.bookmark_application(&match_id) {
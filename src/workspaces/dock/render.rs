@@ -4,7 +4,7 @@ use taffy::LengthPercentageAuto;
 
 use crate::{
     config::Config,
-    theme::theme_colors,
+    theme::{component_style, theme_colors, Component},
     workspaces::{
         utils::{draw_balloon_rect, FONT_CACHE},
         Application,
@@ -114,15 +114,15 @@ pub fn setup_miniwindow_icon(layer: &Layer, inner_layer: &Layer, _icon_width: f3
 }
 
 pub fn setup_label(new_layer: &Layer, label_text: String) {
-    let _draw_scale = Config::with(|config| config.screen_scale as f32);
-    let text_size = 26.0;
-    let font_family = Config::with(|config| config.font_family.clone());
+    let style = component_style(Component::Dock);
+    let text_size = style.font_size(26.0);
+    let font_style = layers::skia::FontStyle::new(
+        style.font_weight(layers::skia::font_style::Weight::NORMAL),
+        layers::skia::font_style::Width::NORMAL,
+        layers::skia::font_style::Slant::Upright,
+    );
     let font = FONT_CACHE.with(|font_cache| {
-        font_cache.make_font_with_fallback(
-            font_family,
-            layers::skia::FontStyle::default(),
-            text_size,
-        )
+        font_cache.make_font_with_fallback(style.font_family(), font_style, text_size)
     });
 
     let text = label_text.clone();
@@ -138,7 +138,7 @@ pub fn setup_label(new_layer: &Layer, label_text: String) {
     // Fixed height based on font size, not measured text bounds
     let label_size_height = text_size + arrow_height + text_padding_v * 2.0 + safe_margin * 2.0;
 
-    let rect_corner_radius = 10.0;
+    let rect_corner_radius = style.corner_radius(10.0);
     let arrow_width = 25.0;
     let arrow_corner_radius = 3.0;
     // Calculate tooltip dimensions
@@ -157,32 +157,16 @@ pub fn setup_label(new_layer: &Layer, label_text: String) {
         arrow_corner_radius,
     );
 
+    let text_color = style.text_color(theme_colors().text_primary).c4f();
     let draw_label = move |canvas: &layers::skia::Canvas, w: f32, h: f32| -> layers::skia::Rect {
         // Tooltip parameters
 
         let text = text.clone();
         let tooltip_height = h - safe_margin * 2.0;
 
-        // Paint for the tooltip background
-        let mut paint = layers::skia::Paint::default();
-
-        // choose colors according to theme scheme so tooltip looks correct in dark mode
-        let (bg_col, text_col) = Config::with(|c| match c.theme_scheme {
-            crate::theme::ThemeScheme::Light => (
-                layers::skia::Color4f::new(157.0 / 255.0, 157.0 / 255.0, 157.0 / 255.0, 1.0),
-                theme_colors().text_primary.c4f(),
-            ),
-            crate::theme::ThemeScheme::Dark => (
-                layers::skia::Color4f::new(157.0 / 255.0, 157.0 / 255.0, 157.0 / 255.0, 1.0),
-                theme_colors().text_primary.c4f(),
-            ),
-        });
-        paint.set_color4f(bg_col, None);
-        paint.set_anti_alias(true);
-
         // // Paint for the text
         let mut text_paint = layers::skia::Paint::default();
-        text_paint.set_color4f(text_col, None);
+        text_paint.set_color4f(text_color, None);
         text_paint.set_anti_alias(true);
 
         // // Draw the text inside the tooltip
@@ -195,7 +179,7 @@ pub fn setup_label(new_layer: &Layer, label_text: String) {
     let label_tree = LayerTreeBuilder::default()
         .key(format!("{}_label", new_layer.key()))
         .shape(layers::prelude::Shape::from_path(&arrow_path))
-        .blend_mode(layers::prelude::BlendMode::BackgroundBlur)
+        .blend_mode(style.blend_mode())
         .layout_style(taffy::Style {
             position: taffy::Position::Absolute,
             max_size: taffy::geometry::Size {
@@ -214,12 +198,12 @@ pub fn setup_label(new_layer: &Layer, label_text: String) {
             width: taffy::Dimension::Length(label_size_width),
             height: taffy::Dimension::Length(label_size_height),
         })
-        .background_color(theme_colors().materials_ultrathick)
+        .background_color(style.background_color(theme_colors().materials_ultrathick))
         .position(Point {
             x: -label_size_width / 2.0,
             y: -label_size_height - 10.0 + safe_margin,
         })
-        .shadow_color(theme_colors().shadow_color)
+        .shadow_color(style.shadow_color(theme_colors().shadow_color))
         .shadow_offset(((0.0, 0.0).into(), None))
         .shadow_radius((20.0, None))
         .opacity((0.0, None))
@@ -9,7 +9,7 @@ use layers::{
     prelude::{taffy, Layer, Point},
     skia,
     taffy::{prelude::FromLength, style::Style},
-    types::{BlendMode, Size},
+    types::Size,
     view::{BuildLayerTree, LayerTreeBuilder},
 };
 use smithay::{reexports::wayland_server::backend::ObjectId, utils::IsAlive};
@@ -18,7 +18,7 @@ use tokio::sync::mpsc;
 use crate::{
     config::{Config, DockBookmark},
     shell::WindowElement,
-    theme::theme_colors,
+    theme::{component_style, theme_colors, Component},
     utils::Observer,
     workspaces::{apps_info::ApplicationsInfo, Application, WorkspacesModel},
 };
@@ -105,6 +105,10 @@ impl DockView {
         icon_size + padding_top + padding_bottom
     }
 
+    fn bar_corner_radius(bar_height: f32) -> f32 {
+        component_style(Component::Dock).corner_radius(bar_height / 3.5)
+    }
+
     pub fn new(layers_engine: Arc<Engine>) -> Self {
         let draw_scale = Config::with(|config| config.screen_scale) as f32 * 0.8;
         let dock_size_multiplier = Config::with(|config| config.dock.size.clamp(0.5, 2.0)) as f32;
@@ -143,6 +147,7 @@ impl DockView {
         view_layer.add_sublayer(&bar_layer);
         let initial_bar_height =
             Self::calculate_bar_height(scaled_icon_size, dock_size_multiplier * draw_scale);
+        let style = component_style(Component::Dock);
         let bar_tree = LayerTreeBuilder::default()
             .key("dock-bar")
             .pointer_events(false)
@@ -150,11 +155,11 @@ impl DockView {
                 width: taffy::percent(1.0),
                 height: taffy::Dimension::Length(initial_bar_height),
             })
-            .blend_mode(BlendMode::BackgroundBlur)
-            .background_color(theme_colors().materials_medium)
+            .blend_mode(style.blend_mode())
+            .background_color(style.background_color(theme_colors().materials_medium))
             .border_width((1.0 * draw_scale, None))
-            .border_color(theme_colors().materials_highlight)
-            .shadow_color(theme_colors().shadow_color)
+            .border_color(style.border_color(theme_colors().materials_highlight))
+            .shadow_color(style.shadow_color(theme_colors().shadow_color))
             .shadow_offset(((0.0, 0.0).into(), None))
             .shadow_radius((20.0, None))
             .layout_style(taffy::Style {
@@ -315,6 +320,42 @@ impl DockView {
             dock.update_state(&state);
         });
     }
+    /// Applies changed dock settings: size and bookmarks
+    pub fn reload_config(&self) {
+        self.load_configured_bookmarks();
+        self.render_dock();
+    }
+    /// Applies the current theme to the bar and the labels
+    pub fn reload_theme(&self) {
+        let style = component_style(Component::Dock);
+        self.bar_layer.set_blend_mode(style.blend_mode());
+        self.bar_layer.set_background_color(
+            style.background_color(theme_colors().materials_medium),
+            None,
+        );
+        self.bar_layer
+            .set_border_color(style.border_color(theme_colors().materials_highlight), None);
+        self.bar_layer
+            .set_shadow_color(style.shadow_color(theme_colors().shadow_color), None);
+
+        let state = self.get_state();
+        {
+            let app_layers = self.app_layers.read().unwrap();
+            for (app, _) in self.display_entries(&state) {
+                if let Some(entry) = app_layers.get(&app.match_id) {
+                    let name = app.desktop_name().unwrap_or(app.identifier.clone());
+                    setup_label(&entry.label_layer, name);
+                }
+            }
+            let miniwindow_layers = self.miniwindow_layers.read().unwrap();
+            for (window, title) in state.minimized_windows.iter() {
+                if let Some((_, _, label_layer, _)) = miniwindow_layers.get(window) {
+                    setup_label(label_layer, title.clone());
+                }
+            }
+        }
+        self.render_dock();
+    }
     pub fn update_state(&self, state: &DockModel) {
         {
             *self.state.write().unwrap() = state.clone();
@@ -393,7 +434,7 @@ impl DockView {
         });
 
         self.bar_layer
-            .set_border_corner_radius(bar_height / 3.5, None);
+            .set_border_corner_radius(Self::bar_corner_radius(bar_height), None);
 
         self.resize_handle.set_size(
             Size {
@@ -820,7 +861,7 @@ impl DockView {
             height: taffy::Dimension::Length(bar_height),
         });
         self.bar_layer
-            .set_border_corner_radius(bar_height / 3.5, None);
+            .set_border_corner_radius(Self::bar_corner_radius(bar_height), None);
 
         self.resize_handle.set_size(
             Size {
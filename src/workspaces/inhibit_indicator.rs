@@ -0,0 +1,161 @@
+use std::sync::{atomic::AtomicBool, Arc};
+
+use layers::{
+    engine::{animation::Transition, Engine},
+    prelude::*,
+    types::Size,
+};
+use smithay::utils::IsAlive;
+
+use crate::{config::Config, theme::theme_colors, workspaces::utils::FONT_CACHE};
+
+#[derive(Debug, Clone, Default, PartialEq, Hash)]
+pub struct InhibitIndicatorModel {
+    pub text: String,
+}
+
+/// Badge at the top of the screen while an app inhibits compositor shortcuts
+///
+/// # InhibitIndicatorView Layer Structure
+///
+/// ```diagram
+/// InhibitIndicatorView
+/// └── inhibit_indicator_container `inhibit_indicator_container`
+///     └── inhibit_indicator `inhibit_indicator`
+/// ```
+#[derive(Debug, Clone)]
+pub struct InhibitIndicatorView {
+    pub wrap_layer: Layer,
+    pub view: View<InhibitIndicatorModel>,
+    active: Arc<AtomicBool>,
+}
+
+impl IsAlive for InhibitIndicatorView {
+    fn alive(&self) -> bool {
+        self.active.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+impl InhibitIndicatorView {
+    pub fn new(layers_engine: Arc<Engine>) -> Self {
+        let draw_scale = Config::with(|config| config.screen_scale) as f32;
+        let wrap = layers_engine.new_layer();
+        wrap.set_key("inhibit_indicator_container");
+        wrap.set_size(Size::percent(1.0, 1.0), None);
+        wrap.set_layout_style(taffy::Style {
+            position: taffy::Position::Absolute,
+            display: taffy::Display::Flex,
+            justify_content: Some(taffy::JustifyContent::Center),
+            align_items: Some(taffy::AlignItems::FlexStart),
+            padding: taffy::Rect {
+                top: taffy::LengthPercentage::Length(12.0 * draw_scale),
+                ..taffy::Rect::zero()
+            },
+            ..Default::default()
+        });
+        wrap.set_opacity(0.0, None);
+        wrap.set_hidden(true);
+        wrap.set_pointer_events(false);
+
+        let layer = layers_engine.new_layer();
+        layers_engine.add_layer(&wrap);
+        wrap.add_sublayer(&layer);
+        layer.set_pointer_events(false);
+
+        let view = View::new(
+            "inhibit_indicator",
+            InhibitIndicatorModel::default(),
+            Box::new(render_inhibit_indicator_view),
+        );
+        view.mount_layer(layer);
+
+        Self {
+            wrap_layer: wrap,
+            view,
+            active: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn show(&self, model: InhibitIndicatorModel) {
+        self.view.update_state(&model);
+        if self.alive() {
+            return;
+        }
+        self.active
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        self.wrap_layer.set_hidden(false);
+        self.wrap_layer
+            .set_opacity(1.0, Some(Transition::ease_out_quad(0.15)));
+    }
+
+    pub fn hide(&self) {
+        self.active
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+        let tr = self
+            .wrap_layer
+            .set_opacity(0.0, Some(Transition::ease_in_quad(0.1)));
+        tr.on_finish(
+            |l: &Layer, _p: f32| {
+                l.set_hidden(true);
+            },
+            true,
+        );
+    }
+}
+
+fn indicator_font(size: f32) -> layers::skia::Font {
+    let font_family = Config::with(|c| c.font_family.clone());
+    let style = layers::skia::FontStyle::new(
+        layers::skia::font_style::Weight::MEDIUM,
+        layers::skia::font_style::Width::NORMAL,
+        layers::skia::font_style::Slant::Upright,
+    );
+    FONT_CACHE.with(|font_cache| font_cache.make_font_with_fallback(&font_family, style, size))
+}
+
+pub fn render_inhibit_indicator_view(
+    state: &InhibitIndicatorModel,
+    _view: &View<InhibitIndicatorModel>,
+) -> LayerTree {
+    let draw_scale = Config::with(|config| config.screen_scale) as f32;
+
+    let text_size = 12.0 * draw_scale;
+    let padding = 14.0 * draw_scale;
+    let height = text_size * 2.4;
+    let text_width = indicator_font(text_size)
+        .measure_str(&state.text, None)
+        .1
+        .width();
+    let width = text_width + padding * 2.0;
+
+    let text = state.text.clone();
+    let draw_content = move |canvas: &layers::skia::Canvas, w: f32, h: f32| {
+        let font = indicator_font(text_size);
+        let mut paint = layers::skia::Paint::new(theme_colors().text_primary.c4f(), None);
+        paint.set_anti_alias(true);
+        let baseline = h / 2.0 + text_size / 3.0;
+        canvas.draw_str(&text, (padding, baseline), &font, &paint);
+        layers::skia::Rect::from_xywh(0.0, 0.0, w, h)
+    };
+
+    LayerTreeBuilder::default()
+        .key("inhibit_indicator")
+        .size((
+            Size {
+                width: taffy::Dimension::Length(width),
+                height: taffy::Dimension::Length(height),
+            },
+            Some(Transition::ease_out_quad(0.2)),
+        ))
+        .blend_mode(BlendMode::BackgroundBlur)
+        .background_color((
+            PaintColor::Solid {
+                color: theme_colors().materials_thick,
+            },
+            None,
+        ))
+        .content(Some(draw_content))
+        .border_corner_radius((BorderRadius::new_single(height / 2.0), None))
+        .build()
+        .unwrap()
+}
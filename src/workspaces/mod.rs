@@ -16,18 +16,28 @@ use layers::{
 use smithay::{
     desktop::{layer_map_for_output, Space, WindowSurface},
     output::Output,
-    reexports::wayland_server::{backend::ObjectId, Resource},
+    reexports::{
+        calloop::channel::Sender,
+        wayland_server::{backend::ObjectId, Resource},
+    },
     utils::{IsAlive, Rectangle},
 };
 
+use recent_documents::{Bookmark, RecentDocuments};
 use wayland_server::DisplayHandle;
 use workspace::WorkspaceView;
 
+mod app_expose;
 mod app_switcher;
 mod background;
 mod dnd_view;
 mod dock;
+mod inhibit_indicator;
 mod popup_overlay;
+mod prompt_view;
+pub mod recent_documents;
+pub mod split_view;
+pub mod wallpaper;
 pub mod workspace;
 
 pub mod utils;
@@ -38,18 +48,26 @@ mod window_view;
 mod workspace_selector;
 
 pub use background::BackgroundView;
-pub use window_selector::{WindowSelectorView, WindowSelectorWindow};
+pub use window_selector::{SelectionDirection, WindowSelectorView, WindowSelectorWindow};
 pub use window_view::{WindowView, WindowViewBaseModel, WindowViewSurface};
 
+pub use app_expose::{AppExposeEntry, AppExposeView};
 pub use app_switcher::AppSwitcherView;
 pub use apps_info::ApplicationsInfo;
 pub use dnd_view::DndView;
 pub use dock::DockView;
+pub use inhibit_indicator::{InhibitIndicatorModel, InhibitIndicatorView};
 pub use popup_overlay::PopupOverlayView;
+pub use prompt_view::{PromptLine, PromptModel, PromptView};
+pub use split_view::{SplitDivider, SplitView};
 pub use workspace_selector::{WorkspaceSelectorView, WORKSPACE_SELECTOR_PREVIEW_WIDTH};
 
 use crate::{
-    config::Config,
+    config::{
+        wallpaper::PickedWallpaper,
+        workspaces::{SavedWorkspace, SavedWorkspaces},
+        Config,
+    },
     shell::WindowElement,
     utils::{natural_layout::LayoutRect, Observable, Observer},
 };
@@ -75,6 +93,8 @@ pub struct WorkspacesModel {
     /// The physical height of the workspace
     pub height: i32,
     pub scale: f64,
+    /// Outputs showing the workspaces
+    pub outputs: Vec<Output>,
 }
 
 pub struct Workspaces {
@@ -88,12 +108,21 @@ pub struct Workspaces {
     pub workspace_selector_view: Arc<WorkspaceSelectorView>,
     pub dock: Arc<DockView>,
     pub app_switcher: Arc<AppSwitcherView>,
+    /// Windows and recent documents of one app
+    pub app_expose: AppExposeView,
     pub window_views: Arc<RwLock<HashMap<ObjectId, WindowView>>>,
     pub dnd_view: DndView,
     pub popup_overlay: PopupOverlayView,
+    pub prompt: PromptView,
+    /// Continuations of a pending key sequence or keyboard mode
+    pub key_hints: PromptView,
+    /// Shown while an app inhibits compositor shortcuts
+    pub inhibit_indicator: InhibitIndicatorView,
 
     // gestures states
     pub show_all: Arc<AtomicBool>,
+    /// Exposé shows the windows of every workspace, grouped per workspace
+    expose_global: Arc<AtomicBool>,
     pub show_desktop: Arc<AtomicBool>,
     pub show_all_gesture: Arc<AtomicI32>,
     pub show_desktop_gesture: Arc<AtomicI32>,
@@ -111,6 +140,14 @@ pub struct Workspaces {
     expose_layer: Layer,
     observers: Vec<Weak<dyn Observer<WorkspacesModel>>>,
     expose_dragged_window: Arc<std::sync::Mutex<Option<ObjectId>>>,
+    /// Text typed in exposé, only windows matching it are shown
+    expose_filter: RwLock<String>,
+    wallpapers: std::sync::Mutex<wallpaper::WallpaperLoader>,
+    /// Wallpaper picked through the Wallpaper portal
+    picked_wallpaper: std::sync::Mutex<Option<PickedWallpaper>>,
+    recent_documents: std::sync::Mutex<RecentDocuments>,
+    /// App shown in App Exposé, shown again when its documents are read
+    app_expose_app_id: Option<String>,
 }
 
 /// # Workspaces Layer Structure
@@ -142,6 +179,10 @@ pub struct Workspaces {
 /// ├── dock
 /// ├── popup_overlay (popups rendered on top of everything)
 /// ├── app_switcher
+/// ├── app_expose (the windows and documents of one app)
+/// ├── key_hints (continuations of a key sequence)
+/// ├── inhibit_indicator (an app receives all keys)
+/// ├── prompt (questions to the user, above everything)
 /// ├── workspace_selector_view
 /// │   ├── workspace_selector_view_content
 /// │   │   ├── workspace_selector_desktop_1
@@ -162,6 +203,11 @@ impl Workspaces {
         self.expose_update_if_needed();
     }
 
+    /// Whether a window is dragged out of exposé
+    pub fn is_window_selector_dragging(&self) -> bool {
+        self.expose_dragged_window.lock().unwrap().is_some()
+    }
+
     pub fn end_window_selector_drag(&self, window_id: &ObjectId) {
         let mut dragging = self.expose_dragged_window.lock().unwrap();
         if dragging.as_ref() == Some(window_id) {
@@ -237,6 +283,7 @@ impl Workspaces {
 
         let app_switcher = AppSwitcherView::new(layers_engine.clone());
         let app_switcher = Arc::new(app_switcher);
+        let app_expose = AppExposeView::new(layers_engine.clone());
 
         let workspace_selector_layer = layers_engine.new_layer();
         workspace_selector_layer.set_pointer_events(false);
@@ -257,6 +304,10 @@ impl Workspaces {
         layer_shell_overlay.set_pointer_events(false);
         layers_engine.add_layer(&layer_shell_overlay);
 
+        let key_hints = PromptView::new(layers_engine.clone());
+        let inhibit_indicator = InhibitIndicatorView::new(layers_engine.clone());
+        let prompt = PromptView::new(layers_engine.clone());
+
         let workspace_selector_view = Arc::new(WorkspaceSelectorView::new(
             layers_engine.clone(),
             workspace_selector_layer.clone(),
@@ -273,14 +324,19 @@ impl Workspaces {
             workspaces_layer,
             expose_layer,
             app_switcher: app_switcher.clone(),
+            app_expose,
             workspace_selector_view: workspace_selector_view.clone(),
             dock: dock.clone(),
             dnd_view,
             popup_overlay,
+            prompt,
+            key_hints,
+            inhibit_indicator,
             overlay_layer,
             layer_shell_background,
             layer_shell_overlay,
             show_all: Arc::new(AtomicBool::new(false)),
+            expose_global: Arc::new(AtomicBool::new(false)),
             show_desktop: Arc::new(AtomicBool::new(false)),
             show_all_gesture: Arc::new(AtomicI32::new(0)),
             show_desktop_gesture: Arc::new(AtomicI32::new(0)),
@@ -289,10 +345,20 @@ impl Workspaces {
             observers: Vec::new(),
             layers_engine,
             expose_dragged_window: Arc::new(std::sync::Mutex::new(None)),
+            expose_filter: RwLock::new(String::new()),
+            wallpapers: Default::default(),
+            picked_wallpaper: std::sync::Mutex::new(PickedWallpaper::state_file().load()),
+            recent_documents: Default::default(),
+            app_expose_app_id: None,
             display_handle,
         };
-        workspaces.add_workspace();
-        workspaces.add_workspace();
+        let saved = SavedWorkspaces::state_file().load();
+        let names = Config::with(|config| config.workspaces.initial(saved.as_ref()));
+        for name in names {
+            let (_, workspace) = workspaces.push_workspace();
+            workspace.set_name(name);
+        }
+        workspaces.reload_backgrounds();
 
         workspaces.add_listener(dock.clone());
         workspaces.add_listener(app_switcher.clone());
@@ -367,8 +433,32 @@ impl Workspaces {
             workspace.update_layout(logical_index, width, height);
             let selector_layer = workspace.window_selector_view.layer.clone();
             selector_layer.set_size(Size::points(width, height), None);
-            selector_layer.set_position((logical_index as f32 * width, 0.0), None);
         }
+        self.layout_window_selectors(None);
+    }
+
+    /// Places the exposé of each workspace: side by side like the
+    /// workspaces, or all over the current workspace in global exposé, where
+    /// only the current one shows its background
+    fn layout_window_selectors(&self, transition: Option<Transition>) {
+        let global = self.is_expose_global();
+        self.with_model(|model| {
+            let width = model.width as f32;
+            for (position, workspace) in model.workspaces.iter().enumerate() {
+                let slot = if global {
+                    model.current_workspace
+                } else {
+                    position
+                };
+                let selector = &workspace.window_selector_view;
+                selector
+                    .layer
+                    .set_position((slot as f32 * width, 0.0), transition);
+                selector
+                    .background_layer
+                    .set_hidden(global && position != model.current_workspace);
+            }
+        });
     }
 
     pub fn with_model_mut<T>(&self, f: impl FnOnce(&mut WorkspacesModel) -> T) -> T {
@@ -392,8 +482,13 @@ impl Workspaces {
             return false;
         }
 
-        // Check if app switcher is visible
-        if self.app_switcher.alive() {
+        // Check if app switcher or App Exposé are visible
+        if self.app_switcher.alive() || self.app_expose.alive() {
+            return false;
+        }
+
+        // Check if a prompt, the key hints or the inhibit indicator are visible
+        if self.prompt.alive() || self.key_hints.alive() || self.inhibit_indicator.alive() {
             return false;
         }
 
@@ -428,14 +523,19 @@ impl Workspaces {
     /// Returns Some(WindowElement) if the current workspace is in fullscreen mode
     /// and has a fullscreen window.
     pub fn get_fullscreen_window(&self) -> Option<WindowElement> {
-        let current_workspace = self.get_current_workspace();
-        if !current_workspace.get_fullscreen_mode() {
+        let current_index = self.with_model(|m| m.current_workspace);
+        self.fullscreen_window_at(current_index)
+    }
+
+    /// The fullscreen window of the workspace at `index`, the first one if
+    /// the workspace is split
+    pub fn fullscreen_window_at(&self, index: usize) -> Option<WindowElement> {
+        let workspace = self.get_workspace_at(index)?;
+        if !workspace.get_fullscreen_mode() {
             return None;
         }
-
-        // Find the fullscreen window in the current workspace
-        let current_index = self.with_model(|m| m.current_workspace);
-        self.spaces[current_index]
+        self.spaces
+            .get(index)?
             .elements()
             .find(|w| w.is_fullscreen())
             .cloned()
@@ -446,6 +546,160 @@ impl Workspaces {
         self.show_all.load(std::sync::atomic::Ordering::Relaxed)
     }
 
+    /// Whether exposé shows the windows of every workspace
+    pub fn is_expose_global(&self) -> bool {
+        self.expose_global
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Sets whether exposé shows the windows of every workspace, grouped per
+    /// workspace, and lays the workspaces out for it. Called before exposé
+    /// opens; closing global exposé turns it off once the windows are back.
+    pub fn set_expose_global(&self, global: bool) {
+        if self.is_expose_global() == global {
+            return;
+        }
+        self.expose_global
+            .store(global, std::sync::atomic::Ordering::Relaxed);
+        self.layout_window_selectors(None);
+        let count = self.with_model(|model| model.workspaces.len());
+        for index in 0..count {
+            self.expose_show_all_layout(index);
+        }
+    }
+
+    /// Position of the workspace whose group lies under `point` in global
+    /// exposé
+    pub fn expose_group_at(&self, point: (f32, f32)) -> Option<usize> {
+        if !self.is_expose_global() {
+            return None;
+        }
+        self.with_model(|model| {
+            model
+                .workspaces
+                .iter()
+                .position(|workspace| workspace.window_selector_view.group_contains(point))
+        })
+    }
+
+    /// The window selectors the keyboard goes through: the one of every
+    /// workspace in global exposé, the current one otherwise
+    fn expose_selectors(&self) -> Vec<Arc<WindowSelectorView>> {
+        let global = self.is_expose_global();
+        self.with_model(|model| {
+            model
+                .workspaces
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| global || *index == model.current_workspace)
+                .map(|(_, workspace)| workspace.window_selector_view.clone())
+                .collect()
+        })
+    }
+
+    /// The window highlighted in exposé
+    pub fn expose_selected_window(&self) -> Option<ObjectId> {
+        self.expose_selectors()
+            .iter()
+            .find_map(|selector| selector.selection())
+            .and_then(|selection| selection.window_id)
+    }
+
+    /// Moves the highlight to the nearest window in `direction`, or to the
+    /// first window when none is highlighted. In global exposé the selector
+    /// roots share the screen coordinates, so the highlight crosses groups.
+    pub fn expose_move_selection(&self, direction: SelectionDirection) {
+        let (rects, current) = self.expose_selection_rects();
+        let index = match current {
+            Some(current) => window_selector::neighbour_selection(&rects, current, direction),
+            None => (!rects.is_empty()).then_some(0),
+        };
+        if let Some(index) = index {
+            self.expose_select(index);
+        }
+    }
+
+    /// Moves the highlight to the next window, going round past the ends
+    pub fn expose_cycle_selection(&self, forward: bool) {
+        let (rects, current) = self.expose_selection_rects();
+        let count = rects.len();
+        if count == 0 {
+            return;
+        }
+        let index = match current {
+            Some(current) if forward => (current + 1) % count,
+            Some(current) => (current + count - 1) % count,
+            None if forward => 0,
+            None => count - 1,
+        };
+        self.expose_select(index);
+    }
+
+    /// Highlights the first window unless one is highlighted already
+    pub fn expose_select_first(&self) {
+        if self.expose_selection_rects().1.is_none() {
+            self.expose_select(0);
+        }
+    }
+
+    /// The windows of every selector of [`Self::expose_selectors`] in order,
+    /// with the position of the highlighted one
+    fn expose_selection_rects(&self) -> (Vec<window_selector::WindowSelection>, Option<usize>) {
+        let mut rects = Vec::new();
+        let mut current = None;
+        for selector in self.expose_selectors() {
+            if let Some(selection) = selector.selection().filter(|_| current.is_none()) {
+                current = Some(rects.len() + selection.index);
+            }
+            rects.extend(selector.rects());
+        }
+        (rects, current)
+    }
+
+    /// Highlights the window at `index` of [`Self::expose_selection_rects`],
+    /// clearing the highlight of the other selectors
+    fn expose_select(&self, index: usize) {
+        let mut index = Some(index);
+        for selector in self.expose_selectors() {
+            let count = selector.rects().len();
+            match index {
+                Some(position) if position < count => {
+                    selector.select(Some(position));
+                    index = None;
+                }
+                _ => {
+                    if selector.selection().is_some() {
+                        selector.select(None);
+                    }
+                    index = index.map(|position| position - count);
+                }
+            }
+        }
+    }
+
+    /// The workspace whose exposé takes the pointer at `point`: the one a
+    /// window is dragged from, the one whose group is under the pointer in
+    /// global exposé, or the current one
+    pub fn expose_workspace_at(&self, point: (f32, f32)) -> Arc<WorkspaceView> {
+        let workspaces = self.with_model(|model| model.workspaces.clone());
+        workspaces
+            .iter()
+            .find(|workspace| {
+                workspace
+                    .window_selector_view
+                    .drag_state
+                    .read()
+                    .unwrap()
+                    .is_some()
+            })
+            .or_else(|| {
+                self.expose_group_at(point)
+                    .and_then(|position| workspaces.get(position))
+            })
+            .cloned()
+            .unwrap_or_else(|| self.get_current_workspace())
+    }
+
     /// Check if expose mode is currently transitioning (either via gesture or animation)
     /// Returns true if we're in the middle of opening or closing expose mode
     pub fn is_expose_transitioning(&self) -> bool {
@@ -514,12 +768,18 @@ impl Workspaces {
     /// - Gesture completion: `expose_end()` (finalize at current position)
     /// - Update layout during window drag: `expose_show_all(0.0, false)` (recalculate without animation)
     pub fn expose_show_all(&self, delta: f32, end_gesture: bool) {
+        // Exposé takes its mirrors back from App Exposé
+        if delta > 0.0 && self.app_expose.alive() {
+            self.app_expose.hide();
+            self.app_expose.restore_mirrors();
+        }
         let current_workspace_index = self.get_current_workspace_index();
         let num_workspaces = self.with_model(|m| m.workspaces.len());
 
         // Update all workspaces during gesture AND at end for consistent overlay visibility
+        let global = self.is_expose_global();
         for i in 0..num_workspaces {
-            let animated = end_gesture && i == current_workspace_index;
+            let animated = end_gesture && (global || i == current_workspace_index);
             self.expose_show_all_workspace(i, delta, end_gesture, animated);
         }
     }
@@ -630,11 +890,15 @@ impl Workspaces {
         let target_gesture = if target_show_all { 1000 } else { 0 };
         self.show_all_gesture
             .store(target_gesture, std::sync::atomic::Ordering::Relaxed);
+        if !target_show_all {
+            self.clear_expose_filter();
+        }
 
         // Update all workspaces so they all transition together
         let num_workspaces = self.with_model(|m| m.workspaces.len());
+        let global = self.is_expose_global();
         for i in 0..num_workspaces {
-            let animated = i == current_workspace;
+            let animated = global || i == current_workspace;
             let workspace_transition = if animated { Some(transition) } else { None };
             self.expose_show_all_end(i, current_delta, target_show_all, workspace_transition);
         }
@@ -649,6 +913,10 @@ impl Workspaces {
             self.expose_show_desktop(-1.0, true);
             return;
         }
+        if show && self.app_expose.alive() {
+            self.app_expose.hide();
+            self.app_expose.restore_mirrors();
+        }
 
         // Set the gesture state to target value
         const MULTIPLIER: f32 = 1000.0;
@@ -657,6 +925,14 @@ impl Workspaces {
             .store(target_gesture, std::sync::atomic::Ordering::Relaxed);
         self.show_all
             .store(show, std::sync::atomic::Ordering::Relaxed);
+        if !show {
+            self.workspace_selector_view.stop_rename();
+            self.workspace_selector_view.stop_reorder();
+            self.clear_expose_filter();
+            self.get_current_workspace()
+                .window_selector_view
+                .select(None);
+        }
 
         // Create smooth spring transition (zero velocity for keyboard shortcuts)
         let spring = Spring::with_duration_and_bounce(0.3, 0.1);
@@ -667,6 +943,14 @@ impl Workspaces {
 
         let current_workspace = self.get_current_workspace_index();
         let delta_normalized = if show { 1.0 } else { 0.0 };
+        if self.is_expose_global() {
+            // Every workspace moves its windows in global exposé, the
+            // current one last so it drives the selector and the dock
+            let num_workspaces = self.with_model(|m| m.workspaces.len());
+            for i in (0..num_workspaces).filter(|i| *i != current_workspace) {
+                self.expose_show_all_end(i, delta_normalized, show, Some(transition));
+            }
+        }
         self.expose_show_all_end(current_workspace, delta_normalized, show, Some(transition));
     }
 
@@ -758,13 +1042,35 @@ impl Workspaces {
         let screen_size_h = size.y - padding_top - padding_bottom - workspace_selector_height;
 
         let offset_y = 200.0;
-        let layout_rect = LayoutRect::new(
+        let mut layout_rect = LayoutRect::new(
             0.0,
             workspace_selector_height,
             screen_size_w,
             screen_size_h - offset_y,
         );
+        // Global exposé gives each workspace a cell of the same area
+        let mut group = None;
+        if self.is_expose_global() {
+            let count = self.with_model(|m| m.workspaces.len());
+            let cells = window_selector::workspace_group_cells(count, &layout_rect, 40.0);
+            if let Some(cell) = cells.get(workspace_index) {
+                layout_rect = *cell;
+                group = Some(LayoutRect::new(
+                    cell.x,
+                    cell.y + offset_y,
+                    cell.width,
+                    cell.height,
+                ));
+            }
+        }
+        workspace.window_selector_view.set_group(group);
         let dragging_window = self.expose_dragged_window.lock().unwrap().clone();
+        let filter = self.expose_filter();
+        let running_apps = if filter.is_empty() {
+            Vec::new()
+        } else {
+            self.dock.get_state().running_apps
+        };
         let windows = self.with_model(|model| {
             if let Some(workspace_model) = model.workspaces.get(workspace_index) {
                 let windows_list = workspace_model.windows_list.read().unwrap();
@@ -779,6 +1085,21 @@ impl Workspaces {
                         if window.is_minimised() {
                             continue;
                         }
+                        if !filter.is_empty() {
+                            let title = window.xdg_title();
+                            let app_id = window.display_app_id(&self.display_handle);
+                            let app_name = running_apps
+                                .iter()
+                                .find(|app| app.identifier == app_id)
+                                .and_then(|app| app.desktop_name())
+                                .unwrap_or_default();
+                            if !window_selector::matches_filter(
+                                &filter,
+                                &[&title, &app_id, &app_name],
+                            ) {
+                                continue;
+                            }
+                        }
                         if let Some(bbox) = space.element_geometry(window) {
                             let bbox = bbox.to_f64().to_physical(scale);
                             window.mirror_layer().set_size(
@@ -816,6 +1137,13 @@ impl Workspaces {
         workspace
             .window_selector_view
             .update_windows(layout_rect, offset_y, &windows);
+        if group.is_some() {
+            // The filter stays above the groups rather than in a cell
+            workspace.window_selector_view.set_filter_center((
+                screen_size_w / 2.0,
+                workspace_selector_height + offset_y / 2.0,
+            ));
+        }
         true
     }
 
@@ -870,6 +1198,16 @@ impl Workspaces {
         // Check if this is the current workspace early, so we can use it for window animations
         let current_workspace_index = self.get_current_workspace_index();
         let is_current_workspace = workspace_index == current_workspace_index;
+        // Global exposé moves the windows of every workspace, the other
+        // workspaces' windows fly in from their side of the screen
+        let global = self.is_expose_global();
+        let animate_workspace = is_current_workspace || global;
+        let start_offset_x = if global {
+            let width = self.with_model(|m| m.width as f32);
+            (workspace_index as f32 - current_workspace_index as f32) * width
+        } else {
+            0.0
+        };
 
         // Hide popup overlay when entering expose mode
         self.popup_overlay.set_hidden(is_gesture_ongoing);
@@ -890,6 +1228,7 @@ impl Workspaces {
             .read()
             .unwrap();
         let dragged_window = self.expose_dragged_window.lock().unwrap().clone();
+        let filtering = show_all && !self.expose_filter().is_empty();
 
         // Show overlay only when: not animating, gesture ended, and value is 1.0
         let window_selector_overlay = workspace_view.window_selector_view.overlay_layer.clone();
@@ -940,6 +1279,16 @@ impl Workspaces {
                         if window.is_minimised() {
                             continue;
                         }
+                        // Windows left out by the filter fade out where they are
+                        if let Some(layer) = window_selector.layer_for_window(window_id) {
+                            let opacity = if filtering && !bin.contains_key(window_id) {
+                                0.0
+                            } else {
+                                1.0
+                            };
+                            let opacity_transition = transition.filter(|_| animate_workspace);
+                            layer.set_opacity(opacity, opacity_transition);
+                        }
                         if let Some(bbox) = space.element_geometry(window) {
                             let bbox = bbox.to_f64().to_physical(scale);
                             if let Some(rect) = bin.get(window_id) {
@@ -957,14 +1306,14 @@ impl Workspaces {
                                 // Interpolate between current and target positions
                                 let scale = 1.0.interpolate(&target_scale, delta);
                                 let delta_clamped = delta.clamp(0.0, 1.0);
-                                let window_x = bbox.loc.x as f32;
+                                let window_x = bbox.loc.x as f32 + start_offset_x;
                                 let window_y = bbox.loc.y as f32;
                                 let x = window_x.interpolate(&to_x, delta_clamped);
                                 let y = window_y.interpolate(&to_y, delta_clamped);
 
                                 if let Some(layer) = window_selector.layer_for_window(window_id) {
                                     // Only animate if this is the current workspace AND a transition is provided
-                                    if transition.is_some() && is_current_workspace {
+                                    if transition.is_some() && animate_workspace {
                                         let translation =
                                             layer.change_position(layers::types::Point { x, y });
                                         let scale_change =
@@ -1014,6 +1363,39 @@ impl Workspaces {
             // Set overlay opacity to match the workspace selector opacity (fade in as we enter expose)
 
             let window_selector_overlay_ref = window_selector_overlay.clone();
+            // Global exposé shows the selection of every workspace, and once
+            // closed the workspaces go back side by side
+            let (selector_overlays, selector_homes) = self.with_model(|model| {
+                let width = model.width as f32;
+                let selectors = model
+                    .workspaces
+                    .iter()
+                    .map(|workspace| workspace.window_selector_view.clone());
+                let overlays: Vec<Layer> = if global {
+                    selectors
+                        .clone()
+                        .map(|selector| selector.overlay_layer.clone())
+                        .collect()
+                } else {
+                    vec![window_selector_overlay.clone()]
+                };
+                let homes: Vec<(Layer, Layer, f32)> = if global && !show_all {
+                    selectors
+                        .enumerate()
+                        .map(|(position, selector)| {
+                            (
+                                selector.layer.clone(),
+                                selector.background_layer.clone(),
+                                position as f32 * width,
+                            )
+                        })
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                (overlays, homes)
+            });
+            let expose_global = self.expose_global.clone();
             let expose_layer = self.expose_layer.clone();
             let workspace_selector_view_layer = self.workspace_selector_view.layer.clone();
             let layer_shell_overlay_ref = self.layer_shell_overlay.clone();
@@ -1034,7 +1416,16 @@ impl Workspaces {
                 transaction.on_finish(
                     move |_: &Layer, _: f32| {
                         let opacity = if show_all { 1.0 } else { 0.0 };
-                        window_selector_overlay_ref.set_opacity(opacity, None);
+                        for overlay in selector_overlays.iter() {
+                            overlay.set_opacity(opacity, None);
+                        }
+                        if !selector_homes.is_empty() {
+                            expose_global.store(false, std::sync::atomic::Ordering::Relaxed);
+                            for (layer, background, x) in selector_homes.iter() {
+                                layer.set_position((*x, 0.0), None);
+                                background.set_hidden(false);
+                            }
+                        }
                         expose_layer.set_hidden(!show_all);
                         workspace_selector_view_layer.set_hidden(!show_all);
                         // Restore layer shell overlay when exiting expose mode
@@ -1264,9 +1655,49 @@ impl Workspaces {
     }
 
     pub fn expose_update_if_needed(&self) {
+        if self.is_expose_global() {
+            let count = self.with_model(|m| m.workspaces.len());
+            for index in 0..count {
+                self.expose_update_if_needed_workspace(index);
+            }
+            return;
+        }
         let current_workspace_index = self.get_current_workspace_index();
         self.expose_update_if_needed_workspace(current_workspace_index);
     }
+    /// The text typed in exposé to filter the windows
+    pub fn expose_filter(&self) -> String {
+        self.expose_filter.read().unwrap().clone()
+    }
+
+    /// Shows only the windows whose title or app name contain `filter`,
+    /// laying exposé out again around them
+    pub fn set_expose_filter(&self, filter: &str) {
+        *self.expose_filter.write().unwrap() = filter.to_string();
+        let global = self.is_expose_global();
+        let count = self.with_model(|model| {
+            for (index, workspace) in model.workspaces.iter().enumerate() {
+                // Global exposé shows the filter once, with the current workspace
+                let shown = if global && index != model.current_workspace {
+                    ""
+                } else {
+                    filter
+                };
+                workspace.window_selector_view.set_filter(shown);
+            }
+            model.workspaces.len()
+        });
+        for index in 0..count {
+            self.expose_update_if_needed_workspace(index);
+        }
+    }
+
+    fn clear_expose_filter(&self) {
+        if !self.expose_filter.read().unwrap().is_empty() {
+            self.set_expose_filter("");
+        }
+    }
+
     pub fn expose_update_if_needed_workspace(&self, workspace_index: usize) {
         let relayout = self.expose_show_all_layout(workspace_index);
         if self.get_show_all() && relayout {
@@ -1357,6 +1788,11 @@ impl Workspaces {
             self.notify_observers(model);
         });
 
+        // Exposé lays the other windows out again
+        if let Some(index) = self.window_workspace_index(we) {
+            self.expose_update_if_needed_workspace(index);
+        }
+
         // Focus should move to the next topmost (non-minimized) window or to none.
         let index = self.with_model(|m| m.current_workspace);
         let next = self.spaces[index].elements().rev().find_map(|e| {
@@ -1613,6 +2049,7 @@ impl Workspaces {
             }
         }
 
+        self.app_expose.forget_window(window_id);
         self.with_model(|m| {
             for workspace_view in m.workspaces.iter() {
                 workspace_view.unmap_window(window_id);
@@ -1819,6 +2256,34 @@ impl Workspaces {
         }
     }
 
+    /// Moves a window within the workspace at `workspace_index`
+    pub fn place_window(
+        &mut self,
+        we: &WindowElement,
+        workspace_index: usize,
+        location: impl Into<smithay::utils::Point<i32, smithay::utils::Logical>>,
+        transition: Option<Transition>,
+    ) {
+        let location = location.into();
+        if let Some(space) = self.spaces.get_mut(workspace_index) {
+            space.map_element(we.clone(), location, false);
+        }
+        if let Some(workspace) = self.get_workspace_at(workspace_index) {
+            workspace.map_window(we, location, None);
+        }
+        if let Some(view) = self.get_window_view(&we.id()) {
+            let scale = Config::with(|c| c.screen_scale);
+            let position = location.to_f64().to_physical(scale);
+            view.window_layer.set_position(
+                layers::types::Point {
+                    x: position.x as f32,
+                    y: position.y as f32,
+                },
+                transition,
+            );
+        }
+    }
+
     pub fn raise_next_app_window(&mut self) -> Option<ObjectId> {
         let windows = self.get_current_app_windows();
         let mut wid = None;
@@ -2017,6 +2482,109 @@ impl Workspaces {
         Some(wid)
     }
 
+    /// Shows App Exposé with the windows of `app_id` on every workspace and
+    /// the documents the app opened recently. `app_id` is matched against
+    /// both the xdg app id and the app id shown in the dock.
+    pub fn show_app_expose(&mut self, app_id: &str) {
+        if self.get_show_all() {
+            self.expose_set_visible(false);
+        }
+        if self.get_show_desktop() {
+            self.expose_show_desktop(-2.0, true);
+        }
+        let scale = Config::with(|c| c.screen_scale);
+        let current_workspace_index = self.get_current_workspace_index();
+        let windows = self.with_model(|model| {
+            let mut windows = Vec::new();
+            for (index, workspace) in model.workspaces.iter().enumerate() {
+                let Some(space) = self.spaces.get(index) else {
+                    continue;
+                };
+                for window_id in workspace.windows_list.read().unwrap().iter() {
+                    let Some(window) = self.get_window_for_surface(window_id) else {
+                        continue;
+                    };
+                    if window.xdg_app_id() != app_id
+                        && window.display_app_id(&self.display_handle) != app_id
+                    {
+                        continue;
+                    }
+                    let mirror = workspace.window_selector_view.layer_for_window(window_id);
+                    let (Some(bbox), Some(mirror)) = (space.element_geometry(window), mirror)
+                    else {
+                        continue;
+                    };
+                    let bbox = bbox.to_f64().to_physical(scale);
+                    windows.push(AppExposeEntry {
+                        window_id: window_id.clone(),
+                        title: window.xdg_title().to_string(),
+                        geometry: LayoutRect::new(
+                            bbox.loc.x as f32,
+                            bbox.loc.y as f32,
+                            bbox.size.w as f32,
+                            bbox.size.h as f32,
+                        ),
+                        minimized: window.is_minimised(),
+                        on_screen: index == current_workspace_index,
+                        mirror,
+                        parent: workspace.window_selector_view.windows_layer.clone(),
+                    });
+                }
+            }
+            windows
+        });
+        if windows.is_empty() {
+            return;
+        }
+
+        let app = self
+            .dock
+            .get_state()
+            .running_apps
+            .into_iter()
+            .find(|app| app.identifier == app_id);
+        let app_name = app
+            .as_ref()
+            .and_then(|app| app.desktop_name())
+            .unwrap_or_else(|| app_id.to_string());
+        // Apps are listed in recently-used.xbel by their name or program
+        let mut names = vec![app_id.to_string(), app_name.clone()];
+        names.extend(app_id.rsplit('.').next().map(str::to_string));
+        names.extend(
+            app.as_ref()
+                .and_then(|app| app.command(&[]))
+                .and_then(|(cmd, _)| cmd.rsplit('/').next().map(str::to_string)),
+        );
+        // Listed as last read, the list is read again off the main loop
+        let documents = {
+            let mut recent_documents = self.recent_documents.lock().unwrap();
+            recent_documents.reload();
+            recent_documents.for_app(&names, app_expose::MAX_DOCUMENTS)
+        };
+        self.app_expose_app_id = Some(app_id.to_string());
+
+        let size = self.workspaces_layer.render_size_transformed();
+        self.app_expose
+            .show(app_name, app, windows, documents, (size.x, size.y));
+    }
+
+    pub fn hide_app_expose(&self) {
+        self.app_expose.hide();
+    }
+
+    /// Closes App Exposé on one of its windows, restoring it when minimized
+    /// and raising it on its workspace
+    pub fn app_expose_activate(&mut self, window_id: &ObjectId) -> Option<ObjectId> {
+        self.app_expose.hide();
+        self.app_expose.restore_mirrors();
+        let window = self.get_window_for_surface(window_id)?;
+        if window.is_minimised() {
+            let window_id = self.unminimize_window(window_id)?;
+            return self.focus_app_with_window(&window_id);
+        }
+        self.focus_app_with_window(window_id)
+    }
+
     /// Update the workspace model using elements from Space: windows_list, app_windows_map, zindex_application_list
     /// - app_windows_map: is a map of app_id to a list of toplevel surfaces
     /// - applications_list: is the list of app_id in the order they are opened
@@ -2134,6 +2702,8 @@ impl Workspaces {
         for space in self.spaces.iter_mut() {
             space.map_output(output, location);
         }
+        self.update_model_outputs();
+        self.reload_backgrounds();
     }
 
     /// Detach an output from every workspace
@@ -2143,11 +2713,27 @@ impl Workspaces {
         for space in self.spaces.iter_mut() {
             space.unmap_output(output);
         }
+        self.update_model_outputs();
+        self.reload_backgrounds();
+    }
+
+    fn update_model_outputs(&self) {
+        self.with_model_mut(|m| {
+            m.outputs = self.outputs.clone();
+            self.notify_observers(m);
+        });
     }
 
     // Workspaces Management
 
     pub fn add_workspace(&mut self) -> (usize, Arc<WorkspaceView>) {
+        let added = self.push_workspace();
+        self.reload_backgrounds();
+        self.save_workspaces();
+        added
+    }
+
+    fn push_workspace(&mut self) -> (usize, Arc<WorkspaceView>) {
         let mut new_space = Space::default();
 
         if !self.spaces.is_empty() {
@@ -2180,6 +2766,9 @@ impl Workspaces {
         (index, workspace)
     }
 
+    /// The first empty workspace after the current one, added when there is
+    /// none; an added workspace is left out of the saved ones since it only
+    /// hosts a fullscreen window
     pub fn get_next_free_workspace(&mut self) -> (usize, Arc<WorkspaceView>) {
         let current_workspace = self.get_current_workspace_index();
         if current_workspace < self.spaces.len() - 1 {
@@ -2189,7 +2778,9 @@ impl Workspaces {
                 }
             }
         }
-        self.add_workspace()
+        let added = self.push_workspace();
+        self.reload_backgrounds();
+        added
     }
 
     pub fn remove_workspace_at(&mut self, n: usize) {
@@ -2204,8 +2795,8 @@ impl Workspaces {
 
             if n < m.workspaces.len() {
                 m.workspaces.remove(n);
-                if m.current_workspace >= m.workspaces.len() {
-                    m.current_workspace = m.workspaces.len() - 1;
+                if n < m.current_workspace || m.current_workspace >= m.workspaces.len() {
+                    m.current_workspace -= 1;
                 }
             }
             m.clone()
@@ -2220,8 +2811,9 @@ impl Workspaces {
                     return;
                 }
             }
-            // move all windows to previous workspace
+            // move all windows to the workspace that is current now
             let space_to_remove = self.spaces.remove(n);
+            self.remap_window_workspaces(|index| removed_position(index, n));
             for e in space_to_remove.elements() {
                 let location = space_to_remove.element_location(e).unwrap_or_default();
                 // Drop fullscreen state so the window restores to its normal size on the target workspace
@@ -2244,6 +2836,262 @@ impl Workspaces {
         }
         self.update_workspaces_layout();
         self.notify_observers(&workspace_model);
+        // Wallpapers by number follow the position
+        self.reload_backgrounds();
+        self.save_workspaces();
+    }
+
+    /// Flags the workspace at `index` as wanting attention, until it is
+    /// shown
+    pub fn set_workspace_urgent(&self, index: usize) {
+        self.with_model(|m| {
+            if index == m.current_workspace {
+                return;
+            }
+            if let Some(workspace) = m.workspaces.get(index) {
+                workspace.set_urgent(true);
+                self.notify_observers(m);
+            }
+        });
+    }
+
+    /// Names a workspace, `None` restores the default name
+    pub fn rename_workspace(&self, index: usize, name: Option<String>) {
+        self.with_model(|m| {
+            if let Some(workspace) = m.workspaces.get(index) {
+                workspace.set_name(name);
+                self.notify_observers(m);
+            }
+        });
+        self.reload_backgrounds();
+        self.save_workspaces();
+    }
+
+    /// Moves the workspace at `from` to `to`, the workspaces in between
+    /// shift by one. The current workspace stays on screen.
+    pub fn move_workspace(&mut self, from: usize, to: usize) {
+        let count = self.spaces.len();
+        if from == to || from >= count || to >= count {
+            return;
+        }
+        let space = self.spaces.remove(from);
+        self.spaces.insert(to, space);
+        let current = self.with_model_mut(|m| {
+            let workspace = m.workspaces.remove(from);
+            m.workspaces.insert(to, workspace);
+            m.current_workspace = moved_position(m.current_workspace, from, to);
+            self.notify_observers(m);
+            m.current_workspace
+        });
+        self.remap_window_workspaces(|index| moved_position(index, from, to));
+
+        self.update_workspaces_layout();
+        self.scroll_to_workspace_index(current, Some(Transition::ease_out_quad(0.0)));
+        self.reload_backgrounds();
+        self.save_workspaces();
+    }
+
+    /// With `remove_empty`, removes the workspaces having neither a name nor
+    /// a window, down to `count` workspaces. The current workspace and the
+    /// one at `keep` stay; returns where `keep` ends up.
+    pub fn remove_empty_workspaces(&mut self, keep: usize) -> usize {
+        let (remove_empty, min_count) = Config::with(|config| {
+            (
+                config.workspaces.remove_empty,
+                config.workspaces.min_count(),
+            )
+        });
+        if !remove_empty {
+            return keep;
+        }
+        let mut keep = keep;
+        let mut removed = false;
+        for index in (0..self.spaces.len()).rev() {
+            if self.spaces.len() <= min_count {
+                break;
+            }
+            let Some(workspace) = self.get_workspace_at(index) else {
+                continue;
+            };
+            if index == keep
+                || index == self.get_current_workspace_index()
+                || workspace.get_name().is_some()
+                || workspace.get_fullscreen_mode()
+                || self.spaces[index].elements().next().is_some()
+            {
+                continue;
+            }
+            self.remove_workspace_at(index);
+            keep = removed_position(keep, index);
+            removed = true;
+        }
+        if removed {
+            let current = self.get_current_workspace_index();
+            self.scroll_to_workspace_index(current, Some(Transition::ease_out_quad(0.0)));
+        }
+        keep
+    }
+
+    /// Keeps the workspaces remembered by windows, by position, once the
+    /// workspaces moved
+    fn remap_window_workspaces(&self, position: impl Fn(usize) -> usize) {
+        for window in self.windows_map.values() {
+            window.set_workspace(position(window.get_workspace()));
+            if window.is_fullscreen() {
+                window.set_fullscreen(true, position(window.get_fullscreen_workspace()));
+            }
+        }
+    }
+
+    /// Remembers the workspaces and their names for the next session;
+    /// fullscreen workspaces only last as long as their window
+    fn save_workspaces(&self) {
+        let saved = self.with_model(|m| SavedWorkspaces {
+            workspaces: m
+                .workspaces
+                .iter()
+                .filter(|workspace| !workspace.get_fullscreen_mode())
+                .map(|workspace| SavedWorkspace {
+                    name: workspace.get_name(),
+                })
+                .collect(),
+        });
+        if let Err(err) = SavedWorkspaces::state_file().save(&saved) {
+            tracing::warn!("Failed to save the workspaces: {}", err);
+        }
+    }
+
+    /// Shows the configured wallpaper of every workspace, cross-fading the
+    /// ones that changed
+    pub fn reload_backgrounds(&self) {
+        let workspaces = self.with_model(|m| m.workspaces.clone());
+        let scale = Config::with(|c| c.screen_scale) as f32;
+        let outputs: Vec<(String, skia::Rect)> = self
+            .outputs
+            .iter()
+            .filter_map(|output| {
+                let geometry = self.output_geometry(output)?;
+                let frame = skia::Rect::from_xywh(
+                    geometry.loc.x as f32 * scale,
+                    geometry.loc.y as f32 * scale,
+                    geometry.size.w as f32 * scale,
+                    geometry.size.h as f32 * scale,
+                );
+                Some((output.name(), frame))
+            })
+            .collect();
+        let picked = self
+            .picked_wallpaper
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(PickedWallpaper::wallpaper);
+        let (wallpapers, transition) = Config::with(|config| {
+            let wallpapers: Vec<_> = workspaces
+                .iter()
+                .enumerate()
+                .flat_map(|(index, workspace)| {
+                    let name = workspace.get_name();
+                    outputs
+                        .iter()
+                        .map(|(output, _)| {
+                            config.wallpaper.resolve(
+                                index,
+                                name.as_deref(),
+                                Some(output),
+                                picked.as_ref(),
+                                &config.background_image,
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            (wallpapers, config.wallpaper.transition_ms as f32 / 1000.0)
+        });
+        let mut contents = self
+            .wallpapers
+            .lock()
+            .unwrap()
+            .load_all(&wallpapers, crate::theme::scheme())
+            .into_iter();
+        for workspace in workspaces.iter() {
+            let wallpapers = outputs
+                .iter()
+                .map(|(output, frame)| (output.clone(), *frame, contents.next().flatten()))
+                .collect();
+            workspace
+                .background_view
+                .set_wallpapers(wallpapers, transition);
+        }
+    }
+
+    /// Sends the wallpaper images to `decoder` once decoded off the main loop
+    pub fn set_wallpaper_decoder(&self, decoder: Sender<wallpaper::DecodedWallpaper>) {
+        self.wallpapers.lock().unwrap().set_decoder(decoder);
+    }
+
+    /// Shows a wallpaper image decoded off the main loop
+    pub fn wallpaper_decoded(&self, decoded: wallpaper::DecodedWallpaper) {
+        let shown = self.wallpapers.lock().unwrap().finish_decoding(decoded);
+        if shown {
+            self.reload_backgrounds();
+        }
+    }
+
+    /// Sends the recent documents to `loader` once read off the main loop
+    pub fn set_recent_documents_loader(&self, loader: Sender<Vec<Bookmark>>) {
+        self.recent_documents.lock().unwrap().set_loader(loader);
+    }
+
+    /// Keeps the recent documents read off the main loop, App Exposé is
+    /// shown again when they changed
+    pub fn recent_documents_loaded(&mut self, bookmarks: Vec<Bookmark>) {
+        let changed = self
+            .recent_documents
+            .lock()
+            .unwrap()
+            .finish_loading(bookmarks);
+        if !changed || !self.app_expose.alive() {
+            return;
+        }
+        if let Some(app_id) = self.app_expose_app_id.clone() {
+            self.show_app_expose(&app_id);
+        }
+    }
+
+    /// The wallpaper picked through the Wallpaper portal
+    pub fn picked_wallpaper(&self) -> Option<PickedWallpaper> {
+        self.picked_wallpaper.lock().unwrap().clone()
+    }
+
+    /// Cross-fades to `picked`, or back to the configured wallpapers
+    pub fn set_picked_wallpaper(&self, picked: Option<PickedWallpaper>) {
+        *self.picked_wallpaper.lock().unwrap() = picked;
+        self.reload_backgrounds();
+    }
+
+    /// Shows the next image of the slideshows whose interval is over
+    pub fn advance_slideshows(&self) {
+        let advanced = self
+            .wallpapers
+            .lock()
+            .unwrap()
+            .advance_slideshows(std::time::Instant::now());
+        if advanced {
+            self.reload_backgrounds();
+        }
+    }
+
+    /// Renders the dock, app switcher, workspace selector and exposé again
+    /// after [`theme::reload`](crate::theme::reload)
+    pub fn reload_theme(&self) {
+        self.dock.reload_theme();
+        self.app_switcher.reload_theme();
+        self.workspace_selector_view.reload_theme();
+        let workspaces = self.with_model(|m| m.workspaces.clone());
+        for workspace in workspaces {
+            workspace.window_selector_view.reload_theme();
+        }
     }
 
     pub fn get_workspace_at(&self, i: usize) -> Option<Arc<WorkspaceView>> {
@@ -2297,6 +3145,7 @@ impl Workspaces {
                 return;
             }
             m.current_workspace = i;
+            m.workspaces[i].set_urgent(false);
         });
         self.update_workspace_model();
         self.scroll_to_workspace_index(i, transition)
@@ -2341,6 +3190,10 @@ impl Workspaces {
                 // and windows positioned, but don't animate dock (it's shared across workspaces)
                 self.expose_update_if_needed_workspace(i);
             }
+            // Global exposé stays in place while the workspaces scroll below
+            if self.is_expose_global() {
+                self.layout_window_selectors(Some(transition));
+            }
 
             let workspace_width = self.with_model(|m| m.width as f32);
             if workspace_width > 0.0 {
@@ -2363,7 +3216,7 @@ impl Workspaces {
         let (num_workspaces, workspace_width, scale) =
             self.with_model(|m| (m.workspaces.len(), m.width as f32, m.scale as f32));
 
-        if num_workspaces == 0 || workspace_width <= 0.0 {
+        if num_workspaces == 0 || workspace_width <= 0.0 || self.is_expose_global() {
             return;
         }
 
@@ -2463,6 +3316,21 @@ impl Workspaces {
         self.space().outputs_for_element(element)
     }
 
+    /// The output showing `element` in the space of workspace `index`, the
+    /// first output of that space when it is not placed yet
+    pub fn workspace_output_for_element(
+        &self,
+        index: usize,
+        element: &WindowElement,
+    ) -> Option<Output> {
+        let space = self.spaces.get(index)?;
+        space
+            .outputs_for_element(element)
+            .into_iter()
+            .next()
+            .or_else(|| space.outputs().next().cloned())
+    }
+
     fn apply_scroll_offset(
         &self,
         offset: f32,
@@ -2558,15 +3426,37 @@ impl Workspaces {
         self.space().element_geometry(we)
     }
 
+    /// Geometry of a window in the workspace holding it, which may not be
+    /// the current one
+    pub fn window_geometry(
+        &self,
+        we: &WindowElement,
+    ) -> Option<smithay::utils::Rectangle<i32, smithay::utils::Logical>> {
+        self.find_space_for_element(we)
+            .and_then(|space| space.element_geometry(we))
+    }
+
+    /// Location of a window in the workspace holding it
+    pub fn window_location(
+        &self,
+        we: &WindowElement,
+    ) -> Option<smithay::utils::Point<i32, smithay::utils::Logical>> {
+        self.find_space_for_element(we)
+            .and_then(|space| space.element_location(we))
+    }
+
+    /// Index of the workspace holding a window
+    pub fn window_workspace_index(&self, we: &WindowElement) -> Option<usize> {
+        self.find_space_index_for_element(we)
+    }
+
     // Add these helper methods
-    #[allow(dead_code)]
     fn find_space_for_element(&self, element: &WindowElement) -> Option<&Space<WindowElement>> {
         self.spaces
             .iter()
             .find(|space| space.elements().any(|e| e.id() == element.id()))
     }
 
-    #[allow(dead_code)]
     fn find_space_index_for_element(&self, element: &WindowElement) -> Option<usize> {
         self.spaces
             .iter()
@@ -2627,6 +3517,28 @@ impl Workspaces {
     }
 }
 
+/// Where the workspace at `index` ends up once the one at `from` moved to `to`
+fn moved_position(index: usize, from: usize, to: usize) -> usize {
+    if index == from {
+        to
+    } else if from < index && index <= to {
+        index - 1
+    } else if to <= index && index < from {
+        index + 1
+    } else {
+        index
+    }
+}
+
+/// Where the workspace at `index` ends up once the one at `removed` is gone
+fn removed_position(index: usize, removed: usize) -> usize {
+    if index > removed {
+        index - 1
+    } else {
+        index
+    }
+}
+
 /// Helper to convert WlrLayer to string for layer keys
 fn wlr_layer_to_str(layer: smithay::wayland::shell::wlr_layer::Layer) -> &'static str {
     use smithay::wayland::shell::wlr_layer::Layer as WlrLayer;
@@ -2737,3 +3649,24 @@ impl Observable<WorkspacesModel> for Workspaces {
         Box::new(self.observers.iter().cloned())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moving_a_workspace_shifts_the_ones_between() {
+        // [a, b, c, d] with a moved to 2 gives [b, c, a, d]
+        let moved: Vec<_> = (0..4).map(|index| moved_position(index, 0, 2)).collect();
+        assert_eq!(moved, vec![2, 0, 1, 3]);
+        // [a, b, c, d] with d moved to 1 gives [a, d, b, c]
+        let moved: Vec<_> = (0..4).map(|index| moved_position(index, 3, 1)).collect();
+        assert_eq!(moved, vec![0, 2, 3, 1]);
+    }
+
+    #[test]
+    fn removing_a_workspace_shifts_the_ones_after() {
+        let removed: Vec<_> = [0, 2, 3].map(|index| removed_position(index, 1)).to_vec();
+        assert_eq!(removed, vec![0, 1, 2]);
+    }
+}
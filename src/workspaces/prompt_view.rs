@@ -0,0 +1,223 @@
+use std::{
+    hash::{Hash, Hasher},
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use layers::{
+    engine::{animation::Transition, Engine},
+    prelude::*,
+    types::Size,
+};
+use smithay::utils::IsAlive;
+
+use crate::{config::Config, theme::theme_colors, workspaces::utils::FONT_CACHE};
+
+/// A row of the prompt, e.g. a shortcut description and its trigger
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PromptLine {
+    pub text: String,
+    pub detail: String,
+    pub highlighted: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PromptModel {
+    pub title: String,
+    pub lines: Vec<PromptLine>,
+    /// Key hints shown at the bottom of the panel
+    pub hint: String,
+}
+
+impl Hash for PromptModel {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.title.hash(state);
+        for line in &self.lines {
+            line.text.hash(state);
+            line.detail.hash(state);
+            line.highlighted.hash(state);
+        }
+        self.hint.hash(state);
+    }
+}
+
+/// Keyboard driven panel used when the compositor needs an answer from the user
+///
+/// The view only renders the model, key handling is done by the caller
+/// while the prompt is alive.
+///
+/// # PromptView Layer Structure
+///
+/// ```diagram
+/// PromptView
+/// └── prompt_container `prompt_container`
+///     └── prompt `prompt`
+/// ```
+#[derive(Debug, Clone)]
+pub struct PromptView {
+    pub wrap_layer: Layer,
+    pub view: View<PromptModel>,
+    active: Arc<AtomicBool>,
+}
+
+impl IsAlive for PromptView {
+    fn alive(&self) -> bool {
+        self.active.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+impl PromptView {
+    pub fn new(layers_engine: Arc<Engine>) -> Self {
+        let wrap = layers_engine.new_layer();
+        wrap.set_key("prompt_container");
+        wrap.set_size(Size::percent(1.0, 1.0), None);
+        wrap.set_layout_style(taffy::Style {
+            position: taffy::Position::Absolute,
+            display: taffy::Display::Flex,
+            justify_content: Some(taffy::JustifyContent::Center),
+            align_items: Some(taffy::AlignItems::Center),
+            justify_items: Some(taffy::JustifyItems::Center),
+            ..Default::default()
+        });
+        wrap.set_opacity(0.0, None);
+        wrap.set_hidden(true);
+        wrap.set_pointer_events(false);
+
+        let layer = layers_engine.new_layer();
+        layers_engine.add_layer(&wrap);
+        wrap.add_sublayer(&layer);
+        layer.set_pointer_events(false);
+
+        let view = View::new(
+            "prompt",
+            PromptModel::default(),
+            Box::new(render_prompt_view),
+        );
+        view.mount_layer(layer);
+
+        Self {
+            wrap_layer: wrap,
+            view,
+            active: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Shows the prompt, or updates it if it is already visible
+    pub fn show(&self, model: PromptModel) {
+        self.view.update_state(&model);
+        if self.alive() {
+            return;
+        }
+        self.active
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        self.wrap_layer.set_hidden(false);
+        self.wrap_layer
+            .set_opacity(1.0, Some(Transition::ease_out_quad(0.15)));
+    }
+
+    pub fn hide(&self) {
+        self.active
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+        let tr = self
+            .wrap_layer
+            .set_opacity(0.0, Some(Transition::ease_in_quad(0.1)));
+        tr.on_finish(
+            |l: &Layer, _p: f32| {
+                l.set_hidden(true);
+            },
+            true,
+        );
+    }
+}
+
+pub fn render_prompt_view(state: &PromptModel, _view: &View<PromptModel>) -> LayerTree {
+    let draw_scale = Config::with(|config| config.screen_scale) as f32;
+
+    let padding = 24.0 * draw_scale;
+    let title_size = 16.0 * draw_scale;
+    let text_size = 13.0 * draw_scale;
+    let line_height = text_size * 2.2;
+    let width = 520.0 * draw_scale;
+    let height = padding * 2.0
+        + title_size * 2.0
+        + state.lines.len() as f32 * line_height
+        + if state.hint.is_empty() {
+            0.0
+        } else {
+            text_size * 2.4
+        };
+
+    let state = state.clone();
+    let draw_content = move |canvas: &layers::skia::Canvas, w: f32, h: f32| {
+        let font_family = Config::with(|c| c.font_family.clone());
+        let make_font = |weight, size| {
+            let style = layers::skia::FontStyle::new(
+                weight,
+                layers::skia::font_style::Width::NORMAL,
+                layers::skia::font_style::Slant::Upright,
+            );
+            FONT_CACHE
+                .with(|font_cache| font_cache.make_font_with_fallback(&font_family, style, size))
+        };
+        let title_font = make_font(layers::skia::font_style::Weight::BOLD, title_size);
+        let text_font = make_font(layers::skia::font_style::Weight::NORMAL, text_size);
+
+        let mut primary = layers::skia::Paint::new(theme_colors().text_primary.c4f(), None);
+        primary.set_anti_alias(true);
+        let mut secondary = layers::skia::Paint::new(theme_colors().text_secondary.c4f(), None);
+        secondary.set_anti_alias(true);
+        let selection = layers::skia::Paint::new(theme_colors().fills_primary.c4f(), None);
+
+        let mut y = padding + title_size;
+        canvas.draw_str(&state.title, (padding, y), &title_font, &primary);
+        y += title_size;
+
+        for line in &state.lines {
+            if line.highlighted {
+                let rect =
+                    layers::skia::Rect::from_xywh(padding / 2.0, y, w - padding, line_height);
+                let rrect =
+                    layers::skia::RRect::new_rect_xy(rect, line_height / 4.0, line_height / 4.0);
+                canvas.draw_rrect(rrect, &selection);
+            }
+            let baseline = y + line_height / 2.0 + text_size / 3.0;
+            canvas.draw_str(&line.text, (padding, baseline), &text_font, &primary);
+            let detail_width = text_font
+                .measure_str(&line.detail, Some(&secondary))
+                .1
+                .width();
+            canvas.draw_str(
+                &line.detail,
+                (w - padding - detail_width, baseline),
+                &text_font,
+                &secondary,
+            );
+            y += line_height;
+        }
+
+        if !state.hint.is_empty() {
+            canvas.draw_str(&state.hint, (padding, h - padding), &text_font, &secondary);
+        }
+        layers::skia::Rect::from_xywh(0.0, 0.0, w, h)
+    };
+
+    LayerTreeBuilder::default()
+        .key("prompt")
+        .size((
+            Size {
+                width: taffy::Dimension::Length(width),
+                height: taffy::Dimension::Length(height),
+            },
+            Some(Transition::ease_out_quad(0.2)),
+        ))
+        .blend_mode(BlendMode::BackgroundBlur)
+        .background_color((
+            PaintColor::Solid {
+                color: theme_colors().materials_thick,
+            },
+            None,
+        ))
+        .content(Some(draw_content))
+        .border_corner_radius((BorderRadius::new_single(14.0 * draw_scale), None))
+        .build()
+        .unwrap()
+}
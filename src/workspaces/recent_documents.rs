@@ -0,0 +1,295 @@
+//! Documents recently opened by an app, read from the `recently-used.xbel`
+//! list that GTK and KDE apps keep in `$XDG_DATA_HOME`
+//!
+//! Each bookmark of the list is a file and the apps that opened it:
+//!
+//! ```xml
+//! <bookmark href="file:///home/me/notes.txt" modified="2024-05-02T10:00:00Z">
+//!   <info><metadata owner="http://freedesktop.org">
+//!     <bookmark:applications>
+//!       <bookmark:application name="gedit" exec="&apos;gedit %u&apos;"
+//!         modified="2024-05-02T10:00:00Z" count="3"/>
+//!     </bookmark:applications>
+//!   </metadata></info>
+//! </bookmark>
+//! ```
+
+use std::path::PathBuf;
+
+use smithay::reexports::calloop::channel::Sender;
+use tracing::warn;
+
+use crate::config::default_apps::xdg_data_home;
+
+/// Namespace of the `bookmark:` elements
+const BOOKMARK_NS: &str = "http://www.freedesktop.org/standards/desktop-bookmarks";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RecentDocument {
+    pub path: PathBuf,
+    /// File name shown for the document
+    pub name: String,
+    /// When the app last used the document, as written in the list
+    pub modified: String,
+}
+
+/// A local document of the list and the apps that used it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    path: PathBuf,
+    applications: Vec<BookmarkApplication>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BookmarkApplication {
+    /// Lowercase, like `program`
+    name: String,
+    program: String,
+    modified: String,
+}
+
+/// The list as last read, it is read again off the main loop each time
+/// App Exposé opens
+#[derive(Debug, Default)]
+pub struct RecentDocuments {
+    bookmarks: Vec<Bookmark>,
+    loader: Option<Sender<Vec<Bookmark>>>,
+    loading: bool,
+}
+
+impl RecentDocuments {
+    /// Sends the list to `loader` each time it is read, and reads it now
+    pub fn set_loader(&mut self, loader: Sender<Vec<Bookmark>>) {
+        self.loader = Some(loader);
+        self.reload();
+    }
+
+    /// Reads the list again on a worker thread, unless it is being read
+    pub fn reload(&mut self) {
+        let Some(loader) = self.loader.clone() else {
+            return;
+        };
+        if self.loading {
+            return;
+        }
+        let result = std::thread::Builder::new()
+            .name("recent-documents".into())
+            .spawn(move || {
+                let _ = loader.send(read_bookmarks());
+            });
+        match result {
+            Ok(_) => self.loading = true,
+            Err(err) => warn!(?err, "Failed to read the recent documents"),
+        }
+    }
+
+    /// Keeps the list read off the main loop, returns whether it changed
+    pub fn finish_loading(&mut self, bookmarks: Vec<Bookmark>) -> bool {
+        self.loading = false;
+        let changed = bookmarks != self.bookmarks;
+        self.bookmarks = bookmarks;
+        changed
+    }
+
+    /// Existing documents used by an app known by any of `names`, most
+    /// recent first
+    pub fn for_app(&self, names: &[String], limit: usize) -> Vec<RecentDocument> {
+        let mut documents = documents_for_app(&self.bookmarks, names);
+        documents.truncate(limit);
+        documents
+    }
+}
+
+/// The existing local documents of `recently-used.xbel`
+fn read_bookmarks() -> Vec<Bookmark> {
+    let Some(path) = xdg_data_home().map(|dir| dir.join("recently-used.xbel")) else {
+        return Vec::new();
+    };
+    let Ok(xbel) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let mut bookmarks = parse_bookmarks(&xbel);
+    bookmarks.retain(|bookmark| bookmark.path.exists());
+    bookmarks
+}
+
+/// The local documents of `xbel` and the apps that used them
+fn parse_bookmarks(xbel: &str) -> Vec<Bookmark> {
+    let document = match roxmltree::Document::parse(xbel) {
+        Ok(document) => document,
+        Err(err) => {
+            warn!(%err, "Failed to parse the recent documents");
+            return Vec::new();
+        }
+    };
+    document
+        .root_element()
+        .children()
+        .filter(|node| node.has_tag_name("bookmark"))
+        .filter_map(|bookmark| {
+            let path = bookmark.attribute("href").and_then(file_path)?;
+            let applications = bookmark
+                .descendants()
+                .filter(|node| node.has_tag_name((BOOKMARK_NS, "application")))
+                .map(|application| BookmarkApplication {
+                    name: application
+                        .attribute("name")
+                        .unwrap_or_default()
+                        .to_lowercase(),
+                    program: application
+                        .attribute("exec")
+                        .and_then(exec_program)
+                        .unwrap_or_default()
+                        .to_lowercase(),
+                    modified: application
+                        .attribute("modified")
+                        .or_else(|| bookmark.attribute("modified"))
+                        .unwrap_or_default()
+                        .to_string(),
+                })
+                .collect();
+            Some(Bookmark { path, applications })
+        })
+        .collect()
+}
+
+/// The documents of `bookmarks` opened by an app known by any of `names`,
+/// most recent first. Apps match by name or by the program they run.
+fn documents_for_app(bookmarks: &[Bookmark], names: &[String]) -> Vec<RecentDocument> {
+    let names: Vec<String> = names
+        .iter()
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_lowercase())
+        .collect();
+    let mut documents: Vec<RecentDocument> = bookmarks
+        .iter()
+        .filter_map(|bookmark| {
+            let modified = bookmark
+                .applications
+                .iter()
+                .filter(|application| {
+                    names.contains(&application.name) || names.contains(&application.program)
+                })
+                .map(|application| application.modified.clone())
+                .max()?;
+            let name = bookmark
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            Some(RecentDocument {
+                path: bookmark.path.clone(),
+                name,
+                modified,
+            })
+        })
+        .collect();
+    // Dates are ISO 8601 in UTC, they sort as text
+    documents.sort_by(|a, b| b.modified.cmp(&a.modified));
+    documents
+}
+
+/// Path of a `file://` URI
+fn file_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| path.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    Some(PathBuf::from(String::from_utf8_lossy(&decoded).to_string()))
+}
+
+/// Program name of an `exec` line like `'gedit %u'`
+fn exec_program(exec: &str) -> Option<String> {
+    let exec = exec.trim().trim_matches('\'').trim_matches('"');
+    let program = exec.split_whitespace().next()?;
+    program.rsplit('/').next().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XBEL: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xbel version="1.0"
+      xmlns:bookmark="http://www.freedesktop.org/standards/desktop-bookmarks"
+      xmlns:mime="http://www.freedesktop.org/standards/shared-mime-info">
+  <bookmark href="file:///home/me/notes.txt" added="2024-05-01T09:00:00Z" modified="2024-05-01T09:00:00Z">
+    <info><metadata owner="http://freedesktop.org">
+      <bookmark:applications>
+        <bookmark:application name="gedit" exec="&apos;gedit %u&apos;" modified="2024-05-01T09:00:00Z" count="1"/>
+      </bookmark:applications>
+    </metadata></info>
+  </bookmark>
+  <bookmark href="file:///home/me/Road%20%26%20Trip.odt" modified="2024-05-03T10:00:00Z">
+    <info><metadata owner="http://freedesktop.org">
+      <bookmark:applications>
+        <bookmark:application name="LibreOffice" exec="&apos;/usr/bin/soffice %u&apos;" modified="2024-05-03T10:00:00Z" count="2"/>
+        <bookmark:application name="gedit" exec="&apos;gedit %u&apos;" modified="2024-05-02T08:00:00Z" count="1"/>
+      </bookmark:applications>
+    </metadata></info>
+  </bookmark>
+  <bookmark href="https://example.com/page" modified="2024-05-04T10:00:00Z">
+    <info><metadata owner="http://freedesktop.org">
+      <bookmark:applications>
+        <bookmark:application name="gedit" exec="&apos;gedit %u&apos;" modified="2024-05-04T10:00:00Z" count="1"/>
+      </bookmark:applications>
+    </metadata></info>
+  </bookmark>
+</xbel>"#;
+
+    fn names(documents: &[RecentDocument]) -> Vec<&str> {
+        documents.iter().map(|d| d.name.as_str()).collect()
+    }
+
+    #[test]
+    fn documents_of_an_app_are_most_recent_first() {
+        let documents = documents_for_app(
+            &parse_bookmarks(XBEL),
+            &["org.gnome.gedit".into(), "Gedit".into()],
+        );
+        assert_eq!(names(&documents), ["Road & Trip.odt", "notes.txt"]);
+        assert_eq!(documents[0].path, PathBuf::from("/home/me/Road & Trip.odt"));
+        assert_eq!(documents[0].modified, "2024-05-02T08:00:00Z");
+    }
+
+    #[test]
+    fn apps_match_by_the_program_they_run() {
+        let bookmarks = parse_bookmarks(XBEL);
+        let documents = documents_for_app(&bookmarks, &["soffice".into()]);
+        assert_eq!(names(&documents), ["Road & Trip.odt"]);
+        assert!(documents_for_app(&bookmarks, &["firefox".into()]).is_empty());
+    }
+
+    #[test]
+    fn attributes_are_read_in_any_order_and_quoting() {
+        let xbel = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xbel version="1.0" xmlns:bm="http://www.freedesktop.org/standards/desktop-bookmarks">
+  <bookmark modified='2024-05-05T10:00:00Z' href='file:///home/me/Q&amp;A&#46;txt'>
+    <info><metadata owner="http://freedesktop.org">
+      <bm:applications>
+        <bm:application count="1" exec="&apos;gedit %u&apos;" name="gedit"/>
+      </bm:applications>
+    </metadata></info>
+  </bookmark>
+</xbel>"#;
+        let documents = documents_for_app(&parse_bookmarks(xbel), &["gedit".into()]);
+        assert_eq!(names(&documents), ["Q&A.txt"]);
+        assert_eq!(documents[0].modified, "2024-05-05T10:00:00Z");
+    }
+}
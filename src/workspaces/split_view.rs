@@ -0,0 +1,237 @@
+//! Split View: two windows side by side on a fullscreen workspace
+//!
+//! # SplitDivider Layer Structure
+//!
+//! ```diagram
+//! WorkspaceView
+//! └── workspace_view
+//!     ├── background_view
+//!     ├── workspace_windows_container
+//!     └── split_divider
+//! ```
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use layers::{
+    engine::Engine,
+    prelude::{taffy, Layer},
+    skia,
+    types::Size,
+};
+use smithay::{
+    input::pointer::{ButtonEvent, CursorIcon, CursorImageStatus, MotionEvent},
+    reexports::wayland_server::backend::ObjectId,
+    utils::{Logical, Rectangle},
+};
+
+use crate::{config::Config, interactive_view::ViewInteractions, theme::theme_colors};
+
+/// Width of the divider between the two windows
+pub const DIVIDER_WIDTH: i32 = 8;
+/// Narrowest share of the screen a window can be dragged to
+const MIN_RATIO: f64 = 0.25;
+const MAX_RATIO: f64 = 0.75;
+
+/// The windows of a fullscreen workspace shown side by side
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitView {
+    pub left: ObjectId,
+    pub right: ObjectId,
+    /// Share of the width taken by the left window
+    pub ratio: f64,
+}
+
+impl SplitView {
+    pub fn new(left: ObjectId, right: ObjectId) -> Self {
+        Self {
+            left,
+            right,
+            ratio: 0.5,
+        }
+    }
+
+    pub fn contains(&self, id: &ObjectId) -> bool {
+        self.left == *id || self.right == *id
+    }
+
+    /// The window sharing the workspace with `id`
+    pub fn other(&self, id: &ObjectId) -> Option<&ObjectId> {
+        if self.left == *id {
+            Some(&self.right)
+        } else if self.right == *id {
+            Some(&self.left)
+        } else {
+            None
+        }
+    }
+}
+
+/// Where the windows and the divider go in `area`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitLayout {
+    pub left: Rectangle<i32, Logical>,
+    pub divider: Rectangle<i32, Logical>,
+    pub right: Rectangle<i32, Logical>,
+}
+
+/// Splits `area` with the left window taking `ratio` of the width left by
+/// the divider
+pub fn split_layout(area: Rectangle<i32, Logical>, ratio: f64) -> SplitLayout {
+    let ratio = ratio.clamp(MIN_RATIO, MAX_RATIO);
+    let available = (area.size.w - DIVIDER_WIDTH).max(0);
+    let left_width = (available as f64 * ratio).round() as i32;
+    let (x, y, h) = (area.loc.x, area.loc.y, area.size.h);
+    SplitLayout {
+        left: Rectangle::from_loc_and_size((x, y), (left_width, h)),
+        divider: Rectangle::from_loc_and_size((x + left_width, y), (DIVIDER_WIDTH, h)),
+        right: Rectangle::from_loc_and_size(
+            (x + left_width + DIVIDER_WIDTH, y),
+            (available - left_width, h),
+        ),
+    }
+}
+
+/// The ratio putting the middle of the divider at `x`
+pub fn ratio_at(area: Rectangle<i32, Logical>, x: f64) -> f64 {
+    let available = (area.size.w - DIVIDER_WIDTH).max(1) as f64;
+    let left_width = x - area.loc.x as f64 - DIVIDER_WIDTH as f64 / 2.0;
+    (left_width / available).clamp(MIN_RATIO, MAX_RATIO)
+}
+
+/// The bar between the windows of a split, dragged to resize them
+#[derive(Clone)]
+pub struct SplitDivider {
+    pub layer: Layer,
+    dragging: Arc<AtomicBool>,
+}
+
+impl SplitDivider {
+    pub fn new(layers_engine: &Engine, parent: &Layer) -> Self {
+        let layer = layers_engine.new_layer();
+        layer.set_key("split_divider");
+        layer.set_layout_style(taffy::Style {
+            position: taffy::Position::Absolute,
+            ..Default::default()
+        });
+        layer.set_pointer_events(false);
+        layer.set_hidden(true);
+        layers_engine.append_layer(&layer, Some(parent.id));
+        Self {
+            layer,
+            dragging: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Shows the divider over `rect`, in workspace coordinates
+    pub fn show(&self, rect: Rectangle<i32, Logical>) {
+        let scale = Config::with(|config| config.screen_scale);
+        let rect = rect.to_f64().to_physical(scale);
+        self.layer
+            .set_position((rect.loc.x as f32, rect.loc.y as f32), None);
+        self.layer
+            .set_size(Size::points(rect.size.w as f32, rect.size.h as f32), None);
+        let colors = theme_colors();
+        let background = colors.materials_thick.c4f();
+        let handle = colors.text_tertiary.c4f();
+        self.layer
+            .set_draw_content(move |canvas: &skia::Canvas, w: f32, h: f32| {
+                let bounds = skia::Rect::from_wh(w, h);
+                canvas.draw_rect(bounds, &skia::Paint::new(background, None));
+                let handle_w = w / 2.0;
+                let handle_h = (w * 6.0).min(h);
+                let handle_rect = skia::Rect::from_xywh(
+                    (w - handle_w) / 2.0,
+                    (h - handle_h) / 2.0,
+                    handle_w,
+                    handle_h,
+                );
+                let mut paint = skia::Paint::new(handle, None);
+                paint.set_anti_alias(true);
+                canvas.draw_round_rect(handle_rect, handle_w / 2.0, handle_w / 2.0, &paint);
+                bounds
+            });
+        self.layer.set_hidden(false);
+    }
+
+    pub fn hide(&self) {
+        self.dragging.store(false, Ordering::SeqCst);
+        self.layer.set_hidden(true);
+    }
+}
+
+impl<Backend: crate::state::Backend> ViewInteractions<Backend> for SplitDivider {
+    fn id(&self) -> Option<usize> {
+        Some(self.layer.id.0.into())
+    }
+    fn is_alive(&self) -> bool {
+        !self.layer.hidden()
+    }
+    fn on_motion(
+        &self,
+        _seat: &smithay::input::Seat<crate::Otto<Backend>>,
+        data: &mut crate::Otto<Backend>,
+        event: &MotionEvent,
+    ) {
+        data.set_cursor(&CursorImageStatus::Named(CursorIcon::ColResize));
+        if self.dragging.load(Ordering::SeqCst) {
+            data.resize_split_view(event.location.x);
+        }
+    }
+    fn on_leave(&self, _serial: smithay::utils::Serial, _time: u32) {
+        self.dragging.store(false, Ordering::SeqCst);
+    }
+    fn on_button(
+        &self,
+        _seat: &smithay::input::Seat<crate::Otto<Backend>>,
+        data: &mut crate::Otto<Backend>,
+        event: &ButtonEvent,
+    ) {
+        let pressed = event.state == smithay::backend::input::ButtonState::Pressed;
+        self.dragging.store(pressed, Ordering::SeqCst);
+        if !pressed {
+            data.set_cursor(&CursorImageStatus::default_named());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen() -> Rectangle<i32, Logical> {
+        Rectangle::from_loc_and_size((0, 0), (1928, 1080))
+    }
+
+    #[test]
+    fn halves_share_the_width_around_the_divider() {
+        let layout = split_layout(screen(), 0.5);
+        assert_eq!(
+            layout.left,
+            Rectangle::from_loc_and_size((0, 0), (960, 1080))
+        );
+        assert_eq!(
+            layout.divider,
+            Rectangle::from_loc_and_size((960, 0), (DIVIDER_WIDTH, 1080))
+        );
+        assert_eq!(
+            layout.right,
+            Rectangle::from_loc_and_size((968, 0), (960, 1080))
+        );
+    }
+
+    #[test]
+    fn dragging_the_divider_is_clamped() {
+        let area = screen();
+        let ratio = ratio_at(area, 480.0 + DIVIDER_WIDTH as f64 / 2.0);
+        assert!((ratio - 0.25).abs() < 1e-9, "ratio {ratio}");
+        assert_eq!(ratio_at(area, 10.0), MIN_RATIO);
+        assert_eq!(ratio_at(area, 1920.0), MAX_RATIO);
+
+        let layout = split_layout(area, 0.9);
+        assert_eq!(layout.left.size.w, 1440);
+        assert_eq!(layout.right.size.w, 480);
+    }
+}
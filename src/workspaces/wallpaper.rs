@@ -0,0 +1,392 @@
+//! Loading the wallpapers of `[wallpaper]` and running their slideshows
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use layers::{skia, types::Color};
+use rand::seq::SliceRandom;
+use smithay::reexports::calloop::channel::Sender;
+use tracing::warn;
+
+use crate::{
+    config::{
+        files::expand_home,
+        wallpaper::{ScalingMode, Wallpaper},
+    },
+    theme::{parse_color, ThemeScheme},
+    utils::image_from_path,
+};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(600);
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "svg"];
+
+#[derive(Clone)]
+pub enum WallpaperFill {
+    Image(skia::Image),
+    Color(Color),
+    /// Colors from top to bottom, rotated clockwise by `angle` degrees
+    Gradient {
+        colors: Vec<Color>,
+        angle: f32,
+    },
+}
+
+/// What a background view draws
+#[derive(Clone)]
+pub struct WallpaperContent {
+    /// Identifies the content, a different one cross-fades
+    pub key: String,
+    pub fill: WallpaperFill,
+    pub mode: ScalingMode,
+    /// Shown around images that do not cover the screen
+    pub backdrop: Color,
+}
+
+impl WallpaperContent {
+    pub fn image(key: String, image: skia::Image, mode: ScalingMode) -> Self {
+        Self {
+            key,
+            fill: WallpaperFill::Image(image),
+            mode,
+            backdrop: Color::new_rgba(0.0, 0.0, 0.0, 1.0),
+        }
+    }
+}
+
+struct Slideshow {
+    images: Vec<PathBuf>,
+    position: usize,
+    interval: Duration,
+    shuffle: bool,
+    shown_at: Instant,
+}
+
+impl Slideshow {
+    fn new(directory: &Path, interval: Duration, shuffle: bool) -> Self {
+        let mut slideshow = Self {
+            images: list_images(directory),
+            position: 0,
+            interval,
+            shuffle,
+            shown_at: Instant::now(),
+        };
+        if shuffle {
+            slideshow.images.shuffle(&mut rand::thread_rng());
+        }
+        slideshow
+    }
+
+    fn current(&self) -> Option<&PathBuf> {
+        self.images.get(self.position)
+    }
+
+    fn advance(&mut self, now: Instant) {
+        self.shown_at = now;
+        self.position += 1;
+        if self.position >= self.images.len() {
+            self.position = 0;
+            if self.shuffle {
+                self.images.shuffle(&mut rand::thread_rng());
+            }
+        }
+    }
+}
+
+/// An image decoded off the main loop, `None` if it could not be
+pub type DecodedWallpaper = (PathBuf, Option<skia::Image>);
+
+/// Decoded images and slideshow positions of the wallpapers shown
+#[derive(Default)]
+pub struct WallpaperLoader {
+    images: HashMap<PathBuf, skia::Image>,
+    /// By directory, workspaces showing the same one stay in step
+    slideshows: HashMap<PathBuf, Slideshow>,
+    /// Images being decoded, they are not shown until they are
+    decoding: HashSet<PathBuf>,
+    /// Images that could not be decoded, not tried again while shown
+    failed: HashSet<PathBuf>,
+    decoder: Option<Sender<DecodedWallpaper>>,
+}
+
+impl WallpaperLoader {
+    /// Decodes images on a thread of their own and sends them to `decoder`;
+    /// images asked for before are decoded now
+    pub fn set_decoder(&mut self, decoder: Sender<DecodedWallpaper>) {
+        for path in &self.decoding {
+            decode_off_thread(path.clone(), decoder.clone());
+        }
+        self.decoder = Some(decoder);
+    }
+
+    /// Keeps an image decoded off the main loop, returns whether it can be
+    /// shown
+    pub fn finish_decoding(&mut self, (path, image): DecodedWallpaper) -> bool {
+        self.decoding.remove(&path);
+        match image {
+            Some(image) => {
+                self.images.insert(path, image);
+                true
+            }
+            None => {
+                warn!(path = %path.display(), "Failed to load the wallpaper");
+                self.failed.insert(path);
+                false
+            }
+        }
+    }
+
+    /// Loads the content of each wallpaper; images and slideshows no longer
+    /// shown are dropped. `None` where nothing could be loaded yet, images
+    /// not decoded before are decoded off the main loop.
+    pub fn load_all(
+        &mut self,
+        wallpapers: &[Wallpaper],
+        scheme: ThemeScheme,
+    ) -> Vec<Option<WallpaperContent>> {
+        let mut images = HashMap::new();
+        let mut slideshows = HashMap::new();
+        let mut failed = HashSet::new();
+        let contents = wallpapers
+            .iter()
+            .map(|wallpaper| {
+                self.load(wallpaper, scheme, &mut images, &mut slideshows, &mut failed)
+            })
+            .collect();
+        self.images = images;
+        self.slideshows = slideshows;
+        self.failed = failed;
+        contents
+    }
+
+    fn load(
+        &mut self,
+        wallpaper: &Wallpaper,
+        scheme: ThemeScheme,
+        images: &mut HashMap<PathBuf, skia::Image>,
+        slideshows: &mut HashMap<PathBuf, Slideshow>,
+        failed: &mut HashSet<PathBuf>,
+    ) -> Option<WallpaperContent> {
+        let mode = wallpaper.mode.unwrap_or_default();
+        let color = wallpaper.color.as_deref().and_then(parse_color);
+        let backdrop = color.unwrap_or(Color::new_rgba(0.0, 0.0, 0.0, 1.0));
+
+        let mut path = None;
+        if let Some(directory) = &wallpaper.directory {
+            let directory = expand_home(directory);
+            if !slideshows.contains_key(&directory) {
+                let slideshow = self.slideshows.remove(&directory).unwrap_or_else(|| {
+                    let interval = wallpaper
+                        .interval_secs
+                        .map_or(DEFAULT_INTERVAL, Duration::from_secs);
+                    Slideshow::new(&directory, interval, wallpaper.shuffle.unwrap_or(false))
+                });
+                slideshows.insert(directory.clone(), slideshow);
+            }
+            path = slideshows[&directory].current().cloned();
+            if path.is_none() {
+                warn!(directory = %directory.display(), "No images for the wallpaper slideshow");
+            }
+        }
+        let path = path.or_else(|| wallpaper.image_for(scheme).map(expand_home));
+
+        if let Some(path) = path {
+            let image = images
+                .get(&path)
+                .cloned()
+                .or_else(|| self.images.remove(&path));
+            let Some(image) = image else {
+                if self.failed.contains(&path) {
+                    failed.insert(path);
+                } else {
+                    self.decode(path);
+                }
+                return None;
+            };
+            images.insert(path.clone(), image.clone());
+            return Some(WallpaperContent {
+                key: path.display().to_string(),
+                fill: WallpaperFill::Image(image),
+                mode,
+                backdrop,
+            });
+        }
+
+        let gradient: Vec<Color> = wallpaper
+            .gradient
+            .iter()
+            .filter_map(|color| parse_color(color))
+            .collect();
+        if gradient.len() >= 2 {
+            let angle = wallpaper.gradient_angle.unwrap_or(0.0);
+            return Some(WallpaperContent {
+                key: format!("gradient {:?} {angle}", wallpaper.gradient),
+                fill: WallpaperFill::Gradient {
+                    colors: gradient,
+                    angle,
+                },
+                mode,
+                backdrop,
+            });
+        }
+
+        color.map(|color| WallpaperContent {
+            key: format!("color {:?}", wallpaper.color),
+            fill: WallpaperFill::Color(color),
+            mode,
+            backdrop,
+        })
+    }
+
+    fn decode(&mut self, path: PathBuf) {
+        if !self.decoding.insert(path.clone()) {
+            return;
+        }
+        if let Some(decoder) = &self.decoder {
+            decode_off_thread(path, decoder.clone());
+        }
+    }
+
+    /// Moves the slideshows whose interval is over to their next image.
+    /// Returns whether any did.
+    pub fn advance_slideshows(&mut self, now: Instant) -> bool {
+        let mut advanced = false;
+        for slideshow in self.slideshows.values_mut() {
+            if slideshow.images.len() > 1 && now >= slideshow.shown_at + slideshow.interval {
+                slideshow.advance(now);
+                advanced = true;
+            }
+        }
+        advanced
+    }
+}
+
+/// Reads and decodes the image at `path` without blocking the main loop
+fn decode_off_thread(path: PathBuf, decoder: Sender<DecodedWallpaper>) {
+    let result = std::thread::Builder::new()
+        .name("wallpaper-decode".into())
+        .spawn(move || {
+            // Images from files are decoded when first drawn, unless done here
+            let image = image_from_path(&path.to_string_lossy(), (2048, 2048)).map(|image| {
+                image
+                    .make_raster_image(None, skia::image::CachingHint::Allow)
+                    .unwrap_or(image)
+            });
+            let _ = decoder.send((path, image));
+        });
+    if let Err(err) = result {
+        warn!(?err, "Failed to decode the wallpaper");
+    }
+}
+
+/// Image files of `directory` sorted by name
+fn list_images(directory: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let mut images: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| {
+                    IMAGE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+                })
+        })
+        .collect();
+    images.sort();
+    images
+}
+
+/// Draws `content` over a `w` × `h` screen
+pub fn draw_wallpaper(canvas: &skia::Canvas, w: f32, h: f32, content: &WallpaperContent) {
+    let screen = skia::Rect::from_xywh(0.0, 0.0, w, h);
+    let mut paint = skia::Paint::new(content.backdrop.c4f(), None);
+    canvas.draw_rect(screen, &paint);
+
+    match &content.fill {
+        WallpaperFill::Color(color) => {
+            paint.set_color4f(color.c4f(), None);
+            canvas.draw_rect(screen, &paint);
+        }
+        WallpaperFill::Gradient { colors, angle } => {
+            let radians = angle.to_radians();
+            let direction = (-radians.sin(), radians.cos());
+            let half = (w * direction.0.abs() + h * direction.1.abs()) / 2.0;
+            let center = (w / 2.0, h / 2.0);
+            let start = (center.0 - direction.0 * half, center.1 - direction.1 * half);
+            let end = (center.0 + direction.0 * half, center.1 + direction.1 * half);
+            let colors: Vec<skia::Color> = colors.iter().map(|c| c.c4f().to_color()).collect();
+            paint.set_shader(skia::Shader::linear_gradient(
+                (start, end),
+                colors.as_slice(),
+                None,
+                skia::TileMode::Clamp,
+                None,
+                None,
+            ));
+            canvas.draw_rect(screen, &paint);
+        }
+        WallpaperFill::Image(image) => {
+            let (image_w, image_h) = (image.width() as f32, image.height() as f32);
+            let (scale_x, scale_y, tile) = match content.mode {
+                ScalingMode::Fill => {
+                    let scale = (w / image_w).max(h / image_h);
+                    (scale, scale, false)
+                }
+                ScalingMode::Fit => {
+                    let scale = (w / image_w).min(h / image_h);
+                    (scale, scale, false)
+                }
+                ScalingMode::Center => (1.0, 1.0, false),
+                ScalingMode::Tile => (1.0, 1.0, true),
+                ScalingMode::Stretch => (w / image_w, h / image_h, false),
+            };
+            let (offset_x, offset_y) = if tile {
+                (0.0, 0.0)
+            } else {
+                ((w - image_w * scale_x) / 2.0, (h - image_h * scale_y) / 2.0)
+            };
+            let mut matrix = skia::Matrix::new_identity();
+            matrix.set_scale_translate((scale_x, scale_y), (offset_x, offset_y));
+            let tile_mode = if tile {
+                skia::TileMode::Repeat
+            } else {
+                skia::TileMode::Decal
+            };
+            paint.set_color4f(skia::Color4f::new(1.0, 1.0, 1.0, 1.0), None);
+            paint.set_shader(image.to_shader(
+                (tile_mode, tile_mode),
+                skia::SamplingOptions::default(),
+                &matrix,
+            ));
+            canvas.draw_rect(screen, &paint);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slideshows_list_images_by_name_and_wrap_around() {
+        let directory = std::env::temp_dir().join(format!("otto-slideshow-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        for name in ["b.png", "a.JPG", "notes.txt"] {
+            std::fs::write(directory.join(name), b"").unwrap();
+        }
+
+        let mut slideshow = Slideshow::new(&directory, Duration::from_secs(60), false);
+        assert_eq!(slideshow.images.len(), 2);
+        assert_eq!(slideshow.current(), Some(&directory.join("a.JPG")));
+        slideshow.advance(Instant::now());
+        assert_eq!(slideshow.current(), Some(&directory.join("b.png")));
+        slideshow.advance(Instant::now());
+        assert_eq!(slideshow.current(), Some(&directory.join("a.JPG")));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
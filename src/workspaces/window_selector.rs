@@ -16,7 +16,7 @@ use std::{
 use crate::{
     config::Config,
     interactive_view::ViewInteractions,
-    theme::theme_colors,
+    theme::{self, accent_color, component_style, Component},
     utils::natural_layout::{natural_layout, LayoutRect},
 };
 
@@ -25,6 +25,8 @@ use super::{utils::FONT_CACHE, WORKSPACE_SELECTOR_PREVIEW_WIDTH};
 // Logical (unscaled) values - will be multiplied by screen scale when used
 const WINDOW_SELECTOR_DRAG_THRESHOLD_LOGICAL: f32 = 1.5;
 const WORKSPACE_SELECTOR_TARGET_Y_LOGICAL: f32 = 200.0;
+const WINDOW_CONTROL_SIZE_LOGICAL: f32 = 22.0;
+const BTN_MIDDLE: u32 = 0x112;
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct WindowSelection {
@@ -41,6 +43,16 @@ pub struct WindowSelection {
 pub struct WindowSelectorState {
     pub rects: Vec<WindowSelection>,
     pub current_selection: Option<usize>,
+    /// Text typed to filter the windows, shown above them
+    pub filter: String,
+    /// Centre of the filter text
+    pub filter_center: (f32, f32),
+    /// Area of the workspace in global exposé, drawn behind its windows
+    pub group: Option<LayoutRect>,
+    /// Button of the highlighted window under the pointer
+    pub hovered_control: Option<WindowControl>,
+    /// [`theme::generation`] of the rendering
+    pub theme: u64,
 }
 
 impl Hash for WindowSelectorState {
@@ -51,6 +63,12 @@ impl Hash for WindowSelectorState {
             .map(|x| self.rects.get(*x).unwrap());
         current.hash(state);
         self.rects.hash(state);
+        self.filter.hash(state);
+        self.filter_center.0.to_bits().hash(state);
+        self.filter_center.1.to_bits().hash(state);
+        self.group.hash(state);
+        self.hovered_control.hash(state);
+        self.theme.hash(state);
     }
 }
 
@@ -64,6 +82,159 @@ impl Hash for WindowSelection {
     }
 }
 
+/// Buttons shown on the window under the pointer in exposé
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowControl {
+    Close,
+    /// Minimizes the window to the dock
+    Minimize,
+    /// Moves the window to a new fullscreen workspace
+    Fullscreen,
+}
+
+impl WindowControl {
+    pub const ALL: [WindowControl; 3] = [Self::Close, Self::Minimize, Self::Fullscreen];
+
+    /// Centre of the button on `rect`, the buttons sit in a row in its top
+    /// left corner
+    fn center(self, rect: &WindowSelection, size: f32) -> (f32, f32) {
+        let index = Self::ALL
+            .iter()
+            .position(|control| *control == self)
+            .unwrap_or_default();
+        (rect.x + size + index as f32 * size * 1.5, rect.y + size)
+    }
+
+    /// The button of `rect` under `point`, for buttons `size` wide
+    pub fn at(rect: &WindowSelection, point: (f32, f32), size: f32) -> Option<Self> {
+        Self::ALL.into_iter().find(|control| {
+            let (x, y) = control.center(rect, size);
+            (point.0 - x).hypot(point.1 - y) <= size / 2.0
+        })
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Self::Close => Color::new_rgba(1.0, 0.37, 0.34, 1.0),
+            Self::Minimize => Color::new_rgba(1.0, 0.74, 0.18, 1.0),
+            Self::Fullscreen => Color::new_rgba(0.16, 0.78, 0.25, 1.0),
+        }
+    }
+
+    /// Draws the button centred on `center`, with its symbol when `symbol`
+    fn draw(self, canvas: &skia::Canvas, center: (f32, f32), size: f32, symbol: bool) {
+        let mut paint = skia::Paint::new(self.color().c4f(), None);
+        paint.set_anti_alias(true);
+        canvas.draw_circle(center, size / 2.0, &paint);
+        if !symbol {
+            return;
+        }
+        let mut paint = skia::Paint::new(Color::new_rgba(0.0, 0.0, 0.0, 0.6).c4f(), None);
+        paint.set_anti_alias(true);
+        paint.set_stroke_width(size / 10.0);
+        paint.set_stroke_cap(skia::paint::Cap::Round);
+        let (x, y) = center;
+        let d = size / 5.0;
+        match self {
+            Self::Close => {
+                canvas.draw_line((x - d, y - d), (x + d, y + d), &paint);
+                canvas.draw_line((x - d, y + d), (x + d, y - d), &paint);
+            }
+            Self::Minimize => {
+                canvas.draw_line((x - d * 1.3, y), (x + d * 1.3, y), &paint);
+            }
+            Self::Fullscreen => {
+                let mut path = skia::Path::new();
+                path.move_to((x - d * 1.2, y - d * 1.2));
+                path.line_to((x + d * 0.6, y - d * 1.2));
+                path.line_to((x - d * 1.2, y + d * 0.6));
+                path.close();
+                path.move_to((x + d * 1.2, y + d * 1.2));
+                path.line_to((x - d * 0.6, y + d * 1.2));
+                path.line_to((x + d * 1.2, y - d * 0.6));
+                path.close();
+                canvas.draw_path(&path, &paint);
+            }
+        }
+    }
+}
+
+/// Size of the buttons of the window under the pointer in exposé
+fn window_control_size() -> f32 {
+    WINDOW_CONTROL_SIZE_LOGICAL * Config::with(|config| config.screen_scale) as f32
+}
+
+/// Direction of a keyboard move between the windows of exposé
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// The window next to the one at `current` in `direction`: the nearest
+/// one whose centre lies that way, preferring windows in line with it
+pub fn neighbour_selection(
+    rects: &[WindowSelection],
+    current: usize,
+    direction: SelectionDirection,
+) -> Option<usize> {
+    let center = |rect: &WindowSelection| (rect.x + rect.w / 2.0, rect.y + rect.h / 2.0);
+    let (cx, cy) = center(rects.get(current)?);
+    rects
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != current)
+        .filter_map(|(index, rect)| {
+            let (x, y) = center(rect);
+            let (along, across) = match direction {
+                SelectionDirection::Left => (cx - x, y - cy),
+                SelectionDirection::Right => (x - cx, y - cy),
+                SelectionDirection::Up => (cy - y, x - cx),
+                SelectionDirection::Down => (y - cy, x - cx),
+            };
+            (along > 0.0).then_some((index, along + across.abs() * 2.0))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index)
+}
+
+/// Whether any of `texts` contains `filter`, ignoring case. An empty filter
+/// matches everything.
+pub fn matches_filter(filter: &str, texts: &[&str]) -> bool {
+    let filter = filter.to_lowercase();
+    texts
+        .iter()
+        .any(|text| text.to_lowercase().contains(&filter))
+}
+
+/// Cells of a grid sharing `area` between the `count` workspaces of global
+/// exposé, row by row with `gap` between them. A last row that is not full
+/// is centred.
+pub fn workspace_group_cells(count: usize, area: &LayoutRect, gap: f32) -> Vec<LayoutRect> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let columns = (count as f32).sqrt().ceil() as usize;
+    let rows = count.div_ceil(columns);
+    let width = (area.width - gap * (columns - 1) as f32) / columns as f32;
+    let height = (area.height - gap * (rows - 1) as f32) / rows as f32;
+    (0..count)
+        .map(|index| {
+            let (row, column) = (index / columns, index % columns);
+            let in_row = columns.min(count - row * columns);
+            let indent = (columns - in_row) as f32 * (width + gap) / 2.0;
+            LayoutRect::new(
+                area.x + indent + column as f32 * (width + gap),
+                area.y + row as f32 * (height + gap),
+                width,
+                height,
+            )
+        })
+        .collect()
+}
+
 #[derive(Clone, Hash)]
 pub struct WindowSelectorWindow {
     pub id: ObjectId,
@@ -113,6 +284,8 @@ pub struct WindowSelectorView {
     pub press_location: Arc<RwLock<Option<(f32, f32)>>>,
     pub pointer_down: Arc<AtomicBool>,
     pub pressed_selection: Arc<RwLock<Option<WindowSelection>>>,
+    /// Button of a window pressed, it runs when released over it
+    pub pressed_control: Arc<RwLock<Option<WindowControl>>>,
     pub drag_state: Arc<RwLock<Option<DragState>>>,
     pub expose_bin: Arc<RwLock<HashMap<ObjectId, LayoutRect>>>,
     layout_hash: Arc<RwLock<u64>>,
@@ -172,6 +345,11 @@ impl WindowSelectorView {
         let state = WindowSelectorState {
             rects: vec![],
             current_selection: None,
+            filter: String::new(),
+            filter_center: (0.0, 0.0),
+            group: None,
+            hovered_control: None,
+            theme: theme::generation(),
         };
         let view = layers::prelude::View::new(
             format!("window_selector_view_{}", index),
@@ -220,11 +398,76 @@ impl WindowSelectorView {
             press_location: Arc::new(RwLock::new(None)),
             pointer_down: Arc::new(AtomicBool::new(false)),
             pressed_selection: Arc::new(RwLock::new(None)),
+            pressed_control: Arc::new(RwLock::new(None)),
             drag_state: Arc::new(RwLock::new(None)),
             expose_bin: Arc::new(RwLock::new(HashMap::new())),
             layout_hash: Arc::new(RwLock::new(0)),
         }
     }
+    /// Renders the labels and selection again with the current theme
+    pub fn reload_theme(&self) {
+        let mut state = self.view.get_state();
+        state.theme = theme::generation();
+        self.view.update_state(&state);
+    }
+
+    /// The highlighted window
+    pub fn selection(&self) -> Option<WindowSelection> {
+        let state = self.view.get_state();
+        state
+            .current_selection
+            .and_then(|index| state.rects.get(index).cloned())
+    }
+
+    /// Highlights the window at `index` in the selector state
+    pub fn select(&self, index: Option<usize>) {
+        let mut state = self.view.get_state();
+        state.current_selection = index.filter(|index| *index < state.rects.len());
+        self.view.update_state(&state);
+    }
+
+    /// The windows laid out, in order
+    pub fn rects(&self) -> Vec<WindowSelection> {
+        self.view.get_state().rects.clone()
+    }
+
+    /// Shows the text typed to filter the windows
+    pub fn set_filter(&self, filter: &str) {
+        let mut state = self.view.get_state();
+        if state.filter != filter {
+            state.filter = filter.to_string();
+            self.view.update_state(&state);
+        }
+    }
+
+    /// Moves the text typed to filter the windows
+    pub fn set_filter_center(&self, center: (f32, f32)) {
+        let mut state = self.view.get_state();
+        if state.filter_center != center {
+            state.filter_center = center;
+            self.view.update_state(&state);
+        }
+    }
+
+    /// Sets the area drawn behind the windows in global exposé
+    pub fn set_group(&self, group: Option<LayoutRect>) {
+        let mut state = self.view.get_state();
+        if state.group != group {
+            state.group = group;
+            self.view.update_state(&state);
+        }
+    }
+
+    /// Whether `point` lies in the area of the workspace in global exposé
+    pub fn group_contains(&self, point: (f32, f32)) -> bool {
+        self.view.get_state().group.is_some_and(|group| {
+            point.0 >= group.x
+                && point.0 < group.x + group.width
+                && point.1 >= group.y
+                && point.1 < group.y + group.height
+        })
+    }
+
     pub fn layer_for_window(&self, window: &ObjectId) -> Option<Layer> {
         self.windows.read().unwrap().get(window).cloned()
     }
@@ -388,33 +631,29 @@ impl WindowSelectorView {
                 .map(|s| s.window_id.clone());
         }
 
-        // Do not allow dragging when the current workspace is fullscreen
-        if otto
-            .workspaces
-            .get_current_workspace()
-            .get_fullscreen_mode()
-        {
-            return None;
-        }
-
         let selection = self.pressed_selection.read().unwrap().clone();
         let selection = selection?;
 
         if let Some(window_id) = &selection.window_id {
-            let window_in_space = otto
-                .workspaces
-                .space()
-                .elements()
-                .any(|w| w.id() == *window_id);
-
-            if !window_in_space {
+            let window = otto.workspaces.windows_map.get(window_id)?;
+            let workspace_index = otto.workspaces.window_workspace_index(window)?;
+
+            // Global exposé shows the windows of every workspace, otherwise
+            // only those of the current one can be dragged
+            if !otto.workspaces.is_expose_global()
+                && workspace_index != otto.workspaces.get_current_workspace_index()
+            {
                 return None;
             }
 
-            if let Some(window) = otto.workspaces.windows_map.get(window_id) {
-                if window.is_fullscreen() {
-                    return None;
-                }
+            // Do not allow dragging out of a fullscreen workspace
+            if otto
+                .workspaces
+                .get_workspace_at(workspace_index)
+                .is_some_and(|workspace| workspace.get_fullscreen_mode())
+                || window.is_fullscreen()
+            {
+                return None;
             }
         }
         let window_layer = selection
@@ -475,20 +714,21 @@ impl WindowSelectorView {
 }
 
 pub fn get_paragraph_for_text(text: &str, font_size: f32) -> skia::textlayout::Paragraph {
+    let style = component_style(Component::Expose);
     let mut text_style = skia::textlayout::TextStyle::new();
 
     text_style.set_font_size(font_size);
     let font_style = skia::FontStyle::new(
-        skia::font_style::Weight::SEMI_BOLD,
+        style.font_weight(skia::font_style::Weight::SEMI_BOLD),
         skia::font_style::Width::CONDENSED,
         skia::font_style::Slant::Upright,
     );
     text_style.set_font_style(font_style);
     text_style.set_letter_spacing(-1.0);
-    let foreground_paint = skia::Paint::new(skia::Color4f::new(0.1, 0.1, 0.1, 0.9), None);
+    let text_color = style.text_color(Color::new_rgba(0.1, 0.1, 0.1, 0.9));
+    let foreground_paint = skia::Paint::new(text_color.c4f(), None);
     text_style.set_foreground_paint(&foreground_paint);
-    let ff = Config::with(|c| c.font_family.clone());
-    text_style.set_font_families(&[ff]);
+    text_style.set_font_families(&[style.font_family()]);
 
     let mut paragraph_style = skia::textlayout::ParagraphStyle::new();
     paragraph_style.set_text_style(&text_style);
@@ -514,7 +754,8 @@ pub fn view_window_selector(
 ) -> LayerTree {
     let draw_scale = Config::with(|config| config.screen_scale) as f32 * 0.8;
 
-    let font_size: f32 = 20.0 * draw_scale;
+    let style = component_style(Component::Expose);
+    let font_size: f32 = style.font_size(20.0 * draw_scale);
     let current = state
         .current_selection
         .map(|x| state.rects.get(x).unwrap().clone())
@@ -538,10 +779,24 @@ pub fn view_window_selector(
         .as_ref()
         .map(|(window_selection, _)| window_selection.clone());
 
+    let accent_color = accent_color();
+    let group = state.group;
+    let group_color = style.background_color(Color::new_rgba(1.0, 1.0, 1.0, 0.1));
+    let hovered_control = state.hovered_control;
+    let control_size = window_control_size();
     let draw_container = Some(move |canvas: &skia::Canvas, w, h| {
+        if let Some(group) = group {
+            let paint = skia::Paint::new(group_color.c4f(), None);
+            let rrect = skia::RRect::new_rect_xy(
+                skia::Rect::from_xywh(group.x, group.y, group.width, group.height),
+                16.0 * draw_scale,
+                16.0 * draw_scale,
+            );
+            canvas.draw_rrect(rrect, &paint);
+        }
         if window_selection.is_some() {
             let window_selection = window_selection.as_ref().unwrap();
-            let color = theme_colors().accents_blue.c4f();
+            let color = accent_color.c4f();
             let mut paint = skia::Paint::new(color, None);
             paint.set_stroke(true);
             paint.set_stroke_width(12.0 * draw_scale);
@@ -558,6 +813,12 @@ pub fn view_window_selector(
             );
 
             canvas.draw_rrect(rrect, &paint);
+
+            // The symbols show once the pointer is on one of the buttons
+            for control in WindowControl::ALL {
+                let center = control.center(window_selection, control_size);
+                control.draw(canvas, center, control_size, hovered_control.is_some());
+            }
         }
         skia::Rect::from_xywh(0.0, 0.0, w, h)
     });
@@ -582,6 +843,59 @@ pub fn view_window_selector(
             text_bounding_box.height() + text_padding_y * 2.0
         },
     );
+    let mut children = vec![LayerTreeBuilder::default()
+        .key("window_selector_label")
+        .layout_style(taffy::Style {
+            position: taffy::Position::Absolute,
+            ..Default::default()
+        })
+        .position((
+            (
+                text_rect.x + text_rect.w / 2.0 - text_bounding_box.width() / 2.0,
+                text_rect.y + text_rect.h / 2.0 - text_bounding_box.height() / 2.0,
+            )
+                .into(),
+            None,
+        ))
+        .size((text_layer_size, None))
+        .blend_mode(style.blend_mode())
+        .border_corner_radius((
+            BorderRadius::new_single(style.corner_radius(8.0 * draw_scale)),
+            None,
+        ))
+        .background_color((
+            PaintColor::Solid {
+                color: style.background_color(Color::new_rgba(1.0, 1.0, 1.0, 0.4)),
+            },
+            None,
+        ))
+        .shadow_color((
+            style.shadow_color(Color::new_rgba(0.0, 0.0, 0.0, 0.2)),
+            None,
+        ))
+        .shadow_offset(((0.0, 0.0).into(), None))
+        .shadow_radius((5.0, None))
+        // .shadow_spread((10.0, None))
+        .content(Some(move |canvas: &skia::Canvas, w, h| {
+            let mut paragraph = get_paragraph_for_text(&text_rect.window_title, font_size);
+            paragraph.layout(w);
+            // let text_x = TEXT_PADDING_X;
+            let text_y = text_padding_y;
+
+            paragraph.paint(canvas, (0.0, text_y));
+            let safe = 200.0 * draw_scale;
+            skia::Rect::from_xywh(-safe, -safe, w + safe * 2.0, h + safe * 2.0)
+        }))
+        .build()
+        .unwrap()];
+    if !state.filter.is_empty() {
+        children.push(view_filter_label(
+            &state.filter,
+            state.filter_center,
+            font_size,
+            draw_scale,
+        ));
+    }
     LayerTreeBuilder::default()
         .key(view.get_key())
         .position(((0.0, 0.0).into(), None))
@@ -589,45 +903,70 @@ pub fn view_window_selector(
         .content(draw_container)
         // .picture_cached(false)
         // .image_cache(false)
-        .children(vec![LayerTreeBuilder::default()
-            .key("window_selector_label")
-            .layout_style(taffy::Style {
-                position: taffy::Position::Absolute,
-                ..Default::default()
-            })
-            .position((
-                (
-                    text_rect.x + text_rect.w / 2.0 - text_bounding_box.width() / 2.0,
-                    text_rect.y + text_rect.h / 2.0 - text_bounding_box.height() / 2.0,
-                )
-                    .into(),
-                None,
-            ))
-            .size((text_layer_size, None))
-            .blend_mode(layers::prelude::BlendMode::BackgroundBlur)
-            .border_corner_radius((BorderRadius::new_single(8.0 * draw_scale), None))
-            .background_color((
-                PaintColor::Solid {
-                    color: Color::new_rgba(1.0, 1.0, 1.0, 0.4),
-                },
-                None,
-            ))
-            .shadow_color((Color::new_rgba(0.0, 0.0, 0.0, 0.2), None))
-            .shadow_offset(((0.0, 0.0).into(), None))
-            .shadow_radius((5.0, None))
-            // .shadow_spread((10.0, None))
-            .content(Some(move |canvas: &skia::Canvas, w, h| {
-                let mut paragraph = get_paragraph_for_text(&text_rect.window_title, font_size);
-                paragraph.layout(w);
-                // let text_x = TEXT_PADDING_X;
-                let text_y = text_padding_y;
-
-                paragraph.paint(canvas, (0.0, text_y));
-                let safe = 200.0 * draw_scale;
-                skia::Rect::from_xywh(-safe, -safe, w + safe * 2.0, h + safe * 2.0)
-            }))
-            .build()
-            .unwrap()])
+        .children(children)
+        .build()
+        .unwrap()
+}
+
+/// The filter typed in exposé, centred on `center`
+fn view_filter_label(
+    filter: &str,
+    center: (f32, f32),
+    font_size: f32,
+    draw_scale: f32,
+) -> LayerTree {
+    let style = component_style(Component::Expose);
+    let text = filter.to_string();
+    let mut paragraph = get_paragraph_for_text(&text, font_size);
+    paragraph.layout(1000.0 * draw_scale);
+    let bounds = paragraph
+        .get_rects_for_range(
+            0..text.len(),
+            skia::textlayout::RectHeightStyle::Tight,
+            skia::textlayout::RectWidthStyle::Tight,
+        )
+        .iter()
+        .fold(skia::Rect::new_empty(), |acc, b| {
+            skia::Rect::join2(acc, b.rect)
+        });
+    let padding_x = 16.0 * draw_scale;
+    let padding_y = 8.0 * draw_scale;
+    let width = bounds.width() + padding_x * 2.0;
+    let height = bounds.height() + padding_y * 2.0;
+    LayerTreeBuilder::default()
+        .key("window_selector_filter")
+        .layout_style(taffy::Style {
+            position: taffy::Position::Absolute,
+            ..Default::default()
+        })
+        .position((
+            (center.0 - width / 2.0, center.1 - height / 2.0).into(),
+            None,
+        ))
+        .size((layers::types::Size::points(width, height), None))
+        .blend_mode(style.blend_mode())
+        .border_corner_radius((
+            BorderRadius::new_single(style.corner_radius(height / 2.0)),
+            None,
+        ))
+        .background_color((
+            PaintColor::Solid {
+                color: style.background_color(Color::new_rgba(1.0, 1.0, 1.0, 0.6)),
+            },
+            None,
+        ))
+        .shadow_color((
+            style.shadow_color(Color::new_rgba(0.0, 0.0, 0.0, 0.2)),
+            None,
+        ))
+        .shadow_offset(((0.0, 0.0).into(), None))
+        .shadow_radius((5.0, None))
+        .content(Some(move |canvas: &skia::Canvas, w, h| {
+            let mut paragraph = get_paragraph_for_text(&text, font_size);
+            paragraph.layout(w);
+            paragraph.paint(canvas, (0.0, padding_y));
+            skia::Rect::from_xywh(0.0, 0.0, w, h)
+        }))
         .build()
         .unwrap()
 }
@@ -702,9 +1041,23 @@ impl WindowSelectorView {
             *stored_hash = layout_hash;
         }
 
+        // Keep the filter and the highlighted window across layouts
+        let previous = self.view.get_state();
+        let selected = previous
+            .current_selection
+            .and_then(|index| previous.rects.get(index))
+            .and_then(|rect| rect.window_id.clone());
         let mut state = WindowSelectorState {
             rects: vec![],
             current_selection: None,
+            filter: previous.filter,
+            filter_center: (
+                layout_rect.x + layout_rect.width / 2.0,
+                layout_rect.y + offset_y / 2.0,
+            ),
+            group: previous.group,
+            hovered_control: None,
+            theme: theme::generation(),
         };
 
         for (index, window) in windows.iter().enumerate() {
@@ -724,6 +1077,12 @@ impl WindowSelectorView {
                 });
             }
         }
+        state.current_selection = selected.and_then(|selected| {
+            state
+                .rects
+                .iter()
+                .position(|rect| rect.window_id.as_ref() == Some(&selected))
+        });
 
         self.view.update_state(&state);
     }
@@ -773,6 +1132,28 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WindowSelecto
             // Get the dragged window's bounds
             if let Some(drag_state) = self.drag_state.read().unwrap().as_ref() {
                 let drag_bounds = drag_state.window_layer.render_bounds_transformed();
+                let dragged_window = otto
+                    .workspaces
+                    .windows_map
+                    .get(&drag_state.window_id)
+                    .cloned();
+                let source_pos = dragged_window
+                    .as_ref()
+                    .and_then(|window| otto.workspaces.window_workspace_index(window))
+                    .unwrap_or_else(|| otto.workspaces.get_current_workspace_index());
+                // Fullscreen workspaces only take a second window as a split
+                let accepts_window = |target_pos: usize| {
+                    target_pos != source_pos
+                        && otto
+                            .workspaces
+                            .get_workspace_at(target_pos)
+                            .is_some_and(|ws| {
+                                !ws.get_fullscreen_mode()
+                                    || dragged_window.as_ref().is_some_and(|window| {
+                                        otto.can_split_with(window, target_pos)
+                                    })
+                            })
+                };
 
                 for target in drop_targets {
                     // Map view index to workspace position
@@ -786,14 +1167,8 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WindowSelecto
                         );
                         continue;
                     };
-                    if target_pos == otto.workspaces.get_current_workspace_index() {
-                        continue; // Skip current workspace
-                    }
-                    // Skip fullscreen workspaces - can't drop windows into them
-                    if let Some(ws) = otto.workspaces.get_workspace_at(target_pos) {
-                        if ws.get_fullscreen_mode() {
-                            continue;
-                        }
+                    if !accepts_window(target_pos) {
+                        continue; // Skip the window's own workspace
                     }
                     // Use Skia's intersect to check if drag bounds overlap with drop target
                     if drag_bounds.intersects(target.drop_layer.render_bounds_transformed()) {
@@ -801,6 +1176,17 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WindowSelecto
                         break;
                     }
                 }
+
+                // In global exposé the windows can also be dropped on the
+                // group of another workspace
+                if new_drop_target.is_none() {
+                    new_drop_target = otto
+                        .workspaces
+                        .expose_group_at(cursor_point)
+                        .filter(|target_pos| accepts_window(*target_pos))
+                        .and_then(|target_pos| otto.workspaces.get_workspace_at(target_pos))
+                        .map(|ws| ws.index);
+                }
             }
 
             // Update drag state and visual feedback if target changed
@@ -863,27 +1249,54 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WindowSelecto
                 }
             })
             .map(|x| x.index);
+        let hovered_control = rect
+            .and_then(|index| state.rects.get(index))
+            .and_then(|rect| WindowControl::at(rect, cursor_point, window_control_size()));
 
         self.view.update_state(&WindowSelectorState {
-            rects: state.rects,
             current_selection: rect,
+            hovered_control,
+            ..state
         });
     }
+    fn on_leave(&self, _serial: smithay::utils::Serial, _time: u32) {
+        // In global exposé the pointer moves on to the group of another
+        // workspace, which highlights its own windows
+        if self.drag_state.read().unwrap().is_none() {
+            self.select(None);
+        }
+    }
     fn on_button(
         &self,
         _seat: &smithay::input::Seat<crate::Otto<Backend>>,
         otto: &mut crate::Otto<Backend>,
         event: &smithay::input::pointer::ButtonEvent,
     ) {
+        // Middle click closes the window under the pointer
+        if event.button == BTN_MIDDLE {
+            if event.state == ButtonState::Released {
+                let window = self
+                    .selection()
+                    .and_then(|selection| selection.window_id)
+                    .and_then(|id| otto.workspaces.get_window_for_surface(&id).cloned());
+                if let Some(window) = window {
+                    otto.close_window(&window);
+                }
+            }
+            return;
+        }
         match event.state {
             ButtonState::Pressed => {
                 self.pointer_down.store(true, Ordering::SeqCst);
                 let pointer_location = self.current_pointer_or_default(otto);
                 *self.press_location.write().unwrap() = Some(pointer_location);
 
+                // A press on a button of the window doesn't drag it
                 let state = self.view.get_state();
+                *self.pressed_control.write().unwrap() = state.hovered_control;
                 let selection = state
                     .current_selection
+                    .filter(|_| state.hovered_control.is_none())
                     .and_then(|index| state.rects.get(index).cloned());
 
                 if let Some(selection) = selection {
@@ -922,16 +1335,22 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WindowSelecto
                                     .cloned(),
                                 target_pos,
                             ) {
-                                // Get position in current workspace before moving
+                                // Get position in its workspace before moving
                                 let position = otto
                                     .workspaces
-                                    .space()
-                                    .element_location(&window_element)
+                                    .window_location(&window_element)
                                     .unwrap_or_default();
 
                                 // Clear dragging state
                                 *otto.workspaces.expose_dragged_window.lock().unwrap() = None;
 
+                                // A window dropped on a fullscreen workspace splits it
+                                if otto.join_split_view(&window_element, target_pos) {
+                                    self.clear_press_context();
+                                    otto.set_cursor(&CursorImageStatus::default_named());
+                                    return;
+                                }
+
                                 // Move window to target workspace
                                 // Note: unmap_window no longer removes the mirror layer to avoid SlotMap key issues
                                 otto.workspaces.move_window_to_workspace(
@@ -969,6 +1388,23 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WindowSelecto
                 self.clear_press_context();
 
                 let selector_state = self.view.get_state();
+                let pressed_control = self.pressed_control.write().unwrap().take();
+                if let Some(control) = pressed_control {
+                    // Exposé stays open, the other windows move into the room left
+                    let window = self
+                        .selection()
+                        .filter(|_| selector_state.hovered_control == Some(control))
+                        .and_then(|selection| selection.window_id)
+                        .and_then(|id| otto.workspaces.get_window_for_surface(&id).cloned());
+                    if let Some(window) = window {
+                        match control {
+                            WindowControl::Close => otto.close_window(&window),
+                            WindowControl::Minimize => otto.minimize_window(&window),
+                            WindowControl::Fullscreen => otto.fullscreen_window(&window),
+                        }
+                    }
+                    return;
+                }
                 if let Some(index) = selector_state.current_selection {
                     if let Some(window_selection) = selector_state.rects.get(index) {
                         if let Some(wid) = window_selection.window_id.clone() {
@@ -988,3 +1424,103 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WindowSelecto
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32) -> WindowSelection {
+        WindowSelection {
+            x,
+            y,
+            w: 100.0,
+            h: 100.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn selection_moves_to_the_nearest_window_in_line() {
+        // 0 1 2
+        //  3   4
+        let rects = [
+            rect(0.0, 0.0),
+            rect(200.0, 0.0),
+            rect(400.0, 0.0),
+            rect(100.0, 200.0),
+            rect(500.0, 200.0),
+        ];
+        assert_eq!(
+            neighbour_selection(&rects, 0, SelectionDirection::Right),
+            Some(1)
+        );
+        assert_eq!(
+            neighbour_selection(&rects, 1, SelectionDirection::Left),
+            Some(0)
+        );
+        assert_eq!(
+            neighbour_selection(&rects, 1, SelectionDirection::Down),
+            Some(3)
+        );
+        assert_eq!(
+            neighbour_selection(&rects, 4, SelectionDirection::Up),
+            Some(2)
+        );
+        assert_eq!(
+            neighbour_selection(&rects, 3, SelectionDirection::Right),
+            Some(4)
+        );
+        assert_eq!(neighbour_selection(&rects, 0, SelectionDirection::Up), None);
+        assert_eq!(neighbour_selection(&rects, 9, SelectionDirection::Up), None);
+    }
+
+    #[test]
+    fn window_controls_sit_in_the_top_left_corner() {
+        let window = WindowSelection {
+            x: 100.0,
+            y: 100.0,
+            w: 400.0,
+            h: 300.0,
+            ..Default::default()
+        };
+        let at = |x, y| WindowControl::at(&window, (x, y), 20.0);
+        assert_eq!(at(121.0, 118.0), Some(WindowControl::Close));
+        assert_eq!(at(150.0, 120.0), Some(WindowControl::Minimize));
+        assert_eq!(at(181.0, 125.0), Some(WindowControl::Fullscreen));
+        assert_eq!(at(135.0, 120.0), None);
+        assert_eq!(at(120.0, 140.0), None);
+    }
+
+    #[test]
+    fn workspace_groups_share_the_area_in_rows() {
+        let area = LayoutRect::new(0.0, 0.0, 410.0, 210.0);
+        let cells = workspace_group_cells(3, &area, 10.0);
+        assert_eq!(
+            cells,
+            [
+                LayoutRect::new(0.0, 0.0, 200.0, 100.0),
+                LayoutRect::new(210.0, 0.0, 200.0, 100.0),
+                LayoutRect::new(105.0, 110.0, 200.0, 100.0),
+            ]
+        );
+        assert_eq!(workspace_group_cells(1, &area, 10.0), [area]);
+        assert!(workspace_group_cells(0, &area, 10.0).is_empty());
+    }
+
+    #[test]
+    fn filter_matches_title_or_app_ignoring_case() {
+        assert!(matches_filter("", &["Mail"]));
+        assert!(matches_filter(
+            "inbox",
+            &["Inbox - Mail", "org.gnome.Evolution"]
+        ));
+        assert!(matches_filter(
+            "evo",
+            &["Inbox - Mail", "org.gnome.Evolution"]
+        ));
+        assert!(!matches_filter(
+            "term",
+            &["Inbox - Mail", "org.gnome.Evolution"]
+        ));
+    }
+}
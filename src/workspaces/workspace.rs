@@ -1,5 +1,5 @@
-use super::{BackgroundView, WindowSelectorView};
-use crate::{config::Config, shell::WindowElement, utils::image_from_path};
+use super::{ApplicationsInfo, BackgroundView, SplitDivider, SplitView, WindowSelectorView};
+use crate::{config::Config, shell::WindowElement};
 use core::fmt;
 
 use layers::{
@@ -26,8 +26,11 @@ pub struct WorkspaceView {
     pub layers_engine: Arc<Engine>,
     pub workspace_layer: Layer,
     pub windows_layer: Layer,
+    pub split_divider: SplitDivider,
 
     fullscreen_mode: Arc<AtomicBool>,
+    urgent: Arc<AtomicBool>,
+    split_view: Arc<RwLock<Option<SplitView>>>,
     is_fullscreen_animating: Arc<AtomicBool>,
     name: Arc<RwLock<Option<String>>>,
     window_base_layers: Arc<RwLock<HashMap<ObjectId, Layer>>>,
@@ -56,6 +59,7 @@ impl fmt::Debug for WorkspaceView {
 ///     │   ├── window
 ///     │   ├── window
 ///     │   └── window
+///     ├── split_divider
 ///     └── overlay
 ///         └── fullscreen_surface
 /// ```
@@ -103,13 +107,10 @@ impl WorkspaceView {
 
         layers_engine.append_layer(&background_layer, Some(workspace_layer.id));
         layers_engine.append_layer(&windows_layer, Some(workspace_layer.id));
+        let split_divider = SplitDivider::new(&layers_engine, &workspace_layer);
 
-        let background_view = BackgroundView::new(index, background_layer.clone());
-        let background_path = Config::with(|c| c.background_image.clone());
-        if let Some(background_image) = image_from_path(&background_path, (2048, 2048)) {
-            background_view.set_image(background_image);
-        }
-        let background_view = Arc::new(background_view);
+        // The wallpaper is set by `Workspaces::reload_backgrounds`
+        let background_view = Arc::new(BackgroundView::new(index, background_layer.clone()));
 
         let window_selector_view = WindowSelectorView::new(
             index,
@@ -120,14 +121,6 @@ impl WorkspaceView {
 
         let window_selector_view = Arc::new(window_selector_view);
 
-        if let Some(background_image) = image_from_path(&background_path, (2048, 2048)) {
-            background_view.set_image(background_image);
-        } else {
-            tracing::warn!(
-                "Failed to load background image from path: {}",
-                background_path
-            );
-        }
         Self {
             index,
             windows_list: Arc::new(RwLock::new(Vec::new())),
@@ -136,7 +129,10 @@ impl WorkspaceView {
             layers_engine,
             windows_layer,
             workspace_layer,
+            split_divider,
             fullscreen_mode: Arc::new(AtomicBool::new(false)),
+            urgent: Arc::new(AtomicBool::new(false)),
+            split_view: Arc::new(RwLock::new(None)),
             is_fullscreen_animating: Arc::new(AtomicBool::new(false)),
             name: Arc::new(RwLock::new(None)),
             window_base_layers: Arc::new(RwLock::new(HashMap::new())),
@@ -257,6 +253,26 @@ impl WorkspaceView {
             .load(std::sync::atomic::Ordering::Relaxed)
     }
 
+    /// Whether a window of the workspace asked for attention since the
+    /// workspace was last shown
+    pub fn is_urgent(&self) -> bool {
+        self.urgent.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    pub fn set_urgent(&self, urgent: bool) {
+        self.urgent
+            .store(urgent, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// The two windows sharing this fullscreen workspace, if split
+    pub fn get_split_view(&self) -> Option<SplitView> {
+        self.split_view.read().unwrap().clone()
+    }
+
+    pub fn set_split_view(&self, split_view: Option<SplitView>) {
+        *self.split_view.write().unwrap() = split_view;
+    }
+
     pub fn set_name(&self, name: Option<String>) {
         *self.name.write().unwrap() = name;
     }
@@ -264,6 +280,22 @@ impl WorkspaceView {
     pub fn get_name(&self) -> Option<String> {
         self.name.read().unwrap().clone()
     }
+
+    /// Names the workspace after the app's desktop name, looked up in the
+    /// background, falling back to `app_id`
+    pub fn set_name_from_app(&self, app_id: String) {
+        if app_id.is_empty() {
+            return;
+        }
+        let name = self.name.clone();
+        tokio::spawn(async move {
+            let display_name = ApplicationsInfo::get_app_info_by_id(&app_id)
+                .await
+                .and_then(|app_info| app_info.desktop_name())
+                .unwrap_or(app_id);
+            *name.write().unwrap() = Some(display_name);
+        });
+    }
 }
 
 impl Drop for WorkspaceView {
@@ -12,7 +12,7 @@ use smithay::{
 use crate::{
     config::Config,
     interactive_view::ViewInteractions,
-    theme::{self, theme_colors},
+    theme::{self, accent_color, component_style, theme_colors, Component},
     utils::{
         button_press_filter, button_press_scale, button_release_filter, button_release_scale,
         draw_named_icon, draw_text_content, Observer,
@@ -22,6 +22,9 @@ use crate::{
 use super::WorkspacesModel;
 
 pub const WORKSPACE_SELECTOR_PREVIEW_WIDTH: f32 = 300.0;
+const WORKSPACE_SELECTOR_GAP: f32 = 50.0;
+/// Distance the pointer moves before a pressed desktop gets dragged
+const REORDER_THRESHOLD: f32 = 20.0;
 
 #[derive(Clone, Debug)]
 pub struct WorkspaceDropTarget {
@@ -52,11 +55,35 @@ impl Hash for WorkspaceViewState {
     }
 }
 
+/// A desktop dragged along the strip to reorder the workspaces
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct WorkspaceReorder {
+    /// Position of the dragged desktop
+    from: usize,
+    /// Position it takes when dropped
+    to: usize,
+    /// Horizontal distance dragged
+    offset: f32,
+}
+
+impl Hash for WorkspaceReorder {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.from.hash(state);
+        self.to.hash(state);
+        self.offset.to_bits().hash(state);
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct WorkspaceSelectorViewState {
     workspaces: Vec<WorkspaceViewState>,
     current: usize,
     drop_hover_index: Option<usize>,
+    /// Workspace whose name is being edited, and the text typed so far
+    renaming: Option<(usize, String)>,
+    reorder: Option<WorkspaceReorder>,
+    /// [`theme::generation`] of the rendering
+    theme: u64,
 }
 
 impl Hash for WorkspaceSelectorViewState {
@@ -64,6 +91,9 @@ impl Hash for WorkspaceSelectorViewState {
         self.workspaces.hash(state);
         self.current.hash(state);
         self.drop_hover_index.hash(state);
+        self.renaming.hash(state);
+        self.reorder.hash(state);
+        self.theme.hash(state);
     }
 }
 
@@ -75,6 +105,7 @@ pub struct WorkspaceSelectorView {
     pub drop_targets: Arc<RwLock<Vec<WorkspaceDropTarget>>>,
     pub drop_hover_index: Arc<RwLock<Option<usize>>>,
     pressed_action: Arc<RwLock<Option<String>>>,
+    press_location: Arc<RwLock<Option<Point>>>,
 }
 
 /// # WorkspaceSelectorView Layer Structure
@@ -99,6 +130,9 @@ impl WorkspaceSelectorView {
             workspaces: Vec::new(),
             current: 0,
             drop_hover_index: None,
+            renaming: None,
+            reorder: None,
+            theme: theme::generation(),
         };
         let view = View::new(
             "workspace_selector_view",
@@ -139,9 +173,17 @@ impl WorkspaceSelectorView {
             drop_targets,
             drop_hover_index,
             pressed_action,
+            press_location: Arc::new(RwLock::new(None)),
         }
     }
 
+    /// Renders the selector again with the current theme
+    pub fn reload_theme(&self) {
+        let mut state = self.view.get_state();
+        state.theme = theme::generation();
+        self.view.update_state(&state);
+    }
+
     /// Get current drop targets (updated after each render)
     pub fn get_drop_targets(&self) -> Vec<WorkspaceDropTarget> {
         self.drop_targets.read().unwrap().clone()
@@ -161,6 +203,102 @@ impl WorkspaceSelectorView {
     pub fn get_drop_hover(&self) -> Option<usize> {
         *self.drop_hover_index.read().unwrap()
     }
+
+    /// Shows a text field with `name` in place of the label of the
+    /// workspace with view index `workspace_index`
+    pub fn start_rename(&self, workspace_index: usize, name: String) {
+        let mut state = self.view.get_state();
+        state.renaming = Some((workspace_index, name));
+        self.view.update_state(&state);
+    }
+
+    pub fn is_renaming(&self) -> bool {
+        self.view.get_state().renaming.is_some()
+    }
+
+    /// Text of the field being edited
+    pub fn rename_text(&self) -> Option<String> {
+        self.view.get_state().renaming.map(|(_, text)| text)
+    }
+
+    /// Replaces the text of the field being edited
+    pub fn set_rename_text(&self, text: String) {
+        let mut state = self.view.get_state();
+        if let Some((_, name)) = state.renaming.as_mut() {
+            *name = text;
+            self.view.update_state(&state);
+        }
+    }
+
+    /// Hides the text field, returning the workspace and the typed text
+    pub fn stop_rename(&self) -> Option<(usize, String)> {
+        let mut state = self.view.get_state();
+        let renaming = state.renaming.take()?;
+        self.view.update_state(&state);
+        Some(renaming)
+    }
+
+    /// Drops the dragged desktop, returning the position it was dragged
+    /// from and the one it was dropped at
+    pub fn stop_reorder(&self) -> Option<(usize, usize)> {
+        let mut state = self.view.get_state();
+        let reorder = state.reorder.take()?;
+        self.view.update_state(&state);
+        Some((reorder.from, reorder.to))
+    }
+
+    /// Drags the pressed desktop along the strip, once the pointer moved
+    /// far enough from where it was pressed
+    fn update_reorder(&self, location: Point) {
+        let Some(start) = *self.press_location.read().unwrap() else {
+            return;
+        };
+        let Some(view_index) = self
+            .pressed_action
+            .read()
+            .unwrap()
+            .as_deref()
+            .and_then(|key| key.strip_prefix("workspace_selector_desktop_"))
+            .and_then(|idx| idx.parse::<usize>().ok())
+        else {
+            return;
+        };
+        let mut state = self.view.get_state();
+        let offset = location.x - start.x;
+        let from = match state.reorder {
+            Some(reorder) => reorder.from,
+            None if offset.abs() < REORDER_THRESHOLD || state.renaming.is_some() => return,
+            None => match state.workspaces.iter().position(|w| w.index == view_index) {
+                Some(from) => from,
+                None => return,
+            },
+        };
+        state.reorder = Some(WorkspaceReorder {
+            from,
+            to: reorder_target(from, offset, state.workspaces.len()),
+            offset,
+        });
+        self.view.update_state(&state);
+    }
+}
+
+/// Position a desktop dragged by `offset` from `from` is dropped at
+fn reorder_target(from: usize, offset: f32, count: usize) -> usize {
+    let slot = WORKSPACE_SELECTOR_PREVIEW_WIDTH + WORKSPACE_SELECTOR_GAP;
+    let target = from as f32 + (offset / slot).round();
+    target.clamp(0.0, count.saturating_sub(1) as f32) as usize
+}
+
+/// Slots the desktop at `index` shifts by to make room for the one dragged
+/// from `from` to `to`
+fn reorder_shift(index: usize, from: usize, to: usize) -> f32 {
+    if from < index && index <= to {
+        -1.0
+    } else if to <= index && index < from {
+        1.0
+    } else {
+        0.0
+    }
 }
 
 fn render_workspace_selector_view(
@@ -168,6 +306,8 @@ fn render_workspace_selector_view(
     view: &View<WorkspaceSelectorViewState>,
 ) -> LayerTree {
     let worspaces = state.workspaces.clone();
+    let style = component_style(Component::WorkspaceSelector);
+    let preview_radius = style.corner_radius(20.0);
 
     let workspaces_tree = worspaces
         .iter()
@@ -182,7 +322,7 @@ fn render_workspace_selector_view(
             let is_drop_hover = state_drop_hover_index - 1 == (i as i32) && !current;
 
             let mut border_width = 0.0;
-            let border_color = theme_colors().accents_blue;
+            let border_color = accent_color();
 
             if current {
                 border_width = 8.0;
@@ -199,6 +339,27 @@ fn render_workspace_selector_view(
             let preview_width = WORKSPACE_SELECTOR_PREVIEW_WIDTH;
             let scale = preview_width / workspace_width;
             let preview_height = workspace_height * scale;
+            let editing = state
+                .renaming
+                .as_ref()
+                .filter(|(index, _)| *index == workspace_index)
+                .map(|(_, text)| text.clone());
+            let label_color = if editing.is_some() {
+                theme_colors().text_primary
+            } else {
+                Color::new_rgba(0.0, 0.0, 0.0, 0.5)
+            };
+            // The layout places the desktops, shifting the anchor point moves
+            // them while one is dragged; the dragged one follows the pointer
+            let (shift, shift_transition) = match state.reorder {
+                Some(reorder) if reorder.from == i => (reorder.offset, None),
+                Some(reorder) => (
+                    reorder_shift(i, reorder.from, reorder.to)
+                        * (preview_width + WORKSPACE_SELECTOR_GAP),
+                    Some(Transition::spring(0.3, 0.1)),
+                ),
+                None => (0.0, None),
+            };
 
             LayerTreeBuilder::with_key(format!(
                 "workspace_selector_desktop_{}",
@@ -220,6 +381,7 @@ fn render_workspace_selector_view(
                 },
                 None,
             ))
+            .anchor_point((Point::new(-shift / preview_width, 0.0), shift_transition))
             .children(vec![
                 LayerTreeBuilder::with_key(format!(
                     "workspace_selector_desktop_content_{}",
@@ -283,7 +445,7 @@ fn render_workspace_selector_view(
                             .picture_cached(true)
                             .image_cache(true)
                             .color_filter(color_filter)
-                            .border_corner_radius(BorderRadius::new_single(20.0 / scale))
+                            .border_corner_radius(BorderRadius::new_single(preview_radius / scale))
                             .clip_children(true)
                             .clip_content(true)
                             .pointer_events(true)
@@ -312,7 +474,7 @@ fn render_workspace_selector_view(
                             ))
                             .border_width((border_width, None))
                             .border_color(border_color)
-                            .border_corner_radius(BorderRadius::new_single(20.0))
+                            .border_corner_radius(BorderRadius::new_single(preview_radius))
                             .pointer_events(false)
                             .build()
                             .unwrap(),
@@ -369,9 +531,24 @@ fn render_workspace_selector_view(
                         None,
                     ))
                     // .background_color(theme_colors().accents_purple)
+                    .background_color(if editing.is_some() {
+                        theme_colors().fills_secondary
+                    } else {
+                        Color::new_rgba(0.0, 0.0, 0.0, 0.0)
+                    })
+                    .border_corner_radius(BorderRadius::new_single(10.0))
+                    .border_width((if editing.is_some() { 2.0 } else { 0.0 }, None))
+                    .border_color(accent_color())
                     .content(draw_text_content(
-                        w.name.clone(),
-                        theme::text_styles::title_3_regular(),
+                        // The text field ends with a caret
+                        editing
+                            .map(|text| format!("{text}|"))
+                            .unwrap_or_else(|| w.name.clone()),
+                        style.text_style(
+                            17.0 * Config::with(|c| c.screen_scale) as f32,
+                            layers::skia::font_style::Weight::NORMAL,
+                            label_color,
+                        ),
                         layers::skia::textlayout::TextAlign::Center,
                     ))
                     .build()
@@ -396,9 +573,9 @@ fn render_workspace_selector_view(
             },
             None,
         ))
-        .background_color(theme_colors().materials_medium)
-        .blend_mode(BlendMode::BackgroundBlur)
-        .shadow_color(theme_colors().shadow_color)
+        .background_color(style.background_color(theme_colors().materials_medium))
+        .blend_mode(style.blend_mode())
+        .shadow_color(style.shadow_color(theme_colors().shadow_color))
         .shadow_offset(((0.0, -5.0).into(), None))
         .shadow_radius((20.0, None))
         .children(vec![
@@ -408,7 +585,7 @@ fn render_workspace_selector_view(
                     flex_direction: taffy::FlexDirection::Row,
                     align_items: Some(taffy::AlignItems::Center),
                     justify_content: Some(taffy::AlignContent::Center),
-                    gap: taffy::length(50.0),
+                    gap: taffy::length(WORKSPACE_SELECTOR_GAP),
                     padding: taffy::Rect {
                         bottom: taffy::length(20.0),
                         top: taffy::length(30.0),
@@ -528,15 +705,18 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WorkspaceSele
             }
         }
 
-        if hover {
+        if state.reorder.is_some() {
+            let cursor = CursorImageStatus::Named(CursorIcon::Grabbing);
+            data.set_cursor(&cursor);
+        } else if hover {
             let cursor = CursorImageStatus::Named(CursorIcon::Pointer);
             data.set_cursor(&cursor);
         } else {
             let cursor = CursorImageStatus::Named(CursorIcon::default());
             data.set_cursor(&cursor);
         }
-        let mut cursor_location = self.cursor_location.write().unwrap();
-        *cursor_location = location;
+        *self.cursor_location.write().unwrap() = location;
+        self.update_reorder(location);
     }
     fn on_button(
         &self,
@@ -573,8 +753,16 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WorkspaceSele
             ButtonState::Pressed => {
                 let mut pressed = self.pressed_action.write().unwrap();
                 *pressed = hovered_key(&location);
+                *self.press_location.write().unwrap() = Some(*location);
             }
             ButtonState::Released => {
+                *self.press_location.write().unwrap() = None;
+                // A dragged desktop is dropped rather than clicked
+                if let Some((from, to)) = self.stop_reorder() {
+                    *self.pressed_action.write().unwrap() = None;
+                    otto.workspaces.move_workspace(from, to);
+                    return;
+                }
                 let release_key = hovered_key(&location);
                 let mut pressed = self.pressed_action.write().unwrap();
                 if let (Some(pressed_key), Some(release_key)) = (pressed.clone(), release_key) {
@@ -603,3 +791,25 @@ impl<Backend: crate::state::Backend> ViewInteractions<Backend> for WorkspaceSele
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dragged_desktop_lands_on_the_nearest_slot() {
+        let slot = WORKSPACE_SELECTOR_PREVIEW_WIDTH + WORKSPACE_SELECTOR_GAP;
+        assert_eq!(reorder_target(1, 0.4 * slot, 4), 1);
+        assert_eq!(reorder_target(1, 0.6 * slot, 4), 2);
+        assert_eq!(reorder_target(1, -3.0 * slot, 4), 0);
+        assert_eq!(reorder_target(1, 5.0 * slot, 4), 3);
+    }
+
+    #[test]
+    fn desktops_between_make_room() {
+        let shifts: Vec<_> = (0..4).map(|index| reorder_shift(index, 0, 2)).collect();
+        assert_eq!(shifts, vec![0.0, -1.0, -1.0, 0.0]);
+        let shifts: Vec<_> = (0..4).map(|index| reorder_shift(index, 3, 1)).collect();
+        assert_eq!(shifts, vec![0.0, 1.0, 1.0, 0.0]);
+    }
+}
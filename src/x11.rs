@@ -5,6 +5,7 @@ use std::{
 };
 
 use crate::{
+    input::actions::KeyAction,
     render::*,
     render_elements::workspace_render_elements::WorkspaceRenderElements,
     shell::WindowElement,
@@ -111,12 +112,17 @@ impl DmabufHandler for Otto<X11Data> {
 delegate_dmabuf!(Otto<X11Data>);
 
 impl Backend for X11Data {
+    type InputDevice = smithay::backend::x11::X11VirtualDevice;
+
     fn seat_name(&self) -> String {
         "x11".to_owned()
     }
     fn backend_name(&self) -> &'static str {
         "x11"
     }
+    fn process_key_action(state: &mut Otto<Self>, action: KeyAction) {
+        state.process_key_action_on_first_output(action);
+    }
     fn reset_buffers(&mut self, _output: &Output) {
         self.surface.reset_buffers();
     }
@@ -539,6 +545,7 @@ pub fn run_x11() {
             state.workspaces.refresh_space();
             state.popups.cleanup();
             display_handle.flush_clients().unwrap();
+            state.dispatch_ipc_events();
         }
     }
 }